@@ -14,29 +14,42 @@ pub struct CommandArgs {
     prefix_command: bool,
     slash_command: bool,
     context_menu_command: Option<String>,
+    primary_entry_point: bool,
+    entry_point_handler: Option<syn::Ident>,
 
     // When changing these, document it in parent file!
     // TODO: decide why darling(multiple) feels wrong here but not in e.g. localizations (because
     //  if it's actually irrational, the inconsistency should be fixed)
     subcommands: crate::util::List<syn::Path>,
     aliases: crate::util::List<String>,
+    slash_aliases: crate::util::List<String>,
     subcommand_required: bool,
     invoke_on_edit: bool,
     reuse_response: bool,
     track_deletion: bool,
     track_edits: bool,
     broadcast_typing: bool,
+    allow_bot_invocations: bool,
+    // In milliseconds
+    broadcast_typing_delay: Option<u64>,
     #[darling(multiple)]
     check: Vec<syn::Path>,
     on_error: Option<syn::Path>,
+    fallback: Option<syn::Path>,
     rename: Option<String>,
     #[darling(multiple)]
     name_localized: Vec<crate::util::Tuple2<String>>,
     #[darling(multiple)]
     description_localized: Vec<crate::util::Tuple2<String>>,
+    #[darling(multiple)]
+    context_menu_name_localized: Vec<crate::util::Tuple2<String>>,
+    #[darling(multiple)]
+    help_text_localized: Vec<crate::util::Tuple2<String>>,
     discard_spare_arguments: bool,
     hide_in_help: bool,
     ephemeral: bool,
+    response_mode: Option<syn::Ident>,
+    rerun_button: bool,
     default_member_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_bot_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
@@ -44,16 +57,25 @@ pub struct CommandArgs {
     guild_only: bool,
     dm_only: bool,
     nsfw_only: bool,
+    feature: Option<String>,
     identifying_name: Option<String>,
     category: Option<String>,
     custom_data: Option<syn::Expr>,
+    deprecated: Option<darling::util::Override<DeprecatedArgs>>,
 
     manual_cooldowns: Option<bool>,
+    inherit_cooldowns: Option<bool>,
+    delete_button: Option<bool>,
     has_modifier: bool,
 
     install_context: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     interaction_context: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
 
+    // In seconds
+    timeout: Option<u64>,
+    spawn_policy: Option<syn::Ident>,
+    log_level: Option<syn::Ident>,
+    error_reply_policy: Option<syn::Ident>,
     // In seconds
     global_cooldown: Option<u64>,
     user_cooldown: Option<u64>,
@@ -62,6 +84,14 @@ pub struct CommandArgs {
     member_cooldown: Option<u64>,
 }
 
+/// Representation of the `deprecated(...)` command attribute argument
+#[derive(Default, Debug, darling::FromMeta)]
+#[darling(default)]
+struct DeprecatedArgs {
+    message: Option<String>,
+    replacement: Option<String>,
+}
+
 /// Representation of the function parameter attribute arguments
 #[derive(Default, Debug, darling::FromMeta)]
 #[darling(default)]
@@ -83,6 +113,9 @@ struct ParamArgs {
     lazy: bool,
     flag: bool,
     rest: bool,
+    until: Option<String>,
+    required: bool,
+    optional: bool,
 }
 
 /// Part of the Invocation struct. Represents a single parameter of a Discord command.
@@ -93,6 +126,17 @@ struct CommandParameter {
     span: proc_macro2::Span,
 }
 
+impl CommandParameter {
+    /// Whether the parameter is `#[optional]` without being an `Option` or `Vec`, so that it's
+    /// filled with its default value when omitted
+    fn defaults_when_omitted(&self) -> bool {
+        self.args.optional
+            && !self.args.flag
+            && crate::util::extract_type_parameter("Option", &self.type_).is_none()
+            && crate::util::extract_type_parameter("Vec", &self.type_).is_none()
+    }
+}
+
 /// Passed to prefix and slash command spec generators; contains info to be included in command spec
 pub struct Invocation {
     parameters: Vec<CommandParameter>,
@@ -107,6 +151,110 @@ pub struct Invocation {
     args: CommandArgs,
 }
 
+/// Generates code which runs the parsed parameters, bound to `param_idents`, through the
+/// `FrameworkOptions::argument_transformers`. Expects `ctx` to be a prefix or application context.
+fn generate_argument_transforms(
+    inv: &Invocation,
+    param_idents: &[syn::Ident],
+) -> proc_macro2::TokenStream {
+    let transform = |value: proc_macro2::TokenStream| {
+        quote::quote! {
+            ctx.framework.options.argument_transformers
+                .apply(ctx.into(), #value)
+                .await
+                .map_err(|error| ::lumi::FrameworkError::new_command(ctx.into(), error))?
+        }
+    };
+
+    let transforms = inv
+        .parameters
+        .iter()
+        .zip(param_idents)
+        // Flags and inline choices aren't free-form user input
+        .filter(|(p, _)| !p.args.flag && p.args.choices.is_none())
+        .map(|(p, ident)| {
+            if crate::util::extract_type_parameter("Option", &p.type_).is_some() {
+                let transformed = transform(quote::quote! { value });
+                quote::quote! {
+                    let #ident = match #ident {
+                        Some(value) => Some(#transformed),
+                        None => None,
+                    };
+                }
+            } else if crate::util::extract_type_parameter("Vec", &p.type_).is_some() {
+                let transformed = transform(quote::quote! { value });
+                quote::quote! {
+                    let #ident = {
+                        let mut values = Vec::with_capacity(#ident.len());
+                        for value in #ident {
+                            values.push(#transformed);
+                        }
+                        values
+                    };
+                }
+            } else {
+                let transformed = transform(quote::quote! { #ident });
+                quote::quote! { let #ident = #transformed; }
+            }
+        });
+    quote::quote! { #( #transforms )* }
+}
+
+/// Returns whether the command function returns something else than `Result<(), E>`, which is
+/// then sent as response via `IntoCreateReply`. Type aliases are assumed to return `()`.
+fn returns_reply(inv: &Invocation) -> bool {
+    let syn::ReturnType::Type(_, return_type) = &inv.function.sig.output else {
+        return false;
+    };
+    let syn::Type::Path(path) = &**return_type else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    let syn::PathArguments::AngleBracketed(generics) = &segment.arguments else {
+        return false;
+    };
+    if segment.ident != "Result" {
+        return false;
+    }
+    match generics.args.first() {
+        Some(syn::GenericArgument::Type(syn::Type::Tuple(tuple))) => !tuple.elems.is_empty(),
+        Some(syn::GenericArgument::Type(_)) => true,
+        _ => false,
+    }
+}
+
+/// Generates code which awaits the `call` to the command function and converts its result into
+/// the return value of the action. Expects `ctx` to be a prefix or application context.
+fn generate_action_call(
+    inv: &Invocation,
+    call: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let call = quote::quote! {
+        #call
+            .await
+            .map_err(|error| lumi::FrameworkError::new_command(
+                ctx.into(),
+                error,
+            ))
+    };
+    if !returns_reply(inv) {
+        return call;
+    }
+
+    quote::quote! {
+        let reply = #call?;
+        if let Some(reply) = ::lumi::IntoCreateReply::into_create_reply(reply) {
+            let ctx: ::lumi::Context<'_, _, _> = ctx.into();
+            ctx.send(reply)
+                .await
+                .map_err(|error| lumi::FrameworkError::new_command(ctx, error.into()))?;
+        }
+        Ok(())
+    }
+}
+
 fn extract_help_from_doc_comments(attrs: &[syn::Attribute]) -> (Option<String>, Option<String>) {
     let mut doc_lines = String::new();
     for attr in attrs {
@@ -146,15 +294,33 @@ pub fn command(
     if function.sig.output == syn::ReturnType::Default {
         return Err(syn::Error::new(
             function.sig.span(),
-            "command function must return Result<(), ...>",
+            "command function must return Result<..., ...>",
         )
         .into());
     }
 
     // Verify that at least one command type was enabled
-    if !args.prefix_command && !args.slash_command && args.context_menu_command.is_none() {
-        let err_msg = "you must enable at least one of `prefix_command`, `slash_command` or \
-            `context_menu_command`";
+    if !args.prefix_command
+        && !args.slash_command
+        && args.context_menu_command.is_none()
+        && !args.primary_entry_point
+    {
+        let err_msg = "you must enable at least one of `prefix_command`, `slash_command`, \
+            `context_menu_command` or `primary_entry_point`";
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+    }
+
+    // Primary entry point commands replace the slash command and can't take any options
+    if args.primary_entry_point && args.slash_command {
+        let err_msg = "`primary_entry_point` and `slash_command` are mutually exclusive";
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+    }
+    if args.primary_entry_point && function.sig.inputs.len() > 1 {
+        let err_msg = "primary entry point commands cannot have arguments";
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+    }
+    if args.entry_point_handler.is_some() && !args.primary_entry_point {
+        let err_msg = "`entry_point_handler` requires `primary_entry_point`";
         return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
     }
 
@@ -197,6 +363,14 @@ pub fn command(
             let message = "#[rename = \"...\"] must be specified for pattern parameters";
             return Err(syn::Error::new(pattern.pat.span(), message).into());
         };
+        if attrs.required && attrs.optional {
+            let message = "#[required] and #[optional] cannot be used together";
+            return Err(syn::Error::new(span, message).into());
+        }
+        if attrs.optional && attrs.choices.is_some() {
+            let message = "#[optional] cannot be used with #[choices], use Option instead";
+            return Err(syn::Error::new(span, message).into());
+        }
         parameters.push(CommandParameter {
             name,
             type_: (*pattern.ty).clone(),
@@ -270,10 +444,12 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         true => Some(prefix::generate_prefix_action(&inv)?),
         false => None,
     });
-    let slash_action = wrap_option(match inv.args.slash_command {
-        true => Some(slash::generate_slash_action(&inv)?),
-        false => None,
-    });
+    let slash_action = wrap_option(
+        match inv.args.slash_command || inv.args.primary_entry_point {
+            true => Some(slash::generate_slash_action(&inv)?),
+            false => None,
+        },
+    );
     let context_menu_action = wrap_option(match &inv.args.context_menu_command {
         Some(_) => Some(slash::generate_context_menu_action(&inv)?),
         None => None,
@@ -304,6 +480,8 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let category = wrap_option_to_string(inv.args.category.as_ref());
 
     let manual_cooldowns = wrap_option(inv.args.manual_cooldowns);
+    let inherit_cooldowns = inv.args.inherit_cooldowns.unwrap_or(true);
+    let delete_button = wrap_option(inv.args.delete_button);
     let cooldown_config = generate_cooldown_config(&inv.args);
 
     let default_member_permissions = &inv.default_member_permissions;
@@ -314,8 +492,17 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let guild_only = inv.args.guild_only;
     let dm_only = inv.args.dm_only;
     let nsfw_only = inv.args.nsfw_only;
+    let feature = wrap_option_to_string(inv.args.feature.as_ref());
 
     let has_modifier = inv.args.has_modifier;
+    let slash_command_kind = match inv.args.primary_entry_point {
+        true => quote::quote! { Some(lumi::serenity_prelude::CommandType::PrimaryEntryPoint) },
+        false => quote::quote! { None },
+    };
+    let entry_point_handler = match &inv.args.entry_point_handler {
+        Some(handler) => quote::quote! { ::lumi::EntryPointHandler::#handler },
+        None => quote::quote! { ::lumi::EntryPointHandler::AppHandler },
+    };
 
     let install_context = &inv.install_context;
     let interaction_context = &inv.interaction_context;
@@ -326,21 +513,80 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     };
 
     let checks = &inv.args.check;
+    let check_names = checks
+        .iter()
+        .map(|check| {
+            let name = check
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default();
+            quote::quote! { #name }
+        })
+        .collect::<Vec<_>>();
     // Box::pin the callback in order to store it in a struct
     let on_error = match &inv.args.on_error {
         Some(on_error) => quote::quote! { Some(|err| Box::pin(#on_error(err))) },
         None => quote::quote! { None },
     };
+    // Reuse the prefix action generated for the fallback command
+    let fallback_action = match &inv.args.fallback {
+        Some(fallback) => quote::quote! { #fallback().prefix_action },
+        None => quote::quote! { None },
+    };
 
     let invoke_on_edit = inv.args.invoke_on_edit || inv.args.track_edits;
     let reuse_response = inv.args.reuse_response || inv.args.track_edits;
     let track_deletion = inv.args.track_deletion || inv.args.track_edits;
-    let broadcast_typing = inv.args.broadcast_typing;
+    let broadcast_typing = inv.args.broadcast_typing || inv.args.broadcast_typing_delay.is_some();
+    let allow_bot_invocations = inv.args.allow_bot_invocations;
+    let broadcast_typing_delay = wrap_option_and_map(
+        inv.args.broadcast_typing_delay,
+        quote::quote!(::std::time::Duration::from_millis),
+    );
     let aliases = &inv.args.aliases.0;
+    let slash_aliases = &inv.args.slash_aliases.0;
     let subcommands = &inv.args.subcommands.0;
 
     let parameters = slash::generate_parameters(&inv)?;
     let ephemeral = inv.args.ephemeral;
+    let response_mode = match &inv.args.response_mode {
+        Some(mode) => quote::quote! { ::lumi::ResponseMode::#mode },
+        None => quote::quote! { ::lumi::ResponseMode::Immediate },
+    };
+    let rerun_button = inv.args.rerun_button;
+    let spawn_policy = match &inv.args.spawn_policy {
+        Some(policy) => quote::quote! { ::lumi::SpawnPolicy::#policy },
+        None => quote::quote! { ::lumi::SpawnPolicy::Inline },
+    };
+    let log_level = match &inv.args.log_level {
+        Some(level) => quote::quote! { ::lumi::CommandLogLevel::#level },
+        None => quote::quote! { ::lumi::CommandLogLevel::Span },
+    };
+    let error_reply_policy = match &inv.args.error_reply_policy {
+        Some(policy) => quote::quote! { ::lumi::ErrorReplyPolicy::#policy },
+        None => quote::quote! { ::lumi::ErrorReplyPolicy::Auto },
+    };
+    let timeout = wrap_option_and_map(
+        inv.args.timeout,
+        quote::quote!(::std::time::Duration::from_secs),
+    );
+    let deprecated = match &inv.args.deprecated {
+        Some(deprecated) => {
+            // A bare `deprecated` has neither message nor replacement
+            let args = deprecated.as_ref().explicit();
+            let message = wrap_option_to_string(args.and_then(|args| args.message.as_ref()));
+            let replacement =
+                wrap_option_to_string(args.and_then(|args| args.replacement.as_ref()));
+            quote::quote! { Some(::lumi::DeprecationNotice {
+                message: #message,
+                replacement: #replacement,
+                sunset: None,
+                __non_exhaustive: (),
+            }) }
+        }
+        None => quote::quote! { None },
+    };
     let custom_data = match &inv.args.custom_data {
         Some(custom_data) => quote::quote! { Box::new(#custom_data) },
         None => quote::quote! { Box::new(()) },
@@ -349,6 +595,9 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let name_localizations = iter_tuple_2_to_vec_map(inv.args.name_localized.into_iter());
     let description_localizations =
         iter_tuple_2_to_vec_map(inv.args.description_localized.into_iter());
+    let context_menu_name_localizations =
+        iter_tuple_2_to_vec_map(inv.args.context_menu_name_localized.into_iter());
+    let help_text_localizations = iter_tuple_2_to_vec_map(inv.args.help_text_localized.into_iter());
 
     let function_ident =
         std::mem::replace(&mut inv.function.sig.ident, syn::parse_quote! { inner });
@@ -368,8 +617,11 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
 
             ::lumi::Command {
                 prefix_action: #prefix_action,
+                fallback_action: #fallback_action,
                 slash_action: #slash_action,
                 context_menu_action: #context_menu_action,
+                slash_command_kind: #slash_command_kind,
+                entry_point_handler: #entry_point_handler,
 
                 subcommands: vec![ #( #subcommands() ),* ],
                 subcommand_required: #subcommand_required,
@@ -379,12 +631,18 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 identifying_name: Cow::Borrowed(#identifying_name),
                 source_code_name: Cow::Borrowed(#function_name),
                 category: #category,
+                group: None,
                 description: #description,
                 description_localizations: #description_localizations,
                 help_text: #help_text,
+                help_text_localizations: #help_text_localizations,
                 hide_in_help: #hide_in_help,
+                deprecated: #deprecated,
                 manual_cooldowns: #manual_cooldowns,
-                cooldowns: std::sync::Mutex::new(::lumi::Cooldowns::new()),
+                inherit_cooldowns: #inherit_cooldowns,
+                delete_button: #delete_button,
+                cooldowns: ::lumi::Cooldowns::new(),
+                payload_cache: Default::default(),
                 cooldown_config: #cooldown_config,
                 reuse_response: #reuse_response,
                 default_member_permissions: #default_member_permissions,
@@ -394,10 +652,17 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 guild_only: #guild_only,
                 dm_only: #dm_only,
                 nsfw_only: #nsfw_only,
+                feature: #feature,
                 install_context: #install_context,
                 interaction_context: #interaction_context,
-                checks: vec![ #( |ctx| Box::pin(#checks(ctx)) ),* ],
+                rerun_button: #rerun_button,
+                slash_aliases: Cow::Borrowed(&[ #( Cow::Borrowed(#slash_aliases), )* ]),
+                checks: vec![ #( ::lumi::Check::new(|ctx| Box::pin(#checks(ctx))).name(#check_names) ),* ],
                 on_error: #on_error,
+                timeout: #timeout,
+                spawn_policy: #spawn_policy,
+                log_level: #log_level,
+                error_reply_policy: #error_reply_policy,
                 parameters: vec![ #( #parameters ),* ],
                 custom_data: #custom_data,
 
@@ -406,10 +671,14 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 aliases: Cow::Borrowed(&[ #( Cow::Borrowed(#aliases), )* ]),
                 invoke_on_edit: #invoke_on_edit,
                 track_deletion: #track_deletion,
+                allow_bot_invocations: #allow_bot_invocations,
                 broadcast_typing: #broadcast_typing,
+                broadcast_typing_delay: #broadcast_typing_delay,
 
                 context_menu_name: #context_menu_name,
+                context_menu_name_localizations: #context_menu_name_localizations,
                 ephemeral: #ephemeral,
+                response_mode: #response_mode,
 
                 __non_exhaustive: (),
             }
@@ -427,7 +696,7 @@ fn generate_cooldown_config(args: &CommandArgs) -> proc_macro2::TokenStream {
     ];
 
     if all_cooldowns.iter().all(Option::is_none) {
-        return quote::quote!(std::sync::RwLock::default());
+        return quote::quote!(::lumi::parking_lot::RwLock::default());
     }
 
     let to_seconds_path = quote::quote!(std::time::Duration::from_secs);
@@ -439,7 +708,7 @@ fn generate_cooldown_config(args: &CommandArgs) -> proc_macro2::TokenStream {
     let member_cooldown = wrap_option_and_map(args.member_cooldown, &to_seconds_path);
 
     quote::quote!(
-        std::sync::RwLock::new(::lumi::CooldownConfig {
+        ::lumi::parking_lot::RwLock::new(::lumi::CooldownConfig {
             global: #global_cooldown,
             user: #user_cooldown,
             guild: #guild_cooldown,
@@ -8,18 +8,31 @@ fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStre
         Lazy,
         Flag,
         Rest,
+        Until(String),
     }
-    let modifier = match (p.args.lazy, p.args.rest, p.args.flag) {
-        (false, false, false) => Modifier::None,
-        (true, false, false) => Modifier::Lazy,
-        (false, true, false) => Modifier::Rest,
-        (false, false, true) => Modifier::Flag,
+    let modifier = match (p.args.lazy, p.args.rest, p.args.flag, &p.args.until) {
+        (false, false, false, None) => Modifier::None,
+        (true, false, false, None) => Modifier::Lazy,
+        (false, true, false, None) => Modifier::Rest,
+        (false, false, true, None) => Modifier::Flag,
+        (false, false, false, Some(keyword)) => Modifier::Until(keyword.clone()),
         _ => {
             let message = "modifiers like #[lazy] or #[rest] currently cannot be used together";
             return Err(syn::Error::new(p.span, message));
         }
     };
     let type_ = &p.type_;
+    // Parsed as Option and replaced with the default value afterwards, see generate_prefix_action
+    let type_ = match p.defaults_when_omitted() {
+        true => match modifier {
+            Modifier::Until(_) => {
+                let message = "#[optional] currently cannot be used with #[until]";
+                return Err(syn::Error::new(p.span, message));
+            }
+            _ => syn::parse_quote! { Option<#type_> },
+        },
+        false => type_.clone(),
+    };
     Ok(match modifier {
         Modifier::Flag => {
             if p.type_ != syn::parse_quote! { bool } {
@@ -33,6 +46,7 @@ fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStre
         }
         Modifier::Lazy => quote::quote! { #[lazy] (#type_) },
         Modifier::Rest => quote::quote! { #[rest] (#type_) },
+        Modifier::Until(keyword) => quote::quote! { #[until = #keyword] (#type_) },
         Modifier::None => quote::quote! { (#type_) },
     })
 }
@@ -46,6 +60,18 @@ pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStre
         .iter()
         .map(quote_parameter)
         .collect::<Result<Vec<_>, syn::Error>>()?;
+    let param_names = inv.parameters.iter().map(|p| &p.name);
+    let defaulted_params = inv
+        .parameters
+        .iter()
+        .zip(&param_idents)
+        .filter(|(p, _)| p.defaults_when_omitted())
+        .map(|(_, ident)| ident);
+    let argument_transforms = super::generate_argument_transforms(inv, &param_idents);
+    let action_call = super::generate_action_call(
+        inv,
+        quote::quote! { inner(ctx.into(), #( #param_idents, )* ) },
+    );
     let wildcard_arg = match inv.args.discard_spare_arguments {
         true => Some(quote::quote! { #[rest] (Option<String>), }),
         false => None,
@@ -53,7 +79,7 @@ pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStre
 
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
-            let ( #( #param_idents, )* .. ) = ::lumi::parse_prefix_args!(
+            let (( #( #param_idents, )* .. ), inputs) = ::lumi::parse_prefix_args_with_inputs!(
                 ctx.serenity_context(), ctx.msg, ctx.args, 0 =>
                 #( #param_specs, )*
                 #wildcard_arg
@@ -62,20 +88,17 @@ pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStre
                 input,
                 error,
             ))?;
+            ctx.parameter_inputs.get_or_init(|| {
+                [ #( #param_names ),* ].into_iter().zip(inputs).collect()
+            });
+            #( let #defaulted_params = #defaulted_params.unwrap_or_default(); )*
+            #argument_transforms
 
-            let is_framework_cooldown = !ctx.command.manual_cooldowns
-                .unwrap_or_else(|| ctx.framework.options.manual_cooldowns);
-
-            if is_framework_cooldown {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
+            if ::lumi::is_framework_cooldown(ctx.into(), ctx.command) {
+                ::lumi::start_cooldowns(ctx.into());
             }
 
-            inner(ctx.into(), #( #param_idents, )* )
-                .await
-                .map_err(|error| lumi::FrameworkError::new_command(
-                    ctx.into(),
-                    error,
-                ))
+            #action_call
         })
     })
 }
@@ -38,6 +38,13 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
         if param.args.flag {
             required = false;
         }
+        // Explicit #[required] or #[optional] overrides the inference from the type
+        if param.args.required {
+            required = true;
+        }
+        if param.args.optional {
+            required = false;
+        }
 
         let param_name = &param.name;
         let name_localizations =
@@ -52,6 +59,10 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
                     partial: &str,
                 | Box::pin(#autocomplete_fn(ctx.into(), partial))) }
             }
+            // Choice parameters with too many choices are autocompleted automatically
+            None if inv.args.slash_command && param.args.choices.is_none() => {
+                quote::quote! { <#type_ as ::lumi::SlashArgument>::autocomplete_callback() }
+            }
             None => quote::quote! { None },
         };
 
@@ -169,12 +180,20 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
                 let choice_indices = (0..choices.0.len()).map(syn::Index::from);
                 let choice_vals = &choices.0;
                 quote::quote! { INLINE_CHOICE #t [#(#choice_indices: #choice_vals),*] }
+            } else if p.defaults_when_omitted() {
+                quote::quote! { OPTIONAL #t }
             } else {
                 quote::quote! { #t }
             }
         })
         .collect::<Vec<_>>();
 
+    let argument_transforms = super::generate_argument_transforms(inv, &param_identifiers);
+    let action_call = super::generate_action_call(
+        inv,
+        quote::quote! { inner(ctx.into(), #( #param_identifiers, )*) },
+    );
+
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
             // idk why this can't be put in the macro itself (where the lint is triggered) and
@@ -185,20 +204,13 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
                 ctx.serenity_context(), ctx.interaction, ctx.args =>
                 #( (#param_names: #param_types), )*
             ).await.map_err(|error| error.to_framework_error(ctx))?;
+            #argument_transforms
 
-            let is_framework_cooldown = !ctx.command.manual_cooldowns
-                .unwrap_or_else(|| ctx.framework.options.manual_cooldowns);
-
-            if is_framework_cooldown {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
+            if ::lumi::is_framework_cooldown(ctx.into(), ctx.command) {
+                ::lumi::start_cooldowns(ctx.into());
             }
 
-            inner(ctx.into(), #( #param_identifiers, )*)
-                .await
-                .map_err(|error| lumi::FrameworkError::new_command(
-                    ctx.into(),
-                    error,
-                ))
+            #action_call
         })
     })
 }
@@ -212,26 +224,20 @@ pub fn generate_context_menu_action(
             return Err(syn::Error::new(
                 inv.function.sig.inputs.span(),
                 "Context menu commands require exactly one parameter",
-            ))
+            ));
         }
     };
 
+    let action_call = super::generate_action_call(inv, quote::quote! { inner(ctx.into(), value) });
+
     Ok(quote::quote! {
         <#param_type as ::lumi::ContextMenuParameter<_, _>>::to_action(|ctx, value| {
             Box::pin(async move {
-                let is_framework_cooldown = !ctx.command.manual_cooldowns
-                    .unwrap_or_else(|| ctx.framework.options.manual_cooldowns);
-
-                if is_framework_cooldown {
-                    ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
+                if ::lumi::is_framework_cooldown(ctx.into(), ctx.command) {
+                    ::lumi::start_cooldowns(ctx.into());
                 }
 
-                inner(ctx.into(), value)
-                    .await
-                    .map_err(|error| lumi::FrameworkError::new_command(
-                        ctx.into(),
-                        error,
-                    ))
+                #action_call
             })
         })
     })
@@ -25,17 +25,22 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `prefix_command`: Generate a prefix command
 - `slash_command`: Generate a slash command
 - `context_menu_command`: Generate a context menu command
+- `primary_entry_point`: Generate a primary entry point command (for Activities-enabled apps). Replaces `slash_command` and cannot have arguments
+- `entry_point_handler`: Who handles the primary entry point command, `entry_point_handler = "DiscordLaunchActivity"` to let Discord launch the Activity. Defaults to `AppHandler`. See `lumi::EntryPointHandler`
 
 ## Meta properties
 
 - `subcommands`: List of subcommands `subcommands("foo", "bar", "baz")`
 - `name_localized`: Adds localized name of the parameter `name_localized("locale", "new_name")` (slash-only)
 - `description_localized`: Adds localized description of the parameter `description_localized("locale", "Description")` (slash-only)
+- `context_menu_name_localized`: Adds localized context menu name `context_menu_name_localized("locale", "New name")` (context menu only)
+- `help_text_localized`: Adds localized help text `help_text_localized("locale", "Help text")`, used instead of the doc comment help text for users with that locale
 - `rename`: Choose an alternative command name instead of the function name
     - Useful if your command name is a Rust keyword, like `move`
 - `aliases`: Command name aliases (only applies to prefix commands)
+- `slash_aliases`: Additional names under which the slash command is registered `slash_aliases("old_name")` (slash-only, top-level commands only)
 - `category`: Category of this command which affects placement in the help command
-- `custom_data`: Arbitrary expression that will be boxed and stored in `Command::custom_data`
+- `custom_data`: Arbitrary expression that will be boxed and stored in `Command::custom_data`, readable with `Command::custom_data::<Type>()`
 - `identifying_name`: Optionally, a unique identifier for this command for your personal usage
 - `install_context`: Installation contexts where this command is available (slash-only)
 - `interaction_context`: Interaction contexts where this command is available (slash-only)
@@ -52,22 +57,29 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `guild_only`: Restricts command callers to only run on a guild
 - `dm_only`: Restricts command callers to only run on a DM
 - `nsfw_only`: Restricts command callers to only run on a NSFW channel
+- `feature`: Feature flag which must be enabled by `FrameworkOptions::feature_gate` for the command to run. Applies to subcommands too
 - `subcommand_required`: Requires a subcommand to be specified (prefix only)
+- `fallback`: Path to a prefix command which is run instead if none of this command's subcommands were recognized. Overrides `subcommand_required` (prefix only)
+    - Checks, cooldowns and other settings of this command apply, not those of the fallback command
 - `check`: Path to a function which is invoked for every invocation. If the function returns false, the command is not executed (can be used multiple times)
+    - The check is registered in `Command::checks` under its function name
 
 ## Help-related arguments
 
 - `hide_in_help`: Hide this command in help menus
+- `deprecated`: Marks the command as deprecated, see `Command::deprecated`. Optionally with a replacement and explanation `deprecated(replacement = "/info stats", message = "Stats moved to the info command")`
 
 ## Edit tracking (prefix only)
 
 - `track_edits`: Shorthand for `invoke_on_edit`, `track_deletion`, and `reuse_response` (prefix only)
 - `invoke_on_edit`: Reruns the command if an existing invocation message is edited (prefix only)
 - `track_deletion`: Deletes the bot response to a command if the command message is deleted (prefix only)
+- `allow_bot_invocations`: Accept invocations from bots and webhooks even if `ignore_bots` is set (prefix only)
 - `reuse_response`: After the first response, post subsequent responses as edits to the initial message (prefix only)
 
 ## Cooldown
 - `manual_cooldowns`: Allows overriding the framework's built-in cooldowns tracking without affecting other commands.
+- `inherit_cooldowns`: Whether the cooldowns of parent commands apply to this subcommand, and are started when it runs. Default `true` (`inherit_cooldowns = false` to disable)
 - `global_cooldown`: Minimum duration in seconds between invocations, globally
 - `user_cooldown`: Minimum duration in seconds between invocations, per user
 - `guild_cooldown`: Minimum duration in seconds between invocations, per guild
@@ -76,11 +88,19 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 
 ## Other
 
-- `on_error`: Error handling function
+- `on_error`: Error handling function, returning `Result<(), serenity::Error>`
+- `timeout`: Cancel the command with `FrameworkError::CommandTimeout` if it runs longer than this many seconds
+- `log_level`: How invocations are logged. Not at all by default, `log_level = "Info"` to log the invocation string, or `log_level = "Debug"` to log without message content. See `lumi::CommandLogLevel`
+- `spawn_policy`: How the command is executed, `spawn_policy = "Blocking"` for CPU-heavy or blocking commands (requires the `blocking_commands` feature). See `lumi::SpawnPolicy`
 - `broadcast_typing`: Trigger a typing indicator while command runs (prefix only)
+- `broadcast_typing_delay`: Only show the typing indicator if the command runs longer than this many milliseconds. Implies `broadcast_typing` (prefix only)
 - `discard_spare_arguments`: Don't throw an error if the user supplies too many arguments (prefix only)
 - `ephemeral`: Make bot responses ephemeral if possible (slash only)
     - Only lumi's functions, like `lumi::send_reply`, respect this preference
+- `response_mode`: Defer the response before the command runs, `response_mode = "DeferPublic"` or `response_mode = "DeferEphemeral"` (slash only)
+- `rerun_button`: Add a button to the initial response which reruns the command with the same arguments (slash only)
+- `error_reply_policy`: How `lumi::builtins::on_error` delivers error messages, `error_reply_policy = "Ephemeral"`, `"Public"` or `"Dm"`. `DeleteAfter` takes a duration and must be set on the returned `lumi::Command` instead. See `lumi::ErrorReplyPolicy`
+- `delete_button`: Add a 🗑 button to responses which lets the invoking user or moderators delete them. Overrides `FrameworkOptions::delete_button` (`delete_button = false` disables it for this command)
 
 # Function parameters
 
@@ -113,6 +133,13 @@ are multiple attributes you can use on parameters:
 - `#[lazy]`: Can be used on Option and Vec parameters and is equivalent to regular expressions' laziness (prefix-only)
 - `#[flag]`: Can be used on a bool parameter to set the bool to true if the user typed the parameter name literally (prefix-only)
     - For example with `async fn my_command(ctx: Context<'_>, #[flag] my_flag: bool)`, `~my_command` would set my_flag to false, and `~my_command my_flag` would set my_flag to true
+- `#[required]`: Registers the parameter as required, even if it's an `Option` or `Vec` (slash-only)
+    - Discord then always sends a value, but the parameter is still `None` in case it doesn't, e.g. with outdated command registrations
+- `#[optional]`: Registers the parameter as optional, even if it's neither an `Option` nor a `Vec`
+    - If the user omits the parameter, it's set to its `Default::default()` value, in both slash and prefix commands
+- `#[until = "keyword"]`: Use the rest of the message up to the keyword for this parameter, and continue parsing after the keyword (prefix-only)
+    - For example with `async fn remind(ctx: Context<'_>, #[until = "in"] task: String, duration: String)`, `~remind water plants in 2h` would set task to `water plants` and duration to `2h`
+    - The last occurrence of the keyword is tried first, so `~remind log in in 2h` sets task to `log in`
 
 # Help text
 
@@ -145,6 +172,21 @@ lumi::Command {
 }
 ```
 
+# Returning responses
+
+Instead of `Result<(), Error>`, the command function may return `Result<R, Error>` for any `R`
+implementing `lumi::IntoCreateReply`, like `String`, `serenity::CreateEmbed` or
+`lumi::CreateReply`. The returned value is then sent as response after the function completes.
+`Error` must implement `From<serenity::Error>` for this. The return type must be spelled out as
+`Result<R, Error>`; type aliases are treated like `Result<(), Error>`.
+
+```rust
+#[lumi::command(slash_command, prefix_command)]
+pub async fn ping(ctx: Context<'_>) -> Result<String, Error> {
+    Ok("Pong!".to_owned())
+}
+```
+
 # Internals
 
 Internally, this attribute macro generates a function with a single `lumi::Command`
@@ -224,6 +266,13 @@ Example invocations:
 - `~yourcommand ChoiceB`
 - `~yourcommand cHoIcEb` - names are case-insensitive
 
+# Many choices
+
+Discord allows at most 25 choices per slash command parameter. Enums with more variants are
+registered as string parameters instead, with an autocomplete callback suggesting the choices
+that match the user's input. The chosen name is parsed back into the enum like in prefix
+commands. An explicit `#[autocomplete]` callback on the parameter takes precedence.
+
 # Localization
 
 In slash commands, you can take advantage of Discord's localization.
@@ -49,13 +49,18 @@ where
     async fn pop_from(
         args: &'a str,
         attachment_index: usize,
-        ctx: &serenity::Context,
-        msg: &serenity::Message,
+        _: &serenity::Context,
+        _: &serenity::Message,
     ) -> PopArgumentResult<'a, Self> {
-        let (args, attach_idx, value) = String::pop_from(args, attachment_index, ctx, msg).await?;
+        let (args, value) = crate::prefix_argument::pop_string(args).map_err(|err| {
+            (
+                Box::new(err) as Box<dyn std::error::Error + Send + Sync>,
+                Some(args.to_owned()),
+            )
+        })?;
         match T::from_str(&value) {
-            Ok(value) => Ok((args, attach_idx, Self(value))),
-            Err(err) => Err((Box::new(err), Some(value))),
+            Ok(value) => Ok((args, attachment_index, Self(value))),
+            Err(err) => Err((Box::new(err), Some(value.into_owned()))),
         }
     }
 }
@@ -0,0 +1,480 @@
+//! Structured audit trail of command invocations, see [`AuditSink`]
+
+use crate::serenity_prelude as serenity;
+use std::time::{Duration, SystemTime};
+
+/// Receives an [`InvocationRecord`] for every completed command invocation, including
+/// invocations that were denied by a permission check.
+///
+/// Store in [`crate::FrameworkOptions::audit_sink`]. [`JsonLinesAuditSink`] writes the records
+/// to a file; implement this trait yourself to write them to a database or log service instead.
+///
+/// Records are sent before the error (if any) is passed to the `on_error` handler. Errors that
+/// occur before a command was resolved, like [`crate::FrameworkError::UnknownCommand`], aren't
+/// recorded.
+#[async_trait::async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Stores a single record. Failures should be handled (e.g. logged) by the sink itself.
+    async fn record(&self, record: InvocationRecord);
+
+    /// Receives the warnings about incoherent command configuration found on startup, see
+    /// [`check_command_contexts`]. Does nothing by default.
+    async fn record_warning(&self, warning: ContextWarning) {
+        let _ = warning;
+    }
+}
+
+/// How an invocation recorded in an [`InvocationRecord`] ended
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InvocationOutcome {
+    /// The command ran and returned successfully
+    Success,
+    /// The user or channel wasn't allowed to run the command, e.g. because of missing
+    /// permissions or a failed check
+    Denied(crate::ErrorCode),
+    /// The command was run or attempted, but failed with the given error
+    Failed(crate::ErrorCode),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl InvocationOutcome {
+    /// Classifies the result of an invocation
+    pub(crate) fn from_result<T, E>(result: &Result<(), crate::FrameworkError<'_, T, E>>) -> Self {
+        let code = match result {
+            Ok(()) => return Self::Success,
+            Err(error) => error.code(),
+        };
+        match code {
+            crate::ErrorCode::MissingBotPermissions
+            | crate::ErrorCode::MissingUserPermissions
+            | crate::ErrorCode::PermissionFetchFailed
+            | crate::ErrorCode::NotAnOwner
+            | crate::ErrorCode::GuildOnly
+            | crate::ErrorCode::DmOnly
+            | crate::ErrorCode::NsfwOnly
+            | crate::ErrorCode::CommandDisabled
+            | crate::ErrorCode::WrongChannel
+            | crate::ErrorCode::FeatureDisabled
+            | crate::ErrorCode::CommandCheckFailed => Self::Denied(code),
+            _ => Self::Failed(code),
+        }
+    }
+
+    /// Returns `success`, `denied` or `failed`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Denied(_) => "denied",
+            Self::Failed(_) => "failed",
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+/// A single entry in the audit trail, passed to [`AuditSink::record`]
+#[derive(Clone, Debug)]
+pub struct InvocationRecord {
+    /// [`crate::Command::qualified_name`] of the invoked command
+    pub command: String,
+    /// See [`crate::Context::redacted_invocation_string`]
+    pub invocation: String,
+    /// The user who invoked the command
+    pub user_id: serenity::UserId,
+    /// The guild the command was invoked in, if any
+    pub guild_id: Option<serenity::GuildId>,
+    /// The channel the command was invoked in
+    pub channel_id: serenity::GenericChannelId,
+    /// Whether this was a slash or context menu command, as opposed to a prefix command
+    pub application_command: bool,
+    /// When the invocation completed
+    pub timestamp: SystemTime,
+    /// Time from the start of dispatching until the invocation completed
+    pub duration: Duration,
+    /// How the invocation ended
+    pub outcome: InvocationOutcome,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl InvocationRecord {
+    /// Creates a record of the invocation behind `ctx` which ended with `result`
+    pub fn new<T: Send + Sync + 'static, E>(
+        ctx: crate::Context<'_, T, E>,
+        result: &Result<(), crate::FrameworkError<'_, T, E>>,
+    ) -> Self {
+        Self {
+            command: ctx.command().qualified_name.to_string(),
+            invocation: ctx.redacted_invocation_string(),
+            user_id: ctx.author().id,
+            guild_id: ctx.guild_id(),
+            channel_id: ctx.channel_id(),
+            application_command: matches!(ctx, crate::Context::Application(_)),
+            timestamp: SystemTime::now(),
+            duration: ctx.dispatch_started().elapsed(),
+            outcome: InvocationOutcome::from_result(result),
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Serializes this record into a single line of JSON, as written by [`JsonLinesAuditSink`]
+    pub fn to_json(&self) -> serde_json::Value {
+        let timestamp = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let error_code = match self.outcome {
+            InvocationOutcome::Denied(code) | InvocationOutcome::Failed(code) => {
+                Some(code.as_str())
+            }
+            _ => None,
+        };
+        serde_json::json!({
+            "timestamp_ms": timestamp.as_millis() as u64,
+            "command": self.command,
+            "invocation": self.invocation,
+            "user_id": self.user_id.get().to_string(),
+            "guild_id": self.guild_id.map(|id| id.get().to_string()),
+            "channel_id": self.channel_id.get().to_string(),
+            "application_command": self.application_command,
+            "duration_ms": self.duration.as_millis() as u64,
+            "outcome": self.outcome.as_str(),
+            "error_code": error_code,
+        })
+    }
+}
+
+/// An [`AuditSink`] which appends every record as one line of JSON to a file
+///
+/// The file is written on a dedicated thread, so recording never blocks the async runtime.
+/// Records which arrive while the thread is busy are written together. Dropping the sink waits
+/// until all records are written.
+///
+/// ```rust,no_run
+/// # fn main() -> std::io::Result<()> {
+/// let sink = lumi::JsonLinesAuditSink::open("audit.jsonl")?;
+/// lumi::FrameworkOptions::<(), ()> {
+///     audit_sink: Some(std::sync::Arc::new(sink)),
+///     ..Default::default()
+/// };
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct JsonLinesAuditSink {
+    /// Sends lines to the writer thread. Only `None` while dropping
+    lines: Option<std::sync::mpsc::Sender<String>>,
+    /// Appends the lines to the file. Only `None` while dropping
+    writer: Option<std::thread::JoinHandle<()>>,
+}
+
+impl JsonLinesAuditSink {
+    /// Opens `path` for appending, creating it if it doesn't exist, and starts the writer thread
+    pub fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("lumi-audit".into())
+            .spawn(move || write_lines(file, receiver))?;
+        Ok(Self {
+            lines: Some(sender),
+            writer: Some(writer),
+        })
+    }
+}
+
+impl Drop for JsonLinesAuditSink {
+    fn drop(&mut self) {
+        // Closes the channel, so the writer thread exits after writing the remaining lines
+        drop(self.lines.take());
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                tracing::warn!("Audit writer thread panicked");
+            }
+        }
+    }
+}
+
+/// Body of the writer thread of [`JsonLinesAuditSink`]: appends lines to the file until the sink
+/// is dropped
+fn write_lines(file: std::fs::File, lines: std::sync::mpsc::Receiver<String>) {
+    use std::io::Write as _;
+
+    let mut file = std::io::BufWriter::new(file);
+    while let Ok(line) = lines.recv() {
+        let mut result = file.write_all(line.as_bytes());
+        for line in lines.try_iter() {
+            result = result.and_then(|()| file.write_all(line.as_bytes()));
+        }
+        if let Err(e) = result.and_then(|()| file.flush()) {
+            tracing::warn!("Failed to write audit records: {}", e);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditSink for JsonLinesAuditSink {
+    async fn record(&self, record: InvocationRecord) {
+        let mut line = record.to_json().to_string();
+        line.push('\n');
+        let sent = self
+            .lines
+            .as_ref()
+            .is_some_and(|lines| lines.send(line).is_ok());
+        if !sent {
+            tracing::warn!("Failed to write audit record: the writer thread has stopped");
+        }
+    }
+}
+
+/// What's wrong with the contexts of a command in a [`ContextWarning`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ContextWarningKind {
+    /// The command is [`crate::Command::guild_only`] and [`crate::Command::dm_only`], so it can't
+    /// run anywhere
+    GuildOnlyAndDmOnly,
+    /// The command is [`crate::Command::guild_only`], but its
+    /// [`crate::Command::interaction_context`] doesn't include guilds, so it can't run anywhere
+    GuildOnlyOutsideGuilds,
+    /// The command is [`crate::Command::dm_only`], but its
+    /// [`crate::Command::interaction_context`] doesn't include bot DMs, so it can't run anywhere
+    DmOnlyOutsideDms,
+    /// The command is [`crate::Command::guild_only`], but can only be installed to users. It may
+    /// thus be used in guilds the bot isn't in, where the guild isn't cached and most guild
+    /// requests fail
+    GuildOnlyUserInstall,
+    /// The command's [`crate::Command::interaction_context`] includes private channels, which only
+    /// user-installed commands can be used in, but its [`crate::Command::install_context`]
+    /// doesn't include user installs
+    PrivateChannelWithoutUserInstall,
+    /// The command's [`crate::Command::install_context`] or
+    /// [`crate::Command::interaction_context`] is empty, which Discord rejects
+    EmptyContexts,
+    /// A subcommand sets [`crate::Command::install_context`] or
+    /// [`crate::Command::interaction_context`], which Discord only reads from top-level commands
+    SubcommandContexts,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// An incoherent combination of contexts of a command, found by [`check_command_contexts`]
+#[derive(Clone, Debug)]
+pub struct ContextWarning {
+    /// [`crate::Command::qualified_name`] of the affected command
+    pub command: String,
+    /// What's wrong with the command's contexts
+    pub kind: ContextWarningKind,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl std::fmt::Display for ContextWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let problem = match self.kind {
+            ContextWarningKind::GuildOnlyAndDmOnly => "is both guild_only and dm_only",
+            ContextWarningKind::GuildOnlyOutsideGuilds => {
+                "is guild_only, but its interaction_context doesn't include guilds"
+            }
+            ContextWarningKind::DmOnlyOutsideDms => {
+                "is dm_only, but its interaction_context doesn't include bot DMs"
+            }
+            ContextWarningKind::GuildOnlyUserInstall => {
+                "is guild_only, but can only be installed to users, so it may run in guilds \
+                the bot isn't in"
+            }
+            ContextWarningKind::PrivateChannelWithoutUserInstall => {
+                "can be used in private channels, but can't be installed to users"
+            }
+            ContextWarningKind::EmptyContexts => {
+                "has an empty install_context or interaction_context"
+            }
+            ContextWarningKind::SubcommandContexts => {
+                "is a subcommand, but sets install_context or interaction_context, which are \
+                ignored for subcommands"
+            }
+            ContextWarningKind::__NonExhaustive => "has incoherent contexts",
+        };
+        write!(f, "Command `{}` {}", self.command, problem)
+    }
+}
+
+/// Checks the install and interaction contexts of the application commands among `commands`
+/// and their subcommands for combinations that can't work as intended
+///
+/// Discord rejects some of them on registration with unhelpful error messages, others make the
+/// command silently unusable. [`crate::Framework`] runs this on startup, logs the warnings and
+/// passes them to [`AuditSink::record_warning`] of [`crate::FrameworkOptions::audit_sink`].
+pub fn check_command_contexts<T, E>(
+    commands: &[impl std::borrow::Borrow<crate::Command<T, E>>],
+) -> Vec<ContextWarning> {
+    use serenity::{InstallationContext, InteractionContext};
+
+    let mut warnings = Vec::new();
+    for command in commands.iter().map(std::borrow::Borrow::borrow) {
+        let mut warn = |command: &crate::Command<T, E>, kind| {
+            warnings.push(ContextWarning {
+                command: command.qualified_name.to_string(),
+                kind,
+                __non_exhaustive: (),
+            })
+        };
+
+        for (_, subcommand) in crate::walk_commands(&command.subcommands) {
+            if subcommand.install_context.is_some() || subcommand.interaction_context.is_some() {
+                warn(subcommand, ContextWarningKind::SubcommandContexts);
+            }
+        }
+        if command.slash_action.is_none()
+            && command.context_menu_action.is_none()
+            && command.subcommands.is_empty()
+        {
+            continue;
+        }
+
+        let install_context = command.install_context.as_deref();
+        let interaction_context = command.interaction_context.as_deref();
+        let includes = |contexts: Option<&[InteractionContext]>, context| {
+            contexts.is_none_or(|contexts| contexts.contains(&context))
+        };
+
+        if install_context.is_some_and(|c| c.is_empty())
+            || interaction_context.is_some_and(|c| c.is_empty())
+        {
+            warn(command, ContextWarningKind::EmptyContexts);
+            continue;
+        }
+        if command.guild_only && command.dm_only {
+            warn(command, ContextWarningKind::GuildOnlyAndDmOnly);
+        } else if command.guild_only && !includes(interaction_context, InteractionContext::Guild) {
+            warn(command, ContextWarningKind::GuildOnlyOutsideGuilds);
+        } else if command.dm_only && !includes(interaction_context, InteractionContext::BotDm) {
+            warn(command, ContextWarningKind::DmOnlyOutsideDms);
+        }
+
+        let user_install_only = install_context.is_some_and(|contexts| {
+            !contexts.contains(&InstallationContext::Guild)
+                && contexts.contains(&InstallationContext::User)
+        });
+        if command.guild_only && user_install_only {
+            warn(command, ContextWarningKind::GuildOnlyUserInstall);
+        }
+        let no_user_install =
+            install_context.is_some_and(|contexts| !contexts.contains(&InstallationContext::User));
+        if no_user_install
+            && interaction_context
+                .is_some_and(|contexts| contexts.contains(&InteractionContext::PrivateChannel))
+        {
+            warn(
+                command,
+                ContextWarningKind::PrivateChannelWithoutUserInstall,
+            );
+        }
+    }
+    warnings
+}
+
+/// Sends the record of a completed invocation to [`crate::FrameworkOptions::audit_sink`], if set
+pub(crate) async fn record_invocation<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    result: &Result<(), crate::FrameworkError<'_, T, E>>,
+) {
+    #[cfg(feature = "test-utils")]
+    crate::replay::record_invocation(|| InvocationRecord::new(ctx, result));
+    if let Some(audit_sink) = &ctx.framework().options().audit_sink {
+        audit_sink.record(InvocationRecord::new(ctx, result)).await;
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_check_command_contexts() {
+    use serenity::{InstallationContext, InteractionContext};
+
+    let command = |name: &'static str| crate::Command::<(), ()> {
+        name: name.into(),
+        qualified_name: name.into(),
+        subcommands: vec![crate::Command::default()],
+        ..Default::default()
+    };
+    let kinds = |commands: &[crate::Command<(), ()>]| {
+        check_command_contexts(commands)
+            .into_iter()
+            .map(|warning| warning.kind)
+            .collect::<Vec<_>>()
+    };
+
+    let mut coherent = command("coherent");
+    coherent.guild_only = true;
+    coherent.install_context = Some(vec![InstallationContext::Guild, InstallationContext::User]);
+    assert_eq!(kinds(&[coherent]), []);
+
+    let mut user_install = command("user_install");
+    user_install.guild_only = true;
+    user_install.install_context = Some(vec![InstallationContext::User]);
+    let mut dm_only = command("dm_only");
+    dm_only.dm_only = true;
+    dm_only.interaction_context = Some(vec![InteractionContext::Guild]);
+    let mut private_channel = command("private_channel");
+    private_channel.install_context = Some(vec![InstallationContext::Guild]);
+    private_channel.interaction_context = Some(vec![InteractionContext::PrivateChannel]);
+    assert_eq!(
+        kinds(&[user_install, dm_only, private_channel]),
+        [
+            ContextWarningKind::GuildOnlyUserInstall,
+            ContextWarningKind::DmOnlyOutsideDms,
+            ContextWarningKind::PrivateChannelWithoutUserInstall,
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_json_lines_audit_sink() {
+    let path = std::env::temp_dir().join(format!("lumi-audit-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let record = |command: &str, outcome| InvocationRecord {
+        command: command.to_owned(),
+        invocation: format!("~{}", command),
+        user_id: serenity::UserId::new(1),
+        guild_id: None,
+        channel_id: serenity::GenericChannelId::new(2),
+        application_command: false,
+        timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(3),
+        duration: Duration::from_millis(4),
+        outcome,
+        __non_exhaustive: (),
+    };
+
+    let sink = JsonLinesAuditSink::open(&path).unwrap();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        sink.record(record("ping", InvocationOutcome::Success))
+            .await;
+        let denied = InvocationOutcome::Denied(crate::ErrorCode::NotAnOwner);
+        sink.record(record("shutdown", denied)).await;
+    });
+    // Waits for the writer thread
+    drop(sink);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let lines = contents
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["command"], "ping");
+    assert_eq!(lines[0]["timestamp_ms"], 3000);
+    assert_eq!(lines[0]["error_code"], serde_json::Value::Null);
+    assert_eq!(lines[1]["outcome"], "denied");
+    assert_eq!(
+        lines[1]["error_code"],
+        crate::ErrorCode::NotAnOwner.as_str()
+    );
+}
@@ -0,0 +1,203 @@
+//! Sending a message to many channels or users without hitting Discord's limits, see
+//! [`Broadcast`]
+
+use crate::{BoxFuture, serenity_prelude as serenity};
+
+/// A channel or user that a [`Broadcast`] sends its message to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BroadcastTarget {
+    /// Sends the message in the given channel
+    Channel(serenity::GenericChannelId),
+    /// Sends the message to the given user via DM
+    User(serenity::UserId),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl From<serenity::GenericChannelId> for BroadcastTarget {
+    fn from(channel_id: serenity::GenericChannelId) -> Self {
+        Self::Channel(channel_id)
+    }
+}
+
+impl From<serenity::UserId> for BroadcastTarget {
+    fn from(user_id: serenity::UserId) -> Self {
+        Self::User(user_id)
+    }
+}
+
+/// How far a [`Broadcast`] has progressed, passed to [`Broadcast::on_progress`]
+#[derive(Clone, Copy, Debug)]
+pub struct BroadcastProgress {
+    /// Number of targets that the broadcast finished with, successfully or not
+    pub completed: usize,
+    /// Number of targets that received the message
+    pub delivered: usize,
+    /// Number of targets that the message couldn't be sent to
+    pub failed: usize,
+    /// Total number of targets
+    pub total: usize,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// The outcome of a [`Broadcast`], returned from [`Broadcast::send`]
+#[derive(Debug, Default)]
+pub struct BroadcastReport {
+    /// Targets that received the message
+    pub delivered: Vec<BroadcastTarget>,
+    /// Targets that the message couldn't be sent to, e.g. because a user doesn't accept DMs
+    pub failed: Vec<(BroadcastTarget, serenity::Error)>,
+    /// Targets that weren't attempted because [`Broadcast::max_failures`] was exceeded
+    pub skipped: Vec<BroadcastTarget>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl std::fmt::Display for BroadcastReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Delivered: {}, failed: {}",
+            self.delivered.len(),
+            self.failed.len()
+        )?;
+        if !self.skipped.is_empty() {
+            write!(f, ", skipped: {}", self.skipped.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Sends the same message to a list of channels or users, one after another
+///
+/// Sending an announcement to many channels or DMing many users at once quickly runs into rate
+/// limits, and Discord may flag bots that send DMs in bursts or keep failing to deliver them.
+/// A broadcast waits [`Self::interval`] between messages and stops after [`Self::max_failures`]
+/// failed deliveries, collecting the outcome in a [`BroadcastReport`].
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # async fn _f(
+/// #     ctx: lumi::Context<'_, (), Error>,
+/// #     subscribers: Vec<lumi::serenity_prelude::UserId>,
+/// # ) -> Result<(), Error> {
+/// let report = lumi::Broadcast::new(subscribers)
+///     .interval(std::time::Duration::from_secs(2))
+///     .max_failures(10)
+///     .on_progress(|progress| {
+///         Box::pin(async move {
+///             println!("Sent {} of {}", progress.completed, progress.total);
+///         })
+///     })
+///     .send(ctx.http(), lumi::CreateReply::default().content("A new version is out!"))
+///     .await;
+/// ctx.say(format!("Announcement sent. {}", report)).await?;
+/// # Ok(()) }
+/// ```
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Broadcast<'a> {
+    /// Channels and users to send the message to, in order
+    targets: Vec<BroadcastTarget>,
+    /// Time between two messages
+    interval: std::time::Duration,
+    /// Number of failed deliveries after which the remaining targets are skipped
+    max_failures: Option<usize>,
+    /// Called after every target
+    #[derivative(Debug = "ignore")]
+    on_progress: Option<Box<dyn FnMut(BroadcastProgress) -> BoxFuture<'a, ()> + Send + 'a>>,
+}
+
+impl<'a> Broadcast<'a> {
+    /// Creates a broadcast to the given channels and users
+    pub fn new(targets: impl IntoIterator<Item = impl Into<BroadcastTarget>>) -> Self {
+        Self {
+            targets: targets.into_iter().map(Into::into).collect(),
+            interval: std::time::Duration::from_secs(1),
+            max_failures: None,
+            on_progress: None,
+        }
+    }
+
+    /// Sets the time to wait between two messages
+    ///
+    /// One second by default. Panics if `interval` is zero.
+    #[must_use]
+    pub fn interval(mut self, interval: std::time::Duration) -> Self {
+        assert!(!interval.is_zero(), "broadcast interval must be non-zero");
+        self.interval = interval;
+        self
+    }
+
+    /// Stops the broadcast once the message couldn't be sent to this many targets
+    ///
+    /// The remaining targets are listed in [`BroadcastReport::skipped`]. Unlimited by default.
+    #[must_use]
+    pub fn max_failures(mut self, max_failures: usize) -> Self {
+        self.max_failures = Some(max_failures);
+        self
+    }
+
+    /// Sets a callback which is awaited after every target, e.g. to show the progress to the user
+    #[must_use]
+    pub fn on_progress(
+        mut self,
+        on_progress: impl FnMut(BroadcastProgress) -> BoxFuture<'a, ()> + Send + 'a,
+    ) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    /// Sends the message to all targets and returns once the broadcast is done
+    pub async fn send(
+        mut self,
+        http: &serenity::Http,
+        reply: crate::CreateReply<'_>,
+    ) -> BroadcastReport {
+        let total = self.targets.len();
+        let mut report = BroadcastReport::default();
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let mut targets = self.targets.into_iter();
+        while let Some(target) = targets.next() {
+            if self
+                .max_failures
+                .is_some_and(|max_failures| report.failed.len() >= max_failures)
+            {
+                report.skipped.push(target);
+                report.skipped.extend(targets);
+                break;
+            }
+
+            interval.tick().await;
+            let message = reply.clone().to_message();
+            let result = match target {
+                BroadcastTarget::Channel(channel_id) => {
+                    channel_id.send_message(http, message).await
+                }
+                BroadcastTarget::User(user_id) => user_id.direct_message(http, message).await,
+                BroadcastTarget::__NonExhaustive => unreachable!(),
+            };
+            match result {
+                Ok(_) => report.delivered.push(target),
+                Err(e) => report.failed.push((target, e)),
+            }
+
+            if let Some(on_progress) = &mut self.on_progress {
+                on_progress(BroadcastProgress {
+                    completed: report.delivered.len() + report.failed.len(),
+                    delivered: report.delivered.len(),
+                    failed: report.failed.len(),
+                    total,
+                    __non_exhaustive: (),
+                })
+                .await;
+            }
+        }
+        report
+    }
+}
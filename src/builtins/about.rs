@@ -0,0 +1,171 @@
+//! Showing diagnostic information about the bot, see [`about`]
+
+use std::borrow::Cow;
+
+use crate::serenity_prelude as serenity;
+
+/// Information about the bot shown by [`about`], which the framework can't determine itself
+#[derive(Clone, Debug)]
+pub struct BotMetadata {
+    /// Name of the bot
+    pub name: Cow<'static, str>,
+    /// Version of the bot
+    pub version: Cow<'static, str>,
+    /// Link to the bot's website or source code
+    pub url: Option<Cow<'static, str>>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl BotMetadata {
+    /// Creates the metadata from the bot's name and version, usually `env!("CARGO_PKG_NAME")` and
+    /// `env!("CARGO_PKG_VERSION")`
+    pub fn new(name: impl Into<Cow<'static, str>>, version: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            url: None,
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Sets a link to the bot's website or source code
+    #[must_use]
+    pub fn url(mut self, url: impl Into<Cow<'static, str>>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// Formats a duration as days, hours, minutes and seconds, omitting leading zero units
+fn format_uptime(uptime: std::time::Duration) -> String {
+    let seconds = uptime.as_secs();
+    let units = [
+        (seconds / 86400, "d"),
+        (seconds / 3600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let units = units
+        .iter()
+        .skip_while(|&&(value, unit)| value == 0 && unit != "s")
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect::<Vec<_>>();
+    units.join(" ")
+}
+
+/// Formats the shards of this process with their latencies. Beyond a few shards, only a latency
+/// summary is shown to stay within Discord's message length limit
+fn format_shards(shards: &[(u32, Option<std::time::Duration>)], current_shard: u32) -> String {
+    const MAX_LISTED: usize = 10;
+
+    let mut text = format!(
+        "\nShards: {} (this is shard {})",
+        shards.len(),
+        current_shard
+    );
+    if shards.len() <= MAX_LISTED {
+        for (shard_id, latency) in shards {
+            let latency = match latency {
+                Some(latency) => format!("{} ms", latency.as_millis()),
+                None => "unknown".to_owned(),
+            };
+            text += &format!("\n- Shard {}: {}", shard_id, latency);
+        }
+        return text;
+    }
+
+    let latencies = shards
+        .iter()
+        .filter_map(|(_, latency)| latency.map(|latency| latency.as_millis()))
+        .collect::<Vec<_>>();
+    if let (Some(min), Some(max)) = (latencies.iter().min(), latencies.iter().max()) {
+        let average = latencies.iter().sum::<u128>() / latencies.len() as u128;
+        text += &format!(
+            "\nLatency: {} ms average, {} ms min, {} ms max",
+            average, min, max
+        );
+    }
+    let unknown = shards.len() - latencies.len();
+    if unknown > 0 {
+        text += &format!("\nLatency unknown for {} shards", unknown);
+    }
+    text
+}
+
+/// Responds with diagnostic information about the bot: its name and version, the lumi version,
+/// the uptime, the number of cached guilds, and the shards of this process with their gateway
+/// latencies (summarized if there are many).
+///
+/// A ready to use diagnostics command:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Shows information about this bot
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn about(ctx: Context<'_>) -> Result<(), Error> {
+///     let metadata =
+///         lumi::builtins::BotMetadata::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+///     lumi::builtins::about(ctx, &metadata).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn about<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    metadata: &BotMetadata,
+) -> Result<(), serenity::Error> {
+    let mut response = format!("**{}** v{}", metadata.name, metadata.version);
+    if let Some(url) = &metadata.url {
+        response += &format!("\n<{}>", url);
+    }
+    response += &format!("\nFramework: lumi v{}", env!("CARGO_PKG_VERSION"));
+    if let Some(uptime) = ctx.framework().uptime() {
+        response += &format!("\nUptime: {}", format_uptime(uptime));
+    }
+    response += &format!("\nGuilds: {}", ctx.cache().guild_count());
+
+    let mut shards = ctx
+        .serenity_context()
+        .runners
+        .iter()
+        .map(|runner| (u32::from(runner.key().0), runner.value().0.latency))
+        .collect::<Vec<_>>();
+    shards.sort_by_key(|&(shard_id, _)| shard_id);
+    let current_shard = u32::from(ctx.serenity_context().shard_id.0);
+    response += &format_shards(&shards, current_shard);
+
+    ctx.say(response).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_format_uptime() {
+    use std::time::Duration;
+
+    assert_eq!(format_uptime(Duration::from_secs(0)), "0s");
+    assert_eq!(format_uptime(Duration::from_secs(61)), "1m 1s");
+    assert_eq!(format_uptime(Duration::from_secs(90061)), "1d 1h 1m 1s");
+    assert_eq!(format_uptime(Duration::from_secs(7200)), "2h 0m 0s");
+}
+
+#[cfg(test)]
+#[test]
+fn test_format_shards() {
+    use std::time::Duration;
+
+    assert_eq!(
+        format_shards(&[(0, Some(Duration::from_millis(40))), (1, None)], 0),
+        "\nShards: 2 (this is shard 0)\n- Shard 0: 40 ms\n- Shard 1: unknown"
+    );
+
+    let shards = (0..100)
+        .map(|i| (i, (i > 0).then(|| Duration::from_millis(u64::from(i)))))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        format_shards(&shards, 3),
+        "\nShards: 100 (this is shard 3)\nLatency: 50 ms average, 1 ms min, 99 ms max\n\
+        Latency unknown for 1 shards"
+    );
+}
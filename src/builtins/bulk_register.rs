@@ -0,0 +1,188 @@
+//! Registering application commands in many guilds at once
+
+use crate::serenity_prelude as serenity;
+
+/// Configuration for [`register_in_guilds`]
+#[derive(Clone, Debug)]
+pub struct BulkRegistrationOptions {
+    /// How many guilds are registered concurrently
+    ///
+    /// 5 by default.
+    pub batch_size: usize,
+    /// Pause between two batches, to stay clear of Discord's rate limits
+    ///
+    /// 1 second by default.
+    pub batch_delay: std::time::Duration,
+    /// How often a failed guild registration is retried before it's reported as a failure
+    ///
+    /// Only rate limits (429) and server errors (5xx) are retried, other errors won't go away by
+    /// trying again. 3 by default.
+    pub max_retries: u32,
+    /// Delay before the first retry. Doubled with every subsequent retry.
+    ///
+    /// 2 seconds by default.
+    pub initial_backoff: std::time::Duration,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for BulkRegistrationOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 5,
+            batch_delay: std::time::Duration::from_secs(1),
+            max_retries: 3,
+            initial_backoff: std::time::Duration::from_secs(2),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Passed to the progress callback of [`register_in_guilds`] whenever a guild finished
+#[derive(Debug)]
+pub struct BulkRegistrationProgress<'a> {
+    /// The guild whose registration just finished
+    pub guild_id: serenity::GuildId,
+    /// The outcome of this guild's registration, after all retries
+    pub outcome: Result<&'a super::RegistrationReport, &'a serenity::Error>,
+    /// Number of guilds that finished so far, including this one
+    pub completed: usize,
+    /// Total number of guilds
+    pub total: usize,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Aggregated result of [`register_in_guilds`]
+#[derive(Debug, Default)]
+pub struct BulkRegistrationResult {
+    /// Registration reports of all guilds that were registered successfully
+    pub succeeded: Vec<(serenity::GuildId, super::RegistrationReport)>,
+    /// Errors of all guilds whose registration failed, after all retries
+    pub failed: Vec<(serenity::GuildId, serenity::Error)>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Whether a failed registration may succeed if it's tried again: it was rate limited or Discord
+/// had a server error
+fn is_retryable(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) => {
+            matches!(response.status_code.as_u16(), 429 | 500..=599)
+        }
+        _ => false,
+    }
+}
+
+/// Registers the given application commands in a single guild, retrying rate limits and server
+/// errors with exponential backoff
+async fn register_with_retries(
+    http: &serenity::Http,
+    create_commands: &[serenity::CreateCommand<'static>],
+    guild_id: serenity::GuildId,
+    options: &BulkRegistrationOptions,
+) -> Result<super::RegistrationReport, serenity::Error> {
+    let mut backoff = options.initial_backoff;
+    let mut retries = 0;
+    loop {
+        let result = async {
+            let previous = guild_id.get_commands(http).await?;
+            guild_id.set_commands(http, create_commands).await?;
+            Ok(super::RegistrationReport::compute(
+                &previous,
+                create_commands,
+            ))
+        }
+        .await;
+
+        match result {
+            Err(e) if retries < options.max_retries && is_retryable(&e) => {
+                tracing::warn!(
+                    "Failed to register commands in guild {}, retrying in {:?}: {}",
+                    guild_id,
+                    backoff,
+                    e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Registers the given application commands in many guilds, in batches and with retries.
+///
+/// Unlike calling [`super::register_in_guild`] in a loop, this doesn't bail on the first error.
+/// Instead, guilds which failed because of rate limits or server errors are retried with
+/// exponential backoff, and failed guilds are finally collected in
+/// [`BulkRegistrationResult::failed`].
+///
+/// `on_progress` is called every time a guild finished, e.g. to log progress or to forward it to
+/// a status message via a channel:
+///
+/// ```rust,no_run
+/// # async fn foo(http: &lumi::serenity_prelude::Http, commands: &[lumi::Command<(), ()>], guild_ids: Vec<lumi::serenity_prelude::GuildId>) {
+/// let result = lumi::builtins::register_in_guilds(
+///     http,
+///     commands,
+///     guild_ids,
+///     Default::default(),
+///     |progress| println!("{}/{} guilds done", progress.completed, progress.total),
+/// )
+/// .await;
+/// println!("{} guilds failed", result.failed.len());
+/// # }
+/// ```
+pub async fn register_in_guilds<T, E>(
+    http: &serenity::Http,
+    commands: &[impl std::borrow::Borrow<crate::Command<T, E>>],
+    guild_ids: impl IntoIterator<Item = serenity::GuildId>,
+    options: BulkRegistrationOptions,
+    mut on_progress: impl FnMut(BulkRegistrationProgress<'_>),
+) -> BulkRegistrationResult {
+    let create_commands = super::create_application_commands(commands);
+    let guild_ids = guild_ids.into_iter().collect::<Vec<_>>();
+    let total = guild_ids.len();
+
+    let mut result = BulkRegistrationResult::default();
+    for (i, batch) in guild_ids.chunks(options.batch_size.max(1)).enumerate() {
+        if i != 0 {
+            tokio::time::sleep(options.batch_delay).await;
+        }
+
+        let (create_commands, options) = (&create_commands, &options);
+        let outcomes = futures_util::future::join_all(batch.iter().map(|&guild_id| async move {
+            let outcome = register_with_retries(http, create_commands, guild_id, options).await;
+            (guild_id, outcome)
+        }))
+        .await;
+
+        for (guild_id, outcome) in outcomes {
+            let completed = result.succeeded.len() + result.failed.len() + 1;
+            let outcome = match outcome {
+                Ok(report) => {
+                    result.succeeded.push((guild_id, report));
+                    Ok(&result.succeeded.last().expect("just pushed").1)
+                }
+                Err(error) => {
+                    result.failed.push((guild_id, error));
+                    Err(&result.failed.last().expect("just pushed").1)
+                }
+            };
+            on_progress(BulkRegistrationProgress {
+                guild_id,
+                outcome,
+                completed,
+                total,
+                __non_exhaustive: (),
+            });
+        }
+    }
+    result
+}
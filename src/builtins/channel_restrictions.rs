@@ -0,0 +1,73 @@
+//! Letting server admins restrict commands to certain channels in their guild
+
+use super::{guild_setting_save_failure, guild_setting_target, send_guild_setting_response};
+use crate::serenity_prelude as serenity;
+
+/// Restricts a command or category to the given channels in the guild the command is invoked in,
+/// using [`crate::FrameworkOptions::channel_restrictions`].
+///
+/// `target` is the qualified name of a command, e.g. `config set`, or else the name of a command
+/// category. Passing no channels lifts the restriction. Like the other
+/// [guild settings](crate::builtins#guild-settings), this is meant for admins only.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// # use lumi::serenity_prelude as serenity;
+/// /// Restricts a command or category to the given channels, or lifts the restriction
+/// #[lumi::command(prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+/// pub async fn channels(
+///     ctx: Context<'_>,
+///     target: String,
+///     channels: Vec<serenity::GenericChannelId>,
+/// ) -> Result<(), Error> {
+///     lumi::builtins::set_command_channels(ctx, &target, channels).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn set_command_channels<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    target: &str,
+    channels: Vec<serenity::GenericChannelId>,
+) -> Result<(), serenity::Error> {
+    let response = restrict_command(ctx, target.trim(), channels).await;
+    send_guild_setting_response(ctx, response).await
+}
+
+/// Does the work of [`set_command_channels`] and returns the response
+async fn restrict_command<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    target: &str,
+    channels: Vec<serenity::GenericChannelId>,
+) -> Result<String, String> {
+    let options = ctx.framework().options();
+    let (channel_restrictions, guild_id) =
+        guild_setting_target(ctx, &options.channel_restrictions, "Channel restrictions")?;
+    let commands = || options.commands.iter().map(|c| &**c);
+    let is_category = crate::walk_commands(commands())
+        .any(|(_, command)| command.category.as_deref() == Some(target));
+    let restriction_target = match crate::find_by_qualified_name(commands(), target) {
+        Some(command) => crate::RestrictionTarget::Command(&command.qualified_name),
+        None if is_category => crate::RestrictionTarget::Category(target),
+        None => {
+            return Err(format!("There's no command or category named `{}`", target));
+        }
+    };
+
+    let response = if channels.is_empty() {
+        format!("`{}` can now be used in all channels", target)
+    } else {
+        let channel_mentions = channels
+            .iter()
+            .map(|channel_id| format!("<#{}>", channel_id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("`{}` can now only be used in {}", target, channel_mentions)
+    };
+    let channels = (!channels.is_empty()).then_some(channels);
+    channel_restrictions
+        .set_allowed_channels(guild_id, restriction_target, channels)
+        .await
+        .map_err(guild_setting_save_failure)?;
+    Ok(response)
+}
@@ -0,0 +1,84 @@
+//! Letting server admins define custom command aliases in their guild
+
+use super::{guild_setting_save_failure, guild_setting_target, send_guild_setting_response};
+use crate::serenity_prelude as serenity;
+
+/// Maps a custom alias to a command in the guild the command is invoked in, using
+/// [`crate::PrefixFrameworkOptions::command_aliases`].
+///
+/// `command_name` is the qualified name of the command, e.g. `config set`. Passing `None` removes
+/// the alias. Aliases can't be a single word that's already a command name or alias, since those
+/// take precedence. Only admins should be able to change aliases, see
+/// [guild settings](crate::builtins#guild-settings).
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Adds a custom alias for a command in this server, or removes it if no command is given
+/// #[lumi::command(prefix_command, slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+/// pub async fn alias(
+///     ctx: Context<'_>,
+///     alias: String,
+///     #[rest] command: Option<String>,
+/// ) -> Result<(), Error> {
+///     lumi::builtins::set_command_alias(ctx, &alias, command.as_deref()).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn set_command_alias<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    alias: &str,
+    command_name: Option<&str>,
+) -> Result<(), serenity::Error> {
+    let response = alias_command(ctx, alias.trim(), command_name.map(str::trim)).await;
+    send_guild_setting_response(ctx, response).await
+}
+
+/// Does the work of [`set_command_alias`] and returns the response
+async fn alias_command<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    alias: &str,
+    command_name: Option<&str>,
+) -> Result<String, String> {
+    let options = ctx.framework().options();
+    let (command_aliases, guild_id) = guild_setting_target(
+        ctx,
+        &options.prefix_options.command_aliases,
+        "Custom command aliases",
+    )?;
+    if alias.is_empty() || alias.contains(char::is_whitespace) {
+        return Err("Aliases must be a single word".to_owned());
+    }
+
+    let commands = || options.commands.iter().map(|c| &**c);
+    let case_insensitive = options.prefix_options.case_insensitive_commands;
+    let is_command_name = commands().any(|command| {
+        std::iter::once(&command.name)
+            .chain(command.aliases.iter())
+            .any(|name| match case_insensitive {
+                true => name.eq_ignore_ascii_case(alias),
+                false => name == alias,
+            })
+    });
+    if is_command_name {
+        return Err(format!("`{}` is already a command", alias));
+    }
+
+    let command = match command_name {
+        Some(command_name) => Some(
+            crate::find_by_qualified_name(commands(), command_name)
+                .ok_or_else(|| format!("There's no command named `{}`", command_name))?,
+        ),
+        None => None,
+    };
+    let command_name = command.map(|command| &*command.qualified_name);
+    let stored_alias = crate::command_aliases::normalize_alias(&options.prefix_options, alias);
+    command_aliases
+        .set(guild_id, &stored_alias, command_name)
+        .await
+        .map_err(guild_setting_save_failure)?;
+    Ok(match command_name {
+        Some(command_name) => format!("`{}` now runs `{}`", alias, command_name),
+        None => format!("Removed the alias `{}`", alias),
+    })
+}
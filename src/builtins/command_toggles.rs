@@ -0,0 +1,81 @@
+//! Letting server admins enable and disable commands in their guild
+
+use super::{guild_setting_save_failure, guild_setting_target, send_guild_setting_response};
+use crate::serenity_prelude as serenity;
+
+/// Enables or disables a command in the guild the command is invoked in, using
+/// [`crate::FrameworkOptions::command_toggles`].
+///
+/// `command_name` is the qualified name of the command, e.g. `config set`. The invoking command
+/// and its parent commands can't be disabled, so that admins don't lock themselves out. See
+/// [guild settings](crate::builtins#guild-settings) for who should be allowed to call this.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Enables or disables commands in this server
+/// #[lumi::command(
+///     prefix_command,
+///     slash_command,
+///     guild_only,
+///     required_permissions = "MANAGE_GUILD",
+///     subcommands("enable", "disable"),
+///     subcommand_required
+/// )]
+/// pub async fn command(_: Context<'_>) -> Result<(), Error> {
+///     Ok(())
+/// }
+///
+/// /// Enables a command in this server
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn enable(ctx: Context<'_>, #[rest] command: String) -> Result<(), Error> {
+///     lumi::builtins::set_command_enabled(ctx, &command, true).await?;
+///     Ok(())
+/// }
+///
+/// /// Disables a command in this server
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn disable(ctx: Context<'_>, #[rest] command: String) -> Result<(), Error> {
+///     lumi::builtins::set_command_enabled(ctx, &command, false).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn set_command_enabled<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    command_name: &str,
+    enabled: bool,
+) -> Result<(), serenity::Error> {
+    let response = toggle_command(ctx, command_name.trim(), enabled).await;
+    send_guild_setting_response(ctx, response).await
+}
+
+/// Does the work of [`set_command_enabled`] and returns the response
+async fn toggle_command<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    command_name: &str,
+    enabled: bool,
+) -> Result<String, String> {
+    let options = ctx.framework().options();
+    let (command_toggles, guild_id) =
+        guild_setting_target(ctx, &options.command_toggles, "Command toggles")?;
+    let command =
+        crate::find_by_qualified_name(options.commands.iter().map(|c| &**c), command_name)
+            .ok_or_else(|| format!("There's no command named `{}`", command_name))?;
+
+    let is_own_command = std::iter::once(ctx.command())
+        .chain(ctx.parent_commands().iter().copied())
+        .any(|own_command| own_command.qualified_name == command.qualified_name);
+    if is_own_command && !enabled {
+        return Err(format!("`{}` can't be disabled", command.qualified_name));
+    }
+
+    command_toggles
+        .set(guild_id, &command.qualified_name, enabled)
+        .await
+        .map_err(guild_setting_save_failure)?;
+    Ok(format!(
+        "`{}` is now {}",
+        command.qualified_name,
+        if enabled { "enabled" } else { "disabled" }
+    ))
+}
@@ -0,0 +1,86 @@
+//! Showing users which commands they can't use yet because of cooldowns
+
+use crate::serenity_prelude as serenity;
+
+/// Collects the remaining cooldowns of the given command and its subcommands
+fn collect_remaining_cooldowns<T, E>(
+    command: &crate::Command<T, E>,
+    cooldown_ctx: &crate::CooldownContext,
+    remaining_cooldowns: &mut Vec<(String, crate::RemainingCooldown)>,
+) {
+    if command.hide_in_help {
+        return;
+    }
+
+    let cooldown_config = command.cooldown_config.read();
+    let remaining = command
+        .cooldowns
+        .remaining_cooldown_bucket(cooldown_ctx.clone(), &cooldown_config);
+    if let Some(remaining) = remaining {
+        remaining_cooldowns.push((command.qualified_name.to_string(), remaining));
+    }
+
+    for subcommand in &command.subcommands {
+        collect_remaining_cooldowns(subcommand, cooldown_ctx, remaining_cooldowns);
+    }
+}
+
+/// Returns the remaining cooldown of every command (and subcommand) that the invoking user can't
+/// use right now, keyed by [`crate::Command::qualified_name`].
+///
+/// Commands with [`crate::Command::hide_in_help`] are skipped. See [`cooldowns`] for a ready to
+/// use command response.
+pub fn remaining_cooldowns<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+) -> Vec<(String, crate::RemainingCooldown)> {
+    let cooldown_ctx = ctx.cooldown_context();
+    let mut remaining_cooldowns = Vec::new();
+    for command in &ctx.framework().options().commands {
+        collect_remaining_cooldowns(command, &cooldown_ctx, &mut remaining_cooldowns);
+    }
+    remaining_cooldowns
+}
+
+/// Lists the commands the invoking user is on cooldown for, with the remaining time and the
+/// cooldown bucket, in an ephemeral response.
+///
+/// You probably want to use this by wrapping it in a small `cooldowns` command:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Shows when you can use your commands again
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn cooldowns(ctx: Context<'_>) -> Result<(), Error> {
+///     lumi::builtins::cooldowns(ctx).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn cooldowns<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+) -> Result<(), serenity::Error> {
+    let remaining_cooldowns = remaining_cooldowns(ctx);
+
+    let response = if remaining_cooldowns.is_empty() {
+        "You're not on cooldown for any command".to_owned()
+    } else {
+        let mut response = String::from("You're on cooldown for these commands:\n");
+        for (name, remaining) in remaining_cooldowns {
+            response += &format!(
+                "`{}`: {} seconds left ({} cooldown: {}s)\n",
+                name,
+                remaining.remaining.as_secs(),
+                remaining.bucket,
+                remaining.duration.as_secs()
+            );
+        }
+        response
+    };
+
+    ctx.send(
+        crate::CreateReply::default()
+            .content(response)
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
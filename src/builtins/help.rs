@@ -0,0 +1,249 @@
+//! A paginated help command, see [`help`]
+
+use crate::serenity_prelude as serenity;
+
+use super::{ListingStyle, command_details, command_overview, truncate};
+
+/// Custom ID of the button showing the previous page
+const PREVIOUS_ID: &str = "help.previous";
+/// Custom ID of the button showing the next page
+const NEXT_ID: &str = "help.next";
+/// Discord's limit for the length of message content
+const MAX_CONTENT_LENGTH: usize = 2000;
+/// Room left on each page for the page number
+const PAGE_NUMBER_LENGTH: usize = 32;
+
+/// Optional configuration for how the help message from [`help()`] looks
+#[derive(Clone, Debug)]
+pub struct HelpConfiguration<'a> {
+    /// Extra text displayed at the bottom of every page, e.g. a link to the documentation
+    pub extra_text_at_bottom: &'a str,
+    /// Whether to make the response ephemeral if possible
+    pub ephemeral: bool,
+    /// Whether to list subcommands below their parent command
+    pub show_subcommands: bool,
+    /// Whether to list context menu commands
+    pub show_context_menu_commands: bool,
+    /// Maximum number of lines per page. Pages are also split to stay within Discord's message
+    /// length limit
+    pub lines_per_page: usize,
+    /// How long the page buttons stay usable after the last click
+    pub timeout: std::time::Duration,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for HelpConfiguration<'_> {
+    fn default() -> Self {
+        Self {
+            extra_text_at_bottom: "",
+            ephemeral: true,
+            show_subcommands: false,
+            show_context_menu_commands: false,
+            lines_per_page: 25,
+            timeout: std::time::Duration::from_secs(300),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Splits the lines into pages of at most `max_lines` lines and `max_chars` characters. Lines
+/// longer than a page are truncated, and empty lines at page boundaries are dropped
+fn paginate(lines: &[String], max_lines: usize, max_chars: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut page = String::new();
+    let mut page_lines = 0;
+    for line in lines {
+        let line = truncate(line, max_chars);
+        let page_chars = page.chars().count();
+        if page_lines > 0
+            && (page_lines >= max_lines || page_chars + 1 + line.chars().count() > max_chars)
+        {
+            page.truncate(page.trim_end_matches('\n').len());
+            pages.push(std::mem::take(&mut page));
+            page_lines = 0;
+        }
+        if page_lines == 0 && line.is_empty() {
+            continue;
+        }
+
+        if page_lines > 0 {
+            page.push('\n');
+        }
+        page.push_str(&line);
+        page_lines += 1;
+    }
+    if page_lines > 0 || pages.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+/// Generates the message showing the page at `index`, with page buttons if there's more than one
+fn help_page<'a>(
+    pages: &[String],
+    index: usize,
+    config: &HelpConfiguration<'_>,
+) -> crate::CreateReply<'a> {
+    let mut content = pages[index].clone();
+    if !config.extra_text_at_bottom.is_empty() {
+        content.push_str(&format!("\n\n{}", config.extra_text_at_bottom));
+    }
+    if pages.len() == 1 {
+        return crate::CreateReply::default().content(content);
+    }
+    content.push_str(&format!("\n\n*Page {}/{}*", index + 1, pages.len()));
+
+    let buttons = vec![
+        serenity::CreateButton::new(PREVIOUS_ID)
+            .emoji('◀')
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(index == 0),
+        serenity::CreateButton::new(NEXT_ID)
+            .emoji('▶')
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(index + 1 == pages.len()),
+    ];
+    let rows = vec![serenity::CreateActionRow::buttons(buttons)];
+
+    #[cfg(feature = "unstable")]
+    let rows = rows
+        .into_iter()
+        .map(serenity::CreateComponent::ActionRow)
+        .collect::<Vec<_>>();
+
+    crate::CreateReply::default()
+        .content(content)
+        .components(rows)
+}
+
+/// A help command which lists all commands, or shows the details of a single command.
+///
+/// Without a `command` argument, every command in [`crate::FrameworkOptions::commands`] is
+/// listed with its description, skipping commands with `hide_in_help` set. Commands are grouped
+/// like in [`crate::builtins::help_menu`]: by [`crate::CommandGroup`], and ungrouped commands by
+/// [`crate::Command::category`]. With a `command`
+/// argument (`~help ban` or `~help mod ban`), the command's help text, parameters and
+/// subcommands are shown instead. Long output is split into pages which the command author can
+/// flip through with buttons, until nobody clicked them for [`HelpConfiguration::timeout`].
+///
+/// For an interactive menu with select menus instead, see [`crate::builtins::help_menu`].
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Show help for all commands or a specific one
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn help(
+///     ctx: Context<'_>,
+///     #[description = "Command to show help about"]
+///     #[rest]
+///     command: Option<String>,
+/// ) -> Result<(), Error> {
+///     let config = lumi::builtins::HelpConfiguration {
+///         extra_text_at_bottom: "Type ~help <command> for more info on a command.",
+///         ..Default::default()
+///     };
+///     lumi::builtins::help(ctx, command.as_deref(), config).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn help<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    command: Option<&str>,
+    config: HelpConfiguration<'_>,
+) -> Result<(), serenity::Error> {
+    let commands = &ctx.framework().options().commands;
+    let locale = ctx.locale();
+
+    let lines = match command {
+        Some(query) => {
+            let case_insensitive = ctx
+                .framework()
+                .options()
+                .prefix_options
+                .case_insensitive_commands;
+            let found = crate::find_command(commands, query, case_insensitive, &mut Vec::new())
+                .map(|(command, ..)| command)
+                .or_else(|| {
+                    commands
+                        .iter()
+                        .map(|command| &**command)
+                        .find(|command| command.context_menu_name.as_deref() == Some(query))
+                })
+                .filter(|command| !command.hide_in_help);
+            match found {
+                Some(command) => command_details(command, locale)
+                    .lines()
+                    .map(str::to_owned)
+                    .collect(),
+                None => vec![format!("No such command `{}`", query)],
+            }
+        }
+        None => {
+            let style = ListingStyle {
+                prefix: ctx.prefix(),
+                subcommands: config.show_subcommands,
+                context_menu_commands: config.show_context_menu_commands,
+            };
+            command_overview(commands, locale, &style)
+        }
+    };
+
+    let max_chars = MAX_CONTENT_LENGTH
+        - PAGE_NUMBER_LENGTH
+        - config
+            .extra_text_at_bottom
+            .chars()
+            .count()
+            .min(MAX_CONTENT_LENGTH / 2);
+    let pages = paginate(&lines, config.lines_per_page.max(1), max_chars);
+    let mut index = 0;
+
+    let reply = ctx
+        .send(help_page(&pages, index, &config).ephemeral(config.ephemeral))
+        .await?;
+    if pages.len() == 1 {
+        return Ok(());
+    }
+    let message = reply.message().await?;
+
+    while let Some(interaction) = message
+        .id
+        .collect_component_interactions(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .timeout(config.timeout)
+        .await
+    {
+        match &*interaction.data.custom_id {
+            PREVIOUS_ID => index = index.saturating_sub(1),
+            NEXT_ID => index = (index + 1).min(pages.len() - 1),
+            _ => continue,
+        }
+
+        let page = help_page(&pages, index, &config)
+            .to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+        interaction
+            .create_response(
+                ctx.http(),
+                serenity::CreateInteractionResponse::UpdateMessage(page),
+            )
+            .await?;
+    }
+
+    reply
+        .edit(ctx, crate::CreateReply::default().components(vec![]))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_paginate() {
+    let lines = ["**A**", "`a`", "", "**B**", "`b`"].map(String::from);
+    assert_eq!(paginate(&lines, 25, 2000), ["**A**\n`a`\n\n**B**\n`b`"]);
+    assert_eq!(paginate(&lines, 2, 2000), ["**A**\n`a`", "**B**\n`b`"]);
+    assert_eq!(paginate(&lines, 25, 12), ["**A**\n`a`", "**B**\n`b`"]);
+    assert_eq!(paginate(&[], 25, 2000), [""]);
+}
@@ -0,0 +1,452 @@
+//! An interactive help menu navigated with select menus, see [`help_menu`]
+
+use std::borrow::Borrow;
+
+use crate::serenity_prelude as serenity;
+
+/// Custom ID of the select menu listing the commands or subcommands
+const SELECT_ID: &str = "help.select";
+/// Custom ID of the button navigating to the parent command
+const BACK_ID: &str = "help.back";
+/// Discord's limit for the number of options in a select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+/// Discord's limit for the length of select menu option labels and descriptions
+const MAX_OPTION_LENGTH: usize = 100;
+/// Discord's limit for the length of message content
+const MAX_CONTENT_LENGTH: usize = 2000;
+
+/// Shortens the text to at most `max_chars` characters, marking cut off text with an ellipsis
+pub(super) fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars.saturating_sub(1)) {
+        Some((end, _)) if text[end..].chars().nth(1).is_some() => format!("{}…", &text[..end]),
+        _ => text.to_owned(),
+    }
+}
+
+/// Returns the command's description in the given locale, falling back to the unlocalized one
+pub(super) fn description_localized<'a, T, E>(
+    command: &'a crate::Command<T, E>,
+    locale: Option<&str>,
+) -> Option<&'a str> {
+    locale
+        .and_then(|locale| {
+            command
+                .description_localizations
+                .iter()
+                .find(|(description_locale, _)| description_locale == locale)
+        })
+        .map(|(_, description)| &**description)
+        .or(command.description.as_deref())
+}
+
+/// Follows `path`, a list of indices into the (sub)command lists, and returns the command it
+/// leads to, or `None` for the empty path or if an index is out of bounds
+fn resolve_path<'a, T: 'a, E: 'a>(
+    commands: &'a [impl Borrow<crate::Command<T, E>>],
+    path: &[usize],
+) -> Option<&'a crate::Command<T, E>> {
+    let (&first, rest) = path.split_first()?;
+    rest.iter()
+        .try_fold(commands.get(first)?.borrow(), |command, &index| {
+            command.subcommands.get(index)
+        })
+}
+
+/// Returns the commands selectable at `path`: the top-level commands for the empty path and the
+/// subcommands of the selected command otherwise
+fn selectable_commands<'a, T: 'a, E: 'a>(
+    commands: &'a [impl Borrow<crate::Command<T, E>>],
+    path: &[usize],
+) -> Vec<&'a crate::Command<T, E>> {
+    match resolve_path(commands, path) {
+        Some(command) => command.subcommands.iter().collect(),
+        None => commands.iter().map(Borrow::borrow).collect(),
+    }
+}
+
+/// Formats the list entry of a command: its label, a deprecation marker and its description
+fn command_entry<T, E>(
+    label: &str,
+    command: &crate::Command<T, E>,
+    locale: Option<&str>,
+) -> String {
+    let mut entry = format!("`{}`", label);
+    if command.deprecated.is_some() {
+        entry.push_str(" *(deprecated)*");
+    }
+    if let Some(description) = description_localized(command, locale) {
+        entry.push_str(&format!(" - {}", description));
+    }
+    entry
+}
+
+/// Appends a list of commands with their descriptions, skipping those hidden from help
+fn write_command_list<'a, T: 'a, E: 'a>(
+    text: &mut String,
+    commands: impl IntoIterator<Item = &'a crate::Command<T, E>>,
+    locale: Option<&str>,
+) {
+    for command in commands.into_iter().filter(|command| !command.hide_in_help) {
+        text.push('\n');
+        text.push_str(&command_entry(&command.name, command, locale));
+    }
+}
+
+/// How [`command_overview`] lists the commands
+pub(super) struct ListingStyle<'a> {
+    /// Prepended to every command name, e.g. the prefix of the invocation
+    pub prefix: &'a str,
+    /// Whether to list subcommands, indented below their parent command
+    pub subcommands: bool,
+    /// Whether to list commands that can only be invoked from context menus
+    pub context_menu_commands: bool,
+}
+
+/// Appends the entry of a command, and of its subcommands if configured, to `lines`. `path` is
+/// the space separated names of the parent commands and the command itself
+fn write_overview_entry<T, E>(
+    lines: &mut Vec<String>,
+    path: &str,
+    command: &crate::Command<T, E>,
+    locale: Option<&str>,
+    style: &ListingStyle<'_>,
+) {
+    if command.hide_in_help {
+        return;
+    }
+
+    let is_context_menu_only = command.context_menu_action.is_some()
+        && command.prefix_action.is_none()
+        && command.slash_action.is_none()
+        && command.subcommands.is_empty();
+    let entry = match is_context_menu_only {
+        true if !style.context_menu_commands => return,
+        true => {
+            let name = command.context_menu_name.as_deref().unwrap_or(path);
+            format!("{} (context menu)", command_entry(name, command, locale))
+        }
+        false => command_entry(&format!("{}{}", style.prefix, path), command, locale),
+    };
+    lines.push(entry);
+
+    if style.subcommands {
+        for subcommand in &command.subcommands {
+            let start = lines.len();
+            let path = format!("{} {}", path, subcommand.name);
+            write_overview_entry(lines, &path, subcommand, locale, style);
+            for line in &mut lines[start..] {
+                line.insert_str(0, "  ");
+            }
+        }
+    }
+}
+
+/// Generates the lines of the overview of the top-level commands
+///
+/// Ungrouped commands come first, under the name of their [`crate::Command::category`] or under
+/// "Commands" if they have none. The commands of each [`crate::CommandGroup`] follow under the
+/// group's name.
+pub(super) fn command_overview<T, E>(
+    commands: &[impl Borrow<crate::Command<T, E>>],
+    locale: Option<&str>,
+    style: &ListingStyle<'_>,
+) -> Vec<String> {
+    let commands = commands
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<Vec<&crate::Command<T, E>>>();
+
+    // Title, description and members of each section
+    let mut sections = Vec::<(&str, Option<&str>, Vec<&crate::Command<T, E>>)>::new();
+    let mut categories = Vec::<Option<&str>>::new();
+    for command in commands.iter().filter(|command| command.group.is_none()) {
+        if !categories.contains(&command.category.as_deref()) {
+            categories.push(command.category.as_deref());
+        }
+    }
+    // Uncategorized commands come first
+    categories.sort_by_key(|category| category.is_some());
+    for category in categories {
+        let members = commands
+            .iter()
+            .copied()
+            .filter(|command| command.group.is_none() && command.category.as_deref() == category)
+            .collect();
+        sections.push((category.unwrap_or("Commands"), None, members));
+    }
+
+    let mut groups = Vec::<&crate::CommandGroup<T, E>>::new();
+    for group in commands
+        .iter()
+        .filter_map(|command| command.group.as_deref())
+    {
+        if !groups.iter().any(|g| std::ptr::eq(*g, group)) {
+            groups.push(group);
+        }
+    }
+    for group in groups {
+        let members = commands
+            .iter()
+            .copied()
+            .filter(|command| {
+                command
+                    .group
+                    .as_deref()
+                    .is_some_and(|g| std::ptr::eq(g, group))
+            })
+            .collect();
+        sections.push((&*group.name, group.description.as_deref(), members));
+    }
+
+    let mut lines = Vec::new();
+    for (title, description, members) in sections {
+        let mut entries = Vec::new();
+        for command in members {
+            write_overview_entry(&mut entries, &command.name, command, locale, style);
+        }
+        if entries.is_empty() {
+            continue;
+        }
+
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("**{}**", title));
+        lines.extend(description.map(str::to_owned));
+        lines.extend(entries);
+    }
+    if lines.is_empty() {
+        lines.push("No commands available".to_owned());
+    }
+    lines
+}
+
+/// Generates the detail view of a command, including its parameters and subcommands
+pub(super) fn command_details<T, E>(
+    command: &crate::Command<T, E>,
+    locale: Option<&str>,
+) -> String {
+    let mut text = format!("**{}**", command.qualified_name);
+    if let Some(group) = &command.group {
+        text.push_str(&format!(" ({})", group.name));
+    }
+    if let Some(notice) = &command.deprecated {
+        text.push_str(&format!("\n{}", notice));
+    }
+    if let Some(description) = description_localized(command, locale) {
+        text.push_str(&format!("\n{}", description));
+    }
+    if let Some(help_text) = command.help_text_localized(locale) {
+        text.push_str(&format!("\n\n{}", help_text));
+    }
+
+    if !command.parameters.is_empty() {
+        text.push_str("\n\n__Parameters__");
+        for parameter in &command.parameters {
+            text.push_str(&format!("\n`{}`", parameter.name));
+            if !parameter.required {
+                text.push_str(" (optional)");
+            }
+            if let Some(description) = &parameter.description {
+                text.push_str(&format!(" - {}", description));
+            }
+        }
+    }
+
+    if command.subcommands.iter().any(|sub| !sub.hide_in_help) {
+        text.push_str("\n\n__Subcommands__");
+        write_command_list(&mut text, &command.subcommands, locale);
+    }
+    text
+}
+
+/// Generates the help menu message for the command at `path`, or the command overview for the
+/// empty path
+fn help_menu_page<'a, T: 'a, E: 'a>(
+    commands: &'a [impl Borrow<crate::Command<T, E>>],
+    path: &[usize],
+    locale: Option<&str>,
+) -> crate::CreateReply<'a> {
+    let content = match resolve_path(commands, path) {
+        Some(command) => command_details(command, locale),
+        None => {
+            let style = ListingStyle {
+                prefix: "",
+                subcommands: false,
+                context_menu_commands: true,
+            };
+            command_overview(commands, locale, &style).join("\n")
+        }
+    };
+
+    let options = selectable_commands(commands, path)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, command)| !command.hide_in_help)
+        .take(MAX_SELECT_OPTIONS)
+        .map(|(index, command)| {
+            let option = serenity::CreateSelectMenuOption::new(
+                truncate(&command.name, MAX_OPTION_LENGTH),
+                index.to_string(),
+            );
+            match description_localized(command, locale) {
+                Some(description) => option.description(truncate(description, MAX_OPTION_LENGTH)),
+                None => option,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut rows = Vec::new();
+    if !options.is_empty() {
+        let placeholder = match path.is_empty() {
+            true => "Choose a command",
+            false => "Choose a subcommand",
+        };
+        let kind = serenity::CreateSelectMenuKind::String {
+            options: options.into(),
+        };
+        let select_menu = serenity::CreateSelectMenu::new(SELECT_ID, kind).placeholder(placeholder);
+        rows.push(serenity::CreateActionRow::select_menu(select_menu));
+    }
+    if !path.is_empty() {
+        let back_button = serenity::CreateButton::new(BACK_ID)
+            .label("Back")
+            .style(serenity::ButtonStyle::Secondary);
+        rows.push(serenity::CreateActionRow::buttons(vec![back_button]));
+    }
+
+    #[cfg(feature = "unstable")]
+    let rows = rows
+        .into_iter()
+        .map(serenity::CreateComponent::ActionRow)
+        .collect::<Vec<_>>();
+
+    crate::CreateReply::default()
+        .content(truncate(&content, MAX_CONTENT_LENGTH))
+        .components(rows)
+}
+
+/// An interactive help menu which lets the command author drill into commands, their subcommands
+/// and parameters via select menus.
+///
+/// The menu starts with an overview of all top-level commands. Choosing a command from the select
+/// menu shows its description, help text, parameters and subcommands, and a further select menu
+/// for the subcommands, if it has any. A back button navigates to the parent command. Everything
+/// is generated from the command tree in [`crate::FrameworkOptions::commands`], skipping commands
+/// with `hide_in_help` set. Descriptions and help texts are shown in the user's locale where
+/// available. Deprecated commands (see [`crate::Command::deprecated`]) are marked as such.
+///
+/// Only the command author can navigate the menu. Its components are removed once nobody
+/// interacted with it for `timeout`. Select menus can hold at most 25 options, so further
+/// commands of the same level are listed in the message but can't be chosen.
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Browse the commands of this bot
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn help(ctx: Context<'_>) -> Result<(), Error> {
+///     lumi::builtins::help_menu(ctx, std::time::Duration::from_secs(300)).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn help_menu<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    timeout: std::time::Duration,
+) -> Result<(), serenity::Error> {
+    let commands = &ctx.framework().options().commands;
+    let locale = ctx.locale();
+    let mut path = Vec::new();
+
+    let reply = ctx.send(help_menu_page(commands, &path, locale)).await?;
+    let message = reply.message().await?;
+
+    while let Some(interaction) = message
+        .id
+        .collect_component_interactions(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .timeout(timeout)
+        .await
+    {
+        match (&*interaction.data.custom_id, &interaction.data.kind) {
+            (SELECT_ID, serenity::ComponentInteractionDataKind::StringSelect { values }) => {
+                let index = values.first().and_then(|value| value.parse::<usize>().ok());
+                let selectable = selectable_commands(commands, &path);
+                if let Some(index) = index {
+                    if selectable
+                        .get(index)
+                        .is_some_and(|command| !command.hide_in_help)
+                    {
+                        path.push(index);
+                    }
+                }
+            }
+            (BACK_ID, _) => {
+                path.pop();
+            }
+            _ => continue,
+        }
+
+        let page = help_menu_page(commands, &path, locale)
+            .to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+        interaction
+            .create_response(
+                ctx.http(),
+                serenity::CreateInteractionResponse::UpdateMessage(page),
+            )
+            .await?;
+    }
+
+    reply
+        .edit(ctx, crate::CreateReply::default().components(vec![]))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_truncate() {
+    assert_eq!(truncate("short", 10), "short");
+    assert_eq!(truncate("exactly10!", 10), "exactly10!");
+    assert_eq!(truncate("a bit too long", 10), "a bit too…");
+    assert_eq!(truncate("äöü", 2), "ä…");
+}
+
+#[cfg(test)]
+#[test]
+fn test_command_overview() {
+    let group = std::sync::Arc::new(
+        crate::CommandGroup::<(), ()>::new("Moderation").description("Keep the server tidy"),
+    );
+    let commands = vec![
+        crate::Command {
+            name: "ban".into(),
+            ..Default::default()
+        }
+        .in_group(&group),
+        crate::Command {
+            name: "ping".into(),
+            ..Default::default()
+        },
+        crate::Command {
+            name: "pong".into(),
+            deprecated: Some(crate::DeprecationNotice::new()),
+            ..Default::default()
+        },
+        crate::Command {
+            name: "kick".into(),
+            category: Some("Admin".into()),
+            ..Default::default()
+        },
+    ];
+    let style = ListingStyle {
+        prefix: "~",
+        subcommands: false,
+        context_menu_commands: false,
+    };
+    assert_eq!(
+        command_overview(&commands, None, &style).join("\n"),
+        "**Commands**\n`~ping`\n`~pong` *(deprecated)*\n\n**Admin**\n`~kick`\n\n\
+            **Moderation**\nKeep the server tidy\n`~ban`"
+    );
+}
@@ -0,0 +1,93 @@
+//! Generating the bot's OAuth2 invite link
+
+use std::borrow::Borrow;
+
+use crate::serenity_prelude as serenity;
+
+/// OAuth2 scopes used by [`invite`]: adding the bot user and its application commands
+pub const DEFAULT_INVITE_SCOPES: &[&str] = &["bot", "applications.commands"];
+
+/// Returns the union of [`crate::Command::required_bot_permissions`] of all commands and their
+/// subcommands, i.e. the permissions the bot needs to run every command
+pub fn required_bot_permissions<T, E>(
+    commands: &[impl Borrow<crate::Command<T, E>>],
+) -> serenity::Permissions {
+    crate::walk_commands(commands.iter().map(Borrow::borrow)).fold(
+        serenity::Permissions::empty(),
+        |permissions, (_, command)| permissions | command.required_bot_permissions,
+    )
+}
+
+/// Builds the OAuth2 URL which adds the application to a server with the given permissions and
+/// scopes, e.g. [`DEFAULT_INVITE_SCOPES`]
+pub fn invite_url(
+    application_id: serenity::ApplicationId,
+    permissions: serenity::Permissions,
+    scopes: &[&str],
+) -> String {
+    format!(
+        "https://discord.com/oauth2/authorize?client_id={}&permissions={}&scope={}",
+        application_id,
+        permissions.bits(),
+        scopes.join("%20")
+    )
+}
+
+/// Responds with the bot's invite link, requesting the permissions that the registered commands
+/// need (see [`required_bot_permissions`]) and the [`DEFAULT_INVITE_SCOPES`].
+///
+/// The application ID is determined at runtime, so no client ID needs to be hardcoded. Use
+/// [`invite_custom`] to request other permissions or scopes.
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Add this bot to your server
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn invite(ctx: Context<'_>) -> Result<(), Error> {
+///     lumi::builtins::invite(ctx).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn invite<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+) -> Result<(), serenity::Error> {
+    invite_custom(ctx, None, DEFAULT_INVITE_SCOPES).await
+}
+
+/// Like [`invite`], but requests the given permissions, or the permissions that the registered
+/// commands need if `None`, and the given OAuth2 scopes
+pub async fn invite_custom<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    permissions: Option<serenity::Permissions>,
+    scopes: &[&str],
+) -> Result<(), serenity::Error> {
+    let application_id = match ctx.http().application_id() {
+        Some(application_id) => application_id,
+        None => ctx.http().get_current_application_info().await?.id,
+    };
+    let permissions = permissions
+        .unwrap_or_else(|| required_bot_permissions(&ctx.framework().options().commands));
+
+    let url = invite_url(application_id, permissions, scopes);
+    ctx.send(
+        crate::CreateReply::default()
+            .content(format!("Add me to your server: <{}>", url))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_invite_url() {
+    assert_eq!(
+        invite_url(
+            serenity::ApplicationId::new(1234),
+            serenity::Permissions::SEND_MESSAGES | serenity::Permissions::EMBED_LINKS,
+            DEFAULT_INVITE_SCOPES,
+        ),
+        "https://discord.com/oauth2/authorize?client_id=1234&permissions=18432\
+        &scope=bot%20applications.commands"
+    );
+}
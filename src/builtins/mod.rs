@@ -2,18 +2,152 @@
 //!
 //! This file provides sample commands and utility functions like pagination or error handlers to
 //! use as a starting point for the framework.
+//!
+//! # Guild settings
+//!
+//! [`set_command_enabled`], [`set_command_channels`] and [`set_command_alias`] let server admins
+//! change the settings of their guild in the stores of [`crate::FrameworkOptions`] and reply with
+//! the outcome ephemerally. They don't check any permissions, so restrict the wrapping commands
+//! to admins, e.g. with `required_permissions = "MANAGE_GUILD"`.
+
+mod about;
+pub use about::*;
 
 mod register;
 pub use register::*;
 
+mod bulk_register;
+pub use bulk_register::*;
+
+mod channel_restrictions;
+pub use channel_restrictions::*;
+
+mod command_aliases;
+pub use command_aliases::*;
+
+mod command_toggles;
+pub use command_toggles::*;
+
+mod cooldowns;
+pub use cooldowns::*;
+
+mod help;
+pub use help::*;
+
+mod help_menu;
+pub use help_menu::*;
+
+mod invite;
+pub use invite::*;
+
+mod reaction_menu;
+pub use reaction_menu::*;
+
+mod stats;
+pub use stats::*;
+
 use crate::{CreateReply, serenity_prelude as serenity, serenity_prelude::CreateAllowedMentions};
 
+/// Returns the store and the guild a guild setting builtin changes the setting in, or else the
+/// response explaining why the setting can't be changed
+fn guild_setting_target<'a, T, E, S: ?Sized>(
+    ctx: crate::Context<'_, T, E>,
+    store: &'a Option<std::sync::Arc<S>>,
+    setting: &str,
+) -> Result<(&'a S, serenity::GuildId), String> {
+    let store = store
+        .as_deref()
+        .ok_or_else(|| format!("{} are not supported", setting))?;
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| format!("{} can only be changed in servers", setting))?;
+    Ok((store, guild_id))
+}
+
+/// Logs a settings store error of a guild setting builtin and returns the response for it
+fn guild_setting_save_failure(error: crate::guild_settings::BoxedError) -> String {
+    tracing::warn!("Failed to save a guild setting: {}", error);
+    "Failed to save the setting, please try again later".to_owned()
+}
+
+/// Sends the response of a guild setting builtin, which is the same whether it succeeded or not
+async fn send_guild_setting_response<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    response: Result<String, String>,
+) -> Result<(), serenity::Error> {
+    let response = response.unwrap_or_else(|response| response);
+    ctx.send(CreateReply::default().content(response).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Explains permission errors caused by channel overwrites, since server admins usually look at
+/// the role permissions first
+fn permission_scope_hint(scope: crate::PermissionScope) -> &'static str {
+    match scope {
+        crate::PermissionScope::ChannelOverwrites => {
+            " (denied by this channel's permission settings)"
+        }
+        _ => "",
+    }
+}
+
+/// Sends an error message of [`on_error`] according to the command's
+/// [`crate::Command::error_reply_policy`]. The reply's ephemerality is used for
+/// [`crate::ErrorReplyPolicy::Auto`]
+async fn send_error_reply<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    reply: CreateReply<'_>,
+) -> Result<(), serenity::Error> {
+    match ctx.command().error_reply_policy {
+        crate::ErrorReplyPolicy::Auto | crate::ErrorReplyPolicy::__NonExhaustive => {
+            ctx.send(reply).await?;
+        }
+        crate::ErrorReplyPolicy::Public => {
+            ctx.send(reply.ephemeral(false)).await?;
+        }
+        crate::ErrorReplyPolicy::Ephemeral => {
+            ctx.send(reply.ephemeral(true)).await?;
+        }
+        crate::ErrorReplyPolicy::DeleteAfter(delay) => {
+            ctx.send(reply).await?.delete_after(ctx, delay);
+        }
+        crate::ErrorReplyPolicy::Dm => {
+            let dm = ctx
+                .author()
+                .id
+                .direct_message(ctx.http(), reply.clone().to_message())
+                .await;
+            match dm {
+                // Interactions must be answered, or Discord shows them as failed
+                Ok(_) => {
+                    if let crate::Context::Application(_) = ctx {
+                        let notice = CreateReply::default()
+                            .content("An error occurred, I've sent you the details in a DM")
+                            .ephemeral(true);
+                        ctx.send(notice).await?;
+                    }
+                }
+                Err(e) => {
+                    tracing::debug!("couldn't DM error message, replying instead: {}", e);
+                    ctx.send(reply.ephemeral(true)).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// An error handler that logs errors either via the [`tracing`] crate or via a Discord message. Set
 /// up a logger like tracing subscriber
 /// (e.g. `tracing_subscriber::fmt::init()`) to see the logged errors from this method.
 ///
 /// If the user invoked the command wrong ([`crate::FrameworkError::ArgumentParse`]), the command
-/// help is displayed and the user is directed to the help menu.
+/// help is displayed and the user is directed to the help menu. If the command's registration is
+/// outdated ([`crate::FrameworkError::CommandStructureMismatch`]), the user is told that the
+/// commands need to be registered again.
+///
+/// Error messages are delivered according to the command's [`crate::Command::error_reply_policy`].
 ///
 /// Can return an error if sending the Discord error message failed. You can decide for yourself
 /// how to handle this, for example:
@@ -39,10 +173,12 @@ where
                 .all_roles(false)
                 .all_users(false);
 
-            ctx.send(
+            send_error_reply(
+                ctx,
                 CreateReply::default()
                     .content(error)
-                    .allowed_mentions(mentions),
+                    .allowed_mentions(mentions)
+                    .ephemeral(false),
             )
             .await?;
         }
@@ -57,24 +193,58 @@ where
                 "You must specify one of the following subcommands: {}",
                 subcommands.join(", ")
             );
-            ctx.send(CreateReply::default().content(response).ephemeral(true))
-                .await?;
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
-        crate::FrameworkError::CommandPanic { ctx, payload: _ } => {
+        crate::FrameworkError::CommandPanic {
+            ctx,
+            payload,
+            backtrace,
+        } => {
+            match backtrace {
+                Some(backtrace) => tracing::error!(
+                    "Panic in command `{}`: {:?}\n{}",
+                    ctx.command().qualified_name,
+                    payload,
+                    backtrace
+                ),
+                None => tracing::error!(
+                    "Panic in command `{}`: {:?}",
+                    ctx.command().qualified_name,
+                    payload
+                ),
+            }
+
             // Not showing the payload to the user because it may contain sensitive info
             let embed = serenity::CreateEmbed::default()
                 .title("Internal error")
                 .color((255, 0, 0))
                 .description("An unexpected internal error has occurred.");
 
-            ctx.send(CreateReply::default().embed(embed).ephemeral(true))
-                .await?;
+            send_error_reply(ctx, CreateReply::default().embed(embed).ephemeral(true)).await?;
+        }
+        crate::FrameworkError::CommandTimeout { ctx, timeout } => {
+            tracing::warn!(
+                "Command `{}` timed out after {:?}",
+                ctx.command().qualified_name,
+                timeout
+            );
+
+            let response = "The command took too long to complete and was cancelled.";
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
         crate::FrameworkError::ArgumentParse { ctx, input, error } => {
             // If we caught an argument parse error, give a helpful error message with the
             // command explanation if available
-            let usage = match &ctx.command().help_text {
-                Some(help_text) => &**help_text,
+            let usage = match ctx.command().help_text_localized(ctx.locale()) {
+                Some(help_text) => help_text,
                 None => "Please check the help menu for usage information",
             };
             let response = if let Some(input) = input {
@@ -91,60 +261,125 @@ where
                 .all_roles(false)
                 .all_users(false);
 
-            ctx.send(
+            send_error_reply(
+                ctx,
                 CreateReply::default()
                     .content(response)
-                    .allowed_mentions(mentions),
+                    .allowed_mentions(mentions)
+                    .ephemeral(false),
             )
             .await?;
         }
         crate::FrameworkError::CommandStructureMismatch { ctx, description } => {
-            tracing::error!(
-                "Error: failed to deserialize interaction arguments for `/{}`: {}",
-                ctx.command.name,
-                description,
-            );
+            // Autocomplete interactions fire on every keystroke and can't be responded to with a
+            // message, so the error is reported once the command is actually run
+            if ctx.interaction_type != crate::CommandInteractionType::Command {
+                tracing::debug!(
+                    "failed to deserialize autocomplete arguments for `/{}`: {}",
+                    ctx.command.name,
+                    description,
+                );
+            } else {
+                tracing::error!(
+                    "Error: failed to deserialize interaction arguments for `/{}`: {}",
+                    ctx.command.name,
+                    description,
+                );
+
+                let response = "This command is out of date and can't be run until the bot's \
+                    commands are registered again. Please let the bot owner know.";
+                send_error_reply(
+                    ctx.into(),
+                    CreateReply::default().content(response).ephemeral(true),
+                )
+                .await?;
+            }
         }
-        crate::FrameworkError::CommandCheckFailed { ctx, error } => {
+        crate::FrameworkError::CommandCheckFailed {
+            ctx,
+            error,
+            failed_checks,
+        } => {
+            let names = failed_checks
+                .iter()
+                .filter_map(|check| check.name.as_deref())
+                .collect::<Vec<_>>();
             tracing::error!(
-                "A command check failed in command {} for user {}: {:?}",
+                "A command check failed in command {} for user {}: {:?} (failed checks: {:?})",
                 ctx.command().name,
                 ctx.author().name,
                 error,
+                names,
             );
+
+            let failure_message = failed_checks
+                .iter()
+                .find_map(|check| check.failure_message.as_deref());
+            if let Some(failure_message) = failure_message {
+                send_error_reply(
+                    ctx,
+                    CreateReply::default()
+                        .content(failure_message)
+                        .ephemeral(true),
+                )
+                .await?;
+            }
         }
         crate::FrameworkError::CooldownHit {
             remaining_cooldown,
+            bucket,
+            cooldown_duration,
             ctx,
         } => {
             let msg = format!(
-                "You're too fast. Please wait {} seconds before retrying",
+                "You're too fast ({} cooldown: {}s). Please wait {} seconds before retrying",
+                bucket,
+                cooldown_duration.as_secs(),
                 remaining_cooldown.as_secs()
             );
-            ctx.send(CreateReply::default().content(msg).ephemeral(true))
-                .await?;
+            send_error_reply(ctx, CreateReply::default().content(msg).ephemeral(true)).await?;
         }
         crate::FrameworkError::MissingBotPermissions {
             missing_permissions,
+            channel_id,
+            scope,
             ctx,
         } => {
             let msg = format!(
-                "Command cannot be executed because the bot is lacking permissions: {}",
+                "Command cannot be executed because the bot is lacking permissions in <#{}>: {}{}",
+                channel_id,
                 missing_permissions,
+                permission_scope_hint(scope),
             );
-            ctx.send(CreateReply::default().content(msg).ephemeral(true))
-                .await?;
+            let reply = CreateReply::default().content(msg).ephemeral(true);
+            match ctx {
+                // No point in trying to respond in the channel
+                crate::Context::Prefix(ctx)
+                    if ctx.framework.options().dm_fallback != crate::DmFallback::Disabled
+                        && missing_permissions.send_messages()
+                        && ctx.msg.guild_id.is_some() =>
+                {
+                    crate::send_prefix_reply_as_dm(ctx, reply).await?;
+                }
+                ctx => {
+                    send_error_reply(ctx, reply).await?;
+                }
+            }
         }
         crate::FrameworkError::MissingUserPermissions {
             missing_permissions,
+            channel_id,
+            scope,
             ctx,
         } => {
             let response = if let Some(missing_permissions) = missing_permissions {
                 format!(
-                    "You're lacking permissions for `{}{}`: {}",
+                    "You're lacking permissions for `{}{}` in <#{}>: {}{}",
                     ctx.prefix(),
                     ctx.command().name,
+                    channel_id,
                     missing_permissions,
+                    permission_scope_hint(scope),
                 )
             } else {
                 format!(
@@ -153,32 +388,100 @@ where
                     ctx.command().name,
                 )
             };
-            ctx.send(CreateReply::default().content(response).ephemeral(true))
-                .await?;
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
         crate::FrameworkError::PermissionFetchFailed { ctx } => {
-            ctx.say("An error occurred when fetching permissions.")
-                .await?;
+            let response = "An error occurred when fetching permissions.";
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
         crate::FrameworkError::NotAnOwner { ctx } => {
             let response = "Only bot owners can call this command";
-            ctx.send(CreateReply::default().content(response).ephemeral(true))
-                .await?;
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
+        }
+        crate::FrameworkError::CommandDisabled { ctx } => {
+            let response = "This command is disabled in this server.";
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
+        }
+        crate::FrameworkError::WrongChannel {
+            allowed_channels,
+            ctx,
+        } => {
+            let channels = allowed_channels
+                .iter()
+                .map(|channel_id| format!("<#{}>", channel_id))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let response = if channels.is_empty() {
+                "This command can't be used in this server.".to_owned()
+            } else {
+                format!("This command can only be used in {}.", channels)
+            };
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
+        }
+        crate::FrameworkError::SettingsUnavailable { error, ctx } => {
+            tracing::error!(
+                "Failed to load the guild settings for `{}`: {}",
+                ctx.command().qualified_name,
+                error
+            );
+            let response = "This command is unavailable right now, please try again later.";
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
+        }
+        crate::FrameworkError::FeatureDisabled { ctx, .. } => {
+            let response = "This command isn't available here yet.";
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
         crate::FrameworkError::GuildOnly { ctx } => {
             let response = "You cannot run this command in DMs.";
-            ctx.send(CreateReply::default().content(response).ephemeral(true))
-                .await?;
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
         crate::FrameworkError::DmOnly { ctx } => {
             let response = "You cannot run this command outside DMs.";
-            ctx.send(CreateReply::default().content(response).ephemeral(true))
-                .await?;
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
         crate::FrameworkError::NsfwOnly { ctx } => {
             let response = "You cannot run this command outside NSFW channels.";
-            ctx.send(CreateReply::default().content(response).ephemeral(true))
-                .await?;
+            send_error_reply(
+                ctx,
+                CreateReply::default().content(response).ephemeral(true),
+            )
+            .await?;
         }
         crate::FrameworkError::DynamicPrefix { error, msg, .. } => {
             tracing::error!(
@@ -190,12 +493,18 @@ where
         crate::FrameworkError::UnknownCommand {
             msg_content,
             prefix,
+            candidates,
             ..
         } => {
+            let candidates = candidates
+                .iter()
+                .map(|command| &*command.name)
+                .collect::<Vec<_>>();
             tracing::warn!(
-                "Recognized prefix `{}`, but didn't recognize command name in `{}`",
+                "Recognized prefix `{}`, but didn't recognize command name in `{}` (similar: {:?})",
                 prefix,
                 msg_content,
+                candidates,
             );
         }
         crate::FrameworkError::UnknownInteraction { interaction, .. } => {
@@ -204,6 +513,16 @@ where
         crate::FrameworkError::NonCommandMessage { error, .. } => {
             tracing::warn!("error in non-command message handler: {}", error);
         }
+        crate::FrameworkError::ComponentHandler { error, ctx } => {
+            tracing::warn!(
+                "error in component handler for `{}`: {}",
+                ctx.interaction.data.custom_id,
+                error
+            );
+        }
+        error @ crate::FrameworkError::Internal { .. } => {
+            tracing::warn!("{}", error);
+        }
         crate::FrameworkError::__NonExhaustive(unreachable) => match unreachable {},
     }
 
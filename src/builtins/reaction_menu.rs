@@ -0,0 +1,78 @@
+//! A menu driven by reactions instead of message components
+
+use crate::serenity_prelude::{self as serenity, CollectReactions};
+
+/// Whether a collected reaction is the same emoji as one of the menu choices.
+///
+/// Custom emojis are compared by ID only, because the name in reaction events may be outdated.
+fn is_same_emoji(a: &serenity::ReactionType, b: &serenity::ReactionType) -> bool {
+    match (a, b) {
+        (
+            serenity::ReactionType::Custom { id: a, .. },
+            serenity::ReactionType::Custom { id: b, .. },
+        ) => a == b,
+        (serenity::ReactionType::Unicode(a), serenity::ReactionType::Unicode(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Adds `choices` as reactions to `message` and waits for the command author to react with one of
+/// them.
+///
+/// Returns the index of the chosen reaction in `choices`, or `None` if the author didn't choose
+/// before `timeout` expired. Reactions of other users and with other emojis are ignored.
+///
+/// Useful where message components are not an option, e.g. for users on clients without
+/// component support. Note that collecting reactions requires the `GUILD_MESSAGE_REACTIONS` or
+/// `DIRECT_MESSAGE_REACTIONS` intent.
+///
+/// ```rust,no_run
+/// # async fn foo(ctx: lumi::Context<'_, (), ()>) -> Result<(), lumi::serenity_prelude::Error> {
+/// let reply = ctx.say("Deploy to production?").await?;
+/// let choices = ['✅'.into(), '❌'.into()];
+/// let choice = lumi::builtins::reaction_menu(
+///     ctx,
+///     &*reply.message().await?,
+///     &choices,
+///     std::time::Duration::from_secs(60),
+/// )
+/// .await?;
+///
+/// match choice {
+///     Some(0) => ctx.say("Deploying...").await?,
+///     Some(_) => ctx.say("Cancelled").await?,
+///     None => ctx.say("Timed out").await?,
+/// };
+/// # Ok(()) }
+/// ```
+pub async fn reaction_menu<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    message: &serenity::Message,
+    choices: &[serenity::ReactionType],
+    timeout: std::time::Duration,
+) -> Result<Option<usize>, serenity::Error> {
+    // Start collecting before adding the reactions, so that quick users aren't missed
+    let filter_choices = choices.to_vec();
+    let mut reactions = message
+        .id
+        .collect_reactions(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .filter(move |reaction| {
+            filter_choices
+                .iter()
+                .any(|choice| is_same_emoji(choice, &reaction.emoji))
+        })
+        .timeout(timeout)
+        .stream();
+
+    for choice in choices {
+        message.react(ctx.http(), choice.clone()).await?;
+    }
+
+    let Some(reaction) = futures_util::StreamExt::next(&mut reactions).await else {
+        return Ok(None);
+    };
+    Ok(choices
+        .iter()
+        .position(|choice| is_same_emoji(choice, &reaction.emoji)))
+}
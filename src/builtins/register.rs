@@ -1,7 +1,11 @@
 //! Utilities for registering application commands
 
+use std::borrow::Borrow;
+
 use crate::serenity_prelude::{self as serenity, CollectComponentInteractions};
 
+use super::truncate;
+
 /// Collects all commands into a [`Vec<serenity::CreateCommand>`] builder, which can be used
 /// to register the commands on Discord
 ///
@@ -17,59 +21,506 @@ use crate::serenity_prelude::{self as serenity, CollectComponentInteractions};
 /// # Ok(()) }
 /// ```
 pub fn create_application_commands<T, E>(
-    commands: &[crate::Command<T, E>],
+    commands: &[impl Borrow<crate::Command<T, E>>],
 ) -> Vec<serenity::CreateCommand<'static>> {
-    /// We decided to extract context menu commands recursively, despite the subcommand hierarchy
-    /// not being preserved. Because it's more confusing to just silently discard context menu
-    /// commands if they're not top-level commands.
-    /// https://discord.com/channels/381880193251409931/919310428344029265/947970605985189989
-    fn recursively_add_context_menu_commands<T, E>(
-        builder: &mut Vec<serenity::CreateCommand<'static>>,
-        command: &crate::Command<T, E>,
-    ) {
-        if let Some(context_menu_command) = command.create_as_context_menu_command() {
-            builder.push(context_menu_command);
-        }
-        for subcommand in &command.subcommands {
-            recursively_add_context_menu_commands(builder, subcommand);
-        }
-    }
+    create_application_commands_filtered(commands, |_| true)
+}
+
+/// Like [`create_application_commands`], but only includes top-level commands for which `filter`
+/// returns true.
+///
+/// Context menu commands nested in a command's subcommands are included or excluded together with
+/// the top-level command.
+pub fn create_application_commands_filtered<T, E>(
+    commands: &[impl Borrow<crate::Command<T, E>>],
+    filter: impl Fn(&crate::Command<T, E>) -> bool,
+) -> Vec<serenity::CreateCommand<'static>> {
+    create_application_commands_inner(commands, filter, None)
+}
 
+/// Like [`create_application_commands`], but applies the given [`RegistrationProfile`] on top of
+/// each generated command.
+pub fn create_application_commands_with_profile<T, E>(
+    commands: &[impl Borrow<crate::Command<T, E>>],
+    profile: &RegistrationProfile,
+) -> Vec<serenity::CreateCommand<'static>> {
+    create_application_commands_inner(commands, |_| true, Some(profile))
+}
+
+/// Shared implementation of the `create_application_commands*` functions
+fn create_application_commands_inner<T, E>(
+    commands: &[impl Borrow<crate::Command<T, E>>],
+    filter: impl Fn(&crate::Command<T, E>) -> bool,
+    profile: Option<&RegistrationProfile>,
+) -> Vec<serenity::CreateCommand<'static>> {
     let mut commands_builder = Vec::with_capacity(commands.len());
     for command in commands {
-        if let Some(slash_command) = command.create_as_slash_command() {
+        let command: &crate::Command<T, E> = command.borrow();
+        if !filter(command) {
+            continue;
+        }
+        if let Some(mut slash_command) = command.create_as_slash_command() {
+            slash_command = apply_group(command, slash_command);
+            if let Some(profile) = profile {
+                slash_command = profile.apply(command, slash_command);
+            }
             commands_builder.push(slash_command);
         }
-        recursively_add_context_menu_commands(&mut commands_builder, command);
+        for mut alias in command.create_as_slash_command_aliases() {
+            alias = apply_group(command, alias);
+            if let Some(profile) = profile {
+                alias = profile.apply(command, alias);
+            }
+            commands_builder.push(alias);
+        }
+
+        // We decided to extract context menu commands recursively, despite the subcommand
+        // hierarchy not being preserved. Because it's more confusing to just silently discard
+        // context menu commands if they're not top-level commands.
+        // https://discord.com/channels/381880193251409931/919310428344029265/947970605985189989
+        for (_, command) in crate::walk_commands(std::iter::once(command)) {
+            if let Some(mut context_menu_command) = command.create_as_context_menu_command() {
+                context_menu_command = apply_group(command, context_menu_command);
+                if let Some(profile) = profile {
+                    context_menu_command = profile.apply(command, context_menu_command);
+                }
+                commands_builder.push(context_menu_command);
+            }
+        }
     }
     commands_builder
 }
 
+/// Registers the [`crate::CommandGroup::default_member_permissions`] of the command's group if
+/// the command doesn't set its own
+fn apply_group<T, E>(
+    command: &crate::Command<T, E>,
+    builder: serenity::CreateCommand<'static>,
+) -> serenity::CreateCommand<'static> {
+    match &command.group {
+        Some(group)
+            if command.default_member_permissions.is_empty()
+                && !group.default_member_permissions.is_empty() =>
+        {
+            builder.default_member_permissions(group.default_member_permissions)
+        }
+        _ => builder,
+    }
+}
+
+/// Overrides for registration-time command settings, to register the same command tree with
+/// different defaults in different guilds.
+///
+/// For example, a multi-tenant bot may want stricter default permissions in some servers:
+///
+/// ```rust,no_run
+/// # use lumi::serenity_prelude as serenity;
+/// # async fn foo(http: &serenity::Http, commands: &[lumi::Command<(), ()>]) -> Result<(), serenity::Error> {
+/// # let strict_guild = serenity::GuildId::new(1);
+/// let profile = lumi::builtins::RegistrationProfile {
+///     default_member_permissions: Some(serenity::Permissions::MANAGE_GUILD),
+///     ..Default::default()
+/// };
+/// lumi::builtins::register_in_guild_with_profile(http, commands, strict_guild, &profile).await?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RegistrationProfile {
+    /// If set, overrides [`crate::Command::default_member_permissions`] of all commands
+    ///
+    /// Note that Discord treats empty permissions as "admin-only".
+    pub default_member_permissions: Option<serenity::Permissions>,
+    /// Per-command overrides of [`crate::Command::default_member_permissions`], keyed by command
+    /// name. Takes precedence over [`Self::default_member_permissions`].
+    pub command_default_member_permissions:
+        std::collections::HashMap<String, serenity::Permissions>,
+    /// If set, overrides the interaction contexts of all commands
+    pub interaction_context: Option<Vec<serenity::InteractionContext>>,
+    /// If set, overrides the installation contexts of all commands
+    pub install_context: Option<Vec<serenity::InstallationContext>>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl RegistrationProfile {
+    /// Applies this profile's overrides to a command builder generated from `command`
+    pub fn apply<T, E>(
+        &self,
+        command: &crate::Command<T, E>,
+        mut builder: serenity::CreateCommand<'static>,
+    ) -> serenity::CreateCommand<'static> {
+        let permissions = self
+            .command_default_member_permissions
+            .get(&*command.name)
+            .or(self.default_member_permissions.as_ref());
+        if let Some(&permissions) = permissions {
+            builder = builder.default_member_permissions(permissions);
+        }
+        if let Some(interaction_context) = self.interaction_context.clone() {
+            builder = builder.contexts(interaction_context);
+        }
+        if let Some(install_context) = self.install_context.clone() {
+            builder = builder.integration_types(install_context);
+        }
+        builder
+    }
+}
+
+/// Summary of what a registration changed, compared to the previously registered commands
+///
+/// Returned by [`register_globally`] and [`register_in_guild`]. Commands are identified by their
+/// name; context menu commands are suffixed with their type, e.g. `Ban user (user)`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegistrationReport {
+    /// Commands which weren't registered before
+    pub created: Vec<String>,
+    /// Commands which were registered before but whose payload changed
+    pub updated: Vec<String>,
+    /// Commands which were registered before but are no longer part of the command set
+    pub deleted: Vec<String>,
+    /// Commands which were registered before with an identical payload
+    pub unchanged: Vec<String>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl RegistrationReport {
+    /// Compares the previously registered commands with the new command payloads.
+    ///
+    /// Fields that are missing in the new payload are ignored in the comparison, because Discord
+    /// fills in default values for them.
+    pub fn compute(
+        previous: &[serenity::Command],
+        new: &[serenity::CreateCommand<'static>],
+    ) -> Self {
+        // Serializing plain data structs can't fail, so the fallback is never hit in practice
+        let previous = previous
+            .iter()
+            .map(|cmd| serde_json::to_value(cmd).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let new = new
+            .iter()
+            .map(|cmd| serde_json::to_value(cmd).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        let mut report = Self::default();
+        for new_cmd in &new {
+            let label = command_label(new_cmd);
+            match previous
+                .iter()
+                .find(|previous_cmd| command_label(previous_cmd) == label)
+            {
+                None => report.created.push(label),
+                Some(previous_cmd) if json_matches(new_cmd, previous_cmd) => {
+                    report.unchanged.push(label)
+                }
+                Some(_) => report.updated.push(label),
+            }
+        }
+        for previous_cmd in &previous {
+            let label = command_label(previous_cmd);
+            if !new.iter().any(|new_cmd| command_label(new_cmd) == label) {
+                report.deleted.push(label);
+            }
+        }
+        report
+    }
+
+    /// Returns true if the registration didn't change anything
+    pub fn is_noop(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl std::fmt::Display for RegistrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sections = [
+            ("Created", &self.created),
+            ("Updated", &self.updated),
+            ("Deleted", &self.deleted),
+        ];
+        for (title, commands) in sections {
+            if !commands.is_empty() {
+                writeln!(f, "{} ({}): {}", title, commands.len(), commands.join(", "))?;
+            }
+        }
+        write!(f, "Unchanged: {}", self.unchanged.len())
+    }
+}
+
+/// Builds the label under which a serialized command is listed in a [`RegistrationReport`]
+fn command_label(cmd: &serde_json::Value) -> String {
+    let name = cmd["name"].as_str().unwrap_or_default();
+    // See https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-types
+    match cmd["type"].as_u64() {
+        Some(2) => format!("{name} (user)"),
+        Some(3) => format!("{name} (message)"),
+        _ => name.to_string(),
+    }
+}
+
+/// Checks whether all fields set in `new` have the same value in `previous`. Empty fields in
+/// `new` match missing fields in `previous`.
+fn json_matches(new: &serde_json::Value, previous: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    let is_empty = |value: &Value| match value {
+        Value::Null => true,
+        Value::Array(x) => x.is_empty(),
+        Value::Object(x) => x.is_empty(),
+        _ => false,
+    };
+
+    match (new, previous) {
+        (Value::Object(new), Value::Object(previous)) => {
+            new.iter().all(|(key, new_value)| match previous.get(key) {
+                Some(previous_value) => {
+                    (is_empty(new_value) && is_empty(previous_value))
+                        || json_matches(new_value, previous_value)
+                }
+                None => is_empty(new_value),
+            })
+        }
+        (Value::Array(new), Value::Array(previous)) => {
+            new.len() == previous.len() && new.iter().zip(previous).all(|(a, b)| json_matches(a, b))
+        }
+        (new, previous) => new == previous,
+    }
+}
+
+/// Serializes the application command payloads for the given commands into JSON, exactly as
+/// they would be sent to Discord by [`register_globally`] or [`register_in_guild`].
+///
+/// Useful to review command changes in code review, to feed external registration tooling, or to
+/// snapshot test your command tree:
+///
+/// ```rust,no_run
+/// # fn foo(commands: &[lumi::Command<(), ()>]) -> Result<(), Box<dyn std::error::Error>> {
+/// let json = lumi::builtins::export_commands_json(commands)?;
+/// std::fs::write("commands.json", format!("{json:#}"))?;
+/// # Ok(()) }
+/// ```
+pub fn export_commands_json<T, E>(
+    commands: &[impl Borrow<crate::Command<T, E>>],
+) -> Result<serde_json::Value, serde_json::Error> {
+    serde_json::to_value(create_application_commands(commands))
+}
+
 /// Registers the given list of application commands to Discord as global commands.
 ///
 /// Thin wrapper around [`create_application_commands`] that funnels the returned builder into
 /// [`serenity::Command::set_global_commands`].
+///
+/// The returned [`RegistrationReport`] lists what changed compared to the previously registered
+/// global commands.
 pub async fn register_globally<T, E>(
     http: &serenity::Http,
-    commands: &[crate::Command<T, E>],
-) -> Result<(), serenity::Error> {
+    commands: &[impl Borrow<crate::Command<T, E>>],
+) -> Result<RegistrationReport, serenity::Error> {
     let builder = create_application_commands(commands);
+    let previous = serenity::Command::get_global_commands(http).await?;
     serenity::Command::set_global_commands(http, &builder).await?;
-    Ok(())
+    Ok(RegistrationReport::compute(&previous, &builder))
 }
 
 /// Registers the given list of application commands to Discord as guild-specific commands.
 ///
 /// Thin wrapper around [`create_application_commands`] that funnels the returned builder into
 /// [`serenity::GuildId::set_commands`].
+///
+/// The returned [`RegistrationReport`] lists what changed compared to the previously registered
+/// commands in this guild.
 pub async fn register_in_guild<T, E>(
     http: &serenity::Http,
-    commands: &[crate::Command<T, E>],
+    commands: &[impl Borrow<crate::Command<T, E>>],
     guild_id: serenity::GuildId,
-) -> Result<(), serenity::Error> {
+) -> Result<RegistrationReport, serenity::Error> {
     let builder = create_application_commands(commands);
+    let previous = guild_id.get_commands(http).await?;
     guild_id.set_commands(http, &builder).await?;
-    Ok(())
+    Ok(RegistrationReport::compute(&previous, &builder))
+}
+
+/// Registers a subset of the given application commands to Discord as guild-specific commands.
+///
+/// Only top-level commands for which `filter` returns true are registered, which is useful to
+/// roll out beta commands to specific guilds only:
+///
+/// ```rust,no_run
+/// # async fn foo(http: &lumi::serenity_prelude::Http, commands: &[lumi::Command<(), ()>]) -> Result<(), lumi::serenity_prelude::Error> {
+/// # let beta_guild = lumi::serenity_prelude::GuildId::new(1);
+/// lumi::builtins::register_in_guild_filtered(http, commands, beta_guild, |cmd| {
+///     cmd.category.as_deref() == Some("Beta")
+/// })
+/// .await?;
+/// # Ok(()) }
+/// ```
+///
+/// Note that guild commands replace the full set of this guild's commands, so any commands
+/// excluded by the filter are deleted from the guild.
+pub async fn register_in_guild_filtered<T, E>(
+    http: &serenity::Http,
+    commands: &[impl Borrow<crate::Command<T, E>>],
+    guild_id: serenity::GuildId,
+    filter: impl Fn(&crate::Command<T, E>) -> bool,
+) -> Result<RegistrationReport, serenity::Error> {
+    let builder = create_application_commands_filtered(commands, filter);
+    let previous = guild_id.get_commands(http).await?;
+    guild_id.set_commands(http, &builder).await?;
+    Ok(RegistrationReport::compute(&previous, &builder))
+}
+
+/// Registers the given application commands to Discord as guild-specific commands, with the
+/// overrides of the given [`RegistrationProfile`] applied.
+pub async fn register_in_guild_with_profile<T, E>(
+    http: &serenity::Http,
+    commands: &[impl Borrow<crate::Command<T, E>>],
+    guild_id: serenity::GuildId,
+    profile: &RegistrationProfile,
+) -> Result<RegistrationReport, serenity::Error> {
+    let builder = create_application_commands_with_profile(commands, profile);
+    let previous = guild_id.get_commands(http).await?;
+    guild_id.set_commands(http, &builder).await?;
+    Ok(RegistrationReport::compute(&previous, &builder))
+}
+
+/// A registration step which failed in [`register_application_commands_buttons`], labeled
+/// with the affected command (see [`RegistrationReport`]) or the step
+type RegistrationFailure = (String, serenity::Error);
+
+/// Sets the global commands (if `guild_id` is `None`) or the commands of a guild, like
+/// [`serenity::Command::set_global_commands`] and [`serenity::GuildId::set_commands`].
+///
+/// Discord rejects a bulk overwrite as a whole if a single command is invalid, e.g. because it
+/// exceeds the option limits. In that case, this function falls back to registering the commands
+/// one by one and deleting the stale ones from `previous`, so that a single bad command doesn't
+/// block the others. Returns the commands that were registered and the steps that failed,
+/// starting with the failed bulk overwrite. If `previous` couldn't be fetched, stale commands are
+/// kept in the fallback.
+async fn set_commands_itemized(
+    http: &serenity::Http,
+    guild_id: Option<serenity::GuildId>,
+    previous: &[serenity::Command],
+    commands: Vec<serenity::CreateCommand<'static>>,
+) -> (
+    Vec<serenity::CreateCommand<'static>>,
+    Vec<RegistrationFailure>,
+) {
+    let bulk_result = match guild_id {
+        Some(guild_id) => guild_id.set_commands(http, &commands).await.map(|_| ()),
+        None => serenity::Command::set_global_commands(http, &commands)
+            .await
+            .map(|_| ()),
+    };
+    let bulk_error = match bulk_result {
+        Ok(()) => return (commands, Vec::new()),
+        Err(e) => e,
+    };
+
+    tracing::warn!(
+        "bulk command registration failed, registering commands individually: {}",
+        bulk_error
+    );
+    let mut registered = Vec::new();
+    let mut failures = vec![(
+        "bulk overwrite, registered commands one by one instead".to_string(),
+        bulk_error,
+    )];
+    for command in commands {
+        let label = command_label(&serde_json::to_value(&command).unwrap_or_default());
+        let result = match guild_id {
+            Some(guild_id) => guild_id.create_command(http, command.clone()).await,
+            None => serenity::Command::create_global_command(http, command.clone()).await,
+        };
+        match result {
+            Ok(_) => registered.push(command),
+            Err(e) => failures.push((label, e)),
+        }
+    }
+
+    // Individual registrations don't remove commands that are no longer part of the command set,
+    // unlike the bulk overwrite
+    let registered_labels = registered
+        .iter()
+        .map(|command| command_label(&serde_json::to_value(command).unwrap_or_default()))
+        .collect::<Vec<_>>();
+    for previous_command in previous {
+        let label = command_label(&serde_json::to_value(previous_command).unwrap_or_default());
+        if registered_labels.contains(&label) || failures.iter().any(|(l, _)| *l == label) {
+            continue;
+        }
+        let result = match guild_id {
+            Some(guild_id) => guild_id.delete_command(http, previous_command.id).await,
+            None => serenity::Command::delete_global_command(http, previous_command.id).await,
+        };
+        if let Err(e) = result {
+            failures.push((format!("deleting {}", label), e));
+        }
+    }
+    (registered, failures)
+}
+
+/// Formats the failures of a registration for the status message, listing at most a few of them
+/// to stay within Discord's message length limit
+fn format_registration_failures(failures: &[RegistrationFailure]) -> String {
+    const MAX_LISTED: usize = 10;
+
+    let mut text = format!(":x: {} step(s) failed:", failures.len());
+    for (label, error) in failures.iter().take(MAX_LISTED) {
+        let mut error = error.to_string();
+        if error.len() > 150 {
+            let end = (0..=150)
+                .rev()
+                .find(|&i| error.is_char_boundary(i))
+                .unwrap_or(0);
+            error.truncate(end);
+            error += "…";
+        }
+        text += &format!("\n- `{}`: {}", label, error);
+    }
+    if failures.len() > MAX_LISTED {
+        text += &format!("\n- ...and {} more", failures.len() - MAX_LISTED);
+    }
+    text
+}
+
+/// Formats the status message of [`register_application_commands_buttons`], shortening the
+/// report to stay within Discord's message length limit
+fn format_registration_status(
+    time_taken: std::time::Duration,
+    report: Option<&RegistrationReport>,
+    failures: &[RegistrationFailure],
+) -> String {
+    /// Discord's limit for the length of message content
+    const MAX_LENGTH: usize = 2000;
+
+    let mut status = format!(
+        "{} Took {}ms",
+        match failures.is_empty() {
+            true => ":white_check_mark: Done!",
+            false => ":warning: Done with errors!",
+        },
+        time_taken.as_millis(),
+    );
+    let failures = match failures.is_empty() {
+        true => String::new(),
+        false => format!("\n{}", format_registration_failures(failures)),
+    };
+    match report {
+        Some(report) => {
+            let budget = MAX_LENGTH
+                .saturating_sub(status.chars().count() + failures.chars().count())
+                .saturating_sub("\n```\n\n```".len());
+            status += &format!("\n```\n{}\n```", truncate(&report.to_string(), budget));
+        }
+        None => {
+            status += "\nCouldn't compare with the registered commands, so changes aren't listed"
+        }
+    }
+    status += &failures;
+    truncate(&status, MAX_LENGTH)
 }
 
 /// _Note: you probably want [`register_application_commands_buttons`] instead; it's easier and more
@@ -141,17 +592,32 @@ pub async fn register_application_commands<T: Send + Sync + 'static, E>(
 /// lumi::FrameworkOptions {
 ///     commands: vec![
 ///         // ...
-///         register(),
+///         register().into(),
 ///     ],
 /// #   ..Default::default()
 /// };
 /// ```
 ///
 /// Which you can call like any prefix command, for example `@your_bot register`.
+///
+/// If Discord rejects some of the commands, e.g. because one exceeds the option limits, the other
+/// commands are still registered and the status message lists which commands failed and why.
 pub async fn register_application_commands_buttons<T: Send + Sync + 'static, E>(
     ctx: crate::Context<'_, T, E>,
 ) -> Result<(), serenity::Error> {
-    let create_commands = create_application_commands(&ctx.framework().options().commands);
+    register_application_commands_buttons_filtered(ctx, |_| true).await
+}
+
+/// Like [`register_application_commands_buttons`], but only registers the top-level commands for
+/// which `filter` returns true.
+///
+/// See [`register_in_guild_filtered`].
+pub async fn register_application_commands_buttons_filtered<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    filter: impl Fn(&crate::Command<T, E>) -> bool,
+) -> Result<(), serenity::Error> {
+    let create_commands =
+        create_application_commands_filtered(&ctx.framework().options().commands, filter);
     let num_commands = create_commands.len();
 
     let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
@@ -229,44 +695,89 @@ pub async fn register_application_commands_buttons<T: Send + Sync + 'static, E>(
 
     let start_time = std::time::Instant::now();
 
-    if global {
-        if register {
-            ctx.say(format!(
-                ":gear: Registering {num_commands} global commands...",
-            ))
-            .await?;
-            serenity::Command::set_global_commands(ctx.http(), &create_commands).await?;
-        } else {
-            ctx.say(":gear: Unregistering global commands...").await?;
-            serenity::Command::set_global_commands(ctx.http(), &[]).await?;
-        }
-    } else {
-        let guild_id = match ctx.guild_id() {
-            Some(x) => x,
+    let guild_id = match global {
+        true => None,
+        false => match ctx.guild_id() {
+            Some(x) => Some(x),
             None => {
                 ctx.say(":x: Must be called in guild").await?;
                 return Ok(());
             }
-        };
-        if register {
-            ctx.say(format!(
-                ":gear: Registering {num_commands} guild commands...",
-            ))
-            .await?;
-            guild_id.set_commands(ctx.http(), &create_commands).await?;
-        } else {
-            ctx.say(":gear: Unregistering guild commands...").await?;
-            guild_id.set_commands(ctx.http(), &[]).await?;
+        },
+    };
+    let scope = match global {
+        true => "global",
+        false => "guild",
+    };
+
+    // Errors are collected instead of aborting, so the status message can tell which command
+    // or step broke the registration
+    let mut failures = Vec::new();
+    let previous = match guild_id {
+        Some(guild_id) => guild_id.get_commands(ctx.http()).await,
+        None => serenity::Command::get_global_commands(ctx.http()).await,
+    };
+    let previous = match previous {
+        Ok(previous) => Some(previous),
+        Err(e) => {
+            failures.push(("fetching registered commands".to_string(), e));
+            None
         }
-    }
+    };
+
+    let new_commands = if register {
+        ctx.say(format!(
+            ":gear: Registering {num_commands} {scope} commands...",
+        ))
+        .await?;
+        create_commands
+    } else {
+        ctx.say(format!(":gear: Unregistering {scope} commands..."))
+            .await?;
+        Vec::new()
+    };
+    let (registered, set_failures) = set_commands_itemized(
+        ctx.http(),
+        guild_id,
+        previous.as_deref().unwrap_or_default(),
+        new_commands,
+    )
+    .await;
+    failures.extend(set_failures);
+    let report = previous
+        .as_deref()
+        .map(|previous| RegistrationReport::compute(previous, &registered));
 
-    // Calulate time taken and send message
-    let time_taken = start_time.elapsed();
-    ctx.say(format!(
-        ":white_check_mark: Done! Took {}ms",
-        time_taken.as_millis()
-    ))
-    .await?;
+    let status = format_registration_status(start_time.elapsed(), report.as_ref(), &failures);
+    ctx.say(status).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+#[test]
+fn test_format_registration_status() {
+    let time_taken = std::time::Duration::from_millis(12);
+    let report = RegistrationReport {
+        created: vec!["ping".into()],
+        unchanged: vec!["help".into()],
+        ..Default::default()
+    };
+    assert_eq!(
+        format_registration_status(time_taken, Some(&report), &[]),
+        ":white_check_mark: Done! Took 12ms\n```\nCreated (1): ping\nUnchanged: 1\n```"
+    );
+    assert_eq!(
+        format_registration_status(time_taken, None, &[]),
+        ":white_check_mark: Done! Took 12ms\nCouldn't compare with the registered commands, so \
+        changes aren't listed"
+    );
+
+    let report = RegistrationReport {
+        created: (0..500).map(|i| format!("command{}", i)).collect(),
+        ..Default::default()
+    };
+    let status = format_registration_status(time_taken, Some(&report), &[]);
+    assert!(status.chars().count() <= 2000);
+    assert!(status.ends_with("…\n```"));
+}
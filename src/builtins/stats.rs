@@ -0,0 +1,110 @@
+//! Showing which commands are used how often
+
+use crate::serenity_prelude as serenity;
+
+/// How many commands are listed by [`stats`]
+const MAX_LISTED_COMMANDS: usize = 20;
+
+/// How many of the most active users are listed per command by [`stats`]
+const MAX_LISTED_USERS: usize = 3;
+
+/// Lists the most used commands with their invocation count, when they were last used and by whom,
+/// based on [`crate::FrameworkOptions::usage_stats`], in an ephemeral response.
+///
+/// You probably want to use this by wrapping it in a small owners-only `stats` command:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Shows how often commands are used
+/// #[lumi::command(prefix_command, slash_command, owners_only)]
+/// pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+///     lumi::builtins::stats(ctx).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn stats<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+) -> Result<(), serenity::Error> {
+    let response = match ctx.framework().usage_stats() {
+        None => "Usage statistics are not enabled".to_owned(),
+        Some(usage_stats) => format_stats(&usage_stats.commands()),
+    };
+
+    ctx.send(
+        crate::CreateReply::default()
+            .content(response)
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Formats the usage statistics of the most used commands, listing as many of them as fit into
+/// Discord's message length limit
+fn format_stats(commands: &[(String, crate::CommandUsage)]) -> String {
+    /// Discord's limit for the length of message content
+    const MAX_LENGTH: usize = 2000;
+    /// Room left for the "...and N more" line
+    const MORE_LENGTH: usize = 32;
+
+    if commands.is_empty() {
+        return "No commands have been used yet".to_owned();
+    }
+
+    let mut response = String::from("Most used commands:");
+    let mut listed = 0;
+    for (name, usage) in commands.iter().take(MAX_LISTED_COMMANDS) {
+        let last_invoked = usage
+            .last_invoked
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let top_users = usage
+            .top_users(MAX_LISTED_USERS)
+            .iter()
+            .map(|(user_id, count)| format!("<@{}> ({})", user_id, count))
+            .collect::<Vec<_>>();
+        let line = format!(
+            "\n`{}`: {} uses, last <t:{}:R>, top users: {}",
+            name,
+            usage.invocations,
+            last_invoked,
+            top_users.join(", ")
+        );
+        if response.chars().count() + line.chars().count() > MAX_LENGTH - MORE_LENGTH {
+            break;
+        }
+        response += &line;
+        listed += 1;
+    }
+    if commands.len() > listed {
+        response += &format!("\n...and {} more", commands.len() - listed);
+    }
+    response
+}
+
+#[cfg(test)]
+#[test]
+fn test_format_stats() {
+    let usage = |invocations| crate::CommandUsage {
+        invocations,
+        last_invoked: std::time::SystemTime::UNIX_EPOCH,
+        users: [(serenity::UserId::new(1), invocations)].into(),
+        __non_exhaustive: (),
+    };
+
+    assert_eq!(format_stats(&[]), "No commands have been used yet");
+    assert_eq!(
+        format_stats(&[("ping".into(), usage(2))]),
+        "Most used commands:\n`ping`: 2 uses, last <t:0:R>, top users: <@1> (2)"
+    );
+
+    // Long command names are cut off at the message length limit
+    let commands = (0..MAX_LISTED_COMMANDS + 5)
+        .map(|i| (format!("{}{}", "a".repeat(150), i), usage(1)))
+        .collect::<Vec<_>>();
+    let response = format_stats(&commands);
+    assert!(response.chars().count() <= 2000);
+    assert!(response.ends_with("more"));
+}
@@ -0,0 +1,134 @@
+//! Restricting commands to certain channels per guild, see [`ChannelRestrictionStore`]
+
+use crate::{guild_settings::BoxedError, serenity_prelude as serenity};
+use std::collections::HashMap;
+
+/// What a channel restriction applies to
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RestrictionTarget<'a> {
+    /// A single command, identified by [`crate::Command::qualified_name`]
+    Command(&'a str),
+    /// All commands of a [`crate::Command::category`]
+    Category(&'a str),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Stores in which channels commands may be used, per guild.
+///
+/// Store in [`crate::FrameworkOptions::channel_restrictions`]. It's consulted before the checks of
+/// every invocation in a guild. If the command, or else its category, is restricted to a set of
+/// channels and the invocation happened elsewhere, the command fails with
+/// [`crate::FrameworkError::WrongChannel`]. Restricting a parent command restricts all its
+/// subcommands too. Threads count as channels of their own.
+///
+/// [`MemoryChannelRestrictionStore`] keeps the settings in memory only. To keep them across
+/// restarts, implement this trait on top of your database.
+/// [`crate::builtins::set_command_channels`] lets server admins change the settings.
+#[async_trait::async_trait]
+pub trait ChannelRestrictionStore: Send + Sync {
+    /// Returns the channels the target is restricted to, or `None` if it's not restricted
+    async fn allowed_channels(
+        &self,
+        guild_id: serenity::GuildId,
+        target: RestrictionTarget<'_>,
+    ) -> Result<Option<Vec<serenity::GenericChannelId>>, BoxedError>;
+    /// Restricts the target to the given channels, or lifts the restriction if `None`
+    async fn set_allowed_channels(
+        &self,
+        guild_id: serenity::GuildId,
+        target: RestrictionTarget<'_>,
+        channels: Option<Vec<serenity::GenericChannelId>>,
+    ) -> Result<(), BoxedError>;
+}
+
+/// Owned version of [`RestrictionTarget`], used as key in [`MemoryChannelRestrictionStore`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum OwnedRestrictionTarget {
+    /// See [`RestrictionTarget::Command`]
+    Command(String),
+    /// See [`RestrictionTarget::Category`]
+    Category(String),
+}
+
+impl From<RestrictionTarget<'_>> for OwnedRestrictionTarget {
+    fn from(target: RestrictionTarget<'_>) -> Self {
+        match target {
+            RestrictionTarget::Command(name) => Self::Command(name.to_owned()),
+            RestrictionTarget::Category(name) => Self::Category(name.to_owned()),
+            RestrictionTarget::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+/// A [`ChannelRestrictionStore`] which keeps the restrictions in memory
+#[derive(Debug, Default)]
+pub struct MemoryChannelRestrictionStore {
+    /// Allowed channels per guild and restricted command or category
+    restrictions: parking_lot::Mutex<
+        HashMap<(serenity::GuildId, OwnedRestrictionTarget), Vec<serenity::GenericChannelId>>,
+    >,
+}
+
+impl MemoryChannelRestrictionStore {
+    /// Creates a store without any restrictions
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl ChannelRestrictionStore for MemoryChannelRestrictionStore {
+    async fn allowed_channels(
+        &self,
+        guild_id: serenity::GuildId,
+        target: RestrictionTarget<'_>,
+    ) -> Result<Option<Vec<serenity::GenericChannelId>>, BoxedError> {
+        let key = (guild_id, target.into());
+        Ok(self.restrictions.lock().get(&key).cloned())
+    }
+
+    async fn set_allowed_channels(
+        &self,
+        guild_id: serenity::GuildId,
+        target: RestrictionTarget<'_>,
+        channels: Option<Vec<serenity::GenericChannelId>>,
+    ) -> Result<(), BoxedError> {
+        let key = (guild_id, target.into());
+        let mut restrictions = self.restrictions.lock();
+        match channels {
+            Some(channels) => restrictions.insert(key, channels),
+            None => restrictions.remove(&key),
+        };
+        Ok(())
+    }
+}
+
+/// Returns the channels the command is restricted to in the invocation guild, if it's restricted
+/// and the invocation channel isn't among them
+pub(crate) async fn disallowed_channel_restriction<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    command: &crate::Command<T, E>,
+) -> Result<Option<Vec<serenity::GenericChannelId>>, BoxedError> {
+    let (Some(channel_restrictions), Some(guild_id)) = (
+        &ctx.framework().options().channel_restrictions,
+        ctx.guild_id(),
+    ) else {
+        return Ok(None);
+    };
+
+    // A restriction of the command itself takes precedence over one of its category
+    let mut targets = vec![RestrictionTarget::Command(&command.qualified_name)];
+    if let Some(category) = &command.category {
+        targets.push(RestrictionTarget::Category(category));
+    }
+    for target in targets {
+        if let Some(allowed_channels) = channel_restrictions
+            .allowed_channels(guild_id, target)
+            .await?
+        {
+            return Ok((!allowed_channels.contains(&ctx.channel_id())).then_some(allowed_channels));
+        }
+    }
+    Ok(None)
+}
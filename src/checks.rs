@@ -0,0 +1,98 @@
+//! Combinators to compose [`Check`]s from smaller ones
+//!
+//! ```rust
+//! # type Error = Box<dyn std::error::Error + Send + Sync>;
+//! # type Context<'a> = lumi::Context<'a, (), Error>;
+//! # async fn is_owner(_: Context<'_>) -> Result<bool, Error> { Ok(true) }
+//! # async fn is_moderator(_: Context<'_>) -> Result<bool, Error> { Ok(true) }
+//! # async fn is_muted(_: Context<'_>) -> Result<bool, Error> { Ok(true) }
+//! use lumi::{Check, checks};
+//!
+//! // Owners, or moderators who aren't muted
+//! let check: Check<(), Error> = checks::any([
+//!     Check::new(|ctx| Box::pin(is_owner(ctx))),
+//!     checks::all([
+//!         Check::new(|ctx| Box::pin(is_moderator(ctx))),
+//!         checks::not(Check::new(|ctx| Box::pin(is_muted(ctx)))),
+//!     ]),
+//! ])
+//! .name("owner_or_moderator")
+//! .failure_message("Only owners and moderators can use this command");
+//! ```
+
+use crate::{Check, CheckCallback};
+
+/// Creates a check which passes if all of the given checks pass
+///
+/// An empty list of checks always passes.
+pub fn all<T, E>(checks: impl IntoIterator<Item = Check<T, E>>) -> Check<T, E> {
+    Check::from_callback(CheckCallback::All(checks.into_iter().collect()))
+}
+
+/// Creates a check which passes if any of the given checks passes
+///
+/// An empty list of checks never passes.
+pub fn any<T, E>(checks: impl IntoIterator<Item = Check<T, E>>) -> Check<T, E> {
+    Check::from_callback(CheckCallback::Any(checks.into_iter().collect()))
+}
+
+/// Creates a check which passes if the given check fails
+///
+/// If the given check returns an error, the error is passed through.
+pub fn not<T, E>(check: Check<T, E>) -> Check<T, E> {
+    Check::from_callback(CheckCallback::Not(Box::new(check)))
+}
+
+/// Evaluates a check whose function checks are named after their outcome: `pass`, `fail`, or
+/// anything else for an error. Returns the outcome and the function checks run, in order
+#[cfg(test)]
+fn evaluate_named(check: &Check<(), String>) -> (Result<bool, String>, Vec<String>) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let log = parking_lot::Mutex::new(Vec::new());
+    let result = runtime.block_on(check.evaluate(
+        &log,
+        |_, _| Box::pin(async { true }),
+        |check, log| {
+            let name = check.name.as_deref().unwrap_or_default().to_owned();
+            log.lock().push(name.clone());
+            Box::pin(async move {
+                match &*name {
+                    "pass" => Ok(true),
+                    "fail" => Ok(false),
+                    _ => Err(name),
+                }
+            })
+        },
+    ));
+    (result, log.into_inner())
+}
+
+#[cfg(test)]
+#[test]
+fn test_combinators() {
+    let check = |name: &'static str| Check::new(|_| Box::pin(async { Ok(true) })).name(name);
+
+    assert_eq!(evaluate_named(&all([])).0, Ok(true));
+    assert_eq!(evaluate_named(&any([])).0, Ok(false));
+    assert_eq!(evaluate_named(&not(check("pass"))).0, Ok(false));
+    assert_eq!(evaluate_named(&not(check("fail"))).0, Ok(true));
+
+    // Short-circuiting like && and ||
+    let (result, log) = evaluate_named(&all([check("pass"), check("fail"), check("error")]));
+    assert_eq!(result, Ok(false));
+    assert_eq!(log, ["pass", "fail"]);
+    let (result, log) = evaluate_named(&any([check("fail"), check("pass"), check("error")]));
+    assert_eq!(result, Ok(true));
+    assert_eq!(log, ["fail", "pass"]);
+
+    // Errors are returned immediately, even through not
+    let (result, log) = evaluate_named(&any([check("error"), check("pass")]));
+    assert_eq!(result, Err("error".to_owned()));
+    assert_eq!(log, ["error"]);
+    let nested = all([check("pass"), not(any([check("fail"), check("error")]))]);
+    let (result, log) = evaluate_named(&nested);
+    assert_eq!(result, Err("error".to_owned()));
+    assert_eq!(log, ["pass", "fail", "error"]);
+}
@@ -3,8 +3,16 @@
 
 use crate::{serenity_prelude as serenity, CowVec, PopArgumentResult};
 
+/// Discord's limit for the number of choices of a parameter. Choice parameters with more choices
+/// are autocompleted instead.
+const MAX_CHOICES: usize = 25;
+
 /// This trait is implemented by [`crate::macros::ChoiceParameter`]. See its docs for more
 /// information
+///
+/// Discord allows at most 25 choices per parameter. With more choices, the parameter is registered
+/// as an autocompleted string parameter instead, which suggests the choices matching the user's
+/// input.
 pub trait ChoiceParameter: Sized {
     /// Returns all possible choices for this parameter, in the order they will appear in Discord.
     fn list() -> CowVec<crate::CommandParameterChoice>;
@@ -31,6 +39,13 @@ impl<T: ChoiceParameter> crate::SlashArgument for T {
     ) -> ::std::result::Result<Self, crate::SlashArgError> {
         let choice_key = match value {
             serenity::ResolvedValue::Integer(int) => *int as u64,
+            // Autocompleted choice parameter, see `Self::autocomplete_callback`
+            serenity::ResolvedValue::String(name) => {
+                return Self::from_name(name).ok_or_else(|| crate::SlashArgError::Parse {
+                    error: crate::InvalidChoice::default().into(),
+                    input: (*name).to_owned(),
+                });
+            }
             _ => {
                 return Err(crate::SlashArgError::CommandStructureMismatch {
                     description: "expected u64",
@@ -44,34 +59,87 @@ impl<T: ChoiceParameter> crate::SlashArgument for T {
     }
 
     fn create(builder: serenity::CreateCommandOption<'_>) -> serenity::CreateCommandOption<'_> {
-        builder.kind(serenity::CommandOptionType::Integer)
+        match Self::list().len() > MAX_CHOICES {
+            true => builder.kind(serenity::CommandOptionType::String),
+            false => builder.kind(serenity::CommandOptionType::Integer),
+        }
     }
 
     fn choices() -> CowVec<crate::CommandParameterChoice> {
-        Self::list()
+        // Discord doesn't allow choices and autocomplete together
+        match Self::list().len() > MAX_CHOICES {
+            true => std::borrow::Cow::Borrowed(&[]),
+            false => Self::list(),
+        }
+    }
+
+    fn autocomplete_callback<U, E>() -> Option<
+        for<'a> fn(
+            crate::ApplicationContext<'a, U, E>,
+            &'a str,
+        ) -> crate::BoxFuture<'a, serenity::CreateAutocompleteResponse<'a>>,
+    > {
+        match Self::list().len() > MAX_CHOICES {
+            true => Some(autocomplete_choice::<Self, U, E>),
+            false => None,
+        }
     }
 }
 
+/// Suggests the choices whose name or name in the user's locale contains the partial input, for
+/// choice parameters with more than [`MAX_CHOICES`] choices
+fn autocomplete_choice<'a, C: ChoiceParameter, U, E>(
+    ctx: crate::ApplicationContext<'a, U, E>,
+    partial: &'a str,
+) -> crate::BoxFuture<'a, serenity::CreateAutocompleteResponse<'a>> {
+    let partial = partial.to_lowercase();
+    let locale: &str = &ctx.interaction.locale;
+    let response = C::list()
+        .iter()
+        .filter_map(|choice| {
+            let label = choice
+                .localizations
+                .iter()
+                .find(|(choice_locale, _)| choice_locale == locale)
+                .map_or(&choice.name, |(_, localized_name)| localized_name);
+            let matches = label.to_lowercase().contains(&partial)
+                || choice.name.to_lowercase().contains(&partial);
+            matches.then(|| (label.to_string(), choice.name.to_string()))
+        })
+        .take(MAX_CHOICES)
+        .fold(
+            serenity::CreateAutocompleteResponse::new(),
+            |response, (label, name)| response.add_string_choice(label, name),
+        );
+    Box::pin(std::future::ready(response))
+}
+
 #[async_trait::async_trait]
 impl<'a, T: ChoiceParameter> crate::PopArgument<'a> for T {
     async fn pop_from(
         args: &'a str,
         attachment_index: usize,
-        ctx: &serenity::Context,
-        msg: &serenity::Message,
+        _: &serenity::Context,
+        _: &serenity::Message,
     ) -> PopArgumentResult<'a, Self> {
-        let (args, attachment_index, s) =
-            <String as crate::PopArgument<'a>>::pop_from(args, attachment_index, ctx, msg).await?;
+        let (args, s) = crate::prefix_argument::pop_string(args).map_err(|err| {
+            (
+                Box::new(err) as Box<dyn std::error::Error + Send + Sync>,
+                Some(args.to_owned()),
+            )
+        })?;
 
         Ok((
             args,
             attachment_index,
-            Self::from_name(&s).ok_or((
-                Box::new(crate::InvalidChoice {
-                    __non_exhaustive: (),
-                }) as Box<dyn std::error::Error + Send + Sync>,
-                Some(s),
-            ))?,
+            Self::from_name(&s).ok_or_else(|| {
+                (
+                    Box::new(crate::InvalidChoice {
+                        __non_exhaustive: (),
+                    }) as Box<dyn std::error::Error + Send + Sync>,
+                    Some(s.into_owned()),
+                )
+            })?,
         ))
     }
 }
@@ -0,0 +1,134 @@
+//! Custom command aliases per guild, see [`CommandAliasStore`]
+
+use crate::{guild_settings::BoxedError, serenity_prelude as serenity};
+use std::collections::HashMap;
+
+/// Stores custom aliases for prefix commands, per guild.
+///
+/// Store in [`crate::PrefixFrameworkOptions::command_aliases`]. Unlike [`crate::Command::aliases`],
+/// which are fixed at compile time and apply everywhere, these aliases can be configured by server
+/// admins at runtime, e.g. mapping `b` to `ban`. Aliases map to the
+/// [`crate::Command::qualified_name`] of a command, so `cs` can map to `config set`.
+///
+/// The store is consulted when the text after the prefix doesn't start with the name of any
+/// command, so aliases can't shadow regular command names. The aliased command is dispatched
+/// normally, including its checks and cooldowns. If
+/// [`crate::PrefixFrameworkOptions::case_insensitive_commands`] is set, aliases are passed to the
+/// store in lowercase.
+///
+/// [`MemoryCommandAliasStore`] keeps the aliases in memory only. To keep them across restarts,
+/// implement this trait on top of your database. [`crate::builtins::set_command_alias`] lets
+/// server admins change the aliases.
+#[async_trait::async_trait]
+pub trait CommandAliasStore: Send + Sync {
+    /// Returns the qualified name of the command the alias maps to in the guild, if any
+    async fn resolve(
+        &self,
+        guild_id: serenity::GuildId,
+        alias: &str,
+    ) -> Result<Option<String>, BoxedError>;
+    /// Maps the alias to the command with the given qualified name in the guild, or removes the
+    /// alias if `None`
+    async fn set(
+        &self,
+        guild_id: serenity::GuildId,
+        alias: &str,
+        command: Option<&str>,
+    ) -> Result<(), BoxedError>;
+}
+
+/// A [`CommandAliasStore`] which keeps the aliases in memory
+#[derive(Debug, Default)]
+pub struct MemoryCommandAliasStore {
+    /// Qualified command names by guild ID and alias
+    aliases: parking_lot::Mutex<HashMap<(serenity::GuildId, String), String>>,
+}
+
+impl MemoryCommandAliasStore {
+    /// Creates a store without any aliases
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandAliasStore for MemoryCommandAliasStore {
+    async fn resolve(
+        &self,
+        guild_id: serenity::GuildId,
+        alias: &str,
+    ) -> Result<Option<String>, BoxedError> {
+        let key = (guild_id, alias.to_owned());
+        Ok(self.aliases.lock().get(&key).cloned())
+    }
+
+    async fn set(
+        &self,
+        guild_id: serenity::GuildId,
+        alias: &str,
+        command: Option<&str>,
+    ) -> Result<(), BoxedError> {
+        let key = (guild_id, alias.to_owned());
+        let mut aliases = self.aliases.lock();
+        match command {
+            Some(command) => aliases.insert(key, command.to_owned()),
+            None => aliases.remove(&key),
+        };
+        Ok(())
+    }
+}
+
+/// Lowercases the alias if [`crate::PrefixFrameworkOptions::case_insensitive_commands`] is set
+pub(crate) fn normalize_alias<T, E>(
+    prefix_options: &crate::PrefixFrameworkOptions<T, E>,
+    alias: &str,
+) -> String {
+    match prefix_options.case_insensitive_commands {
+        true => alias.to_lowercase(),
+        false => alias.to_owned(),
+    }
+}
+
+/// Rewrites the text after the prefix if it starts with a custom alias of the message's guild,
+/// replacing the alias with the qualified name of the aliased command
+pub(crate) async fn resolve_command_alias<T, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    msg: &serenity::Message,
+    msg_content: &str,
+) -> Option<String> {
+    let command_aliases = framework.options.prefix_options.command_aliases.as_ref()?;
+    let guild_id = msg.guild_id?;
+
+    let (alias, args) = crate::dispatch::split_command_name(msg_content);
+    let alias = normalize_alias(&framework.options.prefix_options, alias);
+    match command_aliases.resolve(guild_id, &alias).await {
+        Ok(command) => command.map(|command| format!("{} {}", command, args)),
+        Err(e) => {
+            tracing::warn!("Failed to resolve command alias `{}`: {}", alias, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_alias_case() {
+    let mut prefix_options = crate::PrefixFrameworkOptions::<(), ()>::default();
+    assert_eq!(normalize_alias(&prefix_options, "Ban"), "Ban");
+    prefix_options.case_insensitive_commands = true;
+    assert_eq!(normalize_alias(&prefix_options, "Ban"), "ban");
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let store = MemoryCommandAliasStore::new();
+        let guild_id = serenity::GuildId::new(1);
+        store.set(guild_id, "B", Some("ban")).await.unwrap();
+        assert_eq!(
+            store.resolve(guild_id, "B").await.unwrap().as_deref(),
+            Some("ban")
+        );
+        assert_eq!(store.resolve(guild_id, "b").await.unwrap(), None);
+    });
+}
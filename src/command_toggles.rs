@@ -0,0 +1,73 @@
+//! Enabling and disabling individual commands per guild, see [`CommandToggleStore`]
+
+use crate::{guild_settings::BoxedError, serenity_prelude as serenity};
+use std::collections::HashSet;
+
+/// Stores which commands are disabled in which guild.
+///
+/// Store in [`crate::FrameworkOptions::command_toggles`]. It's consulted before the other checks
+/// of every invocation in a guild, and disabled commands fail with
+/// [`crate::FrameworkError::CommandDisabled`]. Disabling a parent command disables all its
+/// subcommands too. Commands are identified by [`crate::Command::qualified_name`].
+///
+/// [`MemoryCommandToggleStore`] keeps the settings in memory only. To keep them across restarts,
+/// implement this trait on top of your database. [`crate::builtins::set_command_enabled`] lets
+/// server admins change the settings.
+#[async_trait::async_trait]
+pub trait CommandToggleStore: Send + Sync {
+    /// Returns whether the command with the given qualified name may be used in the guild
+    async fn is_enabled(
+        &self,
+        guild_id: serenity::GuildId,
+        command: &str,
+    ) -> Result<bool, BoxedError>;
+    /// Enables or disables the command with the given qualified name in the guild
+    async fn set(
+        &self,
+        guild_id: serenity::GuildId,
+        command: &str,
+        enabled: bool,
+    ) -> Result<(), BoxedError>;
+}
+
+/// A [`CommandToggleStore`] which keeps the disabled commands in memory
+#[derive(Debug, Default)]
+pub struct MemoryCommandToggleStore {
+    /// Guild IDs and qualified names of all disabled commands
+    disabled: parking_lot::Mutex<HashSet<(serenity::GuildId, String)>>,
+}
+
+impl MemoryCommandToggleStore {
+    /// Creates a store where all commands are enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandToggleStore for MemoryCommandToggleStore {
+    async fn is_enabled(
+        &self,
+        guild_id: serenity::GuildId,
+        command: &str,
+    ) -> Result<bool, BoxedError> {
+        let key = (guild_id, command.to_owned());
+        Ok(!self.disabled.lock().contains(&key))
+    }
+
+    async fn set(
+        &self,
+        guild_id: serenity::GuildId,
+        command: &str,
+        enabled: bool,
+    ) -> Result<(), BoxedError> {
+        let key = (guild_id, command.to_owned());
+        let mut disabled = self.disabled.lock();
+        if enabled {
+            disabled.remove(&key);
+        } else {
+            disabled.insert(key);
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@
 use crate::serenity_prelude as serenity;
 // I usually don't really do imports, but these are very convenient
 use std::collections::HashMap;
+use std::hash::{BuildHasher as _, Hash};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::{Duration, Instant};
 
 /// Subset of [`crate::Context`] so that [`Cooldowns`] can be used without requiring a full [Context](`crate::Context`)
@@ -34,22 +36,99 @@ pub struct CooldownConfig {
     pub __non_exhaustive: (),
 }
 
+/// Number of independently locked shards of [`ShardedInvocations`]
+const SHARD_COUNT: usize = 16;
+
+/// Timestamps of the last invocation per key, split into independently locked shards so that
+/// concurrent invocations by different users, channels or guilds rarely contend
+#[derive(Debug)]
+struct ShardedInvocations<K> {
+    /// Picks the shard of a key
+    hasher: std::collections::hash_map::RandomState,
+    /// Each key is stored in the shard picked by its hash
+    shards: Box<[parking_lot::Mutex<HashMap<K, Instant>>]>,
+}
+
+impl<K: Hash + Eq> ShardedInvocations<K> {
+    /// Creates empty shards
+    fn new() -> Self {
+        Self {
+            hasher: Default::default(),
+            shards: (0..SHARD_COUNT).map(|_| Default::default()).collect(),
+        }
+    }
+
+    /// Returns the shard which stores the key
+    fn shard(&self, key: &K) -> &parking_lot::Mutex<HashMap<K, Instant>> {
+        &self.shards[self.hasher.hash_one(key) as usize % self.shards.len()]
+    }
+
+    /// Returns the last invocation of the key
+    fn get(&self, key: &K) -> Option<Instant> {
+        self.shard(key).lock().get(key).copied()
+    }
+
+    /// Sets the last invocation of the key
+    fn insert(&self, key: K, instant: Instant) {
+        self.shard(&key).lock().insert(key, instant);
+    }
+}
+
+impl<K: Clone> Clone for ShardedInvocations<K> {
+    fn clone(&self) -> Self {
+        Self {
+            hasher: self.hasher.clone(),
+            shards: self
+                .shards
+                .iter()
+                .map(|shard| parking_lot::Mutex::new(shard.lock().clone()))
+                .collect(),
+        }
+    }
+}
+
 /// Tracks all types of cooldowns for a single command
 ///
 /// You probably don't need to use this directly. `#[lumi::command]` automatically generates a
 /// cooldown handler.
-#[derive(Default, Clone, Debug, PartialEq, Eq)]
+///
+/// Safe to share between concurrent invocations without further locking: the global cooldown is
+/// an atomic, and the other buckets are sharded by key, so that only invocations whose users,
+/// channels or guilds land in the same shard contend.
+#[derive(Debug)]
 pub struct CooldownTracker {
-    /// Stores the timestamp of the last global invocation
-    global_invocation: Option<Instant>,
+    /// Reference point of [`Self::global_invocation`]
+    epoch: Instant,
+    /// Nanoseconds from [`Self::epoch`] to the last global invocation, negative if it was before.
+    /// [`i64::MIN`] if there was none
+    global_invocation: AtomicI64,
     /// Stores the timestamps of the last invocation per user
-    user_invocations: HashMap<serenity::UserId, Instant>,
+    user_invocations: ShardedInvocations<serenity::UserId>,
     /// Stores the timestamps of the last invocation per guild
-    guild_invocations: HashMap<serenity::GuildId, Instant>,
+    guild_invocations: ShardedInvocations<serenity::GuildId>,
     /// Stores the timestamps of the last invocation per channel
-    channel_invocations: HashMap<serenity::GenericChannelId, Instant>,
+    channel_invocations: ShardedInvocations<serenity::GenericChannelId>,
     /// Stores the timestamps of the last invocation per member (user and guild)
-    member_invocations: HashMap<(serenity::UserId, serenity::GuildId), Instant>,
+    member_invocations: ShardedInvocations<(serenity::UserId, serenity::GuildId)>,
+}
+
+impl Default for CooldownTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for CooldownTracker {
+    fn clone(&self) -> Self {
+        Self {
+            epoch: self.epoch,
+            global_invocation: AtomicI64::new(self.global_invocation.load(Ordering::Relaxed)),
+            user_invocations: self.user_invocations.clone(),
+            guild_invocations: self.guild_invocations.clone(),
+            channel_invocations: self.channel_invocations.clone(),
+            member_invocations: self.member_invocations.clone(),
+        }
+    }
 }
 
 /// Possible types of command cooldowns.
@@ -69,6 +148,49 @@ pub enum CooldownType {
     Member((serenity::UserId, serenity::GuildId)),
 }
 
+/// Identifies one of the cooldown buckets configured in [`CooldownConfig`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CooldownBucket {
+    /// See [`CooldownConfig::global`]
+    Global,
+    /// See [`CooldownConfig::user`]
+    User,
+    /// See [`CooldownConfig::guild`]
+    Guild,
+    /// See [`CooldownConfig::channel`]
+    Channel,
+    /// See [`CooldownConfig::member`]
+    Member,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for CooldownBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Global => "global",
+            Self::User => "user",
+            Self::Guild => "guild",
+            Self::Channel => "channel",
+            Self::Member => "member",
+            Self::__NonExhaustive => unreachable!(),
+        })
+    }
+}
+
+/// Returned from [`CooldownTracker::remaining_cooldown_bucket`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemainingCooldown {
+    /// Time until the command may be invoked for the next time
+    pub remaining: Duration,
+    /// The bucket with the longest remaining cooldown
+    pub bucket: CooldownBucket,
+    /// The configured cooldown duration of [`Self::bucket`]
+    pub duration: Duration,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
 /// **Renamed to [`CooldownTracker`]**
 pub use CooldownTracker as Cooldowns;
 
@@ -76,14 +198,35 @@ impl CooldownTracker {
     /// Create a new cooldown tracker
     pub fn new() -> Self {
         Self {
-            global_invocation: None,
-            user_invocations: HashMap::new(),
-            guild_invocations: HashMap::new(),
-            channel_invocations: HashMap::new(),
-            member_invocations: HashMap::new(),
+            epoch: Instant::now(),
+            global_invocation: AtomicI64::new(i64::MIN),
+            user_invocations: ShardedInvocations::new(),
+            guild_invocations: ShardedInvocations::new(),
+            channel_invocations: ShardedInvocations::new(),
+            member_invocations: ShardedInvocations::new(),
+        }
+    }
+
+    /// Returns the last global invocation
+    fn global_invocation(&self) -> Option<Instant> {
+        match self.global_invocation.load(Ordering::Relaxed) {
+            i64::MIN => None,
+            nanos if nanos < 0 => Some(self.epoch - Duration::from_nanos(nanos.unsigned_abs())),
+            nanos => Some(self.epoch + Duration::from_nanos(nanos.unsigned_abs())),
         }
     }
 
+    /// Sets the last global invocation
+    fn set_global_invocation(&self, instant: Instant) {
+        // Saturates after 292 years in either direction
+        let nanos = |duration: Duration| i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX);
+        let nanos = match instant.checked_duration_since(self.epoch) {
+            Some(after) => nanos(after),
+            None => -nanos(self.epoch - instant),
+        };
+        self.global_invocation.store(nanos, Ordering::Relaxed);
+    }
+
     /// Queries the cooldown buckets and checks if all cooldowns have expired and command
     /// execution may proceed. If not, Some is returned with the remaining cooldown
     pub fn remaining_cooldown(
@@ -91,46 +234,68 @@ impl CooldownTracker {
         ctx: CooldownContext,
         cooldown_durations: &CooldownConfig,
     ) -> Option<Duration> {
+        self.remaining_cooldown_bucket(ctx, cooldown_durations)
+            .map(|remaining| remaining.remaining)
+    }
+
+    /// Like [`Self::remaining_cooldown`], but also returns which bucket has the longest remaining
+    /// cooldown and its configured duration
+    pub fn remaining_cooldown_bucket(
+        &self,
+        ctx: CooldownContext,
+        cooldown_durations: &CooldownConfig,
+    ) -> Option<RemainingCooldown> {
         let mut cooldown_data = vec![
-            (cooldown_durations.global, self.global_invocation),
             (
+                CooldownBucket::Global,
+                cooldown_durations.global,
+                self.global_invocation(),
+            ),
+            (
+                CooldownBucket::User,
                 cooldown_durations.user,
-                self.user_invocations.get(&ctx.user_id).copied(),
+                self.user_invocations.get(&ctx.user_id),
             ),
             (
+                CooldownBucket::Channel,
                 cooldown_durations.channel,
-                self.channel_invocations.get(&ctx.channel_id).copied(),
+                self.channel_invocations.get(&ctx.channel_id),
             ),
         ];
 
         if let Some(guild_id) = ctx.guild_id {
             cooldown_data.push((
+                CooldownBucket::Guild,
                 cooldown_durations.guild,
-                self.guild_invocations.get(&guild_id).copied(),
+                self.guild_invocations.get(&guild_id),
             ));
             cooldown_data.push((
+                CooldownBucket::Member,
                 cooldown_durations.member,
-                self.member_invocations
-                    .get(&(ctx.user_id, guild_id))
-                    .copied(),
+                self.member_invocations.get(&(ctx.user_id, guild_id)),
             ));
         }
 
         cooldown_data
             .iter()
-            .filter_map(|&(cooldown, last_invocation)| {
+            .filter_map(|&(bucket, cooldown, last_invocation)| {
                 let duration_since = Instant::now().saturating_duration_since(last_invocation?);
                 let cooldown_left = cooldown?.checked_sub(duration_since)?;
-                Some(cooldown_left)
+                Some(RemainingCooldown {
+                    remaining: cooldown_left,
+                    bucket,
+                    duration: cooldown?,
+                    __non_exhaustive: (),
+                })
             })
-            .max()
+            .max_by_key(|remaining| remaining.remaining)
     }
 
     /// Indicates that a command has been executed and all associated cooldowns should start running
-    pub fn start_cooldown(&mut self, ctx: CooldownContext) {
+    pub fn start_cooldown(&self, ctx: CooldownContext) {
         let now = Instant::now();
 
-        self.global_invocation = Some(now);
+        self.set_global_invocation(now);
         self.user_invocations.insert(ctx.user_id, now);
         self.channel_invocations.insert(ctx.channel_id, now);
 
@@ -145,9 +310,9 @@ impl CooldownTracker {
     /// This function is not usually needed for regular usage. It was added to allow for extra
     /// flexibility in cases where you might want to shorten or lengthen a cooldown after
     /// invocation.
-    pub fn set_last_invocation(&mut self, cooldown_type: CooldownType, instant: Instant) {
+    pub fn set_last_invocation(&self, cooldown_type: CooldownType, instant: Instant) {
         match cooldown_type {
-            CooldownType::Global => self.global_invocation = Some(instant),
+            CooldownType::Global => self.set_global_invocation(instant),
             CooldownType::User(user_id) => {
                 self.user_invocations.insert(user_id, instant);
             }
@@ -173,3 +338,34 @@ impl<'a> From<&'a serenity::Message> for CooldownContext {
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_cooldown_tracker() {
+    let config = CooldownConfig {
+        global: Some(Duration::from_secs(10)),
+        user: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+    let user = |id| CooldownContext {
+        user_id: serenity::UserId::new(id),
+        ..Default::default()
+    };
+
+    let tracker = CooldownTracker::new();
+    assert_eq!(tracker.remaining_cooldown(user(1), &config), None);
+    tracker.start_cooldown(user(1));
+    let remaining = tracker.remaining_cooldown_bucket(user(1), &config).unwrap();
+    assert_eq!(remaining.bucket, CooldownBucket::User);
+    // Other users only wait for the global cooldown
+    let remaining = tracker.remaining_cooldown_bucket(user(2), &config).unwrap();
+    assert_eq!(remaining.bucket, CooldownBucket::Global);
+
+    // Clones are snapshots
+    let snapshot = tracker.clone();
+    let expired = Instant::now() - Duration::from_secs(120);
+    tracker.set_last_invocation(CooldownType::Global, expired);
+    tracker.set_last_invocation(CooldownType::User(serenity::UserId::new(1)), expired);
+    assert_eq!(tracker.remaining_cooldown(user(1), &config), None);
+    assert!(snapshot.remaining_cooldown(user(1), &config).is_some());
+}
@@ -0,0 +1,208 @@
+//! Marking commands as deprecated and warning their users, see [`DeprecationNotice`]
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::serenity_prelude as serenity;
+
+/// Marks a command as deprecated, see [`crate::Command::deprecated`]
+///
+/// Deprecated commands keep working, but are marked in the help menu (see
+/// [`crate::builtins::help_menu`]) and their responses start with a one-line warning, at most
+/// once per user and [`DeprecationWarnings::interval`]. Useful to migrate users when renaming or
+/// replacing commands:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// /// Shows bot statistics
+/// #[lumi::command(slash_command, deprecated(replacement = "/info stats"))]
+/// async fn stats(ctx: lumi::Context<'_, (), Error>) -> Result<(), Error> {
+///     Ok(())
+/// }
+/// ```
+///
+/// To announce a [`Self::sunset`], set [`crate::Command::deprecated`] manually.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeprecationNotice {
+    /// Additional explanation appended to the warning
+    pub message: Option<Cow<'static, str>>,
+    /// The command to use instead, e.g. `/info stats`
+    pub replacement: Option<Cow<'static, str>>,
+    /// When the command will be removed
+    pub sunset: Option<serenity::Timestamp>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl DeprecationNotice {
+    /// Creates a notice without explanation, replacement or sunset date
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets [`Self::message`]
+    #[must_use]
+    pub fn message(mut self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Sets [`Self::replacement`]
+    #[must_use]
+    pub fn replacement(mut self, replacement: impl Into<Cow<'static, str>>) -> Self {
+        self.replacement = Some(replacement.into());
+        self
+    }
+
+    /// Sets [`Self::sunset`]
+    #[must_use]
+    pub fn sunset(mut self, sunset: serenity::Timestamp) -> Self {
+        self.sunset = Some(sunset);
+        self
+    }
+}
+
+/// Formats the one-line warning prepended to responses of deprecated commands
+impl std::fmt::Display for DeprecationNotice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("⚠️ This command is deprecated")?;
+        if let Some(sunset) = &self.sunset {
+            // Rendered by Discord as a date in the user's locale and time zone
+            write!(
+                f,
+                " and will be removed on <t:{}:D>",
+                sunset.unix_timestamp()
+            )?;
+        }
+        f.write_str(".")?;
+        if let Some(replacement) = &self.replacement {
+            write!(f, " Use `{}` instead.", replacement)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, " {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Remembers which users were recently warned about which deprecated commands, so that the
+/// warning of a [`DeprecationNotice`] isn't repeated on every invocation
+///
+/// Stored in [`crate::FrameworkOptions::deprecation_warnings`].
+#[derive(Debug)]
+pub struct DeprecationWarnings {
+    /// Minimum time between two warnings for the same user and command
+    interval: std::time::Duration,
+    /// The warnings which were given within the interval
+    warned: parking_lot::Mutex<WarnedUsers>,
+}
+
+/// Contents of [`DeprecationWarnings`]
+#[derive(Debug)]
+struct WarnedUsers {
+    /// When each user was last warned, keyed by [`crate::Command::identifying_name`]
+    by_command: HashMap<String, HashMap<serenity::UserId, std::time::Instant>>,
+    /// When expired warnings were last forgotten
+    last_sweep: std::time::Instant,
+}
+
+impl Default for DeprecationWarnings {
+    fn default() -> Self {
+        Self::new(std::time::Duration::from_secs(24 * 60 * 60))
+    }
+}
+
+impl DeprecationWarnings {
+    /// Creates a tracker which warns each user at most once per `interval` and command. The
+    /// default is one day.
+    pub fn new(interval: std::time::Duration) -> Self {
+        Self {
+            interval,
+            warned: parking_lot::Mutex::new(WarnedUsers {
+                by_command: HashMap::new(),
+                last_sweep: std::time::Instant::now(),
+            }),
+        }
+    }
+
+    /// Minimum time between two warnings for the same user and command
+    pub fn interval(&self) -> std::time::Duration {
+        self.interval
+    }
+
+    /// Returns true if the user should be warned about the command now
+    pub(crate) fn is_due(&self, user_id: serenity::UserId, identifying_name: &str) -> bool {
+        self.is_due_at(user_id, identifying_name, std::time::Instant::now())
+    }
+
+    /// [`Self::is_due`] with the current time passed in
+    fn is_due_at(
+        &self,
+        user_id: serenity::UserId,
+        identifying_name: &str,
+        now: std::time::Instant,
+    ) -> bool {
+        let mut warned = self.warned.lock();
+        // Forget expired warnings at most once per interval, so that checks stay cheap on average
+        if now.saturating_duration_since(warned.last_sweep) >= self.interval {
+            for users in warned.by_command.values_mut() {
+                users.retain(|_, warned_at| {
+                    now.saturating_duration_since(*warned_at) < self.interval
+                });
+            }
+            warned.by_command.retain(|_, users| !users.is_empty());
+            warned.last_sweep = now;
+        }
+        warned
+            .by_command
+            .get(identifying_name)
+            .and_then(|users| users.get(&user_id))
+            .is_none_or(|warned_at| now.saturating_duration_since(*warned_at) >= self.interval)
+    }
+
+    /// Remembers that the user was just warned about the command
+    pub(crate) fn record(&self, user_id: serenity::UserId, identifying_name: &str) {
+        self.record_at(user_id, identifying_name, std::time::Instant::now());
+    }
+
+    /// [`Self::record`] with the current time passed in
+    fn record_at(
+        &self,
+        user_id: serenity::UserId,
+        identifying_name: &str,
+        now: std::time::Instant,
+    ) {
+        let mut warned = self.warned.lock();
+        // Only allocates the key the first time anyone is warned about the command
+        if let Some(users) = warned.by_command.get_mut(identifying_name) {
+            users.insert(user_id, now);
+            return;
+        }
+        warned
+            .by_command
+            .insert(identifying_name.to_owned(), HashMap::from([(user_id, now)]));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_deprecation_warnings() {
+    let start = std::time::Instant::now();
+    let at = |secs| start + std::time::Duration::from_secs(secs);
+    let warnings = DeprecationWarnings::new(std::time::Duration::from_secs(60));
+    let user = serenity::UserId::new(1);
+    assert!(warnings.is_due_at(user, "stats", at(0)));
+    warnings.record_at(user, "stats", at(0));
+    assert!(!warnings.is_due_at(user, "stats", at(30)));
+    assert!(warnings.is_due_at(user, "ping", at(30)));
+    assert!(warnings.is_due_at(serenity::UserId::new(2), "stats", at(30)));
+    assert!(warnings.is_due_at(user, "stats", at(60)));
+    // Swept by the check above
+    assert!(warnings.warned.lock().by_command.is_empty());
+
+    let notice = DeprecationNotice::new().replacement("/info stats");
+    assert_eq!(
+        notice.to_string(),
+        "⚠️ This command is deprecated. Use `/info stats` instead."
+    );
+}
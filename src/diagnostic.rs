@@ -0,0 +1,117 @@
+//! Rich [`miette`] diagnostics for argument parsing errors
+
+/// A [`miette::Diagnostic`] describing a failed argument parse, with the full invocation as
+/// source code and a label pointing at the argument that failed to parse.
+///
+/// Created with [`crate::FrameworkError::argument_parse_diagnostic`]. Render it with a
+/// [`miette::ReportHandler`], e.g. via `miette::Report::new(diagnostic)`.
+#[derive(Debug)]
+pub struct ArgumentParseDiagnostic {
+    /// The parse error message
+    message: String,
+    /// The full invocation, see [`crate::Context::redacted_invocation_string`]
+    invocation: String,
+    /// Location of the failing argument in [`Self::invocation`], if it could be determined
+    span: Option<miette::SourceSpan>,
+    /// Usage information of the command, if available
+    help: Option<String>,
+}
+
+impl ArgumentParseDiagnostic {
+    /// Builds the diagnostic for an argument that failed to parse
+    pub(crate) fn new(
+        message: String,
+        invocation: String,
+        input: Option<&str>,
+        help: Option<String>,
+    ) -> Self {
+        // Skip the command name so that arguments equal to the command name aren't matched
+        let search_start = invocation.find(' ').unwrap_or(0);
+        let span = input.filter(|input| !input.is_empty()).and_then(|input| {
+            let offset = search_start + invocation[search_start..].find(input)?;
+            Some(miette::SourceSpan::new(offset.into(), input.len()))
+        });
+        Self {
+            message,
+            invocation,
+            span,
+            help,
+        }
+    }
+
+    /// Returns the full invocation this diagnostic refers to
+    pub fn invocation(&self) -> &str {
+        &self.invocation
+    }
+}
+
+impl std::fmt::Display for ArgumentParseDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ArgumentParseDiagnostic {}
+
+impl miette::Diagnostic for ArgumentParseDiagnostic {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(Box::new("lumi::argument_parse"))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.help
+            .as_ref()
+            .map(|help| Box::new(help) as Box<dyn std::fmt::Display + 'a>)
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.invocation)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let span = self.span?;
+        Some(Box::new(std::iter::once(
+            miette::LabeledSpan::new_with_span(Some("failed to parse this argument".into()), span),
+        )))
+    }
+}
+
+/// Implements [`miette::Diagnostic`] with a stable error code for the given argument error types
+macro_rules! impl_diagnostic_code {
+    ( $( $type:ty => $code:literal, )* ) => { $(
+        impl miette::Diagnostic for $type {
+            fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+                Some(Box::new($code))
+            }
+        }
+    )* };
+}
+
+impl_diagnostic_code! {
+    crate::TooManyArguments => "lumi::too_many_arguments",
+    crate::TooFewArguments => "lumi::too_few_arguments",
+    crate::MissingAttachment => "lumi::missing_attachment",
+    crate::InvalidChoice => "lumi::invalid_choice",
+    crate::InvalidBool => "lumi::invalid_bool",
+    crate::AmbiguousMember => "lumi::ambiguous_member",
+}
+
+impl<T: Send + Sync + 'static, E> crate::FrameworkError<'_, T, E> {
+    /// If this is a [`crate::FrameworkError::ArgumentParse`], returns a [`miette::Diagnostic`]
+    /// pointing at the argument that failed to parse
+    pub fn argument_parse_diagnostic(&self) -> Option<ArgumentParseDiagnostic> {
+        let Self::ArgumentParse { error, input, ctx } = self else {
+            return None;
+        };
+        let help = ctx
+            .command()
+            .help_text_localized(ctx.locale())
+            .map(str::to_owned);
+        Some(ArgumentParseDiagnostic::new(
+            error.to_string(),
+            ctx.redacted_invocation_string(),
+            input.as_deref(),
+            help,
+        ))
+    }
+}
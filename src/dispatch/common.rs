@@ -2,6 +2,37 @@
 
 use crate::serenity_prelude as serenity;
 
+/// Runs a command action according to [`crate::Command::spawn_policy`], cancelling it with
+/// [`crate::FrameworkError::CommandTimeout`] if it exceeds [`crate::Command::timeout`] or
+/// [`crate::FrameworkOptions::command_timeout`].
+pub(crate) async fn run_action<'a, T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'a, T, E>,
+    action: impl std::future::Future<Output = Result<(), crate::FrameworkError<'a, T, E>>>,
+) -> Result<(), crate::FrameworkError<'a, T, E>> {
+    let timeout = ctx
+        .command()
+        .timeout
+        .or(ctx.framework().options().command_timeout);
+    let action = async {
+        match timeout {
+            // Dropping the action future on timeout cancels the command
+            Some(timeout) => match tokio::time::timeout(timeout, action).await {
+                Ok(result) => result,
+                Err(_) => Err(crate::FrameworkError::CommandTimeout { timeout, ctx }),
+            },
+            None => action.await,
+        }
+    };
+
+    // Panics on a current-thread runtime, which the framework rules out on startup
+    #[cfg(feature = "blocking_commands")]
+    if ctx.command().spawn_policy == crate::SpawnPolicy::Blocking {
+        let runtime = tokio::runtime::Handle::current();
+        return tokio::task::block_in_place(|| runtime.block_on(action));
+    }
+    action.await
+}
+
 /// Fetches the NSFW status of the channel (or thread) the command was executed in.
 async fn check_nsfw_channel<U: Send + Sync + 'static, E>(ctx: crate::Context<'_, U, E>) -> bool {
     match ctx.channel().await {
@@ -30,6 +61,7 @@ async fn check_nsfw_channel<U: Send + Sync + 'static, E>(ctx: crate::Context<'_,
 async fn check_permissions_and_cooldown_single<'a, T: Send + Sync + 'static, E>(
     ctx: crate::Context<'a, T, E>,
     cmd: &'a crate::Command<T, E>,
+    check_cooldown: bool,
 ) -> Result<(), crate::FrameworkError<'a, T, E>> {
     // Skip command checks if `FrameworkOptions::skip_checks_for_owners` is set to true
     if ctx.framework().options.skip_checks_for_owners
@@ -38,6 +70,46 @@ async fn check_permissions_and_cooldown_single<'a, T: Send + Sync + 'static, E>(
         return Ok(());
     }
 
+    if let (Some(command_toggles), Some(guild_id)) =
+        (&ctx.framework().options().command_toggles, ctx.guild_id())
+    {
+        match command_toggles
+            .is_enabled(guild_id, &cmd.qualified_name)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => return Err(crate::FrameworkError::CommandDisabled { ctx }),
+            Err(error) => {
+                crate::guild_settings::on_store_failure(ctx, "command toggles", cmd, error)
+                    .map_err(|error| crate::FrameworkError::SettingsUnavailable { error, ctx })?;
+            }
+        }
+    }
+
+    match crate::channel_restrictions::disallowed_channel_restriction(ctx, cmd).await {
+        Ok(None) => {}
+        Ok(Some(allowed_channels)) => {
+            return Err(crate::FrameworkError::WrongChannel {
+                allowed_channels,
+                ctx,
+            });
+        }
+        Err(error) => {
+            crate::guild_settings::on_store_failure(ctx, "channel restrictions", cmd, error)
+                .map_err(|error| crate::FrameworkError::SettingsUnavailable { error, ctx })?;
+        }
+    }
+
+    if let Some(feature) = &cmd.feature {
+        let enabled = match &ctx.framework().options().feature_gate {
+            Some(feature_gate) => feature_gate.evaluate(feature, ctx),
+            None => false,
+        };
+        if !enabled {
+            return Err(crate::FrameworkError::FeatureDisabled { feature, ctx });
+        }
+    }
+
     if cmd.owners_only && !ctx.framework().options().owners.contains(&ctx.author().id) {
         return Err(crate::FrameworkError::NotAnOwner { ctx });
     }
@@ -64,7 +136,7 @@ async fn check_permissions_and_cooldown_single<'a, T: Send + Sync + 'static, E>(
     }
 
     // Make sure that user has required permissions
-    if let Some((user_missing_permissions, bot_missing_permissions)) =
+    if let Some(((user_missing_permissions, user_scope), (bot_missing_permissions, bot_scope))) =
         super::permissions::calculate_missing(
             ctx,
             cmd.required_permissions,
@@ -76,6 +148,8 @@ async fn check_permissions_and_cooldown_single<'a, T: Send + Sync + 'static, E>(
             return Err(crate::FrameworkError::MissingUserPermissions {
                 ctx,
                 missing_permissions: Some(user_missing_permissions),
+                channel_id: ctx.channel_id(),
+                scope: user_scope,
             });
         }
 
@@ -83,6 +157,8 @@ async fn check_permissions_and_cooldown_single<'a, T: Send + Sync + 'static, E>(
             return Err(crate::FrameworkError::MissingBotPermissions {
                 ctx,
                 missing_permissions: bot_missing_permissions,
+                channel_id: ctx.channel_id(),
+                scope: bot_scope,
             });
         }
     } else {
@@ -91,49 +167,325 @@ async fn check_permissions_and_cooldown_single<'a, T: Send + Sync + 'static, E>(
 
     // Only continue if command checks returns true
     // First perform global checks, then command checks (if necessary)
-    for check in Option::iter(&ctx.framework().options().command_check).chain(&cmd.checks) {
-        match check(ctx).await {
+    let global_check = ctx
+        .framework()
+        .options()
+        .command_check
+        .map(crate::Check::new);
+    run_checks(ctx, global_check.iter().chain(&cmd.checks)).await?;
+
+    if check_cooldown && is_framework_cooldown(ctx, cmd) {
+        check_cooldown_tracker(ctx, &cmd.cooldowns, &cmd.cooldown_config)?;
+    }
+
+    Ok(())
+}
+
+/// Runs the given checks in order, aggregating failures if
+/// [`crate::FrameworkOptions::aggregate_check_failures`] is set
+async fn run_checks<'a, 'c, T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'a, T, E>,
+    checks: impl Iterator<Item = &'c crate::Check<T, E>>,
+) -> Result<(), crate::FrameworkError<'a, T, E>>
+where
+    T: 'c,
+    E: 'c,
+{
+    let mut denied = false;
+    let mut failed_checks = Vec::new();
+    for check in checks {
+        match check.run(ctx).await {
             Ok(true) => {}
             Ok(false) => {
-                return Err(crate::FrameworkError::CommandCheckFailed { ctx, error: None });
+                denied = true;
+                failed_checks.push(check.clone());
+                if !ctx.framework().options().aggregate_check_failures {
+                    break;
+                }
             }
             Err(error) => {
                 return Err(crate::FrameworkError::CommandCheckFailed {
                     error: Some(error),
+                    failed_checks,
                     ctx,
                 });
             }
         }
     }
+    if denied {
+        return Err(crate::FrameworkError::CommandCheckFailed {
+            error: None,
+            failed_checks,
+            ctx,
+        });
+    }
+    Ok(())
+}
 
-    if !ctx.framework().options().manual_cooldowns {
-        let cooldowns = cmd.cooldowns.lock().unwrap();
-        let config = cmd.cooldown_config.read().unwrap();
-        let remaining_cooldown = cooldowns.remaining_cooldown(ctx.cooldown_context(), &config);
-        if let Some(remaining_cooldown) = remaining_cooldown {
-            return Err(crate::FrameworkError::CooldownHit {
-                ctx,
-                remaining_cooldown,
-            });
+/// Fails with [`crate::FrameworkError::CooldownHit`] if the given cooldown is still running
+fn check_cooldown_tracker<'a, T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'a, T, E>,
+    cooldowns: &crate::CooldownTracker,
+    config: &parking_lot::RwLock<crate::CooldownConfig>,
+) -> Result<(), crate::FrameworkError<'a, T, E>> {
+    let config = config.read();
+    let remaining_cooldown = cooldowns.remaining_cooldown_bucket(ctx.cooldown_context(), &config);
+    if let Some(remaining_cooldown) = remaining_cooldown {
+        return Err(crate::FrameworkError::CooldownHit {
+            ctx,
+            remaining_cooldown: remaining_cooldown.remaining,
+            bucket: remaining_cooldown.bucket,
+            cooldown_duration: remaining_cooldown.duration,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the groups of the invoked command and its parent commands, outermost first and without
+/// duplicates, see [`crate::CommandGroup`]
+fn invoked_groups<'a, T, E>(
+    command: &'a crate::Command<T, E>,
+    parent_commands: &[&'a crate::Command<T, E>],
+) -> Vec<&'a crate::CommandGroup<T, E>> {
+    let mut groups = Vec::<&crate::CommandGroup<T, E>>::new();
+    let commands = parent_commands.iter().copied();
+    for command in commands.chain(std::iter::once(command)) {
+        if let Some(group) = &command.group {
+            if !groups.iter().any(|g| std::ptr::eq(*g, &**group)) {
+                groups.push(group);
+            }
         }
     }
+    groups
+}
 
-    Ok(())
+/// Whether the framework tracks the cooldowns of the given command, as opposed to the command
+/// itself (see [`crate::Command::manual_cooldowns`])
+///
+/// Used by the code generated by [`crate::command`].
+#[doc(hidden)]
+pub fn is_framework_cooldown<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    cmd: &crate::Command<T, E>,
+) -> bool {
+    let options = ctx.framework().options();
+    let bypass_cooldowns =
+        options.owners_bypass_cooldowns && options.owners.contains(&ctx.author().id);
+    !cmd.manual_cooldowns.unwrap_or(options.manual_cooldowns) && !bypass_cooldowns
 }
 
 /// Checks if the invoker is allowed to execute this command at this point in time
 ///
+/// The checks of the command's groups (see [`crate::CommandGroup`]) and of all parent commands
+/// (see [`crate::Context::parent_commands`]) are run before the checks of the invoked command.
+/// The parents' cooldowns are only checked if [`crate::Command::inherit_cooldowns`] is set on the
+/// invoked command. The groups' cooldowns are checked last, so that failing checks take
+/// precedence over them.
+///
 /// Doesn't actually start the cooldown timer! This should be done by the caller later, after
-/// argument parsing.
+/// argument parsing, with [`start_cooldowns`].
 /// (A command that didn't even get past argument parsing shouldn't trigger cooldowns)
 #[allow(clippy::needless_lifetimes)] // false positive (clippy issue 7271)
 pub async fn check_permissions_and_cooldown<'a, T: Send + Sync + 'static, E>(
     ctx: crate::Context<'a, T, E>,
 ) -> Result<(), crate::FrameworkError<'a, T, E>> {
+    // Groups are coarser than parent commands, so their checks run first. Their cooldowns are
+    // shared by all members and don't depend on `inherit_cooldowns`
+    let options = ctx.framework().options();
+    let skip_checks = options.skip_checks_for_owners && options.owners.contains(&ctx.author().id);
+    let groups = match skip_checks {
+        true => Vec::new(),
+        false => invoked_groups(ctx.command(), ctx.parent_commands()),
+    };
+    for group in &groups {
+        run_checks(ctx, group.checks.iter()).await?;
+    }
+
+    let inherit_cooldowns = ctx.command().inherit_cooldowns;
     for parent_command in ctx.parent_commands() {
-        check_permissions_and_cooldown_single(ctx, parent_command).await?;
+        check_permissions_and_cooldown_single(ctx, parent_command, inherit_cooldowns).await?;
+    }
+    check_permissions_and_cooldown_single(ctx, ctx.command(), true).await?;
+
+    if is_framework_cooldown(ctx, ctx.command()) {
+        for group in groups {
+            check_cooldown_tracker(ctx, &group.cooldowns, &group.cooldown_config)?;
+        }
     }
-    check_permissions_and_cooldown_single(ctx, ctx.command()).await?;
 
     Ok(())
 }
+
+/// Starts the cooldowns of the invoked command and its groups (see [`crate::CommandGroup`]), and
+/// those of its parent commands if [`crate::Command::inherit_cooldowns`] is set
+///
+/// Called by the framework right before the command action runs, unless cooldowns are handled
+/// manually (see [`crate::Command::manual_cooldowns`]). The parents' cooldowns are started even
+/// though the parents didn't run, so that all their subcommands share the parents' buckets.
+pub fn start_cooldowns<T: Send + Sync + 'static, E>(ctx: crate::Context<'_, T, E>) {
+    let command = ctx.command();
+    for group in invoked_groups(command, ctx.parent_commands()) {
+        group.cooldowns.start_cooldown(ctx.cooldown_context());
+    }
+    let parent_commands = inherited_cooldowns(command, ctx.parent_commands(), |parent_command| {
+        is_framework_cooldown(ctx, parent_command)
+    });
+    for parent_command in parent_commands {
+        parent_command
+            .cooldowns
+            .start_cooldown(ctx.cooldown_context());
+    }
+    command.cooldowns.start_cooldown(ctx.cooldown_context());
+}
+
+/// Returns the parent commands whose cooldowns are started along with the command's own, see
+/// [`crate::Command::inherit_cooldowns`]
+fn inherited_cooldowns<'a, T, E>(
+    command: &crate::Command<T, E>,
+    parent_commands: &'a [&'a crate::Command<T, E>],
+    is_framework_cooldown: impl Fn(&crate::Command<T, E>) -> bool,
+) -> impl Iterator<Item = &'a crate::Command<T, E>> {
+    let parent_commands = match command.inherit_cooldowns {
+        true => parent_commands,
+        false => &[],
+    };
+    parent_commands
+        .iter()
+        .copied()
+        .filter(move |parent_command| is_framework_cooldown(parent_command))
+}
+
+/// Logs an invocation according to [`crate::Command::log_level`] and returns the tracing span to
+/// run the command in
+pub(crate) fn invocation_span<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+) -> tracing::Span {
+    let command = ctx.command();
+    match command.log_level {
+        crate::CommandLogLevel::Span => {
+            tracing::info_span!("command", name = %command.qualified_name)
+        }
+        crate::CommandLogLevel::Info => {
+            let span = tracing::info_span!("command", name = %command.qualified_name);
+            span.in_scope(|| {
+                tracing::info!(
+                    invocation = %ctx.redacted_invocation_string(),
+                    author = %ctx.author().id,
+                    "command invoked"
+                );
+            });
+            span
+        }
+        crate::CommandLogLevel::Debug => {
+            let span = tracing::debug_span!("command", name = %command.qualified_name);
+            span.in_scope(|| tracing::debug!("command invoked"));
+            span
+        }
+        crate::CommandLogLevel::Off | crate::CommandLogLevel::__NonExhaustive => {
+            tracing::Span::none()
+        }
+    }
+}
+
+/// Prefix of the custom ID of delete buttons (see [`crate::Command::delete_button`]), followed by
+/// the ID of the invoking user
+pub(crate) const DELETE_BUTTON_PREFIX: &str = "lumi-delete-";
+
+/// Creates the delete button attached to responses of [`crate::Command::delete_button`] commands
+pub(crate) fn delete_button<'a>(author_id: serenity::UserId) -> serenity::CreateButton<'a> {
+    serenity::CreateButton::new(format!("{}{}", DELETE_BUTTON_PREFIX, author_id))
+        .style(serenity::ButtonStyle::Secondary)
+        .emoji('🗑')
+}
+
+/// Handles a click on a [`crate::Command::delete_button`] button by deleting the message it's
+/// attached to.
+///
+/// Only the invoking user and members with the Manage Messages permission may delete the response.
+/// Returns `Ok(false)` if the component interaction is not a delete button.
+pub async fn dispatch_delete_button<T: Send + Sync + 'static, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    component: &serenity::ComponentInteraction,
+) -> Result<bool, serenity::Error> {
+    let Some(author_id) = component.data.custom_id.strip_prefix(DELETE_BUTTON_PREFIX) else {
+        return Ok(false);
+    };
+
+    let is_author = author_id.parse().ok() == Some(component.user.id);
+    let is_moderator = component
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_messages());
+    if !is_author && !is_moderator {
+        let response = serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content("Only the user who invoked this command can delete this response")
+                .ephemeral(true),
+        );
+        component
+            .create_response(&framework.serenity_context.http, response)
+            .await?;
+        return Ok(true);
+    }
+
+    component
+        .create_response(
+            &framework.serenity_context.http,
+            serenity::CreateInteractionResponse::Acknowledge,
+        )
+        .await?;
+    component
+        .delete_response(&framework.serenity_context.http)
+        .await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+#[test]
+fn test_inherited_cooldowns() {
+    let parent = crate::Command::<(), ()>::default();
+    let manual_parent = crate::Command {
+        manual_cooldowns: Some(true),
+        ..Default::default()
+    };
+    let parent_commands = [&parent, &manual_parent];
+    let is_framework_cooldown =
+        |command: &crate::Command<(), ()>| !command.manual_cooldowns.unwrap_or(false);
+    let inherited = |command| {
+        inherited_cooldowns(command, &parent_commands, is_framework_cooldown)
+            .map(|parent_command| parent_command as *const _)
+            .collect::<Vec<_>>()
+    };
+
+    let command = crate::Command {
+        inherit_cooldowns: true,
+        ..Default::default()
+    };
+    assert_eq!(inherited(&command), [&parent as *const _]);
+    let command = crate::Command {
+        inherit_cooldowns: false,
+        ..Default::default()
+    };
+    assert!(inherited(&command).is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn test_invoked_groups() {
+    let moderation = std::sync::Arc::new(crate::CommandGroup::<(), ()>::new("Moderation"));
+    let admin = std::sync::Arc::new(crate::CommandGroup::new("Admin"));
+    let parent = crate::Command::default().in_group(&moderation);
+    let command = crate::Command::default().in_group(&admin);
+    let names = |command: &crate::Command<(), ()>, parent_commands: &[&crate::Command<(), ()>]| {
+        invoked_groups(command, parent_commands)
+            .iter()
+            .map(|group| group.name.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(&command, &[&parent]), ["Moderation", "Admin"]);
+    let command = crate::Command::default().in_group(&moderation);
+    assert_eq!(names(&command, &[&parent]), ["Moderation"]);
+    assert!(names(&crate::Command::default(), &[]).is_empty());
+}
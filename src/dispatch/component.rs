@@ -0,0 +1,318 @@
+//! Dispatches component interactions (buttons, select menus) onto the handlers in
+//! [`crate::FrameworkOptions::component_handlers`]
+
+use crate::{BoxFuture, serenity_prelude as serenity};
+
+/// Decides which component interactions a [`ComponentHandler`] receives, by their custom ID
+#[derive(Clone, Debug)]
+pub enum ComponentMatcher {
+    /// Custom IDs starting with this string, e.g. `ticket.close:` for `ticket.close:1234`
+    Prefix(std::borrow::Cow<'static, str>),
+    /// Custom IDs matching this regex. Use anchors (`^...$`) to match the whole custom ID
+    Regex(regex::Regex),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl ComponentMatcher {
+    /// Whether the custom ID is handled by the handler with this matcher
+    pub fn matches(&self, custom_id: &str) -> bool {
+        match self {
+            Self::Prefix(prefix) => custom_id.starts_with(&**prefix),
+            Self::Regex(regex) => regex.is_match(custom_id),
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+/// Callback of a [`ComponentHandler`] check. The handler only runs if it returns `Ok(true)`
+pub type ComponentCheck<T, E> = fn(ComponentContext<'_, T, E>) -> BoxFuture<'_, Result<bool, E>>;
+
+/// Handles component interactions whose custom ID matches [`Self::matcher`], registered in
+/// [`crate::FrameworkOptions::component_handlers`]
+///
+/// Lets buttons and select menus outlive the command that sent them, e.g. a "Close ticket" button,
+/// without a separate raw event handler:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # use lumi::serenity_prelude as serenity;
+/// async fn close_ticket(ctx: lumi::ComponentContext<'_, (), Error>) -> Result<(), Error> {
+///     let ticket_id = ctx.custom_id_suffix().unwrap_or_default();
+///     let response = serenity::CreateInteractionResponseMessage::new()
+///         .content(format!("Closed ticket {}", ticket_id));
+///     ctx.interaction
+///         .create_response(ctx.http(), serenity::CreateInteractionResponse::Message(response))
+///         .await?;
+///     Ok(())
+/// }
+///
+/// let options = lumi::FrameworkOptions::<(), Error> {
+///     component_handlers: vec![lumi::ComponentHandler::prefix("ticket.close:", |ctx| {
+///         Box::pin(close_ticket(ctx))
+///     })],
+///     ..Default::default()
+/// };
+/// ```
+///
+/// The framework's own buttons, like [`crate::Command::rerun_button`], have built-in handlers
+/// which take precedence over these. If several handlers match, the first one runs. Errors returned by the handler or its checks are passed to
+/// [`crate::FrameworkOptions::on_error`] as [`crate::FrameworkError::ComponentHandler`].
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct ComponentHandler<T, E> {
+    /// Which custom IDs this handler receives
+    pub matcher: ComponentMatcher,
+    /// Checks which must pass for [`Self::action`] to run. If one of them denies the interaction,
+    /// it's left unanswered, so the checks may respond themselves
+    #[derivative(Debug = "ignore")]
+    pub checks: Vec<ComponentCheck<T, E>>,
+    /// Handles the interaction. Responsible for responding to it
+    #[derivative(Debug = "ignore")]
+    pub action: fn(ComponentContext<'_, T, E>) -> BoxFuture<'_, Result<(), E>>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl<T, E> ComponentHandler<T, E> {
+    /// Creates a handler for custom IDs matched by `matcher`, without checks
+    pub fn new(
+        matcher: ComponentMatcher,
+        action: fn(ComponentContext<'_, T, E>) -> BoxFuture<'_, Result<(), E>>,
+    ) -> Self {
+        Self {
+            matcher,
+            checks: Vec::new(),
+            action,
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Creates a handler for custom IDs starting with `prefix`, see [`ComponentMatcher::Prefix`]
+    pub fn prefix(
+        prefix: impl Into<std::borrow::Cow<'static, str>>,
+        action: fn(ComponentContext<'_, T, E>) -> BoxFuture<'_, Result<(), E>>,
+    ) -> Self {
+        Self::new(ComponentMatcher::Prefix(prefix.into()), action)
+    }
+
+    /// Creates a handler for custom IDs matching `regex`, see [`ComponentMatcher::Regex`]
+    pub fn regex(
+        regex: regex::Regex,
+        action: fn(ComponentContext<'_, T, E>) -> BoxFuture<'_, Result<(), E>>,
+    ) -> Self {
+        Self::new(ComponentMatcher::Regex(regex), action)
+    }
+
+    /// Adds a check to [`Self::checks`]
+    #[must_use]
+    pub fn check(mut self, check: ComponentCheck<T, E>) -> Self {
+        self.checks.push(check);
+        self
+    }
+}
+
+/// Context passed to [`ComponentHandler`]s and their checks
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct ComponentContext<'a, T, E> {
+    /// The component interaction to handle
+    pub interaction: &'a serenity::ComponentInteraction,
+    /// The handler whose [`ComponentHandler::matcher`] matched the custom ID
+    pub handler: &'a ComponentHandler<T, E>,
+    /// Framework context
+    #[derivative(Debug = "ignore")]
+    pub framework: crate::FrameworkContext<'a, T, E>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl<T, E> Copy for ComponentContext<'_, T, E> {}
+impl<T, E> Clone for ComponentContext<'_, T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: Send + Sync + 'static, E> ComponentContext<'a, T, E> {
+    /// Returns serenity's context
+    pub fn serenity_context(&self) -> &'a serenity::Context {
+        self.framework.serenity_context
+    }
+
+    /// Returns serenity's raw Discord API client
+    pub fn http(&self) -> &'a serenity::Http {
+        &self.framework.serenity_context.http
+    }
+
+    /// Retrieves user data
+    pub fn user_data(&self) -> std::sync::Arc<T> {
+        self.framework.user_data()
+    }
+
+    /// Returns the user who clicked the button or used the select menu
+    pub fn author(&self) -> &'a serenity::User {
+        &self.interaction.user
+    }
+
+    /// Returns the custom ID of the component
+    pub fn custom_id(&self) -> &'a str {
+        &self.interaction.data.custom_id
+    }
+
+    /// Returns the rest of the custom ID after the prefix of a [`ComponentMatcher::Prefix`]
+    /// handler, e.g. the ticket ID `1234` in `ticket.close:1234`
+    ///
+    /// Returns `None` for other matchers.
+    pub fn custom_id_suffix(&self) -> Option<&'a str> {
+        match &self.handler.matcher {
+            ComponentMatcher::Prefix(prefix) => self.custom_id().strip_prefix(&**prefix),
+            _ => None,
+        }
+    }
+
+    /// Returns the capture groups of a [`ComponentMatcher::Regex`] handler in the custom ID
+    ///
+    /// Returns `None` for other matchers.
+    pub fn captures(&self) -> Option<regex::Captures<'a>> {
+        match &self.handler.matcher {
+            ComponentMatcher::Regex(regex) => regex.captures(self.custom_id()),
+            _ => None,
+        }
+    }
+}
+
+/// The framework's own buttons, which take precedence over
+/// [`crate::FrameworkOptions::component_handlers`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FrameworkButton {
+    /// See [`crate::Command::rerun_button`]
+    Rerun,
+    /// See [`crate::Command::delete_button`]
+    Delete,
+}
+
+impl FrameworkButton {
+    /// Returns the framework button with the given custom ID, if it is one
+    fn find(custom_id: &str) -> Option<Self> {
+        if custom_id.starts_with(crate::RERUN_BUTTON_PREFIX) {
+            Some(Self::Rerun)
+        } else if custom_id.starts_with(super::common::DELETE_BUTTON_PREFIX) {
+            Some(Self::Delete)
+        } else {
+            None
+        }
+    }
+
+    /// Responds to a click on this button
+    ///
+    /// Awaited directly rather than boxed like [`ComponentHandler::action`], so that dispatching
+    /// doesn't require the error type to be `Send` and `Sync`. Errors are passed to
+    /// [`crate::FrameworkOptions::on_error`], because they're [`serenity::Error`]s rather than the
+    /// user's error type.
+    async fn run<T: Send + Sync + 'static, E>(
+        self,
+        framework: crate::FrameworkContext<'_, T, E>,
+        interaction: &serenity::ComponentInteraction,
+    ) {
+        let (result, operation) = match self {
+            Self::Rerun => (
+                super::dispatch_rerun_button(framework, interaction).await,
+                "respond to rerun button",
+            ),
+            Self::Delete => (
+                super::dispatch_delete_button(framework, interaction).await,
+                "respond to delete button",
+            ),
+        };
+        if let Err(error) = result {
+            let error = crate::FrameworkError::Internal {
+                error,
+                operation,
+                channel_id: Some(interaction.channel_id),
+                message_id: Some(interaction.message.id),
+                framework,
+            };
+            error.handle(framework.options).await;
+        }
+    }
+}
+
+/// Runs the first handler matching the interaction's custom ID, after its checks. The framework's
+/// own buttons are handled first, then [`crate::FrameworkOptions::component_handlers`]
+///
+/// Returns whether a handler matched.
+pub(crate) async fn dispatch_component<'a, T: Send + Sync + 'static, E>(
+    framework: crate::FrameworkContext<'a, T, E>,
+    interaction: &'a serenity::ComponentInteraction,
+) -> Result<bool, crate::FrameworkError<'a, T, E>> {
+    let custom_id = &interaction.data.custom_id;
+    if let Some(button) = FrameworkButton::find(custom_id) {
+        button.run(framework, interaction).await;
+        return Ok(true);
+    }
+
+    let handlers = &framework.options.component_handlers;
+    let Some(handler) = handlers.iter().find(|h| h.matcher.matches(custom_id)) else {
+        return Ok(false);
+    };
+    let ctx = ComponentContext {
+        interaction,
+        handler,
+        framework,
+        __non_exhaustive: (),
+    };
+    run_handler(ctx).await?;
+    Ok(true)
+}
+
+/// Runs a matched handler after its checks
+async fn run_handler<T: Send + Sync + 'static, E>(
+    ctx: ComponentContext<'_, T, E>,
+) -> Result<(), crate::FrameworkError<'_, T, E>> {
+    let handler = ctx.handler;
+    for check in &handler.checks {
+        match check(ctx).await {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::debug!(
+                    "component handler check denied `{}` for user {}",
+                    ctx.custom_id(),
+                    ctx.author().id
+                );
+                return Ok(());
+            }
+            Err(error) => return Err(crate::FrameworkError::ComponentHandler { error, ctx }),
+        }
+    }
+
+    (handler.action)(ctx)
+        .await
+        .map_err(|error| crate::FrameworkError::ComponentHandler { error, ctx })
+}
+
+#[cfg(test)]
+#[test]
+fn test_component_matcher() {
+    let prefix = ComponentMatcher::Prefix("ticket.close:".into());
+    assert!(prefix.matches("ticket.close:1234"));
+    assert!(!prefix.matches("ticket.open:1234"));
+
+    let regex = ComponentMatcher::Regex(regex::Regex::new(r"^poll\.([0-9]+)\.vote$").unwrap());
+    assert!(regex.matches("poll.12.vote"));
+    assert!(!regex.matches("poll.12.vote.extra"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_framework_buttons() {
+    assert_eq!(
+        FrameworkButton::find("lumi-rerun-1234"),
+        Some(FrameworkButton::Rerun)
+    );
+    assert_eq!(
+        FrameworkButton::find("lumi-delete-1234"),
+        Some(FrameworkButton::Delete)
+    );
+    assert_eq!(FrameworkButton::find("ticket.close:1234"), None);
+}
@@ -0,0 +1,94 @@
+//! Guards against running the same invocation twice, see [`RecentInvocations`]
+
+use std::collections::{HashSet, VecDeque};
+
+/// How long invocation IDs are remembered by [`RecentInvocations`]. Duplicate events are
+/// delivered shortly after a reconnect or resume, not minutes later.
+const DEDUP_WINDOW: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Remembers the [`crate::Context::id`]s of recently dispatched invocations, so that an event
+/// delivered twice by the gateway, e.g. after a reconnect or resume, doesn't run its command twice.
+///
+/// Duplicates are dropped silently. Edited messages get a new [`crate::Context::id`], so edit
+/// tracking still reruns commands. Stored in [`crate::FrameworkOptions::recent_invocations`].
+#[derive(Debug, Default)]
+pub struct RecentInvocations {
+    /// Invocation IDs dispatched within [`DEDUP_WINDOW`]
+    invocations: parking_lot::Mutex<RecentInvocationsInner>,
+}
+
+/// Contents of [`RecentInvocations`]
+#[derive(Debug, Default)]
+struct RecentInvocationsInner {
+    /// The remembered invocation IDs
+    ids: HashSet<u64>,
+    /// When each remembered invocation ID was first dispatched, oldest first
+    dispatched: VecDeque<(std::time::Instant, u64)>,
+}
+
+impl RecentInvocations {
+    /// Creates an empty guard
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the invocation as dispatched and forgets expired ones. Returns false if it was
+    /// already dispatched recently, in which case it must not run again.
+    pub(crate) fn claim(&self, id: u64) -> bool {
+        self.claim_at(id, std::time::Instant::now())
+    }
+
+    /// [`Self::claim`] with the current time passed in
+    fn claim_at(&self, id: u64, now: std::time::Instant) -> bool {
+        let mut invocations = self.invocations.lock();
+        let RecentInvocationsInner { ids, dispatched } = &mut *invocations;
+        while let Some(&(dispatched_at, expired_id)) = dispatched.front() {
+            if now.saturating_duration_since(dispatched_at) < DEDUP_WINDOW {
+                break;
+            }
+            dispatched.pop_front();
+            ids.remove(&expired_id);
+        }
+
+        if !ids.insert(id) {
+            return false;
+        }
+        dispatched.push_back((now, id));
+        true
+    }
+
+    /// Forgets all invocations, so that the next dispatch of any event runs its command again
+    pub fn clear(&self) {
+        let mut invocations = self.invocations.lock();
+        invocations.ids.clear();
+        invocations.dispatched.clear();
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_claim() {
+    let recent_invocations = RecentInvocations::new();
+    assert!(recent_invocations.claim(1));
+    assert!(recent_invocations.claim(2));
+    assert!(!recent_invocations.claim(1));
+
+    recent_invocations.clear();
+    assert!(recent_invocations.claim(1));
+}
+
+#[cfg(test)]
+#[test]
+fn test_claim_expiry() {
+    let start = std::time::Instant::now();
+    let recent_invocations = RecentInvocations::new();
+    assert!(recent_invocations.claim_at(1, start));
+    assert!(recent_invocations.claim_at(2, start + DEDUP_WINDOW / 2));
+    assert!(!recent_invocations.claim_at(1, start + DEDUP_WINDOW / 2));
+
+    // 1 expired, 2 didn't
+    let later = start + DEDUP_WINDOW;
+    assert!(!recent_invocations.claim_at(2, later));
+    assert!(recent_invocations.claim_at(1, later));
+    assert_eq!(recent_invocations.invocations.lock().dispatched.len(), 2);
+}
@@ -1,16 +1,23 @@
 //! Contains all code to dispatch incoming events onto framework commands
 
 mod common;
+mod component;
+mod dedup;
 mod permissions;
 mod prefix;
 mod slash;
 
 pub use common::*;
+pub use component::*;
+pub use dedup::*;
 pub use prefix::*;
 pub use slash::*;
 
 use crate::serenity_prelude as serenity;
 
+/// When the first Ready event was dispatched, see [`FrameworkContext::uptime`]
+static FIRST_READY: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
 /// A view into data stored by [`crate::Framework`]
 pub struct FrameworkContext<'a, T, E> {
     /// Serenity's context
@@ -39,6 +46,56 @@ impl<'a, T: Send + Sync + 'static, E> FrameworkContext<'a, T, E> {
     pub fn user_data(&self) -> std::sync::Arc<T> {
         self.serenity_context.data::<T>()
     }
+
+    /// Returns the command usage statistics, if enabled in
+    /// [`crate::FrameworkOptions::usage_stats`]
+    pub fn usage_stats(&self) -> Option<&'a crate::UsageStats> {
+        self.options.usage_stats.as_ref()
+    }
+
+    /// Returns the time since the bot first became ready, or `None` if it hasn't yet
+    ///
+    /// Reconnects don't reset the uptime.
+    pub fn uptime(&self) -> Option<std::time::Duration> {
+        FIRST_READY.get().map(|first_ready| first_ready.elapsed())
+    }
+
+    /// Iterates over all commands and subcommands in [`crate::FrameworkOptions::commands`], see
+    /// [`crate::walk_commands`]
+    fn walk_commands(&self) -> impl Iterator<Item = (usize, &'a crate::Command<T, E>)> {
+        crate::walk_commands(self.options.commands.iter().map(|c| &**c))
+    }
+
+    /// Looks up a command or subcommand by its [`crate::Command::identifying_name`]
+    ///
+    /// Unlike the command names, the identifying name isn't localized and doesn't change when
+    /// renaming a command, so it's suitable for referencing commands from external systems like
+    /// dashboards or permission editors.
+    pub fn command_by_identifying_name(
+        &self,
+        identifying_name: &str,
+    ) -> Option<&'a crate::Command<T, E>> {
+        crate::find_by_identifying_name(
+            self.options.commands.iter().map(|c| &**c),
+            identifying_name,
+        )
+    }
+
+    /// Returns the index of the command or subcommand with the given
+    /// [`crate::Command::identifying_name`], for use with [`Self::command_by_index`]
+    ///
+    /// The index is the command's position in a depth-first traversal of the command tree (see
+    /// [`crate::walk_commands`]). It stays the same as long as the command tree doesn't change,
+    /// so it can serve as a compact key, e.g. in component custom IDs.
+    pub fn command_index(&self, identifying_name: &str) -> Option<usize> {
+        self.walk_commands()
+            .position(|(_, command)| command.identifying_name == identifying_name)
+    }
+
+    /// Returns the command or subcommand at the given index, see [`Self::command_index`]
+    pub fn command_by_index(&self, index: usize) -> Option<&'a crate::Command<T, E>> {
+        self.walk_commands().nth(index).map(|(_, command)| command)
+    }
 }
 
 /// Central event handling function of this library
@@ -48,74 +105,166 @@ pub async fn dispatch_event<T: Send + Sync + 'static, E>(
 ) {
     match event {
         serenity::FullEvent::Message { new_message, .. } => {
-            let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
-            let mut parent_commands = Vec::new();
             let trigger = crate::MessageDispatchTrigger::MessageCreate;
-            if let Err(error) = prefix::dispatch_message(
-                framework,
-                new_message,
-                trigger,
-                &invocation_data,
-                &mut parent_commands,
-            )
-            .await
-            {
-                error.handle(framework.options).await;
-            }
+            dispatch_message_event(framework, new_message, trigger, &mut |_| {}).await;
         }
         serenity::FullEvent::MessageUpdate { event, .. } => {
-            if let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker {
-                let result = edit_tracker.write().unwrap().process_message_update(
-                    event,
-                    framework
-                        .options()
-                        .prefix_options
-                        .ignore_edits_if_not_yet_responded,
-                );
-
-                if let Some(previously_tracked) = result {
-                    let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
-                    let mut parent_commands = Vec::new();
-                    let trigger = match previously_tracked {
-                        true => crate::MessageDispatchTrigger::MessageEdit,
-                        false => crate::MessageDispatchTrigger::MessageEditFromInvalid,
-                    };
-                    if let Err(error) = prefix::dispatch_message(
-                        framework,
-                        &event.message,
-                        trigger,
-                        &invocation_data,
-                        &mut parent_commands,
-                    )
-                    .await
-                    {
-                        error.handle(framework.options).await;
-                    }
-                }
-            }
+            dispatch_message_update(framework, event, &mut |_| {}).await;
         }
         serenity::FullEvent::MessageDelete {
             deleted_message_id, ..
         } => {
-            if let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker {
-                let bot_response = edit_tracker
-                    .write()
-                    .unwrap()
-                    .process_message_delete(*deleted_message_id);
-                if let Some(bot_response) = bot_response {
-                    if let Err(e) = bot_response
-                        .delete(&framework.serenity_context.http, None)
-                        .await
-                    {
-                        tracing::warn!("failed to delete bot response: {}", e);
+            dispatch_message_delete(framework, *deleted_message_id).await;
+        }
+        serenity::FullEvent::InteractionCreate { interaction, .. } => {
+            dispatch_interaction_event(framework, interaction, &mut |_| {}).await;
+        }
+        serenity::FullEvent::Ready { data_about_bot, .. } => {
+            FIRST_READY.get_or_init(std::time::Instant::now);
+
+            framework
+                .options
+                .prefix_options
+                .mention_prefixes
+                .get_or_init(|| crate::MentionPrefixes::new(data_about_bot.user.id));
+
+            if let Some(scheduler) = &framework.options.scheduler {
+                // Loading the storage may be slow, and on_ready shouldn't wait for it
+                let scheduler = scheduler.clone();
+                let http = framework.serenity_context.http.clone();
+                let data = framework.user_data();
+                tokio::spawn(async move {
+                    if let Err(e) = scheduler.restore(http, data).await {
+                        tracing::warn!("Failed to restore scheduled jobs: {}", e);
                     }
-                }
+                });
+            }
+
+            if let Some(presence_rotation) = &framework.options.presence_rotation {
+                presence_rotation.start(framework.serenity_context);
+            }
+
+            if let Some(on_ready) = framework.options.on_ready {
+                on_ready(framework, data_about_bot).await;
             }
         }
-        serenity::FullEvent::InteractionCreate {
-            interaction: serenity::Interaction::Command(interaction),
-            ..
-        } => {
+        serenity::FullEvent::CacheReady { guilds, .. } => {
+            if let Some(on_cache_ready) = framework.options.on_cache_ready {
+                on_cache_ready(framework, guilds).await;
+            }
+        }
+        serenity::FullEvent::ShardStageUpdate { event, .. } => {
+            let hook = match event.new {
+                serenity::ConnectionStage::Connected => framework.options.on_shard_connect,
+                serenity::ConnectionStage::Disconnected => framework.options.on_shard_disconnect,
+                _ => None,
+            };
+            if let Some(hook) = hook {
+                hook(framework, event.shard_id).await;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Called with every error produced while dispatching an event, before it's handled. Used by
+/// [`crate::replay`] to record errors.
+pub(crate) type InspectError<'f, T, E> = dyn FnMut(&crate::FrameworkError<'_, T, E>) + Send + 'f;
+
+/// Dispatches a created or edited message onto prefix commands
+pub(crate) async fn dispatch_message_event<T: Send + Sync + 'static, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    msg: &serenity::Message,
+    trigger: crate::MessageDispatchTrigger,
+    inspect_error: &mut InspectError<'_, T, E>,
+) {
+    let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+    let scratch = prefix::PrefixInvocationScratch::new();
+    let mut parent_commands = Vec::new();
+    if let Err(error) = prefix::dispatch_message(
+        framework,
+        msg,
+        trigger,
+        &invocation_data,
+        &scratch,
+        &mut parent_commands,
+    )
+    .await
+    {
+        inspect_error(&error);
+        error.handle(framework.options).await;
+    }
+}
+
+/// Reruns the command of an edited message, if edit tracking is enabled
+pub(crate) async fn dispatch_message_update<T: Send + Sync + 'static, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    event: &serenity::MessageUpdateEvent,
+    inspect_error: &mut InspectError<'_, T, E>,
+) {
+    let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker else {
+        return;
+    };
+    let result = edit_tracker.process_message_update(
+        event,
+        framework
+            .options()
+            .prefix_options
+            .ignore_edits_if_not_yet_responded,
+    );
+
+    if let Some(previously_tracked) = result {
+        let trigger = match previously_tracked {
+            true => crate::MessageDispatchTrigger::MessageEdit,
+            false => crate::MessageDispatchTrigger::MessageEditFromInvalid,
+        };
+        dispatch_message_event(framework, &event.message, trigger, inspect_error).await;
+    }
+}
+
+/// Deletes the bot response to a deleted message, if edit tracking is enabled
+pub(crate) async fn dispatch_message_delete<T: Send + Sync + 'static, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    deleted_message_id: serenity::MessageId,
+) {
+    if let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker {
+        let bot_response = edit_tracker.process_message_delete(deleted_message_id);
+        if let Some(bot_response) = bot_response {
+            if let Err(error) = bot_response
+                .delete(&framework.serenity_context.http, None)
+                .await
+            {
+                let error = crate::FrameworkError::Internal {
+                    error,
+                    operation: "delete bot response",
+                    channel_id: Some(bot_response.channel_id),
+                    message_id: Some(bot_response.id),
+                    framework,
+                };
+                error.handle(framework.options).await;
+            }
+        }
+    }
+}
+
+/// Dispatches an interaction onto application commands, autocomplete callbacks, framework
+/// buttons and component handlers
+pub(crate) async fn dispatch_interaction_event<T: Send + Sync + 'static, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    interaction: &serenity::Interaction,
+    inspect_error: &mut InspectError<'_, T, E>,
+) {
+    match interaction {
+        serenity::Interaction::Command(interaction) => {
+            // Same as ctx.id() of the invocation. Not checked in dispatch_interaction, because
+            // rerun buttons dispatch the original interaction again
+            let recent_invocations = framework.options.recent_invocations.as_ref();
+            if recent_invocations
+                .is_some_and(|recent_invocations| !recent_invocations.claim(interaction.id.get()))
+            {
+                tracing::debug!("ignoring duplicate interaction {}", interaction.id);
+                return;
+            }
             let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
             let mut parent_commands = Vec::new();
             if let Err(error) = slash::dispatch_interaction(
@@ -128,13 +277,11 @@ pub async fn dispatch_event<T: Send + Sync + 'static, E>(
             )
             .await
             {
+                inspect_error(&error);
                 error.handle(framework.options).await;
             }
         }
-        serenity::FullEvent::InteractionCreate {
-            interaction: serenity::Interaction::Autocomplete(interaction),
-            ..
-        } => {
+        serenity::Interaction::Autocomplete(interaction) => {
             let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
             let mut parent_commands = Vec::new();
             if let Err(error) = slash::dispatch_autocomplete(
@@ -147,6 +294,13 @@ pub async fn dispatch_event<T: Send + Sync + 'static, E>(
             )
             .await
             {
+                inspect_error(&error);
+                error.handle(framework.options).await;
+            }
+        }
+        serenity::Interaction::Component(interaction) => {
+            if let Err(error) = component::dispatch_component(framework, interaction).await {
+                inspect_error(&error);
                 error.handle(framework.options).await;
             }
         }
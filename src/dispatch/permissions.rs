@@ -11,6 +11,10 @@ struct PermissionsInfo {
     author_permissions: Option<serenity::Permissions>,
     /// The Permissions of the bot, if requested.
     bot_permissions: Option<serenity::Permissions>,
+    /// The guild-wide Permissions of the author before channel overwrites, if known.
+    author_guild_permissions: Option<serenity::Permissions>,
+    /// The guild-wide Permissions of the bot before channel overwrites, if known.
+    bot_guild_permissions: Option<serenity::Permissions>,
 }
 
 impl PermissionsInfo {
@@ -19,10 +23,85 @@ impl PermissionsInfo {
         Self {
             author_permissions: Some(serenity::Permissions::dm_permissions()),
             bot_permissions: Some(serenity::Permissions::dm_permissions()),
+            author_guild_permissions: None,
+            bot_guild_permissions: None,
         }
     }
 }
 
+/// Gets the permissions of a guild member, e.g. the bot.
+fn get_user_permissions(
+    guild: &serenity::Guild,
+    channel_id: serenity::GenericChannelId,
+    user_id: serenity::UserId,
+) -> Option<serenity::Permissions> {
+    // Should never fail for the bot, as the bot member is always cached
+    let member = guild.members.get(&user_id)?;
+
+    let Some(channel) = guild.channel(channel_id) else {
+        // The message was either:
+        // - Sent in a guild with broken caching
+        // - Not set in a channel or thread?
+        tracing::warn!(
+            "Could not find channel/thread ({channel_id}) for permissions check in cache for guild: {}",
+            guild.id
+        );
+        return None;
+    };
+
+    match channel {
+        serenity::GenericGuildChannelRef::Channel(channel) => {
+            Some(guild.user_permissions_in(channel, member))
+        }
+        serenity::GenericGuildChannelRef::Thread(thread) => {
+            let parent_channel = guild.channels.get(&thread.parent_id)?;
+            let mut parent_permissions = guild.user_permissions_in(parent_channel, member);
+
+            parent_permissions.set(
+                serenity::Permissions::SEND_MESSAGES,
+                parent_permissions.send_messages_in_threads(),
+            );
+
+            Some(parent_permissions)
+        }
+    }
+}
+
+/// Gets the guild-wide permissions of a cached guild member, before channel overwrites.
+///
+/// In threads, `SEND_MESSAGES` is replaced by `SEND_MESSAGES_IN_THREADS` like for the channel
+/// permissions, so that both can be compared.
+fn get_guild_permissions(
+    guild: &serenity::Guild,
+    user_id: serenity::UserId,
+    in_thread: bool,
+) -> Option<serenity::Permissions> {
+    let member = guild.members.get(&user_id)?;
+    let mut permissions = guild.member_permissions(member);
+    if in_thread {
+        permissions.set(
+            serenity::Permissions::SEND_MESSAGES,
+            permissions.send_messages_in_threads(),
+        );
+    }
+    Some(permissions)
+}
+
+/// Determines whether the missing permissions are lacking guild-wide or only denied by channel
+/// overwrites
+fn permission_scope(
+    missing_permissions: serenity::Permissions,
+    guild_permissions: Option<serenity::Permissions>,
+) -> crate::PermissionScope {
+    match guild_permissions {
+        Some(guild_permissions) if guild_permissions.contains(missing_permissions) => {
+            crate::PermissionScope::ChannelOverwrites
+        }
+        Some(_) => crate::PermissionScope::Guild,
+        None => crate::PermissionScope::Unknown,
+    }
+}
+
 /// Retrieves the permissions for the context author and the bot.
 async fn get_author_and_bot_permissions<T, E>(
     ctx: crate::Context<'_, T, E>,
@@ -39,7 +118,7 @@ where
 
     match ctx {
         crate::Context::Application(ctx) => {
-            Some(application::get_author_and_bot_permissions(ctx.interaction))
+            application::get_author_and_bot_permissions(ctx, guild_id, skip_author).await
         }
         crate::Context::Prefix(ctx) => {
             prefix::get_author_and_bot_permissions(ctx, guild_id, skip_author, skip_bot).await
@@ -47,23 +126,28 @@ where
     }
 }
 
-/// Retrieves the set of permissions that are lacking, relative to the given required permission set
+/// Retrieves the set of permissions that are lacking, relative to the given required permission
+/// set, for the author and the bot, along with where they're lacking
 ///
 /// Returns None if permissions couldn't be retrieved.
 pub(super) async fn calculate_missing<T, E>(
     ctx: crate::Context<'_, T, E>,
     author_required_permissions: serenity::Permissions,
     bot_required_permissions: serenity::Permissions,
-) -> Option<(serenity::Permissions, serenity::Permissions)>
+) -> Option<(
+    (serenity::Permissions, crate::PermissionScope),
+    (serenity::Permissions, crate::PermissionScope),
+)>
 where
     T: Send + Sync + 'static,
 {
     // If both user and bot are None, return empty permissions
     if author_required_permissions.is_empty() && bot_required_permissions.is_empty() {
-        return Some((
+        let none_missing = (
             serenity::Permissions::empty(),
-            serenity::Permissions::empty(),
-        ));
+            crate::PermissionScope::Unknown,
+        );
+        return Some((none_missing, none_missing));
     }
 
     // Fetch permissions, returning None if an error occurred
@@ -84,5 +168,11 @@ where
         .map(|permissions| bot_required_permissions - permissions)
         .unwrap_or_default();
 
-    Some((author_missing_perms, bot_missing_perms))
+    let author_scope = permission_scope(author_missing_perms, permissions.author_guild_permissions);
+    let bot_scope = permission_scope(bot_missing_perms, permissions.bot_guild_permissions);
+
+    Some((
+        (author_missing_perms, author_scope),
+        (bot_missing_perms, bot_scope),
+    ))
 }
@@ -2,34 +2,140 @@
 use self::serenity::Permissions;
 use crate::serenity_prelude as serenity;
 
-use super::PermissionsInfo;
+use super::{PermissionsInfo, get_guild_permissions, get_user_permissions};
 
 /// Gets the permissions of the ctx author and the bot.
-pub(super) fn get_author_and_bot_permissions(
-    interaction: &serenity::CommandInteraction,
-) -> PermissionsInfo {
-    let err = "member is Some if interaction is in guild";
-    let author_member = interaction.member.as_ref().expect(err);
+///
+/// The author's permissions are usually included in the interaction. If they're absent, e.g. for
+/// some invocations of user-installed apps, they're computed according to
+/// [`crate::FrameworkOptions::permission_fallback`]. Returns None if that's not possible.
+///
+/// Discord only sends the permissions in the channel, so the guild-wide permissions are only
+/// known if the guild is cached.
+pub(super) async fn get_author_and_bot_permissions<T, E>(
+    ctx: crate::ApplicationContext<'_, T, E>,
+    guild_id: serenity::GuildId,
+    skip_author: bool,
+) -> Option<PermissionsInfo>
+where
+    T: Send + Sync + 'static,
+{
+    let interaction = ctx.interaction;
+    let channel = interaction.channel.as_ref();
+    let in_thread =
+        channel.is_some_and(|c| matches!(c, serenity::GenericInteractionChannel::Thread(_)));
 
-    let err = "should always be some as inside interaction";
-    let mut author_permissions = author_member.permissions.expect(err);
+    let provided_author_permissions = interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions);
+    let author_permissions = match provided_author_permissions {
+        _ if skip_author => None,
+        Some(mut author_permissions) => {
+            if in_thread {
+                author_permissions.set(
+                    Permissions::SEND_MESSAGES,
+                    author_permissions.send_messages_in_threads(),
+                );
+            }
+            Some(author_permissions)
+        }
+        None => Some(compute_author_permissions(ctx, guild_id).await?),
+    };
 
     let mut bot_permissions = interaction.app_permissions;
-
-    let channel = interaction.channel.as_ref();
-    if channel.is_some_and(|c| matches!(c, serenity::GenericInteractionChannel::Thread(_))) {
-        author_permissions.set(
-            Permissions::SEND_MESSAGES,
-            author_permissions.send_messages_in_threads(),
-        );
+    if in_thread {
         bot_permissions.set(
             Permissions::SEND_MESSAGES,
             bot_permissions.send_messages_in_threads(),
         );
     }
 
-    PermissionsInfo {
-        author_permissions: Some(author_permissions),
+    let cache = ctx.cache();
+    let guild = cache.guild(guild_id);
+    let guild_permissions = |user_id| get_guild_permissions(guild.as_deref()?, user_id, in_thread);
+
+    Some(PermissionsInfo {
+        author_permissions,
         bot_permissions: Some(bot_permissions),
+        author_guild_permissions: guild_permissions(interaction.user.id),
+        bot_guild_permissions: guild_permissions(cache.current_user().id),
+    })
+}
+
+/// Computes the author's permissions in the channel if the interaction didn't include them
+async fn compute_author_permissions<T, E>(
+    ctx: crate::ApplicationContext<'_, T, E>,
+    guild_id: serenity::GuildId,
+) -> Option<Permissions>
+where
+    T: Send + Sync + 'static,
+{
+    let fallback = ctx.framework.options.permission_fallback;
+    if fallback == crate::PermissionFallback::Disabled {
+        return None;
+    }
+
+    let channel_id = ctx.interaction.channel_id;
+    let author_id = ctx.interaction.user.id;
+    let cached = ctx
+        .cache()
+        .guild(guild_id)
+        .and_then(|guild| get_user_permissions(&guild, channel_id, author_id));
+    if cached.is_some() || fallback != crate::PermissionFallback::Http {
+        return cached;
+    }
+
+    match fetch_user_permissions(ctx.serenity_context(), guild_id, channel_id, author_id).await {
+        Ok(permissions) => permissions,
+        // Expected if the bot isn't in the guild, e.g. when it's only installed by the user
+        Err(e) if is_forbidden_or_not_found(&e) => {
+            tracing::debug!("Can't fetch permissions of {author_id}: {e}");
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Failed to fetch permissions of {author_id}: {e}");
+            None
+        }
+    }
+}
+
+/// Whether the request failed with 403 Forbidden or 404 Not Found
+fn is_forbidden_or_not_found(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) => {
+            matches!(response.status_code.as_u16(), 403 | 404)
+        }
+        _ => false,
+    }
+}
+
+/// Computes the permissions of a guild member in a channel via HTTP requests.
+///
+/// Returns `Ok(None)` if the channel isn't a guild channel.
+async fn fetch_user_permissions(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::GenericChannelId,
+    user_id: serenity::UserId,
+) -> Result<Option<Permissions>, serenity::Error> {
+    let guild = guild_id.to_partial_guild(&ctx.http).await?;
+    let member = guild_id.member(ctx, user_id).await?;
+
+    match channel_id.to_channel(ctx, Some(guild_id)).await? {
+        serenity::Channel::Guild(channel) => Ok(Some(guild.user_permissions_in(&channel, &member))),
+        serenity::Channel::GuildThread(thread) => {
+            let parent = thread
+                .parent_id
+                .to_guild_channel(ctx, Some(guild_id))
+                .await?;
+            let mut permissions = guild.user_permissions_in(&parent, &member);
+            permissions.set(
+                Permissions::SEND_MESSAGES,
+                permissions.send_messages_in_threads(),
+            );
+            Ok(Some(permissions))
+        }
+        _ => Ok(None),
     }
 }
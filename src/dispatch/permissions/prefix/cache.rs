@@ -2,7 +2,7 @@
 
 use crate::{PrefixContext, serenity_prelude as serenity};
 
-use crate::dispatch::permissions::PermissionsInfo;
+use crate::dispatch::permissions::{PermissionsInfo, get_guild_permissions, get_user_permissions};
 
 /// Gets the permissions of the ctx author and the bot.
 pub(in crate::dispatch::permissions) async fn get_author_and_bot_permissions<T, E>(
@@ -17,60 +17,32 @@ where
     // Should only fail if the guild is not cached, which is fair to bail on.
     let guild = ctx.cache().guild(guild_id)?;
 
+    let channel_id = ctx.channel_id();
+    let in_thread = matches!(
+        guild.channel(channel_id),
+        Some(serenity::GenericGuildChannelRef::Thread(_))
+    );
+
     let author_permissions = if skip_author {
         None
+    } else if ctx.author.id != ctx.msg.author.id {
+        // Proxied message, so the permissions of the webhook are meaningless
+        Some(get_user_permissions(&guild, channel_id, ctx.author.id)?)
     } else {
         Some(ctx.msg.author_permissions(ctx.cache())?)
     };
 
+    let bot_user_id = ctx.framework.serenity_context.cache.current_user().id;
     let bot_permissions = if skip_bot {
         None
     } else {
-        let channel_id = ctx.channel_id();
-        let bot_user_id = ctx.framework.serenity_context.cache.current_user().id;
-        Some(get_bot_permissions(&guild, channel_id, bot_user_id)?)
+        Some(get_user_permissions(&guild, channel_id, bot_user_id)?)
     };
 
     Some(PermissionsInfo {
         author_permissions,
         bot_permissions,
+        author_guild_permissions: get_guild_permissions(&guild, ctx.author.id, in_thread),
+        bot_guild_permissions: get_guild_permissions(&guild, bot_user_id, in_thread),
     })
 }
-
-/// Gets the permissions for the bot.
-fn get_bot_permissions(
-    guild: &serenity::Guild,
-    channel_id: serenity::GenericChannelId,
-    bot_id: serenity::UserId,
-) -> Option<serenity::Permissions> {
-    // Should never fail, as the bot member is always cached
-    let bot_member = guild.members.get(&bot_id)?;
-
-    let Some(channel) = guild.channel(channel_id) else {
-        // The message was either:
-        // - Sent in a guild with broken caching
-        // - Not set in a channel or thread?
-        tracing::warn!(
-            "Could not find channel/thread ({channel_id}) for permissions check in cache for guild: {}",
-            guild.id
-        );
-        return None;
-    };
-
-    match channel {
-        serenity::GenericGuildChannelRef::Channel(channel) => {
-            Some(guild.user_permissions_in(channel, bot_member))
-        }
-        serenity::GenericGuildChannelRef::Thread(thread) => {
-            let parent_channel = guild.channels.get(&thread.parent_id)?;
-            let mut parent_permissions = guild.user_permissions_in(parent_channel, bot_member);
-
-            parent_permissions.set(
-                serenity::Permissions::SEND_MESSAGES,
-                parent_permissions.send_messages_in_threads(),
-            );
-
-            Some(parent_permissions)
-        }
-    }
-}
@@ -1,6 +1,44 @@
 //! Dispatches incoming messages and message edits onto framework commands
 
+use std::borrow::Borrow;
+
 use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
+
+/// Broadcasts typing in a channel, optionally after a delay, until dropped
+enum TypingBroadcast {
+    /// Typing has started immediately
+    Started(serenity::Typing),
+    /// Task which waits for the delay and then broadcasts typing until aborted
+    Delayed(tokio::task::JoinHandle<()>),
+}
+
+impl TypingBroadcast {
+    /// Starts broadcasting typing after `delay`
+    fn start(
+        channel_id: serenity::GenericChannelId,
+        http: std::sync::Arc<serenity::Http>,
+        delay: std::time::Duration,
+    ) -> Self {
+        if delay.is_zero() {
+            return Self::Started(channel_id.start_typing(http));
+        }
+        Self::Delayed(tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _typing = channel_id.start_typing(http);
+            std::future::pending::<()>().await;
+        }))
+    }
+}
+
+impl Drop for TypingBroadcast {
+    fn drop(&mut self) {
+        // Dropping the task's future drops its Typing, which stops the broadcast
+        if let Self::Delayed(task) = self {
+            task.abort();
+        }
+    }
+}
 
 /// Checks if this message is a bot invocation by attempting to strip the prefix
 ///
@@ -27,11 +65,12 @@ async fn strip_prefix<'a, T: Send + Sync + 'static, E>(
                 }
             }
             Err(error) => {
-                (framework.options.on_error)(crate::FrameworkError::DynamicPrefix {
+                crate::FrameworkError::DynamicPrefix {
                     error,
                     ctx: partial_ctx,
                     msg,
-                })
+                }
+                .handle(framework.options)
                 .await;
             }
         }
@@ -72,41 +111,105 @@ async fn strip_prefix<'a, T: Send + Sync + 'static, E>(
                 }
             }
             Err(error) => {
-                (framework.options.on_error)(crate::FrameworkError::DynamicPrefix {
+                crate::FrameworkError::DynamicPrefix {
                     error,
                     ctx: partial_ctx,
                     msg,
-                })
+                }
+                .handle(framework.options)
                 .await;
             }
         }
     }
 
     if framework.options.prefix_options.mention_as_prefix {
-        // Mentions are either <@USER_ID> or <@!USER_ID>
-        if let Some(stripped_content) = (|| {
-            msg.content
-                .strip_prefix("<@")?
-                .trim_start_matches('!')
-                .strip_prefix(
-                    &framework
-                        .serenity_context
-                        .cache
-                        .current_user()
-                        .id
-                        .to_string(),
-                )?
-                .strip_prefix('>')
-        })() {
-            let mention_prefix =
-                &msg.content[..(msg.content.len() as usize - stripped_content.len())];
-            return Some((mention_prefix, stripped_content));
+        if let Some(stripped) = mention_prefixes(framework).strip(&msg.content) {
+            return Some(stripped);
         }
     }
 
     None
 }
 
+/// Returns the bot's [`crate::PrefixFrameworkOptions::mention_prefixes`]
+fn mention_prefixes<'a, T, E>(
+    framework: crate::FrameworkContext<'a, T, E>,
+) -> &'a crate::MentionPrefixes {
+    // Normally filled in on Ready, but the Ready event may not have been dispatched to the
+    // framework, e.g. with manual dispatch
+    framework
+        .options
+        .prefix_options
+        .mention_prefixes
+        .get_or_init(|| {
+            crate::MentionPrefixes::new(framework.serenity_context.cache.current_user().id)
+        })
+}
+
+/// Splits the first word off the message, returning it and the rest without leading whitespace
+pub(crate) fn split_command_name(msg_content: &str) -> (&str, &str) {
+    let mut iter = msg_content.splitn(2, char::is_whitespace);
+    (iter.next().unwrap(), iter.next().unwrap_or("").trim_start())
+}
+
+/// Returns the number of single-character insertions, deletions, substitutions and transpositions
+/// of adjacent characters needed to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // distances[i][j] is the distance between the first i chars of a and the first j chars of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution_cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + substitution_cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// Returns the top-level commands whose name or an alias is similar to the unrecognized
+/// `command_name`, most similar first. See [`crate::FrameworkError::UnknownCommand::candidates`]
+fn similar_commands<'a, T, E>(
+    commands: &'a [impl Borrow<crate::Command<T, E>>],
+    command_name: &str,
+) -> Vec<&'a crate::Command<T, E>> {
+    let command_name = command_name.to_lowercase();
+    let max_distance = (command_name.chars().count() / 3).max(1);
+
+    let mut candidates = commands
+        .iter()
+        .map(Borrow::<crate::Command<T, E>>::borrow)
+        .filter(|command| {
+            !command.hide_in_help
+                && (command.prefix_action.is_some() || !command.subcommands.is_empty())
+        })
+        .filter_map(|command| {
+            let names = std::iter::once(&command.name).chain(command.aliases.iter());
+            let distance = names
+                .map(|name| edit_distance(&name.to_lowercase(), &command_name))
+                .min()?;
+            (distance <= max_distance).then_some((distance, command))
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|(a_distance, a), (b_distance, b)| {
+        a_distance.cmp(b_distance).then_with(|| a.name.cmp(&b.name))
+    });
+    candidates.into_iter().map(|(_, command)| command).collect()
+}
+
 /// Find a command or subcommand within `&[Command]`, given a command invocation without a prefix.
 /// Returns the verbatim command name string as well as the command arguments (i.e. the remaining
 /// string).
@@ -114,7 +217,7 @@ async fn strip_prefix<'a, T: Send + Sync + 'static, E>(
 /// The API must be like this (as opposed to just taking the command name upfront) because of
 /// subcommands.
 pub fn find_command<'a, T, E>(
-    commands: &'a [crate::Command<T, E>],
+    commands: &'a [impl Borrow<crate::Command<T, E>>],
     remaining_message: &'a str,
     case_insensitive: bool,
     parent_commands: &mut Vec<&'a crate::Command<T, E>>,
@@ -125,12 +228,10 @@ pub fn find_command<'a, T, E>(
         |a: &str, b: &str| a == b
     };
 
-    let (command_name, remaining_message) = {
-        let mut iter = remaining_message.splitn(2, char::is_whitespace);
-        (iter.next().unwrap(), iter.next().unwrap_or("").trim_start())
-    };
+    let (command_name, remaining_message) = split_command_name(remaining_message);
 
     for command in commands {
+        let command: &'a crate::Command<T, E> = command.borrow();
         let (primary_name_matches, alias_matches, mod_chars) =
             if command.has_modifier && command.subcommands.is_empty() {
                 let (primary_match, primary_mod) =
@@ -206,23 +307,73 @@ fn starts_with_ignore_ascii_case<'a>(needle: &str, haystack: &'a str) -> (bool,
     (true, h_chars.as_str())
 }
 
+/// Storage for values which are computed while parsing a prefix invocation and borrowed by the
+/// resulting [`crate::PrefixContext`], see [`dispatch_message`]
+///
+/// Needs to be passed in from outside because of lifetime issues. Create a new one per message.
+#[derive(Debug, Default)]
+pub struct PrefixInvocationScratch<'a> {
+    /// The author resolved by [`crate::PrefixFrameworkOptions::proxied_message_resolver`]
+    proxied_author: std::sync::OnceLock<serenity::User>,
+    /// See [`crate::PrefixContext::parameter_inputs`]
+    parameter_inputs: std::sync::OnceLock<Vec<(&'static str, Option<&'a str>)>>,
+    /// The text after the prefix as rewritten by [`resolve_unknown_command`]
+    resolved_content: std::sync::OnceLock<String>,
+    /// The text after the prefix without mentions of the bot, see
+    /// [`crate::PrefixFrameworkOptions::strip_self_mentions`]
+    stripped_content: std::sync::OnceLock<String>,
+}
+
+impl PrefixInvocationScratch<'_> {
+    /// Creates empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Whether any of the commands or their subcommands has [`crate::Command::allow_bot_invocations`]
+fn any_allows_bot_invocations<T, E>(commands: &[impl Borrow<crate::Command<T, E>>]) -> bool {
+    crate::walk_commands(commands.iter().map(|command| command.borrow()))
+        .any(|(_, command)| command.allow_bot_invocations)
+}
+
 /// Manually dispatches a message with the prefix framework
 pub async fn dispatch_message<'a, T: Send + Sync + 'static, E>(
     framework: crate::FrameworkContext<'a, T, E>,
     msg: &'a serenity::Message,
     trigger: crate::MessageDispatchTrigger,
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    scratch: &'a PrefixInvocationScratch<'a>,
     parent_commands: &'a mut Vec<&'a crate::Command<T, E>>,
 ) -> Result<(), crate::FrameworkError<'a, T, E>> {
-    if let Some(ctx) =
-        parse_invocation(framework, msg, trigger, invocation_data, parent_commands).await?
-    {
-        crate::catch_unwind_maybe(run_invocation(ctx))
+    let ctx = parse_invocation(
+        framework,
+        msg,
+        trigger,
+        invocation_data,
+        scratch,
+        parent_commands,
+    )
+    .await?;
+    if let Some(ctx) = ctx {
+        let id = crate::Context::Prefix(ctx).id();
+        let recent_invocations = framework.options.recent_invocations.as_ref();
+        if recent_invocations.is_some_and(|recent_invocations| !recent_invocations.claim(id)) {
+            tracing::debug!("ignoring duplicate message {}", msg.id);
+            return Ok(());
+        }
+        let span = super::invocation_span(ctx.into());
+        let result = crate::catch_unwind_maybe(run_invocation(ctx).instrument(span))
             .await
-            .map_err(|payload| crate::FrameworkError::CommandPanic {
-                payload,
+            .map_err(|panic| crate::FrameworkError::CommandPanic {
+                payload: panic.payload,
+                backtrace: panic.backtrace,
                 ctx: ctx.into(),
-            })??;
+            })
+            .and_then(|result| result);
+        crate::usage_stats::record_invocation(ctx.into(), &result);
+        crate::audit::record_invocation(ctx.into(), &result).await;
+        result?;
     } else if let Some(non_command_message) = framework.options.prefix_options.non_command_message {
         non_command_message(&framework, msg).await.map_err(|e| {
             crate::FrameworkError::NonCommandMessage {
@@ -235,6 +386,38 @@ pub async fn dispatch_message<'a, T: Send + Sync + 'static, E>(
     Ok(())
 }
 
+/// Rewrites text after the prefix that doesn't match any command, using the guild's
+/// [`crate::PrefixFrameworkOptions::command_aliases`] or else the
+/// [`crate::PrefixFrameworkOptions::command_resolver`]
+async fn resolve_unknown_command<T, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    msg: &serenity::Message,
+    msg_content: &str,
+) -> Option<String> {
+    if let Some(content) =
+        crate::command_aliases::resolve_command_alias(framework, msg, msg_content).await
+    {
+        return Some(content);
+    }
+    let command_resolver = framework.options.prefix_options.command_resolver?;
+    let resolved = command_resolver(&framework, msg, msg_content).await?;
+    Some(resolved.content)
+}
+
+/// Whether the message was generated by Discord rather than written by a user or bot, e.g. a
+/// boost notice. Thread creation messages are excluded, see
+/// [`crate::PrefixFrameworkOptions::ignore_thread_creation`]
+fn is_system_message(msg: &serenity::Message) -> bool {
+    !matches!(
+        msg.kind,
+        serenity::MessageType::Regular
+            | serenity::MessageType::InlineReply
+            | serenity::MessageType::ChatInputCommand
+            | serenity::MessageType::ContextMenuCommand
+            | serenity::MessageType::ThreadCreated
+    )
+}
+
 /// Given a Message and some context data, parses prefix, command etc. out of the message and
 /// returns the resulting [`crate::PrefixContext`]. To run the command, see [`run_invocation`].
 ///
@@ -246,10 +429,35 @@ pub async fn parse_invocation<'a, T: Send + Sync + 'static, E>(
     msg: &'a serenity::Message,
     trigger: crate::MessageDispatchTrigger,
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    scratch: &'a PrefixInvocationScratch<'a>,
     parent_commands: &'a mut Vec<&'a crate::Command<T, E>>,
 ) -> Result<Option<crate::PrefixContext<'a, T, E>>, crate::FrameworkError<'a, T, E>> {
-    // Check if we're allowed to invoke from bot messages
-    if msg.author.bot() && framework.options.prefix_options.ignore_bots {
+    let dispatch_started = std::time::Instant::now();
+    let prefix_options = &framework.options.prefix_options;
+
+    if prefix_options.ignore_webhooks && msg.webhook_id.is_some() {
+        return Ok(None);
+    }
+    if prefix_options.ignore_system_messages && is_system_message(msg) {
+        return Ok(None);
+    }
+
+    // Webhook messages may be proxied on behalf of a user, which is resolved after the prefix
+    // check below so that the resolver isn't called for every single webhook message
+    let proxied_message_resolver = framework
+        .options
+        .prefix_options
+        .proxied_message_resolver
+        .filter(|_| msg.webhook_id.is_some());
+
+    // Check if we're allowed to invoke from bot messages. If some commands accept bot messages
+    // anyway, this is checked again once the command is known
+    let ignore_bots = framework.options.prefix_options.ignore_bots;
+    if msg.author.bot()
+        && ignore_bots
+        && proxied_message_resolver.is_none()
+        && !any_allows_bot_invocations(&framework.options.commands)
+    {
         return Ok(None);
     }
 
@@ -267,29 +475,77 @@ pub async fn parse_invocation<'a, T: Send + Sync + 'static, E>(
         return Ok(None);
     }
 
+    if let Some(message_filter) = prefix_options.message_filter {
+        if !message_filter(framework, msg) {
+            return Ok(None);
+        }
+    }
+
     // Strip prefix, trim whitespace between prefix and rest, split rest into command name and args
     let (prefix, msg_content) = match strip_prefix(framework, msg).await {
         Some(x) => x,
         None => return Ok(None),
     };
-    let msg_content = msg_content.trim_start();
+    let mut msg_content = msg_content.trim_start();
+    if framework.options.prefix_options.strip_self_mentions {
+        if let Some(stripped) = mention_prefixes(framework).remove_all(msg_content) {
+            msg_content = scratch.stripped_content.get_or_init(|| stripped);
+        }
+    }
+
+    let mut author = &msg.author;
+    if let Some(proxied_message_resolver) = proxied_message_resolver {
+        if let Some(user) = proxied_message_resolver(&framework, msg).await {
+            author = scratch.proxied_author.get_or_init(|| user);
+        }
+    }
+    let ignore_author = author.bot() && ignore_bots;
 
-    let (command, mod_chars, invoked_command_name, args) = find_command(
+    let case_insensitive = framework.options.prefix_options.case_insensitive_commands;
+    let mut found_command = find_command(
         &framework.options.commands,
         msg_content,
-        framework.options.prefix_options.case_insensitive_commands,
+        case_insensitive,
         parent_commands,
-    )
-    .ok_or(crate::FrameworkError::UnknownCommand {
-        msg,
-        prefix,
-        msg_content,
-        framework,
-        invocation_data,
-        trigger,
-    })?;
+    );
+    if found_command.is_none() && !ignore_author {
+        if let Some(content) = resolve_unknown_command(framework, msg, msg_content).await {
+            let content = scratch.resolved_content.get_or_init(|| content);
+            found_command = find_command(
+                &framework.options.commands,
+                content.trim_start(),
+                case_insensitive,
+                parent_commands,
+            );
+        }
+    }
 
-    let action = match command.prefix_action {
+    let Some((command, mod_chars, invoked_command_name, args)) = found_command else {
+        if ignore_author {
+            return Ok(None);
+        }
+        let (command_name, args) = split_command_name(msg_content);
+        return Err(crate::FrameworkError::UnknownCommand {
+            msg,
+            prefix,
+            msg_content,
+            command_name,
+            args,
+            candidates: similar_commands(&framework.options.commands, command_name),
+            framework,
+            invocation_data,
+            trigger,
+        });
+    };
+    if ignore_author && !command.allow_bot_invocations {
+        return Ok(None);
+    }
+
+    // If the command still has subcommands here, none of them matched
+    let fallback_action = command
+        .fallback_action
+        .filter(|_| !command.subcommands.is_empty());
+    let action = match fallback_action.or(command.prefix_action) {
         Some(x) => x,
         // This command doesn't have a prefix implementation
         None => return Ok(None),
@@ -297,16 +553,19 @@ pub async fn parse_invocation<'a, T: Send + Sync + 'static, E>(
 
     Ok(Some(crate::PrefixContext {
         msg,
+        author,
         prefix,
         invoked_command_name,
         mod_chars,
         args,
+        parameter_inputs: &scratch.parameter_inputs,
         framework,
         parent_commands,
         command,
         invocation_data,
         trigger,
         action,
+        dispatch_started,
         __non_exhaustive: (),
     }))
 }
@@ -326,7 +585,7 @@ pub async fn run_invocation<T: Send + Sync + 'static, E>(
         return Ok(());
     }
 
-    if ctx.command.subcommand_required {
+    if ctx.command.subcommand_required && ctx.command.fallback_action.is_none() {
         // None of this command's subcommands were invoked, or else we'd have the subcommand in
         // ctx.command and not the parent command
         return Err(crate::FrameworkError::SubcommandRequired {
@@ -338,11 +597,15 @@ pub async fn run_invocation<T: Send + Sync + 'static, E>(
 
     // Typing is broadcasted as long as this object is alive
     let _typing_broadcaster = if ctx.command.broadcast_typing {
-        Some(
-            ctx.msg
-                .channel_id
-                .start_typing(ctx.framework.serenity_context.http.clone()),
-        )
+        let delay = ctx
+            .command
+            .broadcast_typing_delay
+            .unwrap_or(ctx.framework.options.prefix_options.broadcast_typing_delay);
+        Some(TypingBroadcast::start(
+            ctx.msg.channel_id,
+            ctx.framework.serenity_context.http.clone(),
+            delay,
+        ))
     } else {
         None
     };
@@ -354,16 +617,23 @@ pub async fn run_invocation<T: Send + Sync + 'static, E>(
     // execute_untracked_edits situation and start an infinite loop
     // Reported by vicky5124 https://discord.com/channels/381880193251409931/381912587505500160/897981367604903966
     if let Some(edit_tracker) = &ctx.framework.options.prefix_options.edit_tracker {
-        edit_tracker
-            .write()
-            .unwrap()
-            .track_command(ctx.msg, ctx.command.track_deletion);
+        edit_tracker.track_command(ctx.msg, ctx.command.track_deletion);
     }
 
     // Execute command
-    (ctx.action)(ctx).await?;
+    super::common::run_action(ctx.into(), (ctx.action)(ctx)).await?;
 
     (ctx.framework.options.post_command)(crate::Context::Prefix(ctx)).await;
 
     Ok(())
 }
+
+#[cfg(test)]
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("ping", "ping"), 0);
+    assert_eq!(edit_distance("ping", "pnig"), 1);
+    assert_eq!(edit_distance("ping", "pin"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+}
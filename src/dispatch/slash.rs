@@ -1,17 +1,25 @@
 //! Dispatches interactions onto framework commands
 
+use std::borrow::Borrow;
+
 use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
 
 /// Check if the interaction with the given name and arguments matches any framework command
 fn find_matching_command<'a, 'b, T, E>(
     interaction_name: &str,
     interaction_options: &'b [serenity::ResolvedOption<'b>],
-    commands: &'a [crate::Command<T, E>],
+    commands: &'a [impl Borrow<crate::Command<T, E>>],
     parent_commands: &mut Vec<&'a crate::Command<T, E>>,
 ) -> Option<(&'a crate::Command<T, E>, &'b [serenity::ResolvedOption<'b>])> {
     commands.iter().find_map(|cmd| {
+        let cmd: &'a crate::Command<T, E> = cmd.borrow();
         if interaction_name != cmd.name
             && Some(interaction_name) != cmd.context_menu_name.as_deref()
+            && !cmd
+                .slash_aliases
+                .iter()
+                .any(|alias| alias == interaction_name)
         {
             return None;
         }
@@ -47,6 +55,7 @@ fn extract_command<'a, T, E>(
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<T, E>>,
 ) -> Result<crate::ApplicationContext<'a, T, E>, crate::FrameworkError<'a, T, E>> {
+    let dispatch_started = std::time::Instant::now();
     let search_result = find_matching_command(
         &interaction.data.name,
         options,
@@ -68,6 +77,7 @@ fn extract_command<'a, T, E>(
         parent_commands,
         has_sent_initial_response,
         invocation_data,
+        dispatch_started,
         __non_exhaustive: (),
     })
 }
@@ -103,6 +113,26 @@ async fn run_command<T: Send + Sync + 'static, E>(
 ) -> Result<(), crate::FrameworkError<'_, T, E>> {
     super::common::check_permissions_and_cooldown(ctx.into()).await?;
 
+    // Defer before any user code runs so that slow pre_command hooks count against the deadline
+    let defer_ephemeral = match ctx.command.response_mode {
+        crate::ResponseMode::DeferPublic => Some(false),
+        crate::ResponseMode::DeferEphemeral => Some(true),
+        _ => None,
+    };
+    if let Some(ephemeral) = defer_ephemeral {
+        if let Err(error) = ctx.defer_response(ephemeral).await {
+            // Not fatal: the command can still try to respond
+            let error = crate::FrameworkError::Internal {
+                error,
+                operation: "defer interaction response",
+                channel_id: Some(ctx.interaction.channel_id),
+                message_id: None,
+                framework: ctx.framework,
+            };
+            error.handle(ctx.framework.options).await;
+        }
+    }
+
     (ctx.framework.options.pre_command)(crate::Context::Application(ctx)).await;
 
     // Check which interaction type we received and grab the command action and, if context menu,
@@ -118,7 +148,7 @@ async fn run_command<T: Send + Sync + 'static, E>(
                 .command
                 .slash_action
                 .ok_or(command_structure_mismatch_error)?;
-            action(ctx).await
+            super::common::run_action(ctx.into(), action(ctx)).await
         }
         serenity::CommandType::User => {
             match (
@@ -128,7 +158,10 @@ async fn run_command<T: Send + Sync + 'static, E>(
                 (
                     Some(crate::ContextMenuCommandAction::User(action)),
                     Some(serenity::ResolvedTarget::User(user, _)),
-                ) => action(ctx, (*user).clone()).await,
+                ) => {
+                    let action = action(ctx, (*user).clone());
+                    super::common::run_action(ctx.into(), action).await
+                }
                 _ => return Err(command_structure_mismatch_error),
             }
         }
@@ -140,10 +173,27 @@ async fn run_command<T: Send + Sync + 'static, E>(
                 (
                     Some(crate::ContextMenuCommandAction::Message(action)),
                     Some(serenity::ResolvedTarget::Message(message)),
-                ) => action(ctx, (*message).clone()).await,
+                ) => {
+                    let action = action(ctx, (*message).clone());
+                    super::common::run_action(ctx.into(), action).await
+                }
                 _ => return Err(command_structure_mismatch_error),
             }
         }
+        serenity::CommandType::PrimaryEntryPoint => match ctx.command.slash_action {
+            Some(action)
+                if ctx.command.is_primary_entry_point()
+                    && ctx.command.entry_point_handler == crate::EntryPointHandler::AppHandler =>
+            {
+                super::common::run_action(ctx.into(), action(ctx)).await
+            }
+            // Discord launches the Activity itself for `DiscordLaunchActivity` entry points, in
+            // which case there's nothing for us to do
+            _ => {
+                tracing::debug!("ignoring unhandled primary entry point interaction");
+                return Ok(());
+            }
+        },
         other => {
             tracing::warn!("unknown interaction command type: {:?}", other);
             return Ok(());
@@ -178,14 +228,80 @@ pub async fn dispatch_interaction<'a, T: Send + Sync + 'static, E>(
         parent_commands,
     )?;
 
-    crate::catch_unwind_maybe(run_command(ctx))
+    let span = super::invocation_span(ctx.into());
+    let result = crate::catch_unwind_maybe(run_command(ctx).instrument(span))
         .await
-        .map_err(|payload| crate::FrameworkError::CommandPanic {
-            payload,
+        .map_err(|panic| crate::FrameworkError::CommandPanic {
+            payload: panic.payload,
+            backtrace: panic.backtrace,
             ctx: ctx.into(),
-        })??;
+        })
+        .and_then(|result| result);
+    crate::usage_stats::record_invocation(ctx.into(), &result);
+    crate::audit::record_invocation(ctx.into(), &result).await;
+    result
+}
 
-    Ok(())
+/// Handles a click on a [`crate::Command::rerun_button`] button by dispatching the original
+/// command interaction again, including all checks.
+///
+/// Returns `Ok(false)` if the component interaction is not a rerun button.
+pub async fn dispatch_rerun_button<T: Send + Sync + 'static, E>(
+    framework: crate::FrameworkContext<'_, T, E>,
+    component: &serenity::ComponentInteraction,
+) -> Result<bool, serenity::Error> {
+    let Some(interaction_id) = component
+        .data
+        .custom_id
+        .strip_prefix(crate::RERUN_BUTTON_PREFIX)
+    else {
+        return Ok(false);
+    };
+    let interaction = interaction_id
+        .parse()
+        .ok()
+        .and_then(|id| framework.options.rerun_tracker.find(id));
+
+    let rejection = match &interaction {
+        None => Some("This command can't be re-run anymore"),
+        Some(interaction) if interaction.user.id != component.user.id => {
+            Some("Only the user who invoked this command can re-run it")
+        }
+        Some(_) => None,
+    };
+    let response = match rejection {
+        Some(rejection) => serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(rejection)
+                .ephemeral(true),
+        ),
+        None => serenity::CreateInteractionResponse::Acknowledge,
+    };
+    component
+        .create_response(&framework.serenity_context.http, response)
+        .await?;
+    let (Some(interaction), None) = (interaction, rejection) else {
+        return Ok(true);
+    };
+
+    let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+    let mut parent_commands = Vec::new();
+    // The initial response was already sent by the original invocation
+    let has_sent_initial_response = std::sync::atomic::AtomicBool::new(true);
+    let options = interaction.data.options();
+    if let Err(error) = dispatch_interaction(
+        framework,
+        &interaction,
+        &has_sent_initial_response,
+        &invocation_data,
+        &options,
+        &mut parent_commands,
+    )
+    .await
+    {
+        error.handle(framework.options).await;
+    }
+    Ok(true)
 }
 
 /// Given the extracted application command data from [`extract_command`], runs the autocomplete
@@ -227,18 +343,19 @@ async fn run_autocomplete<T: Send + Sync + 'static, E>(
     let autocomplete_response = autocomplete_callback(ctx, partial_input).await;
 
     // Send the generates autocomplete response
-    if let Err(e) = ctx
-        .interaction
+    ctx.interaction
         .create_response(
             ctx.http(),
             serenity::CreateInteractionResponse::Autocomplete(autocomplete_response),
         )
         .await
-    {
-        tracing::warn!("couldn't send autocomplete response: {e}");
-    }
-
-    Ok(())
+        .map_err(|error| crate::FrameworkError::Internal {
+            error,
+            operation: "send autocomplete response",
+            channel_id: Some(ctx.interaction.channel_id),
+            message_id: None,
+            framework: ctx.framework,
+        })
 }
 
 /// Dispatches this interaction onto framework commands, i.e. runs the associated autocomplete
@@ -264,8 +381,9 @@ pub async fn dispatch_autocomplete<'a, T: Send + Sync + 'static, E>(
 
     crate::catch_unwind_maybe(run_autocomplete(ctx))
         .await
-        .map_err(|payload| crate::FrameworkError::CommandPanic {
-            payload,
+        .map_err(|panic| crate::FrameworkError::CommandPanic {
+            payload: panic.payload,
+            backtrace: panic.backtrace,
             ctx: ctx.into(),
         })??;
 
@@ -0,0 +1,34 @@
+//! Gradual rollout of commands behind feature flags, see [`FeatureGate`]
+
+/// Decides whether a feature flag is enabled for an invocation
+///
+/// Store in [`crate::FrameworkOptions::feature_gate`]. Commands opt into a flag with
+/// [`crate::Command::feature`], or `#[lumi::command(feature = "new_search")]`. Before such a
+/// command runs, the gate is asked whether the flag is enabled for the invocation, and if not,
+/// [`crate::FrameworkError::FeatureDisabled`] is thrown. Like other checks, this applies to parent
+/// commands as well, so gating a parent command gates all its subcommands.
+///
+/// The context gives access to the guild, channel and author, so the gate can roll out a flag to a
+/// percentage of guilds or to specific users, e.g. by forwarding to a LaunchDarkly-style flag
+/// service. Evaluation is synchronous, so keep flag state cached locally instead of querying a
+/// remote service on every invocation.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Rolls out features to guilds with an even ID first
+/// struct EvenGuilds;
+///
+/// impl lumi::FeatureGate<(), Error> for EvenGuilds {
+///     fn evaluate(&self, flag: &str, ctx: Context<'_>) -> bool {
+///         match flag {
+///             "new_search" => ctx.guild_id().is_some_and(|guild_id| guild_id.get() % 2 == 0),
+///             _ => false,
+///         }
+///     }
+/// }
+/// ```
+pub trait FeatureGate<T, E>: Send + Sync {
+    /// Returns whether `flag` is enabled for this invocation
+    fn evaluate(&self, flag: &str, ctx: crate::Context<'_, T, E>) -> bool;
+}
@@ -10,6 +10,8 @@ pub struct FrameworkBuilder<T, E> {
     commands: Vec<crate::Command<T, E>>,
     /// See [`Self::initialize_owners()`]
     initialize_owners: bool,
+    /// See [`Self::presence_rotation()`]
+    presence_rotation: Option<crate::PresenceRotation>,
 }
 
 impl<T, E> Default for FrameworkBuilder<T, E> {
@@ -18,6 +20,7 @@ impl<T, E> Default for FrameworkBuilder<T, E> {
             options: Default::default(),
             commands: Default::default(),
             initialize_owners: true,
+            presence_rotation: None,
         }
     }
 }
@@ -39,6 +42,16 @@ impl<T, E> FrameworkBuilder<T, E> {
         self
     }
 
+    /// Rotate the bot's presence periodically once the bot is ready, see
+    /// [`crate::PresenceRotation`]
+    ///
+    /// Overrides [`crate::FrameworkOptions::presence_rotation`].
+    #[must_use]
+    pub fn presence_rotation(mut self, presence_rotation: crate::PresenceRotation) -> Self {
+        self.presence_rotation = Some(presence_rotation);
+        self
+    }
+
     /// Build the framework with the specified configuration.
     ///
     /// For more information, see [`FrameworkBuilder`]
@@ -50,8 +63,24 @@ impl<T, E> FrameworkBuilder<T, E> {
         let mut options = self.options.expect("No framework options provided");
 
         // Build framework options by concatenating user-set options with commands and owners
-        options.commands.extend(self.commands);
+        // Commands can't be modified anymore once shared, so prepare them before wrapping
+        let mut commands = self.commands;
+        for command in &mut commands {
+            command.invalidate_payload_cache();
+        }
+        crate::set_qualified_names(&mut commands);
+        crate::set_default_contexts(
+            &mut commands,
+            options.default_install_context.as_deref(),
+            options.default_interaction_context.as_deref(),
+        );
+        options
+            .commands
+            .extend(commands.into_iter().map(std::sync::Arc::new));
         options.initialize_owners = self.initialize_owners;
+        if let Some(presence_rotation) = self.presence_rotation {
+            options.presence_rotation = Some(presence_rotation);
+        }
 
         // Create framework with specified settings
         crate::Framework::new(options)
@@ -0,0 +1,246 @@
+//! Detecting commands which shadow each other, see [`check_command_collisions`]
+
+use std::borrow::Borrow;
+
+/// What kind of names collide in a [`CommandCollision`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommandCollisionKind {
+    /// Two commands on the same level have the same name, and thus the same
+    /// [`crate::Command::qualified_name`]
+    QualifiedName,
+    /// A prefix alias or slash command alias matches the name or an alias of another command
+    Alias,
+    /// A context menu name matches the name, an alias or the context menu name of another command
+    ContextMenuName,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Two commands which are reachable under the same name, making the second one unreachable
+#[derive(Clone, Debug)]
+pub struct CommandCollision {
+    /// What kind of names collide
+    pub kind: CommandCollisionKind,
+    /// The colliding name
+    pub name: String,
+    /// Qualified name of the command which is invoked when using [`Self::name`]
+    pub command: String,
+    /// Qualified name of the command which is shadowed by [`Self::command`]
+    pub shadowed_command: String,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl std::fmt::Display for CommandCollision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            CommandCollisionKind::Alias => "alias",
+            CommandCollisionKind::ContextMenuName => "context menu name",
+            CommandCollisionKind::QualifiedName | CommandCollisionKind::__NonExhaustive => "name",
+        };
+        write!(
+            f,
+            "`{}` shadows `{}` (colliding {} `{}`)",
+            self.command, self.shadowed_command, kind, self.name
+        )
+    }
+}
+
+/// Error returned from [`check_command_collisions`], listing all collisions in the command tree
+#[derive(Clone, Debug, Default)]
+pub struct CommandCollisions {
+    /// All collisions, in the order the command tree is traversed
+    pub collisions: Vec<CommandCollision>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl std::fmt::Display for CommandCollisions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Found {} command collisions", self.collisions.len())?;
+        for collision in &self.collisions {
+            write!(f, "\n- {}", collision)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CommandCollisions {}
+
+/// Which field of a command a name in [`check_names`] comes from
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NameSource {
+    /// [`crate::Command::name`]
+    Name,
+    /// [`crate::Command::aliases`] or [`crate::Command::slash_aliases`]
+    Alias,
+    /// [`crate::Command::context_menu_name`]
+    ContextMenuName,
+}
+
+/// Finds the collisions between commands on the same level of the command tree, which are
+/// looked up by the names returned from `names`
+///
+/// Collisions between two [`crate::Command::name`]s are skipped unless `report_name_collisions`
+/// is set, since they're present in both the prefix and the application command names.
+fn check_names<'a, T: 'a, E: 'a>(
+    siblings: impl IntoIterator<Item = &'a crate::Command<T, E>>,
+    names: impl Fn(&'a crate::Command<T, E>) -> Vec<(String, NameSource)>,
+    report_name_collisions: bool,
+    collisions: &mut Vec<CommandCollision>,
+) {
+    let mut seen = std::collections::HashMap::new();
+    for command in siblings {
+        for (name, source) in names(command) {
+            let Some(&(first, first_source)) = seen.get(&name) else {
+                seen.insert(name, (command, source));
+                continue;
+            };
+            if std::ptr::eq(first, command) {
+                continue;
+            }
+
+            let sources = [first_source, source];
+            let kind = if sources.contains(&NameSource::ContextMenuName) {
+                CommandCollisionKind::ContextMenuName
+            } else if sources.contains(&NameSource::Alias) {
+                CommandCollisionKind::Alias
+            } else if report_name_collisions {
+                CommandCollisionKind::QualifiedName
+            } else {
+                continue;
+            };
+            collisions.push(CommandCollision {
+                kind,
+                name,
+                command: first.qualified_name.to_string(),
+                shadowed_command: command.qualified_name.to_string(),
+                __non_exhaustive: (),
+            });
+        }
+    }
+}
+
+/// Finds the collisions between commands on the same level of the command tree
+///
+/// Prefix commands are matched by name and [`crate::Command::aliases`], application commands by
+/// name, [`crate::Command::context_menu_name`] and [`crate::Command::slash_aliases`].
+fn check_siblings<'a, T: 'a, E: 'a>(
+    siblings: &[&'a crate::Command<T, E>],
+    case_insensitive: bool,
+    collisions: &mut Vec<CommandCollision>,
+) {
+    let prefix_names = |command: &crate::Command<T, E>| {
+        let aliases = command.aliases.iter().map(|a| (&**a, NameSource::Alias));
+        std::iter::once((&*command.name, NameSource::Name))
+            .chain(aliases)
+            .map(|(name, source)| match case_insensitive {
+                true => (name.to_ascii_lowercase(), source),
+                false => (name.to_owned(), source),
+            })
+            .collect()
+    };
+    check_names(siblings.iter().copied(), prefix_names, true, collisions);
+
+    let application_names = |command: &crate::Command<T, E>| {
+        let context_menu_name = command
+            .context_menu_name
+            .as_deref()
+            .filter(|_| command.context_menu_action.is_some())
+            .map(|name| (name, NameSource::ContextMenuName));
+        let slash_aliases = command
+            .slash_aliases
+            .iter()
+            .map(|a| (&**a, NameSource::Alias));
+        std::iter::once((&*command.name, NameSource::Name))
+            .chain(context_menu_name)
+            .chain(slash_aliases)
+            .map(|(name, source)| (name.to_owned(), source))
+            .collect()
+    };
+    check_names(
+        siblings.iter().copied(),
+        application_names,
+        false,
+        collisions,
+    );
+}
+
+/// Checks the command tree for commands which can't be invoked because another command on the
+/// same level has the same name, alias or context menu name
+///
+/// Command lookup takes the first matching command, so the later command is silently
+/// unreachable. Set `case_insensitive` to
+/// [`crate::PrefixFrameworkOptions::case_insensitive_commands`] to also catch prefix names which
+/// only differ in case.
+///
+/// [`crate::Framework`] runs this on startup and logs the collisions. Call it yourself, e.g. in a
+/// test, to fail on collisions:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # #[lumi::command(prefix_command)] async fn ping(ctx: lumi::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+/// # #[lumi::command(prefix_command, rename = "ping")] async fn pong(ctx: lumi::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+/// let collisions = lumi::check_command_collisions(&[ping(), pong()], false).unwrap_err();
+/// assert_eq!(collisions.collisions[0].shadowed_command, "ping");
+/// ```
+pub fn check_command_collisions<T, E>(
+    commands: &[impl Borrow<crate::Command<T, E>>],
+    case_insensitive: bool,
+) -> Result<(), CommandCollisions> {
+    let commands = commands.iter().map(Borrow::borrow).collect::<Vec<_>>();
+
+    let mut collisions = Vec::new();
+    check_siblings(&commands, case_insensitive, &mut collisions);
+    for (_, command) in crate::walk_commands(commands.iter().copied()) {
+        let subcommands = command.subcommands.iter().collect::<Vec<_>>();
+        check_siblings(&subcommands, case_insensitive, &mut collisions);
+    }
+
+    match collisions.is_empty() {
+        true => Ok(()),
+        false => Err(CommandCollisions {
+            collisions,
+            __non_exhaustive: (),
+        }),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_check_command_collisions() {
+    let command = |name: &'static str, aliases: &'static [&'static str]| crate::Command::<(), ()> {
+        name: name.into(),
+        qualified_name: name.into(),
+        aliases: aliases.iter().map(|&alias| alias.into()).collect(),
+        ..Default::default()
+    };
+
+    assert!(check_command_collisions(&[command("a", &["b"]), command("c", &[])], false).is_ok());
+
+    let commands = [
+        command("a", &["b"]),
+        command("b", &[]),
+        command("a", &[]),
+        command("C", &[]),
+        command("c", &[]),
+    ];
+    assert_eq!(
+        check_command_collisions(&commands, false)
+            .unwrap_err()
+            .collisions
+            .iter()
+            .map(|c| (c.kind, &*c.name))
+            .collect::<Vec<_>>(),
+        [
+            (CommandCollisionKind::Alias, "b"),
+            (CommandCollisionKind::QualifiedName, "a"),
+        ]
+    );
+    assert_eq!(
+        check_command_collisions(&commands, true)
+            .unwrap_err()
+            .collisions
+            .len(),
+        3
+    );
+}
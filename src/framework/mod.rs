@@ -3,16 +3,23 @@
 use std::{borrow::Cow, sync::Arc};
 
 pub use builder::*;
+pub use collisions::*;
 
 use crate::serenity_prelude::{self as serenity, TeamMemberRole};
 
 mod builder;
+mod collisions;
 
 /// The main framework struct which stores all data and handles message and interaction dispatch.
 ///
 /// Technically, this is just an optional abstraction over [`crate::dispatch_event`] with some
 /// additional conveniences built-in:
 /// - fills in correct values for [`crate::Command::qualified_name`]: [`set_qualified_names`]
+/// - applies [`crate::FrameworkOptions::default_install_context`] and
+///   [`crate::FrameworkOptions::default_interaction_context`]: [`set_default_contexts`]
+/// - warns about commands which are unreachable because of name collisions:
+///   [`check_command_collisions`]
+/// - warns about incoherent install and interaction contexts: [`crate::check_command_contexts`]
 /// - spawns a background task to periodically clear edit tracker cache
 /// - sets up user data on the first Ready event
 /// - keeps track of shard manager and bot ID automatically
@@ -63,7 +70,50 @@ impl<T, E> Drop for Framework<T, E> {
 #[serenity::async_trait]
 impl<T: Send + Sync + 'static, E: Send + Sync> serenity::Framework for Framework<T, E> {
     async fn init(&mut self, client: &serenity::Client) {
-        set_qualified_names(&mut self.options.commands);
+        let install_context = self.options.default_install_context.as_deref();
+        let interaction_context = self.options.default_interaction_context.as_deref();
+        for command in &mut self.options.commands {
+            match Arc::get_mut(command) {
+                Some(command) => {
+                    // Fields are public, so the command may have been modified since it was last
+                    // registered or exported
+                    command.invalidate_payload_cache();
+                    let command = std::slice::from_mut(command);
+                    set_qualified_names(command);
+                    set_default_contexts(command, install_context, interaction_context);
+                }
+                // Commands are immutable once shared, so they should have been prepared
+                // beforehand. If not, the command still works, just with the wrong qualified
+                // subcommand names or without the default contexts
+                None => {
+                    if !is_prepared(command, install_context, interaction_context) {
+                        tracing::error!(
+                            "Command `{}` was shared before the framework was initialized, but \
+                            its qualified names or default contexts aren't set. Call \
+                            `lumi::set_qualified_names` and `lumi::set_default_contexts` before \
+                            wrapping it in an `Arc`",
+                            command.name
+                        );
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "blocking_commands")]
+        blocking_commands_sanity_check(&self.options.commands);
+
+        if let Err(collisions) = check_command_collisions(
+            &self.options.commands,
+            self.options.prefix_options.case_insensitive_commands,
+        ) {
+            tracing::warn!("{collisions}");
+        }
+        for warning in crate::check_command_contexts(&self.options.commands) {
+            tracing::warn!("{warning}");
+            if let Some(audit_sink) = &self.options.audit_sink {
+                audit_sink.record_warning(warning).await;
+            }
+        }
 
         message_content_intent_sanity_check(
             &self.options.prefix_options,
@@ -111,6 +161,66 @@ pub fn set_qualified_names<T, E>(commands: &mut [crate::Command<T, E>]) {
     }
 }
 
+/// Sets [`crate::Command::install_context`] and [`crate::Command::interaction_context`] of the
+/// commands which don't specify their own to the given defaults
+pub fn set_default_contexts<T, E>(
+    commands: &mut [crate::Command<T, E>],
+    install_context: Option<&[serenity::InstallationContext]>,
+    interaction_context: Option<&[serenity::InteractionContext]>,
+) {
+    for command in commands {
+        let mut changed = false;
+        if let (None, Some(default)) = (&command.install_context, install_context) {
+            command.install_context = Some(default.to_vec());
+            changed = true;
+        }
+        if let (None, Some(default)) = (&command.interaction_context, interaction_context) {
+            command.interaction_context = Some(default.to_vec());
+            changed = true;
+        }
+        if changed {
+            command.invalidate_payload_cache();
+        }
+    }
+}
+
+/// Whether [`set_qualified_names`] and [`set_default_contexts`] with the given defaults would
+/// leave the top-level command unchanged
+fn is_prepared<T, E>(
+    command: &crate::Command<T, E>,
+    install_context: Option<&[serenity::InstallationContext]>,
+    interaction_context: Option<&[serenity::InteractionContext]>,
+) -> bool {
+    fn has_qualified_names<T, E>(parents: &str, commands: &[crate::Command<T, E>]) -> bool {
+        commands.iter().all(|cmd| {
+            cmd.qualified_name.strip_prefix(parents) == Some(&*format!(" {}", cmd.name))
+                && has_qualified_names(&cmd.qualified_name, &cmd.subcommands)
+        })
+    }
+
+    has_qualified_names(&command.name, &command.subcommands)
+        && (command.install_context.is_some() || install_context.is_none())
+        && (command.interaction_context.is_some() || interaction_context.is_none())
+}
+
+/// Panics if a command uses [`crate::SpawnPolicy::Blocking`] but the framework doesn't run on a
+/// multi-threaded runtime, where blocking in place is impossible
+#[cfg(feature = "blocking_commands")]
+fn blocking_commands_sanity_check<T, E>(commands: &[Arc<crate::Command<T, E>>]) {
+    let blocking_command = crate::walk_commands(commands.iter().map(|command| &**command))
+        .map(|(_, command)| command)
+        .find(|command| command.spawn_policy == crate::SpawnPolicy::Blocking);
+    if let Some(command) = blocking_command {
+        let flavor = tokio::runtime::Handle::current().runtime_flavor();
+        assert!(
+            flavor == tokio::runtime::RuntimeFlavor::MultiThread,
+            "Command `{}` uses `SpawnPolicy::Blocking`, which requires the multi-threaded tokio \
+            runtime",
+            command.qualified_name
+        );
+    }
+}
+
 /// Prints a warning on stderr if a prefix is configured but `MESSAGE_CONTENT` is not set
 fn message_content_intent_sanity_check<T, E>(
     prefix_options: &crate::PrefixFrameworkOptions<T, E>,
@@ -173,14 +283,36 @@ pub async fn insert_owners_from_http(
 /// Because e.g. taking a `PrefixFrameworkOptions` reference won't work because tokio tasks need to be
 /// 'static
 fn spawn_edit_tracker_purge_task(
-    edit_tracker: Arc<std::sync::RwLock<crate::EditTracker>>,
+    edit_tracker: Arc<crate::EditTracker>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         loop {
-            edit_tracker.write().unwrap().purge();
+            edit_tracker.purge();
 
             // not sure if the purging interval should be configurable
             tokio::time::sleep(std::time::Duration::from_secs(60)).await;
         }
     })
 }
+
+#[cfg(test)]
+#[test]
+fn test_is_prepared() {
+    let mut commands = [crate::Command::<(), ()> {
+        name: "parent".into(),
+        subcommands: vec![crate::Command {
+            name: "child".into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }];
+    let install_context = &[serenity::InstallationContext::Guild][..];
+    assert!(!is_prepared(&commands[0], None, None));
+
+    set_qualified_names(&mut commands);
+    assert!(is_prepared(&commands[0], None, None));
+    assert!(!is_prepared(&commands[0], Some(install_context), None));
+
+    set_default_contexts(&mut commands, Some(install_context), None);
+    assert!(is_prepared(&commands[0], Some(install_context), None));
+}
@@ -0,0 +1,328 @@
+//! Typed per-guild data shared across commands, see [`GuildDataCache`]
+
+use crate::serenity_prelude as serenity;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A cached value of a [`GuildDataCache`]
+struct Entry<D> {
+    /// Initialized once by the first caller, concurrent callers wait for it
+    value: Arc<tokio::sync::OnceCell<Arc<D>>>,
+    /// When the value was last retrieved, for [`GuildDataCache::with_ttl`]
+    last_accessed: Instant,
+}
+
+/// Cached values of a [`GuildDataCache`] and when expired ones were last swept
+struct Entries<D> {
+    /// Cached values, keyed by guild
+    map: HashMap<serenity::GuildId, Entry<D>>,
+    /// When [`Self::sweep`] last removed expired values
+    last_sweep: Instant,
+}
+
+impl<D> Entries<D> {
+    /// Creates an empty map
+    fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+            last_sweep: Instant::now(),
+        }
+    }
+
+    /// Removes expired values, at most once per `ttl` so that accesses stay cheap on average.
+    /// Values of guilds which are accessed again expire lazily in [`Self::touch`] instead
+    fn sweep(&mut self, ttl: Duration, now: Instant) {
+        if now.saturating_duration_since(self.last_sweep) < ttl {
+            return;
+        }
+        self.map
+            .retain(|_, entry| now.saturating_duration_since(entry.last_accessed) < ttl);
+        self.last_sweep = now;
+    }
+
+    /// Returns the entry of the guild, if it's cached and not expired, and marks it accessed
+    fn touch(
+        &mut self,
+        guild_id: serenity::GuildId,
+        ttl: Option<Duration>,
+        now: Instant,
+    ) -> Option<&mut Entry<D>> {
+        if let Some(ttl) = ttl {
+            self.sweep(ttl, now);
+            let expired = self
+                .map
+                .get(&guild_id)
+                .is_some_and(|entry| now.saturating_duration_since(entry.last_accessed) >= ttl);
+            if expired {
+                self.map.remove(&guild_id);
+            }
+        }
+        let entry = self.map.get_mut(&guild_id)?;
+        entry.last_accessed = now;
+        Some(entry)
+    }
+}
+
+/// Per-guild values of type `D`, initialized lazily on first access
+///
+/// Typical use is caching per-guild configuration loaded from a database, instead of building a
+/// map of guild IDs by hand in the user data. The initializer only runs once per guild, even if
+/// several commands access the guild concurrently. Values are shared as [`Arc`], so use interior
+/// mutability or [`Self::insert`] to update them.
+///
+/// The framework keeps one cache per type in [`crate::FrameworkOptions::guild_data`], accessible
+/// via [`crate::Context::guild_data`]:
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// # async fn load_prefix(guild_id: lumi::serenity_prelude::GuildId) -> Result<String, Error> { todo!() }
+/// struct GuildConfig {
+///     prefix: String,
+/// }
+///
+/// /// Shows the prefix of this server
+/// #[lumi::command(slash_command, guild_only)]
+/// pub async fn prefix(ctx: Context<'_>) -> Result<(), Error> {
+///     let guild_id = ctx.guild_id().unwrap();
+///     let config = ctx
+///         .guild_data::<GuildConfig>()
+///         .get_or_try_init(guild_id, || async {
+///             let prefix = load_prefix(guild_id).await?;
+///             Ok::<_, Error>(GuildConfig { prefix })
+///         })
+///         .await?;
+///     ctx.say(format!("The prefix is `{}`", config.prefix)).await?;
+///     Ok(())
+/// }
+/// ```
+pub struct GuildDataCache<D> {
+    /// Cached values
+    entries: parking_lot::Mutex<Entries<D>>,
+    /// If set, values which weren't accessed for this long are evicted and initialized again on
+    /// the next access
+    ttl: Option<Duration>,
+}
+
+impl<D> std::fmt::Debug for GuildDataCache<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuildDataCache")
+            .field("guilds", &self.entries.lock().map.len())
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl<D> Default for GuildDataCache<D> {
+    fn default() -> Self {
+        Self {
+            entries: parking_lot::Mutex::new(Entries::new()),
+            ttl: None,
+        }
+    }
+}
+
+impl<D> GuildDataCache<D> {
+    /// Creates an empty cache which keeps values until they're removed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty cache which evicts values that weren't accessed for longer than `ttl`
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            entries: parking_lot::Mutex::new(Entries::new()),
+            ttl: Some(ttl),
+        }
+    }
+
+    /// Returns the initialization cell of the guild, creating it if needed
+    fn cell(
+        &self,
+        guild_id: serenity::GuildId,
+        now: Instant,
+    ) -> Arc<tokio::sync::OnceCell<Arc<D>>> {
+        let mut entries = self.entries.lock();
+        if let Some(entry) = entries.touch(guild_id, self.ttl, now) {
+            return entry.value.clone();
+        }
+        let value = Arc::new(tokio::sync::OnceCell::new());
+        entries.map.insert(
+            guild_id,
+            Entry {
+                value: value.clone(),
+                last_accessed: now,
+            },
+        );
+        value
+    }
+
+    /// Returns the value of the guild if it's cached and initialized
+    pub fn get(&self, guild_id: serenity::GuildId) -> Option<Arc<D>> {
+        self.get_at(guild_id, Instant::now())
+    }
+
+    /// [`Self::get`] with the current time passed in
+    fn get_at(&self, guild_id: serenity::GuildId, now: Instant) -> Option<Arc<D>> {
+        let mut entries = self.entries.lock();
+        entries.touch(guild_id, self.ttl, now)?.value.get().cloned()
+    }
+
+    /// Returns the value of the guild, initializing it with `init` if it isn't cached
+    pub async fn get_or_init<F, Fut>(&self, guild_id: serenity::GuildId, init: F) -> Arc<D>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = D>,
+    {
+        let cell = self.cell(guild_id, Instant::now());
+        cell.get_or_init(|| async { Arc::new(init().await) })
+            .await
+            .clone()
+    }
+
+    /// Like [`Self::get_or_init`], but initialization may fail
+    ///
+    /// On failure, nothing is cached and the next access tries to initialize the value again.
+    pub async fn get_or_try_init<F, Fut, Err>(
+        &self,
+        guild_id: serenity::GuildId,
+        init: F,
+    ) -> Result<Arc<D>, Err>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<D, Err>>,
+    {
+        let cell = self.cell(guild_id, Instant::now());
+        let value = cell
+            .get_or_try_init(|| async { init().await.map(Arc::new) })
+            .await?;
+        Ok(value.clone())
+    }
+
+    /// Replaces the value of the guild, e.g. after the guild's configuration was changed
+    pub fn insert(&self, guild_id: serenity::GuildId, value: D) {
+        let entry = Entry {
+            value: Arc::new(tokio::sync::OnceCell::new_with(Some(Arc::new(value)))),
+            last_accessed: Instant::now(),
+        };
+        self.entries.lock().map.insert(guild_id, entry);
+    }
+
+    /// Evicts the value of the guild, so that it's initialized again on the next access. Returns
+    /// the evicted value, if it was initialized
+    pub fn remove(&self, guild_id: serenity::GuildId) -> Option<Arc<D>> {
+        let entry = self.entries.lock().map.remove(&guild_id)?;
+        entry.value.get().cloned()
+    }
+
+    /// Evicts the values of all guilds
+    pub fn clear(&self) {
+        self.entries.lock().map.clear();
+    }
+}
+
+/// One [`GuildDataCache`] per data type, managed by the framework
+///
+/// Stored in [`crate::FrameworkOptions::guild_data`]. Caches are created on first access by
+/// [`Self::cache`] or [`crate::Context::guild_data`].
+#[derive(Default)]
+pub struct GuildData {
+    /// Type-erased [`GuildDataCache`]s, keyed by the type ID of their data
+    caches: parking_lot::Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    /// [`GuildDataCache::with_ttl`] of created caches
+    ttl: Option<Duration>,
+}
+
+impl std::fmt::Debug for GuildData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuildData")
+            .field("caches", &self.caches.lock().len())
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl GuildData {
+    /// Creates an empty store whose caches keep values until they're removed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty store whose caches evict values that weren't accessed for longer than
+    /// `ttl`, see [`GuildDataCache::with_ttl`]
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            caches: parking_lot::Mutex::new(HashMap::new()),
+            ttl: Some(ttl),
+        }
+    }
+
+    /// Returns the cache for data of type `D`, creating it if needed
+    pub fn cache<D: Send + Sync + 'static>(&self) -> Arc<GuildDataCache<D>> {
+        let cache = self
+            .caches
+            .lock()
+            .entry(TypeId::of::<D>())
+            .or_insert_with(|| match self.ttl {
+                Some(ttl) => Arc::new(GuildDataCache::<D>::with_ttl(ttl)),
+                None => Arc::new(GuildDataCache::<D>::new()),
+            })
+            .clone();
+        cache
+            .downcast()
+            .expect("guild data caches are keyed by their type ID")
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_guild_data_cache() {
+    let guild_id = serenity::GuildId::new(1);
+    let cache = GuildDataCache::new();
+    assert_eq!(cache.get(guild_id), None);
+    cache.insert(guild_id, 1);
+    assert_eq!(cache.get(guild_id).as_deref(), Some(&1));
+    assert_eq!(cache.remove(guild_id).as_deref(), Some(&1));
+    assert_eq!(cache.get(guild_id), None);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        assert_eq!(*cache.get_or_init(guild_id, || async { 2 }).await, 2);
+        // Already initialized
+        assert_eq!(*cache.get_or_init(guild_id, || async { 3 }).await, 2);
+        let result = cache
+            .get_or_try_init(serenity::GuildId::new(2), || async { Err::<i32, _>(()) })
+            .await;
+        assert_eq!(result, Err(()));
+        assert_eq!(cache.get(serenity::GuildId::new(2)), None);
+    });
+}
+
+#[cfg(test)]
+#[test]
+fn test_guild_data_ttl() {
+    let ttl = Duration::from_secs(60);
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    let (guild_1, guild_2) = (serenity::GuildId::new(1), serenity::GuildId::new(2));
+    let cache = GuildDataCache::with_ttl(ttl);
+    cache.insert(guild_1, 1);
+    cache.insert(guild_2, 2);
+
+    // Accessing a value keeps it alive through the sweep, the other one is swept
+    assert_eq!(cache.get_at(guild_1, at(30)).as_deref(), Some(&1));
+    assert_eq!(cache.get_at(guild_2, at(72)), None);
+    assert_eq!(cache.entries.lock().map.len(), 1);
+
+    // Expired values are dropped on access even before the next sweep is due
+    assert_eq!(cache.get_at(guild_1, at(96)), None);
+    assert!(cache.entries.lock().map.is_empty());
+
+    // Values of guilds which aren't accessed anymore are swept
+    cache.insert(guild_2, 2);
+    cache.get_at(serenity::GuildId::new(3), at(150));
+    assert!(cache.entries.lock().map.is_empty());
+}
@@ -0,0 +1,47 @@
+//! Plumbing shared by the per-guild settings stores, see [`SettingsStoreFailure`]
+
+/// Error type used by the settings stores
+pub(crate) type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// What to do with an invocation if a settings store fails to load the guild's settings, see
+/// [`crate::FrameworkOptions::settings_store_failure`]
+///
+/// Applies to [`crate::FrameworkOptions::command_toggles`] and
+/// [`crate::FrameworkOptions::channel_restrictions`]. Custom aliases of
+/// [`crate::PrefixFrameworkOptions::command_aliases`] which fail to load are never resolved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SettingsStoreFailure {
+    /// Run the command as if the guild had no settings, so that a storage outage doesn't lock
+    /// everyone out
+    #[default]
+    Allow,
+    /// Fail the invocation with [`crate::FrameworkError::SettingsUnavailable`], so that a storage
+    /// outage doesn't lift any restrictions
+    Deny,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Handles a failed settings store lookup according to
+/// [`crate::FrameworkOptions::settings_store_failure`]
+///
+/// Returns the error back if the invocation must fail.
+pub(crate) fn on_store_failure<T, E>(
+    ctx: crate::Context<'_, T, E>,
+    setting: &str,
+    command: &crate::Command<T, E>,
+    error: BoxedError,
+) -> Result<(), BoxedError> {
+    match ctx.framework().options().settings_store_failure {
+        SettingsStoreFailure::Deny => Err(error),
+        _ => {
+            tracing::warn!(
+                "Failed to load {} of `{}`, ignoring them: {}",
+                setting,
+                command.qualified_name,
+                error
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! Image parameters which accept either an attachment or a URL, see [`ImageInput`]
+
+use crate::serenity_prelude as serenity;
+
+/// Error thrown when an image parameter is given something other than an image attachment or an
+/// image URL
+#[derive(Default, Debug)]
+pub struct InvalidImage {
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for InvalidImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected an image attachment or an image URL")
+    }
+}
+impl std::error::Error for InvalidImage {}
+
+/// Error that occurs in [`ImageInput::download`]
+#[derive(Debug)]
+pub enum ImageDownloadError {
+    /// The image couldn't be fetched
+    Http(reqwest::Error),
+    /// The server didn't respond with an image
+    NotAnImage {
+        /// The content type of the response, if any
+        content_type: Option<String>,
+    },
+    /// The image is larger than the size cap passed to [`ImageInput::download`]
+    TooLarge {
+        /// The size cap in bytes
+        max_size: usize,
+    },
+    /// The URL points to a private, loopback or otherwise non-public IP address. Host names
+    /// resolving to such addresses fail with [`Self::Http`] instead
+    ForbiddenAddress,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for ImageDownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "failed to download image: {e}"),
+            Self::NotAnImage {
+                content_type: Some(content_type),
+            } => write!(f, "expected an image, got `{content_type}`"),
+            Self::NotAnImage { content_type: None } => f.write_str("expected an image"),
+            Self::TooLarge { max_size } => write!(f, "image is larger than {max_size} bytes"),
+            Self::ForbiddenAddress => f.write_str("image URL doesn't point to a public address"),
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl std::error::Error for ImageDownloadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ImageDownloadError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Http(e)
+    }
+}
+
+/// Whether the address is reachable on the public internet. Image URLs are user input, so they
+/// must not make the bot fetch from its own network
+fn is_public_ip(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                // Shared address space (100.64.0.0/10) and "this network" (0.0.0.0/8)
+                || (a == 100 && (b & 0xc0) == 64)
+                || a == 0)
+        }
+        std::net::IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(ip.into()),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// Whether the URL's host is an IP address literal which isn't public. Host names are checked
+/// when they're resolved, see [`PublicResolver`]
+fn is_forbidden_ip_literal(url: &reqwest::Url) -> bool {
+    let host = url.host_str().unwrap_or_default();
+    // IPv6 literals are enclosed in brackets
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    host.parse().is_ok_and(|ip| !is_public_ip(ip))
+}
+
+/// DNS resolver which refuses host names resolving to non-public addresses
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let host = format!("{}:0", name.as_str());
+            let addrs = tokio::task::spawn_blocking(move || {
+                std::net::ToSocketAddrs::to_socket_addrs(&host).map(Vec::from_iter)
+            })
+            .await??;
+            if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+                return Err(ImageDownloadError::ForbiddenAddress.into());
+            }
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// HTTP client shared by all image downloads, which only connects to public addresses, also
+/// when following redirects
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let redirect_policy = reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= 5 {
+                attempt.error("too many redirects")
+            } else if is_forbidden_ip_literal(attempt.url()) {
+                attempt.error(ImageDownloadError::ForbiddenAddress)
+            } else {
+                attempt.follow()
+            }
+        });
+        reqwest::Client::builder()
+            .dns_resolver(std::sync::Arc::new(PublicResolver))
+            .redirect(redirect_policy)
+            .build()
+            .expect("failed to build image download client")
+    })
+}
+
+/// Whether the MIME type is an image type, ignoring parameters like `; charset=...`
+fn is_image_content_type(content_type: &str) -> bool {
+    content_type
+        .trim_start()
+        .get(..6)
+        .is_some_and(|kind| kind.eq_ignore_ascii_case("image/"))
+}
+
+/// Returns the URL if the input is an HTTP(S) URL, optionally wrapped in `<>` to suppress embeds
+fn parse_image_url(input: &str) -> Option<&str> {
+    let url = input.trim();
+    let url = url
+        .strip_prefix('<')
+        .and_then(|url| url.strip_suffix('>'))
+        .unwrap_or(url);
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    (!host.is_empty() && !url.contains(char::is_whitespace)).then_some(url)
+}
+
+/// An image passed as either an attachment or a URL
+///
+/// Image manipulation commands usually want to accept both. As a prefix command parameter, a URL
+/// is taken from the arguments if there's one, and the next attachment of the message otherwise.
+/// As a slash command parameter, it's registered as a string option taking a URL, since Discord
+/// options have a single type. To also accept uploads in slash commands, take an optional
+/// [`serenity::Attachment`] parameter next to it and convert it with [`Self::from_attachment`].
+///
+/// Attachments are checked to be images right away. For URLs, the content type is only known once
+/// the image is fetched with [`Self::download`], which refuses non-public addresses.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Shows the size of an image
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn image_size(ctx: Context<'_>, image: lumi::ImageInput) -> Result<(), Error> {
+///     let bytes = image.download(8 * 1024 * 1024).await?;
+///     ctx.say(format!("That image has {} bytes", bytes.len())).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImageInput {
+    /// URL of the image
+    pub url: String,
+    /// The attachment, if the image was passed as one
+    pub attachment: Option<serenity::Attachment>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl ImageInput {
+    /// Creates an input from an attachment, if it's an image
+    pub fn from_attachment(attachment: &serenity::Attachment) -> Option<Self> {
+        let content_type = attachment.content_type.as_deref()?;
+        is_image_content_type(content_type).then(|| Self {
+            url: attachment.url.to_string(),
+            attachment: Some(attachment.clone()),
+            __non_exhaustive: (),
+        })
+    }
+
+    /// Creates an input from an HTTP(S) URL, optionally wrapped in `<>`, if it looks like one
+    pub fn from_url(input: &str) -> Option<Self> {
+        Some(Self {
+            url: parse_image_url(input)?.to_owned(),
+            attachment: None,
+            __non_exhaustive: (),
+        })
+    }
+
+    /// Downloads the image, failing if it's larger than `max_size` bytes or the server didn't
+    /// respond with an image
+    ///
+    /// The size cap is checked while downloading, so oversized images are aborted early instead of
+    /// being buffered in full.
+    pub async fn download(&self, max_size: usize) -> Result<Vec<u8>, ImageDownloadError> {
+        let too_large = ImageDownloadError::TooLarge { max_size };
+        if let Some(attachment) = &self.attachment {
+            if attachment.size as usize > max_size {
+                return Err(too_large);
+            }
+        }
+
+        let request = http_client().get(&self.url).build()?;
+        if is_forbidden_ip_literal(request.url()) {
+            return Err(ImageDownloadError::ForbiddenAddress);
+        }
+        let mut response = http_client().execute(request).await?.error_for_status()?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(str::to_owned);
+        if !content_type.as_deref().is_some_and(is_image_content_type) {
+            return Err(ImageDownloadError::NotAnImage { content_type });
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > max_size as u64)
+        {
+            return Err(too_large);
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if bytes.len() + chunk.len() > max_size {
+                return Err(too_large);
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> crate::PopArgument<'a> for ImageInput {
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        _: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> crate::PopArgumentResult<'a, Self> {
+        let word = crate::prefix_argument::pop_string(args).ok();
+        if let Some((rest, word)) = &word {
+            if let Some(image) = Self::from_url(word) {
+                return Ok((rest.trim_start(), attachment_index, image));
+            }
+        }
+
+        match msg.attachments.get(attachment_index) {
+            Some(attachment) => match Self::from_attachment(attachment) {
+                Some(image) => Ok((args, attachment_index + 1, image)),
+                None => Err((InvalidImage::default().into(), None)),
+            },
+            None => match word {
+                Some((_, word)) => Err((InvalidImage::default().into(), Some(word.into_owned()))),
+                None => Err((crate::TooFewArguments::default().into(), None)),
+            },
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::SlashArgument for ImageInput {
+    async fn extract(
+        _: &serenity::Context,
+        _: &serenity::CommandInteraction,
+        value: &serenity::ResolvedValue<'_>,
+    ) -> Result<Self, crate::SlashArgError> {
+        let (image, input) = match *value {
+            serenity::ResolvedValue::Attachment(attachment) => (
+                Self::from_attachment(attachment),
+                attachment.filename.to_string(),
+            ),
+            serenity::ResolvedValue::String(string) => (Self::from_url(string), string.into()),
+            _ => {
+                return Err(crate::SlashArgError::CommandStructureMismatch {
+                    description: "expected attachment or string",
+                });
+            }
+        };
+        image.ok_or_else(|| crate::SlashArgError::Parse {
+            error: InvalidImage::default().into(),
+            input,
+        })
+    }
+
+    fn create(builder: serenity::CreateCommandOption<'_>) -> serenity::CreateCommandOption<'_> {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_image_input_parsing() {
+    assert!(is_image_content_type("image/png"));
+    assert!(is_image_content_type("IMAGE/jpeg; charset=binary"));
+    assert!(!is_image_content_type("text/html"));
+    assert!(!is_image_content_type("image"));
+
+    assert_eq!(
+        parse_image_url("https://example.com/cat.png"),
+        Some("https://example.com/cat.png")
+    );
+    assert_eq!(
+        parse_image_url("<http://example.com/cat.png>"),
+        Some("http://example.com/cat.png")
+    );
+    assert_eq!(parse_image_url("https://"), None);
+    assert_eq!(parse_image_url("cat.png"), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_forbidden_addresses() {
+    let forbidden = |url| is_forbidden_ip_literal(&reqwest::Url::parse(url).unwrap());
+    assert!(forbidden("http://127.0.0.1/cat.png"));
+    assert!(forbidden("http://10.0.0.1/cat.png"));
+    assert!(forbidden("http://169.254.169.254/latest/meta-data"));
+    assert!(forbidden("http://100.64.0.1/cat.png"));
+    assert!(forbidden("http://[::1]/cat.png"));
+    assert!(forbidden("http://[::ffff:192.168.0.1]/cat.png"));
+    assert!(forbidden("http://[fd00::1]/cat.png"));
+    assert!(!forbidden("http://93.184.216.34/cat.png"));
+    assert!(!forbidden("http://[2606:2800:220:1::]/cat.png"));
+    assert!(!forbidden("https://example.com/cat.png"));
+}
@@ -155,7 +155,7 @@ subcommands):
 # pub async fn parent(ctx: Context<'_>, arg: String) -> Result<(), Error> { Ok(()) }
 let options = lumi::FrameworkOptions {
     commands: vec![
-        parent(),
+        parent().into(),
     ],
     ..Default::default()
 };
@@ -165,6 +165,10 @@ Subcommands are stored in [`Command::subcommands`]. As with all [`Command`] fiel
 programmatically modify those any way you'd like. The [`command`] macro is just a convenience thing
 to set the fields for you.
 
+To go through the whole command tree, use [`walk_commands`], which yields every command along with
+its nesting depth. [`find_by_qualified_name`] and [`find_by_identifying_name`] look up a single
+command anywhere in the tree.
+
 For another example of subcommands, see `examples/feature_showcase/subcommands.rs`.
 
 ### Big example to showcase many command features
@@ -204,8 +208,9 @@ async fn check(ctx: Context<'_>) -> Result<bool, Error> {
     Ok(!ctx.author().name.starts_with('X'))
 }
 
-async fn error_handler(error: lumi::FrameworkError<'_, Data, Error>) {
+async fn error_handler(error: lumi::FrameworkError<'_, Data, Error>) -> Result<(), serenity::Error> {
     println!("Oh noes, we got an error: {:?}", error);
+    Ok(())
 }
 ```
 
@@ -215,7 +220,7 @@ async fn error_handler(error: lumi::FrameworkError<'_, Data, Error>) {
 # use std::sync::Arc;
 # type Error = Box<dyn std::error::Error + Send + Sync>;
 # type Context<'a> = lumi::Context<'a, (), Error>;
-# async fn my_error_function(_: lumi::FrameworkError<'_, (), Error>) {}
+# async fn my_error_function(_: lumi::FrameworkError<'_, (), Error>) -> Result<(), lumi::serenity_prelude::Error> { Ok(()) }
 # #[lumi::command(prefix_command)] async fn command1(_ctx: Context<'_>) -> Result<(), Error> { Ok(()) }
 # #[lumi::command(prefix_command)] async fn command2(_ctx: Context<'_>) -> Result<(), Error> { Ok(()) }
 # #[lumi::command(prefix_command)] async fn command3(_ctx: Context<'_>) -> Result<(), Error> { Ok(()) }
@@ -238,13 +243,14 @@ let framework = lumi::Framework::builder()
         },
         // This is also where commands go
         commands: vec![
-            command1(),
-            command2(),
+            command1().into(),
+            command2().into(),
             // You can also modify a command by changing the fields of its Command instance
             lumi::Command {
                 // [override fields here]
                 ..command3()
             }
+            .into(),
         ],
         ..Default::default()
     }).build();
@@ -281,6 +287,24 @@ automatically register commands on startup. Also see the docs of
 The lowest level of abstraction for registering commands is [`Command::create_as_slash_command`]
 and [`Command::create_as_context_menu_command`].
 
+# Concurrency model
+
+Every event is dispatched on the task serenity spawned for it, so many commands run concurrently.
+The framework keeps the shared state touched on every invocation small:
+- Framework options and commands are immutable after startup and read without locking
+- Each command has its own [`CooldownTracker`] ([`Command::cooldowns`]). Its global cooldown is
+  an atomic, and the other buckets are split into locked shards by user, channel or guild, so
+  only concurrent invocations of the same command in the same shard contend. Shard locks are held
+  just for a hash map lookup and never across an `.await`
+- The [`EditTracker`] is split into locked shards by message ID, so only prefix responses and
+  message edits or deletions of messages in the same shard contend. Like the cooldown shards,
+  its locks are never held across an `.await`
+- Per-invocation state like [`ApplicationContext::has_sent_initial_response`] lives on the stack
+  of the invocation and is not shared between invocations
+
+All framework locks are [`parking_lot`](https://docs.rs/parking_lot) locks, which don't poison
+and are cheap when uncontended.
+
 # Tips and tricks
 
 ## Type aliases
@@ -372,31 +396,63 @@ fn test_calc() {
 */
 
 mod argument;
+pub mod audit;
+pub mod broadcast;
 pub mod builtins;
+pub mod channel_restrictions;
+pub mod checks;
 pub mod choice_parameter;
+pub mod command_aliases;
+pub mod command_toggles;
 pub mod cooldown;
+pub mod deprecation;
+#[cfg(feature = "miette")]
+pub mod diagnostic;
 pub mod dispatch;
+pub mod feature_gate;
 pub mod framework;
+pub mod guild_data;
+pub mod guild_settings;
+pub mod image_input;
+#[cfg(feature = "fluent")]
+pub mod localization;
+pub mod manifest;
+pub mod mentions;
 pub mod modal;
 pub mod prefix_argument;
+pub mod presence;
+pub mod rate_limit;
+#[cfg(feature = "test-utils")]
+pub mod replay;
 pub mod reply;
+pub mod scheduler;
 pub mod slash_argument;
 pub mod structs;
 pub mod track_edits;
+pub mod usage_stats;
 pub mod macros {
     //! Procedural macros used in lumi, like [`command`]
     #[doc(inline)]
     pub use lumi_macros::*;
 }
 
+#[cfg(feature = "miette")]
+#[doc(no_inline)]
+pub use diagnostic::*;
+#[cfg(feature = "fluent")]
+#[doc(no_inline)]
+pub use localization::*;
 #[doc(no_inline)]
 pub use {
-    argument::*, choice_parameter::*, cooldown::*, dispatch::*, framework::*, macros::*, modal::*,
-    prefix_argument::*, reply::*, slash_argument::*, structs::*, track_edits::*,
+    argument::*, audit::*, broadcast::*, channel_restrictions::*, choice_parameter::*,
+    command_aliases::*, command_toggles::*, cooldown::*, deprecation::*, dispatch::*,
+    feature_gate::*, framework::*, guild_data::*, guild_settings::*, image_input::*, macros::*,
+    manifest::*, mentions::*, modal::*, prefix_argument::*, presence::*, rate_limit::*, reply::*,
+    scheduler::*, slash_argument::*, structs::*, track_edits::*, usage_stats::*,
 };
 
 #[doc(hidden)]
-pub use {async_trait::async_trait, futures_util};
+pub use {async_trait::async_trait, futures_util, parking_lot, tracing};
 
 /// This module re-exports a bunch of items from all over serenity. Useful if you can't
 /// remember the full paths of serenity items.
@@ -417,21 +473,60 @@ use serenity_prelude as serenity; // private alias for crate root docs intradoc-
 /// An owned future has the `'static` lifetime.
 pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
 
+thread_local! {
+    /// Backtrace of the most recent panic on this thread, if captured by the hook installed with
+    /// [`install_panic_backtrace_hook`]
+    static LAST_PANIC_BACKTRACE: std::cell::RefCell<Option<std::backtrace::Backtrace>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Installs a panic hook which captures a backtrace on every panic, so that
+/// [`FrameworkError::CommandPanic::backtrace`](crate::FrameworkError::CommandPanic) is filled in.
+///
+/// The previously installed panic hook is still called afterwards. Capturing backtraces is
+/// expensive, which is why this is opt-in. Calling this function multiple times has no
+/// additional effect.
+///
+/// Only has an effect with the `handle_panics` feature enabled.
+pub fn install_panic_backtrace_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let backtrace = std::backtrace::Backtrace::force_capture();
+            LAST_PANIC_BACKTRACE.with(|last| *last.borrow_mut() = Some(backtrace));
+            previous_hook(info);
+        }));
+    });
+}
+
+/// Information about a panic caught by [`catch_unwind_maybe`]
+#[cfg_attr(not(feature = "handle_panics"), allow(dead_code))]
+struct CaughtPanic {
+    /// Panic payload, if it was a string
+    payload: Option<String>,
+    /// Backtrace of the panic, if [`install_panic_backtrace_hook`] was called
+    backtrace: Option<std::backtrace::Backtrace>,
+}
+
 /// Internal wrapper function for catch_unwind that respects the `handle_panics` feature flag
 async fn catch_unwind_maybe<T>(
     fut: impl std::future::Future<Output = T>,
-) -> Result<T, Option<String>> {
+) -> Result<T, CaughtPanic> {
     #[cfg(feature = "handle_panics")]
     let res = futures_util::FutureExt::catch_unwind(std::panic::AssertUnwindSafe(fut))
         .await
         .map_err(|e| {
-            if let Some(s) = e.downcast_ref::<&str>() {
+            let payload = if let Some(s) = e.downcast_ref::<&str>() {
                 Some(s.to_string())
             } else if let Ok(s) = e.downcast::<String>() {
                 Some(*s)
             } else {
                 None
-            }
+            };
+            // The panic hook runs on the panicking thread, which is the thread polling `fut`
+            let backtrace = LAST_PANIC_BACKTRACE.with(|last| last.borrow_mut().take());
+            CaughtPanic { payload, backtrace }
         });
     #[cfg(not(feature = "handle_panics"))]
     let res = Ok(fut.await);
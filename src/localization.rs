@@ -0,0 +1,399 @@
+//! Loads [Fluent](https://projectfluent.org/) translation files and applies them to commands.
+//!
+//! Translations are looked up by convention. For a command `ping` with a parameter `target`,
+//! a locale file would look like this:
+//!
+//! ```ftl
+//! ping = ping
+//!     .description = Pings a user
+//!     .context-menu-name = Ping this user
+//!     .target--name = target
+//!     .target--description = The user to ping
+//!     .target--choice--Loudly = Loudly
+//! ```
+//!
+//! Subcommands are keyed by their full command path joined with `--`, e.g. `settings--prefix`.
+//! The message value is the localized command name; missing messages or attributes are skipped.
+//!
+//! Names are escaped so that keys never collide and are always valid Fluent identifiers:
+//! - a `-` is written as `-_`, e.g. `dice-roll` becomes `dice-_roll`, so names can't contain `--`
+//! - other characters which aren't allowed in Fluent identifiers are written as `-` followed by
+//!   their hexadecimal code point and `_`, e.g. `café` becomes `caf-e9_`
+//! - names which don't start with an ASCII letter, or start with `x`, are prefixed with `x`, e.g.
+//!   `8ball` becomes `x8ball` and `xkcd` becomes `xxkcd`
+
+/// A Fluent bundle which can be shared across threads
+type FluentBundle = ::fluent::bundle::FluentBundle<
+    ::fluent::FluentResource,
+    intl_memoizer::concurrent::IntlLangMemoizer,
+>;
+
+/// Error that occurs while loading [`Translations`]
+#[derive(Debug)]
+pub enum TranslationsError {
+    /// A translation file or the translations directory couldn't be read
+    Io(std::io::Error),
+    /// A translation file name is not a valid locale
+    InvalidLocale {
+        /// The file name which couldn't be parsed into a locale
+        locale: String,
+    },
+    /// A translation file contains invalid Fluent syntax or duplicate messages
+    InvalidFile {
+        /// Path to the offending file
+        path: std::path::PathBuf,
+        /// Description of the errors in the file
+        message: String,
+    },
+    /// No translation file exists for the main locale
+    MissingMainLocale {
+        /// The main locale passed to [`Translations::load`]
+        locale: String,
+    },
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for TranslationsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read translations: {e}"),
+            Self::InvalidLocale { locale } => write!(f, "invalid locale `{locale}`"),
+            Self::InvalidFile { path, message } => {
+                write!(f, "invalid translation file {}: {}", path.display(), message)
+            }
+            Self::MissingMainLocale { locale } => {
+                write!(f, "no translation file for main locale `{locale}`")
+            }
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl std::error::Error for TranslationsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TranslationsError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// A set of Fluent bundles, one per locale, with one main locale used as a fallback.
+///
+/// Load with [`Self::load`], apply to your command tree with [`Self::apply_to_commands`] and
+/// store in [`crate::FrameworkOptions::translator`] to use [`crate::Context::t`].
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Translations {
+    /// Locale of [`Self::main`]
+    main_locale: String,
+    /// Bundle of the main locale, used when a key is missing in the user's locale
+    #[derivative(Debug = "ignore")]
+    main: FluentBundle,
+    /// Bundles of all other locales, keyed by Discord locale string
+    #[derivative(Debug = "ignore")]
+    other: std::collections::HashMap<String, FluentBundle>,
+}
+
+/// Reads and parses a single `.ftl` file into a bundle
+fn read_ftl(path: &std::path::Path) -> Result<(String, FluentBundle), TranslationsError> {
+    let locale = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_owned();
+    let langid = locale
+        .parse()
+        .map_err(|_| TranslationsError::InvalidLocale {
+            locale: locale.clone(),
+        })?;
+
+    let file_contents = std::fs::read_to_string(path)?;
+    let resource = ::fluent::FluentResource::try_new(file_contents).map_err(|(_, errors)| {
+        TranslationsError::InvalidFile {
+            path: path.to_owned(),
+            message: format!("{errors:?}"),
+        }
+    })?;
+
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    bundle
+        .add_resource(resource)
+        .map_err(|errors| TranslationsError::InvalidFile {
+            path: path.to_owned(),
+            message: format!("{errors:?}"),
+        })?;
+
+    Ok((locale, bundle))
+}
+
+impl Translations {
+    /// Loads all `.ftl` files in the given directory. Each file must be named after a Discord
+    /// locale, e.g. `en-US.ftl` or `de.ftl`.
+    ///
+    /// `main_locale` is the locale of your command definitions, it's used as a fallback for
+    /// missing keys in [`Self::get`].
+    pub fn load(
+        dir: impl AsRef<std::path::Path>,
+        main_locale: &str,
+    ) -> Result<Self, TranslationsError> {
+        let mut main = None;
+        let mut other = std::collections::HashMap::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "ftl") {
+                continue;
+            }
+
+            let (locale, bundle) = read_ftl(&path)?;
+            if locale == main_locale {
+                main = Some(bundle);
+            } else {
+                other.insert(locale, bundle);
+            }
+        }
+
+        Ok(Self {
+            main_locale: main_locale.to_owned(),
+            main: main.ok_or_else(|| TranslationsError::MissingMainLocale {
+                locale: main_locale.to_owned(),
+            })?,
+            other,
+        })
+    }
+
+    /// Returns the locale that was passed to [`Self::load`] as the main locale
+    pub fn main_locale(&self) -> &str {
+        &self.main_locale
+    }
+
+    /// Formats a message or message attribute from a single bundle.
+    ///
+    /// `key` is a message ID, optionally followed by `.attribute`.
+    fn format(
+        bundle: &FluentBundle,
+        key: &str,
+        args: Option<&::fluent::FluentArgs<'_>>,
+    ) -> Option<String> {
+        let (message_id, attribute) = match key.split_once('.') {
+            Some((message_id, attribute)) => (message_id, Some(attribute)),
+            None => (key, None),
+        };
+
+        let message = bundle.get_message(message_id)?;
+        let pattern = match attribute {
+            Some(attribute) => message.get_attribute(attribute)?.value(),
+            None => message.value()?,
+        };
+        let formatted = bundle.format_pattern(pattern, args, &mut vec![]);
+        Some(formatted.into_owned())
+    }
+
+    /// Looks up a translation in the given locale, falling back to the main locale.
+    ///
+    /// `key` is a message ID, optionally followed by `.attribute`.
+    pub fn get(
+        &self,
+        locale: Option<&str>,
+        key: &str,
+        args: Option<&::fluent::FluentArgs<'_>>,
+    ) -> Option<String> {
+        locale
+            .and_then(|locale| self.other.get(locale))
+            .and_then(|bundle| Self::format(bundle, key, args))
+            .or_else(|| Self::format(&self.main, key, args))
+    }
+
+    /// Fills the name, description, parameter and choice localizations of all commands and
+    /// subcommands with the translations of all non-main locales.
+    ///
+    /// See the [module docs](self) for the key convention.
+    pub fn apply_to_commands<T, E>(&self, commands: &mut [crate::Command<T, E>]) {
+        for command in commands {
+            self.apply_to_command(command, None);
+            command.invalidate_payload_cache();
+        }
+    }
+
+    /// Applies translations to a single command and recurses into its subcommands
+    fn apply_to_command<T, E>(&self, command: &mut crate::Command<T, E>, parent_key: Option<&str>) {
+        let key = match parent_key {
+            Some(parent_key) => format!("{parent_key}{KEY_SEPARATOR}{}", fluent_id(&command.name)),
+            None => fluent_id(&command.name),
+        };
+
+        for (locale, bundle) in &self.other {
+            let lookup = |attribute: &str| match attribute {
+                "" => Self::format(bundle, &key, None),
+                attribute => Self::format(bundle, &format!("{key}.{attribute}"), None),
+            };
+
+            if let Some(name) = lookup("") {
+                push_localization(&mut command.name_localizations, locale, name);
+            }
+            if let Some(description) = lookup("description") {
+                push_localization(&mut command.description_localizations, locale, description);
+            }
+            if let Some(name) = lookup("context-menu-name") {
+                push_localization(&mut command.context_menu_name_localizations, locale, name);
+            }
+
+            for parameter in &mut command.parameters {
+                let parameter_key = fluent_id(&parameter.name);
+                if let Some(name) = lookup(&format!("{parameter_key}{KEY_SEPARATOR}name")) {
+                    push_localization(&mut parameter.name_localizations, locale, name);
+                }
+                let description_key = format!("{parameter_key}{KEY_SEPARATOR}description");
+                if let Some(description) = lookup(&description_key) {
+                    push_localization(
+                        &mut parameter.description_localizations,
+                        locale,
+                        description,
+                    );
+                }
+
+                let mut choices = parameter.choices.to_vec();
+                for choice in &mut choices {
+                    let attribute = format!(
+                        "{parameter_key}{KEY_SEPARATOR}choice{KEY_SEPARATOR}{}",
+                        fluent_id(&choice.name)
+                    );
+                    if let Some(name) = lookup(&attribute) {
+                        push_localization(&mut choice.localizations, locale, name);
+                    }
+                }
+                parameter.choices = choices.into();
+            }
+        }
+
+        for subcommand in &mut command.subcommands {
+            self.apply_to_command(subcommand, Some(&key));
+        }
+    }
+}
+
+impl crate::Translator for Translations {
+    /// Formats a Fluent message or message attribute (`message-id.attribute`).
+    ///
+    /// Arguments are passed as Fluent strings, except for numbers that format back to exactly the
+    /// same text, e.g. `3` or `1.5`. Those are passed as Fluent numbers, so they can be used for
+    /// plural selection. This keeps snowflakes and values like `007` intact.
+    fn translate(&self, locale: &str, key: &str, args: &[(&str, &str)]) -> Option<String> {
+        let bundle = match self.other.get(locale) {
+            Some(bundle) => bundle,
+            None if locale == self.main_locale => &self.main,
+            None => return None,
+        };
+
+        let mut fluent_args = ::fluent::FluentArgs::new();
+        for &(name, value) in args {
+            match value.parse::<f64>() {
+                Ok(number) if number.to_string() == value => fluent_args.set(name, number),
+                _ => fluent_args.set(name, value),
+            }
+        }
+        Self::format(bundle, key, Some(&fluent_args))
+    }
+
+    fn default_locale(&self) -> &str {
+        &self.main_locale
+    }
+}
+
+/// Sets the localization for the given locale, replacing any existing one
+fn push_localization(
+    localizations: &mut crate::CowVec<(crate::CowStr, crate::CowStr)>,
+    locale: &str,
+    value: String,
+) {
+    let localizations = localizations.to_mut();
+    localizations.retain(|(existing_locale, _)| existing_locale != locale);
+    localizations.push((locale.to_owned().into(), value.into()));
+}
+
+/// Separates the segments of generated Fluent keys, e.g. `settings--prefix`
+const KEY_SEPARATOR: &str = "--";
+
+/// Converts a name into a segment of a generated Fluent key, see the [module docs](self)
+///
+/// The conversion is reversible, so different names never share a segment, and [`KEY_SEPARATOR`]
+/// can't appear in a segment.
+fn fluent_id(name: &str) -> String {
+    let mut id = String::with_capacity(name.len() + 1);
+    // Fluent identifiers must start with a letter. `x` itself is prefixed too, so that the
+    // prefix can be told apart from a leading `x`
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() && c != 'x') {
+        id.push('x');
+    }
+    for c in name.chars() {
+        match c {
+            '-' => id.push_str("-_"),
+            c if c.is_ascii_alphanumeric() || c == '_' => id.push(c),
+            c => id.push_str(&format!("-{:x}_", u32::from(c))),
+        }
+    }
+    id
+}
+
+#[cfg(test)]
+#[test]
+fn test_fluent_id() {
+    assert_eq!(fluent_id("user_info"), "user_info");
+    assert_eq!(fluent_id("dice-roll"), "dice-_roll");
+    assert_eq!(fluent_id("Very loud!"), "Very-20_loud-21_");
+    assert_eq!(fluent_id("café"), "caf-e9_");
+    assert_ne!(fluent_id("café"), fluent_id("caf_"));
+    assert_eq!(fluent_id("8ball"), "x8ball");
+    assert_eq!(fluent_id("1st"), "x1st");
+    assert_ne!(fluent_id("x1st"), fluent_id("1st"));
+
+    // A subcommand `b--c` of `a` and a subcommand `c` of `a-b` would share the key `a-b--c` if
+    // `-` weren't escaped
+    let key = |path: &[&str]| {
+        path.iter()
+            .map(|name| fluent_id(name))
+            .collect::<Vec<_>>()
+            .join(KEY_SEPARATOR)
+    };
+    assert_ne!(key(&["a", "b-c"]), key(&["a-b", "c"]));
+    assert_ne!(key(&["a-", "b"]), key(&["a", "-b"]));
+    assert_ne!(
+        key(&["target", "description"]),
+        key(&["target--description"])
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_translate_arguments() {
+    let dir = std::env::temp_dir().join(format!("lumi-translations-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("en-US.ftl"),
+        "welcome = Welcome {$user}\n\
+         apples = {$count ->\n    [one] one apple\n   *[other] {$count} apples\n}\n",
+    )
+    .unwrap();
+    let translations = Translations::load(&dir, "en-US").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let translate = |key, args: &[(&str, &str)]| {
+        crate::Translator::translate(&translations, "en-US", key, args)
+            .unwrap()
+            .replace(['\u{2068}', '\u{2069}'], "")
+    };
+    assert_eq!(
+        translate("welcome", &[("user", "123456789012345678")]),
+        "Welcome 123456789012345678"
+    );
+    assert_eq!(translate("welcome", &[("user", "007")]), "Welcome 007");
+    assert_eq!(translate("apples", &[("count", "1")]), "one apple");
+    assert_eq!(translate("apples", &[("count", "2.5")]), "2.5 apples");
+}
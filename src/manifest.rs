@@ -0,0 +1,365 @@
+//! Loads command metadata from a Discord-format application command JSON manifest
+//!
+//! Useful if your commands are designed in external tooling: the manifest is the source of truth
+//! for names, descriptions and localizations, while the handler functions are defined with
+//! [`crate::command`] as usual.
+
+/// A single application command from a manifest
+#[derive(Clone, Debug, Default)]
+pub struct ManifestCommand {
+    /// [Application command type](https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-types):
+    /// 1 for slash commands, 2 for user and 3 for message context menu commands
+    pub kind: u64,
+    /// Command name
+    pub name: String,
+    /// Localized names with locale string as the key
+    pub name_localizations: Vec<(String, String)>,
+    /// Command description. Empty for context menu commands
+    pub description: String,
+    /// Localized descriptions with locale string as the key
+    pub description_localizations: Vec<(String, String)>,
+    /// Parameters, subcommands or subcommand groups of this command
+    pub options: Vec<ManifestOption>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// A single command option (parameter, subcommand or subcommand group) from a manifest
+#[derive(Clone, Debug, Default)]
+pub struct ManifestOption {
+    /// [Option type](https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-option-type):
+    /// 1 for subcommands, 2 for subcommand groups, anything else for parameters
+    pub kind: u64,
+    /// Option name
+    pub name: String,
+    /// Localized names with locale string as the key
+    pub name_localizations: Vec<(String, String)>,
+    /// Option description
+    pub description: String,
+    /// Localized descriptions with locale string as the key
+    pub description_localizations: Vec<(String, String)>,
+    /// Choice names and their localizations, in order
+    pub choices: Vec<(String, Vec<(String, String)>)>,
+    /// Nested options if this option is a subcommand or subcommand group
+    pub options: Vec<ManifestOption>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl ManifestOption {
+    /// Whether this option is a subcommand or subcommand group rather than a parameter
+    pub fn is_subcommand(&self) -> bool {
+        self.kind == 1 || self.kind == 2
+    }
+}
+
+/// A list of application commands in Discord's JSON format, as returned by Discord's
+/// "Get Global Application Commands" endpoint or [`crate::builtins::export_commands_json`].
+#[derive(Clone, Debug, Default)]
+pub struct CommandManifest {
+    /// The commands in this manifest
+    pub commands: Vec<ManifestCommand>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Error returned from [`CommandManifest::apply_to`], listing all manifest entries without a
+/// matching command, subcommand or parameter
+#[derive(Clone, Debug, Default)]
+pub struct UnmatchedManifestEntries {
+    /// Paths of the unmatched entries, e.g. `ban` for a command, `mod ban` for a subcommand or
+    /// `mod ban reason` for a parameter
+    pub entries: Vec<String>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl std::fmt::Display for UnmatchedManifestEntries {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Found {} unmatched manifest entries:",
+            self.entries.len()
+        )?;
+        for entry in &self.entries {
+            write!(f, " `{}`", entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnmatchedManifestEntries {}
+
+/// Reads a localization map like `{"de": "Hallo"}` into a list of pairs
+fn parse_localizations(value: &serde_json::Value) -> Vec<(String, String)> {
+    let Some(map) = value.as_object() else {
+        return Vec::new();
+    };
+    map.iter()
+        .filter_map(|(locale, text)| Some((locale.clone(), text.as_str()?.to_owned())))
+        .collect()
+}
+
+/// Reads a string field, defaulting to an empty string
+fn parse_string(value: &serde_json::Value) -> String {
+    value.as_str().unwrap_or_default().to_owned()
+}
+
+/// Reads a list of command options
+fn parse_options(value: &serde_json::Value) -> Vec<ManifestOption> {
+    let Some(options) = value.as_array() else {
+        return Vec::new();
+    };
+    options
+        .iter()
+        .map(|option| ManifestOption {
+            kind: option["type"].as_u64().unwrap_or(3),
+            name: parse_string(&option["name"]),
+            name_localizations: parse_localizations(&option["name_localizations"]),
+            description: parse_string(&option["description"]),
+            description_localizations: parse_localizations(&option["description_localizations"]),
+            choices: option["choices"]
+                .as_array()
+                .map(|choices| {
+                    choices
+                        .iter()
+                        .map(|choice| {
+                            (
+                                parse_string(&choice["name"]),
+                                parse_localizations(&choice["name_localizations"]),
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            options: parse_options(&option["options"]),
+            __non_exhaustive: (),
+        })
+        .collect()
+}
+
+/// Converts manifest localizations into the representation used in [`crate::Command`]
+fn to_cow_localizations(
+    localizations: &[(String, String)],
+) -> crate::CowVec<(crate::CowStr, crate::CowStr)> {
+    localizations
+        .iter()
+        .map(|(locale, text)| (locale.clone().into(), text.clone().into()))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+impl CommandManifest {
+    /// Parses a manifest from a JSON array of application command objects.
+    ///
+    /// Unknown fields are ignored and missing fields are defaulted, so the manifest only needs to
+    /// contain the fields you want to apply.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let value = serde_json::from_str::<serde_json::Value>(json)?;
+        let commands = value
+            .as_array()
+            .map(|commands| {
+                commands
+                    .iter()
+                    .map(|command| ManifestCommand {
+                        kind: command["type"].as_u64().unwrap_or(1),
+                        name: parse_string(&command["name"]),
+                        name_localizations: parse_localizations(&command["name_localizations"]),
+                        description: parse_string(&command["description"]),
+                        description_localizations: parse_localizations(
+                            &command["description_localizations"],
+                        ),
+                        options: parse_options(&command["options"]),
+                        __non_exhaustive: (),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            commands,
+            __non_exhaustive: (),
+        })
+    }
+
+    /// Applies the manifest metadata to the given command handlers.
+    ///
+    /// Manifest commands are matched to handlers by [`crate::Command::identifying_name`] (by
+    /// default the function name), falling back to [`crate::Command::name`] and
+    /// [`crate::Command::context_menu_name`]. Slash command metadata overwrites names,
+    /// descriptions and localizations of the command and its subcommands, and descriptions and
+    /// localizations of its parameters. Context menu command metadata overwrites
+    /// [`crate::Command::context_menu_name`] and its localizations.
+    ///
+    /// Subcommands and parameters are matched by name. Choices are matched by position, because
+    /// they map to enum variants by index.
+    ///
+    /// Entries without a match are skipped and returned as an error after applying the rest.
+    pub fn apply_to<T, E>(
+        &self,
+        commands: &mut [crate::Command<T, E>],
+    ) -> Result<(), UnmatchedManifestEntries> {
+        let mut unmatched = Vec::new();
+        for manifest_command in &self.commands {
+            let name = manifest_command.name.as_str();
+            let position = commands
+                .iter()
+                .position(|command| command.identifying_name == name)
+                .or_else(|| {
+                    commands.iter().position(|command| {
+                        command.name == name || command.context_menu_name.as_deref() == Some(name)
+                    })
+                });
+            let Some(command) = position.map(|i| &mut commands[i]) else {
+                unmatched.push(manifest_command.name.clone());
+                continue;
+            };
+            command.invalidate_payload_cache();
+
+            if manifest_command.kind == 1 {
+                command.name = manifest_command.name.clone().into();
+                command.name_localizations =
+                    to_cow_localizations(&manifest_command.name_localizations);
+                command.description = Some(manifest_command.description.clone().into());
+                command.description_localizations =
+                    to_cow_localizations(&manifest_command.description_localizations);
+                apply_options(
+                    command,
+                    &manifest_command.name,
+                    &manifest_command.options,
+                    &mut unmatched,
+                );
+            } else {
+                command.context_menu_name = Some(manifest_command.name.clone().into());
+                command.context_menu_name_localizations =
+                    to_cow_localizations(&manifest_command.name_localizations);
+            }
+        }
+
+        match unmatched.is_empty() {
+            true => Ok(()),
+            false => Err(UnmatchedManifestEntries {
+                entries: unmatched,
+                __non_exhaustive: (),
+            }),
+        }
+    }
+}
+
+/// Applies manifest options to either the subcommands or the parameters of a command, pushing
+/// the paths of options without a match to `unmatched`
+fn apply_options<T, E>(
+    command: &mut crate::Command<T, E>,
+    path: &str,
+    options: &[ManifestOption],
+    unmatched: &mut Vec<String>,
+) {
+    for option in options {
+        let option_path = format!("{} {}", path, option.name);
+        if option.is_subcommand() {
+            let subcommand = command.subcommands.iter_mut().find(|subcommand| {
+                subcommand.identifying_name == option.name || subcommand.name == option.name
+            });
+            match subcommand {
+                Some(subcommand) => {
+                    subcommand.name = option.name.clone().into();
+                    subcommand.name_localizations =
+                        to_cow_localizations(&option.name_localizations);
+                    subcommand.description = Some(option.description.clone().into());
+                    subcommand.description_localizations =
+                        to_cow_localizations(&option.description_localizations);
+                    apply_options(subcommand, &option_path, &option.options, unmatched);
+                }
+                None => unmatched.push(option_path),
+            }
+            continue;
+        }
+
+        let parameter = command
+            .parameters
+            .iter_mut()
+            .find(|parameter| parameter.name == option.name);
+        let Some(parameter) = parameter else {
+            unmatched.push(option_path);
+            continue;
+        };
+
+        parameter.name_localizations = to_cow_localizations(&option.name_localizations);
+        parameter.description = Some(option.description.clone().into());
+        parameter.description_localizations =
+            to_cow_localizations(&option.description_localizations);
+
+        // Choices map to enum variants by index, so only the labels can be changed
+        let mut choices = parameter.choices.to_vec();
+        for (choice, (name, localizations)) in choices.iter_mut().zip(&option.choices) {
+            choice.name = name.clone().into();
+            choice.localizations = to_cow_localizations(localizations);
+        }
+        parameter.choices = choices.into();
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_apply_to() {
+    let parameter = |name: &'static str| crate::CommandParameter::<(), ()> {
+        name: name.into(),
+        name_localizations: Default::default(),
+        description: None,
+        description_localizations: Default::default(),
+        required: true,
+        channel_types: None,
+        choices: Default::default(),
+        type_setter: None,
+        autocomplete_callback: None,
+        __non_exhaustive: (),
+    };
+    let mut commands = [
+        crate::Command::<(), ()> {
+            name: "ban".into(),
+            identifying_name: "ban_user".into(),
+            parameters: vec![parameter("user"), parameter("reason")],
+            ..Default::default()
+        },
+        crate::Command {
+            name: "ban_user".into(),
+            identifying_name: "ban_user_legacy".into(),
+            ..Default::default()
+        },
+        crate::Command {
+            name: "ping".into(),
+            identifying_name: "ping_fn".into(),
+            ..Default::default()
+        },
+    ];
+
+    let manifest = CommandManifest::from_json(
+        r#"[
+            {"name": "ban_user", "description": "Ban a user", "options": [
+                {"type": 3, "name": "reason", "description": "Why"},
+                {"type": 6, "name": "target", "description": "Who"}
+            ]},
+            {"name": "ping", "description": "Pong"},
+            {"name": "missing", "description": "Nothing"}
+        ]"#,
+    )
+    .unwrap();
+    let error = manifest.apply_to(&mut commands).unwrap_err();
+    assert_eq!(error.entries, ["ban_user target", "missing"]);
+
+    // Matched by identifying name rather than by the name of the second command
+    assert_eq!(commands[0].description.as_deref(), Some("Ban a user"));
+    assert_eq!(commands[1].description, None);
+    // Matched by name as a fallback
+    assert_eq!(commands[2].description.as_deref(), Some("Pong"));
+
+    // Parameters are matched by name, not position
+    assert_eq!(commands[0].parameters[0].description, None);
+    assert_eq!(
+        commands[0].parameters[1].description.as_deref(),
+        Some("Why")
+    );
+}
@@ -0,0 +1,245 @@
+//! Extracting user, channel and role mentions from text, e.g. to accept several users in a single
+//! string parameter, see [`UserMentions`]
+
+use crate::serenity_prelude as serenity;
+
+/// Error thrown when a mention list parameter doesn't contain a single mention of the expected
+/// kind
+#[derive(Default, Debug)]
+pub struct MissingMentions {
+    /// What was expected, e.g. `user`
+    pub kind: &'static str,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for MissingMentions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Expected one or more {} mentions", self.kind)
+    }
+}
+impl std::error::Error for MissingMentions {}
+
+/// The kinds of mentions that can be extracted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MentionKind {
+    /// `<@id>` or the legacy nickname form `<@!id>`
+    User,
+    /// `<#id>`
+    Channel,
+    /// `<@&id>`
+    Role,
+}
+
+/// Parses the inside of a mention, i.e. the text between `<` and `>`
+fn parse_mention(inner: &str) -> Option<(MentionKind, u64)> {
+    let (kind, id) = if let Some(id) = inner.strip_prefix("@&") {
+        (MentionKind::Role, id)
+    } else if let Some(id) = inner.strip_prefix("@!").or_else(|| inner.strip_prefix('@')) {
+        (MentionKind::User, id)
+    } else if let Some(id) = inner.strip_prefix('#') {
+        (MentionKind::Channel, id)
+    } else {
+        return None;
+    };
+
+    // `u64::from_str` would accept a leading `+`
+    if !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let id = id.parse::<u64>().ok().filter(|&id| id != 0)?;
+    Some((kind, id))
+}
+
+/// Returns the IDs of all mentions of the given kind in the text, without duplicates and in order
+/// of appearance
+fn mention_ids(text: &str, kind: MentionKind) -> Vec<u64> {
+    let mut ids = Vec::new();
+    for part in text.split('<').skip(1) {
+        let Some((inner, _)) = part.split_once('>') else {
+            continue;
+        };
+        match parse_mention(inner) {
+            Some((mention_kind, id)) if mention_kind == kind && !ids.contains(&id) => ids.push(id),
+            _ => {}
+        }
+    }
+    ids
+}
+
+/// Whether the word consists of nothing but mentions of the given kind, e.g. `<@1>` or `<@1><@2>`
+fn is_mention_word(word: &str, kind: MentionKind) -> bool {
+    word.starts_with('<')
+        && word.split('<').skip(1).all(|part| {
+            part.strip_suffix('>')
+                .and_then(parse_mention)
+                .is_some_and(|(mention_kind, _)| mention_kind == kind)
+        })
+}
+
+/// Returns all users mentioned in the text as `<@id>` or `<@!id>`, without duplicates
+///
+/// Discord doesn't resolve mentions inside of string options, so commands taking several users in
+/// a single string parameter (`/warn users: @a @b`) can extract them with this function:
+/// ```rust
+/// # use lumi::serenity_prelude as serenity;
+/// let users = lumi::parse_user_mentions("<@1> and <@!2>, not <@&3> or <#4>");
+/// assert_eq!(users, [serenity::UserId::new(1), serenity::UserId::new(2)]);
+/// ```
+///
+/// See also [`UserMentions`] to do this automatically for a parameter.
+pub fn parse_user_mentions(text: &str) -> Vec<serenity::UserId> {
+    mention_ids(text, MentionKind::User)
+        .into_iter()
+        .map(serenity::UserId::new)
+        .collect()
+}
+
+/// Returns all channels mentioned in the text as `<#id>`, without duplicates
+///
+/// See [`parse_user_mentions`] and [`ChannelMentions`].
+pub fn parse_channel_mentions(text: &str) -> Vec<serenity::ChannelId> {
+    mention_ids(text, MentionKind::Channel)
+        .into_iter()
+        .map(serenity::ChannelId::new)
+        .collect()
+}
+
+/// Returns all roles mentioned in the text as `<@&id>`, without duplicates
+///
+/// See [`parse_user_mentions`] and [`RoleMentions`].
+pub fn parse_role_mentions(text: &str) -> Vec<serenity::RoleId> {
+    mention_ids(text, MentionKind::Role)
+        .into_iter()
+        .map(serenity::RoleId::new)
+        .collect()
+}
+
+/// Implements [`crate::SlashArgument`] and [`crate::PopArgument`] for a mention list type
+macro_rules! impl_mention_list {
+    ($type:ident, $kind:ident, $parse:ident, $kind_name:literal) => {
+        #[async_trait::async_trait]
+        impl crate::SlashArgument for $type {
+            async fn extract(
+                _: &serenity::Context,
+                _: &serenity::CommandInteraction,
+                value: &serenity::ResolvedValue<'_>,
+            ) -> Result<Self, crate::SlashArgError> {
+                let serenity::ResolvedValue::String(value) = *value else {
+                    return Err(crate::SlashArgError::new_command_structure_mismatch(
+                        "expected a String",
+                    ));
+                };
+
+                let ids = $parse(value);
+                if ids.is_empty() {
+                    return Err(crate::SlashArgError::Parse {
+                        error: MissingMentions {
+                            kind: $kind_name,
+                            __non_exhaustive: (),
+                        }
+                        .into(),
+                        input: value.into(),
+                    });
+                }
+                Ok(Self(ids))
+            }
+
+            fn create(
+                builder: serenity::CreateCommandOption<'_>,
+            ) -> serenity::CreateCommandOption<'_> {
+                builder.kind(serenity::CommandOptionType::String)
+            }
+        }
+
+        /// Consumes consecutive words consisting of mentions
+        #[async_trait::async_trait]
+        impl<'a> crate::PopArgument<'a> for $type {
+            async fn pop_from(
+                args: &'a str,
+                attachment_index: usize,
+                _: &serenity::Context,
+                _: &serenity::Message,
+            ) -> crate::PopArgumentResult<'a, Self> {
+                let mut ids = Vec::new();
+                let mut rest = args;
+                while let Ok((new_rest, word)) = crate::prefix_argument::pop_string(rest) {
+                    if !is_mention_word(&word, MentionKind::$kind) {
+                        break;
+                    }
+                    for id in $parse(&word) {
+                        if !ids.contains(&id) {
+                            ids.push(id);
+                        }
+                    }
+                    rest = new_rest;
+                }
+
+                if ids.is_empty() {
+                    let input = crate::prefix_argument::pop_string(args)
+                        .ok()
+                        .map(|(_, word)| word.into_owned());
+                    let error = MissingMentions {
+                        kind: $kind_name,
+                        __non_exhaustive: (),
+                    };
+                    return Err((error.into(), input));
+                }
+                Ok((rest.trim_start(), attachment_index, Self(ids)))
+            }
+        }
+    };
+}
+
+/// A parameter accepting one or more user mentions, e.g. `@a @b`
+///
+/// For slash commands, this is a string option from which all user mentions are extracted (see
+/// [`parse_user_mentions`]), because Discord has no option type for several users. For prefix
+/// commands, consecutive mention words are consumed. Fails if no user is mentioned.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Warn one or more users
+/// #[lumi::command(prefix_command, slash_command)]
+/// pub async fn warn(
+///     ctx: Context<'_>,
+///     #[description = "Users to warn"] users: lumi::UserMentions,
+/// ) -> Result<(), Error> {
+///     ctx.say(format!("Warned {} users", users.0.len())).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UserMentions(pub Vec<serenity::UserId>);
+impl_mention_list!(UserMentions, User, parse_user_mentions, "user");
+
+/// A parameter accepting one or more channel mentions, e.g. `#a #b`. See [`UserMentions`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMentions(pub Vec<serenity::ChannelId>);
+impl_mention_list!(ChannelMentions, Channel, parse_channel_mentions, "channel");
+
+/// A parameter accepting one or more role mentions, e.g. `@a @b`. See [`UserMentions`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoleMentions(pub Vec<serenity::RoleId>);
+impl_mention_list!(RoleMentions, Role, parse_role_mentions, "role");
+
+#[cfg(test)]
+#[test]
+fn test_mention_parsing() {
+    assert_eq!(
+        mention_ids(
+            "<@1> <@!2><@1> <@&3> <#4> <@x> <@+5> <@0> <@6",
+            MentionKind::User
+        ),
+        [1, 2]
+    );
+    assert_eq!(mention_ids("<@&3>, <#4>", MentionKind::Role), [3]);
+    assert_eq!(mention_ids("<@&3>, <#4>", MentionKind::Channel), [4]);
+    assert!(mention_ids("no mentions", MentionKind::User).is_empty());
+
+    assert!(is_mention_word("<@1>", MentionKind::User));
+    assert!(is_mention_word("<@1><@!2>", MentionKind::User));
+    assert!(!is_mention_word("<@1>,", MentionKind::User));
+    assert!(!is_mention_word("<@1><#2>", MentionKind::User));
+    assert!(!is_mention_word("hello", MentionKind::User));
+}
@@ -101,7 +101,7 @@ pub async fn execute_modal<T: Send + Sync + 'static, E, M: Modal>(
     )
     .await?;
     ctx.has_sent_initial_response
-        .store(true, std::sync::atomic::Ordering::SeqCst);
+        .store(true, std::sync::atomic::Ordering::Relaxed);
     Ok(response)
 }
 
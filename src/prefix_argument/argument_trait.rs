@@ -41,7 +41,7 @@ impl<'a> PopArgument<'a> for String {
         msg: &serenity::Message,
     ) -> PopArgumentResult<'a, Self> {
         match pop_string(args) {
-            Ok((args, string)) => Ok((args, attachment_index, string)),
+            Ok((args, string)) => Ok((args, attachment_index, string.into_owned())),
             Err(err) => Err((Box::new(err), Some(args.into()))),
         }
     }
@@ -61,7 +61,7 @@ impl<'a> PopArgument<'a> for bool {
         let value = match string.to_ascii_lowercase().trim() {
             "yes" | "y" | "true" | "t" | "1" | "enable" | "on" => true,
             "no" | "n" | "false" | "f" | "0" | "disable" | "off" => false,
-            _ => return Err((InvalidBool::default().into(), Some(string))),
+            _ => return Err((InvalidBool::default().into(), Some(string.into_owned()))),
         };
 
         Ok((args.trim_start(), attachment_index, value))
@@ -100,7 +100,7 @@ where
     let (args, string) = pop_string(args).map_err(|_| (TooFewArguments::default().into(), None))?;
     let object = T::convert(ctx, msg.guild_id, Some(msg.channel_id), &string)
         .await
-        .map_err(|e| (e.into(), Some(string)))?;
+        .map_err(|e| (e.into(), Some(string.into_owned())))?;
 
     Ok((args.trim_start(), attachment_index, object))
 }
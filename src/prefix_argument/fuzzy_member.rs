@@ -0,0 +1,196 @@
+//! Parsing code for [`FuzzyMember`], a prefix-specific member parameter type with name search
+
+use super::*;
+
+/// How many candidates [`AmbiguousMember`] lists in its error message
+const MAX_LISTED_CANDIDATES: usize = 10;
+
+/// Error thrown when a [`FuzzyMember`] name search matches several members
+#[derive(Default, Debug, Clone)]
+pub struct AmbiguousMember {
+    /// The first members matching the search, at most 10, sorted by username
+    pub candidates: Vec<serenity::Member>,
+    /// How many members match the search, including those not in [`Self::candidates`]
+    pub total: usize,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for AmbiguousMember {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Multiple members match this name: ")?;
+        for (i, member) in self.candidates.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} ({})", member.display_name(), member.user.name)?;
+        }
+        let unlisted = self.total.saturating_sub(self.candidates.len());
+        if unlisted > 0 {
+            write!(f, " and {} more", unlisted)?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for AmbiguousMember {}
+
+/// A guild member which can also be referred to by part of their name
+///
+/// First parses the argument like [`serenity::Member`], i.e. as mention, ID or exact name. If
+/// that fails, searches the cached members of the guild for the argument in their nickname,
+/// display name or username, case-insensitively. Exact matches are preferred over matches at the
+/// start of a name, which are preferred over matches anywhere in a name. If several members
+/// match equally well, [`AmbiguousMember`] is thrown with the candidates.
+///
+/// Only works in guilds and only finds cached members, so enable the `GUILD_MEMBERS` intent.
+/// Prefix-only.
+#[derive(Debug, Clone)]
+pub struct FuzzyMember(pub serenity::Member);
+
+impl std::ops::Deref for FuzzyMember {
+    type Target = serenity::Member;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// How well a name matches the search, from worst to best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchQuality {
+    /// The search is contained in the name
+    Contains,
+    /// The name starts with the search
+    Prefix,
+    /// The name equals the search
+    Exact,
+}
+
+/// Returns the names a member can be searched by: nickname, display name and username
+fn member_names(member: &serenity::Member) -> impl Iterator<Item = &str> {
+    let names = [
+        member.nick.as_deref(),
+        member.user.global_name.as_deref(),
+        Some(&*member.user.name),
+    ];
+    names.into_iter().flatten()
+}
+
+/// Returns how well the best of the names matches the lowercase `search`
+fn match_quality<'a>(names: impl Iterator<Item = &'a str>, search: &str) -> Option<MatchQuality> {
+    names
+        .filter_map(|name| {
+            let name = name.to_lowercase();
+            if name == search {
+                Some(MatchQuality::Exact)
+            } else if name.starts_with(search) {
+                Some(MatchQuality::Prefix)
+            } else if name.contains(search) {
+                Some(MatchQuality::Contains)
+            } else {
+                None
+            }
+        })
+        .max()
+}
+
+/// Searches the cached guild members for the best matches of `search`
+///
+/// Returns the first [`MAX_LISTED_CANDIDATES`] of them by username, and how many there are in
+/// total. Members are only cloned if they're returned.
+fn search_members(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    search: &str,
+) -> (Vec<serenity::Member>, usize) {
+    let Some(guild) = ctx.cache.guild(guild_id) else {
+        return (Vec::new(), 0);
+    };
+    let search = search.to_lowercase();
+
+    let mut best_quality = None;
+    let mut candidates = Vec::new();
+    for member in guild.members.iter() {
+        let Some(quality) = match_quality(member_names(member), &search) else {
+            continue;
+        };
+        if Some(quality) > best_quality {
+            best_quality = Some(quality);
+            candidates.clear();
+        }
+        if Some(quality) == best_quality {
+            candidates.push(member);
+        }
+    }
+
+    let total = candidates.len();
+    let by_username =
+        |a: &&serenity::Member, b: &&serenity::Member| (*a.user.name).cmp(&*b.user.name);
+    if total > MAX_LISTED_CANDIDATES {
+        candidates.select_nth_unstable_by(MAX_LISTED_CANDIDATES, by_username);
+        candidates.truncate(MAX_LISTED_CANDIDATES);
+    }
+    candidates.sort_unstable_by(by_username);
+    (candidates.into_iter().cloned().collect(), total)
+}
+
+#[async_trait::async_trait]
+impl<'a> PopArgument<'a> for FuzzyMember {
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> PopArgumentResult<'a, Self> {
+        let (args, string) =
+            pop_string(args).map_err(|_| (TooFewArguments::default().into(), None))?;
+        let converted = <serenity::Member as serenity::ArgumentConvert>::convert(
+            ctx,
+            msg.guild_id,
+            Some(msg.channel_id),
+            &string,
+        )
+        .await;
+        let error = match converted {
+            Ok(member) => return Ok((args.trim_start(), attachment_index, Self(member))),
+            Err(error) => error,
+        };
+
+        let (mut candidates, total) = match msg.guild_id {
+            Some(guild_id) => search_members(ctx, guild_id, &string),
+            None => (Vec::new(), 0),
+        };
+        match total {
+            0 => Err((error.into(), Some(string.into_owned()))),
+            1 => Ok((
+                args.trim_start(),
+                attachment_index,
+                Self(candidates.remove(0)),
+            )),
+            _ => Err((
+                AmbiguousMember {
+                    candidates,
+                    total,
+                    __non_exhaustive: (),
+                }
+                .into(),
+                Some(string.into_owned()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_match_quality() {
+    let quality = |names: &[&str], search| match_quality(names.iter().copied(), search);
+    assert_eq!(quality(&["Alice"], "alice"), Some(MatchQuality::Exact));
+    assert_eq!(quality(&["Alicia"], "ali"), Some(MatchQuality::Prefix));
+    assert_eq!(quality(&["Malice"], "ali"), Some(MatchQuality::Contains));
+    assert_eq!(quality(&["Bob"], "ali"), None);
+    assert_eq!(quality(&[], "ali"), None);
+    // The best of the names counts
+    assert_eq!(
+        quality(&["Malice", "ali", "Alicia"], "ali"),
+        Some(MatchQuality::Exact)
+    );
+}
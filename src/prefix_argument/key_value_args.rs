@@ -52,9 +52,9 @@ impl KeyValueArgs {
 
         let args = chars.as_str();
         // `args` used to contain "key=value ...", now it contains "value ...", so pop the value off
-        let (args, value) = super::pop_string(args).unwrap_or((args, String::new()));
+        let (args, value) = super::pop_string(args).unwrap_or((args, "".into()));
 
-        Some((args, (key, value)))
+        Some((args, (key, value.into_owned())))
     }
 
     /// Reads as many key-value args as possible from the front of the string and produces a
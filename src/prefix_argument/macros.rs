@@ -5,10 +5,13 @@
 #[macro_export]
 macro_rules! _parse_prefix {
     // All arguments have been consumed
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $( $name:ident )* ] ) => {
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $( ($name:tt $input:ident) )* ] ) => {
         if $args.is_empty() {
-            return Ok(( $( $name, )* ));
+            let inputs: Vec<Option<&str>> = vec![ $( $input, )* ];
+            return Ok(( ( $( $name, )* ), inputs ));
         }
+        let too_many = $crate::TooManyArguments { __non_exhaustive: () };
+        $crate::_record_parse_error(&mut $error, (Box::new(too_many), None), $args);
     };
 
     // Consume Option<T> greedy-first
@@ -16,22 +19,26 @@ macro_rules! _parse_prefix {
         (Option<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
+        let input_before: &str = $args;
         // Try parse the next argument
         match <$type as $crate::PopArgument>::pop_from(&$args, $attachment_index, $ctx, $msg).await {
             // On success, we get a new `$args` which contains only the rest of the args
             Ok(($args, $attachment_index, token)) => {
                 // On success, store `Some(token)` for the parsed argument
                 let token: Option<$type> = Some(token);
+                let input = $crate::_consumed_input(input_before, $args);
                 // And parse the rest of the arguments
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ] $($rest)* );
                 // If the code gets here, parsing the rest of the argument has failed
             },
-            Err(e) => $error = e,
+            // Not consuming anything on failure, so the next parameter sees the same input
+            Err(e) => $crate::_record_parse_error(&mut $error, e, input_before),
         }
         let token: Option<$type> = None;
+        let input: Option<&str> = None;
         // Parse the next arguments without changing the current arg string, thereby skipping the
         // current param
-        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ] $($rest)* );
     };
 
     // Consume Option<T> lazy-first
@@ -40,13 +47,16 @@ macro_rules! _parse_prefix {
         $( $rest:tt )*
     ) => {
         let token: Option<$type> = None;
-        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+        let input: Option<&str> = None;
+        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ] $($rest)* );
+        let input_before: &str = $args;
         match <$type as $crate::PopArgument>::pop_from(&$args, $attachment_index, $ctx, $msg).await {
             Ok(($args, $attachment_index, token)) => {
                 let token: Option<$type> = Some(token);
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                let input = $crate::_consumed_input(input_before, $args);
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err(e) => $crate::_record_parse_error(&mut $error, e, input_before),
         }
     };
 
@@ -57,7 +67,8 @@ macro_rules! _parse_prefix {
     ) => {
         if $args.trim_start().is_empty() {
             let token: Option<$type> = None;
-            $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+            let input: Option<&str> = None;
+            $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ]);
         } else {
             let input = $args.trim_start();
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
@@ -66,9 +77,10 @@ macro_rules! _parse_prefix {
                 Ok(token) => {
                     let $args = "";
                     let token = Some(token);
-                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+                    let input = Some(input.trim_end());
+                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => $crate::_record_parse_error(&mut $error, (e.into(), Some(input.to_owned())), input),
             }
         }
     };
@@ -104,7 +116,8 @@ macro_rules! _parse_prefix {
 
         // This will run at least once
         while let Some(token_rest_args) = token_rest_args.pop() {
-            $crate::_parse_prefix!($ctx $msg token_rest_args attachment => [ $error $($preamble)* tokens ] $($rest)* );
+            let input = $crate::_consumed_input($args, token_rest_args);
+            $crate::_parse_prefix!($ctx $msg token_rest_args attachment => [ $error $($preamble)* (tokens input) ] $($rest)* );
             tokens.pop();
         }
     };
@@ -119,16 +132,18 @@ macro_rules! _parse_prefix {
     ) => {
         let input = $args.trim_start();
         if input.is_empty() {
-            $error = ($crate::TooFewArguments::default().into(), None);
+            let too_few = $crate::TooFewArguments::default();
+            $crate::_record_parse_error(&mut $error, (too_few.into(), None), input);
         } else {
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
                 $ctx, $msg.guild_id, Some($msg.channel_id), input
             ).await {
                 Ok(token) => {
                     let $args = "";
-                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+                    let input = Some(input.trim_end());
+                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => $crate::_record_parse_error(&mut $error, (e.into(), Some(input.to_owned())), input),
             }
         }
     };
@@ -138,15 +153,43 @@ macro_rules! _parse_prefix {
         (#[flag] $name:literal)
         $( $rest:tt )*
     ) => {
+        let input_before: &str = $args;
         match <String as $crate::PopArgument>::pop_from(&$args, $attachment_index, $ctx, $msg).await {
             Ok(($args, $attachment_index, token)) if token.eq_ignore_ascii_case($name) => {
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* true ] $($rest)* );
+                let input = $crate::_consumed_input(input_before, $args);
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (true input) ] $($rest)* );
             },
             // only allow backtracking if the flag didn't match: it's confusing for the user if they
             // precisely set the flag but it's ignored
             _ => {
-                $error = (concat!("Must use either `", $name, "` or nothing as a modifier").into(), None);
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* false ] $($rest)* );
+                let message = concat!("Must use either `", $name, "` or nothing as a modifier");
+                $crate::_record_parse_error(&mut $error, (message.into(), None), input_before);
+                let input: Option<&str> = None;
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (false input) ] $($rest)* );
+            }
+        }
+    };
+
+    // Consume #[until = KEYWORD] T
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[until = $keyword:literal] $type:ty)
+        $( $rest:tt )*
+    ) => {
+        let splits = $crate::_split_until_keyword($args, $keyword);
+        if splits.is_empty() {
+            let too_few = $crate::TooFewArguments::default();
+            $crate::_record_parse_error(&mut $error, (too_few.into(), None), $args);
+        }
+        // See _split_until_keyword for the order in which splits are tried
+        for (input, $args) in splits {
+            match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
+                $ctx, $msg.guild_id, Some($msg.channel_id), input
+            ).await {
+                Ok(token) => {
+                    let input = Some(input);
+                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ] $($rest)* );
+                },
+                Err(e) => $crate::_record_parse_error(&mut $error, (e.into(), Some(input.to_owned())), input),
             }
         }
     };
@@ -156,11 +199,13 @@ macro_rules! _parse_prefix {
         ($type:ty)
         $( $rest:tt )*
     ) => {
+        let input_before: &str = $args;
         match <$type as $crate::PopArgument>::pop_from(&$args, $attachment_index, $ctx, $msg).await {
             Ok(($args, $attachment_index, token)) => {
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                let input = $crate::_consumed_input(input_before, $args);
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* (token input) ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err(e) => $crate::_record_parse_error(&mut $error, e, input_before),
         }
     };
 
@@ -175,6 +220,24 @@ Macro for parsing an argument string into multiple parameter types.
 An invocation of this macro is generated by the [`crate::command`] macro, so you usually don't need
 to use this macro directly.
 
+Parameters are parsed with backtracking:
+- An `Option<T>` parameter first tries to parse `T`. If that fails, or if the remaining parameters
+  can't be parsed afterwards, the parameter becomes `None` and the remaining parameters are parsed
+  from the same position, including the same attachment. A token that doesn't parse into `T` is
+  never consumed.
+- `#[lazy] Option<T>` tries `None` first and only then `T`.
+- `Vec<T>` consumes as many tokens as possible, and gives them back one by one from the end if the
+  remaining parameters can't be parsed otherwise.
+- `#[until = "in"] T` consumes everything up to the last `in` word and skips the keyword itself.
+  Failing that, it stops at earlier `in` words instead, and finally consumes the entire rest of
+  the input.
+
+If no combination of parameters parses the whole input, the error that occurred furthest into the
+input is returned, or the first of those if several occurred at the same position. For example,
+with `(Option<u32>), (String)`, the input `abc def` fails with
+[`TooManyArguments`](crate::TooManyArguments) because of `def`, not with an integer parse error
+because of `abc`.
+
 ```rust,no_run
 # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
 # use lumi::serenity_prelude as serenity;
@@ -211,7 +274,30 @@ assert_eq!(
 #[macro_export]
 macro_rules! parse_prefix_args {
     ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
-        $( #[$attr:ident] )?
+        $( #[$attr:ident $( = $attr_value:literal )?] )?
+        ( $($type:tt)* )
+    ),* $(,)? ) => {
+        async {
+            $crate::parse_prefix_args_with_inputs!(
+                $ctx, $msg, $args, $attachment_index =>
+                $( $( #[$attr $( = $attr_value )?] )? ( $($type)* ) ),*
+            )
+            .await
+            .map(|(values, _inputs)| values)
+        }
+    };
+}
+
+/// Like [`parse_prefix_args!`], but additionally returns the raw input of each parameter
+///
+/// The inputs are returned as `Vec<Option<&str>>` in parameter order, trimmed of surrounding
+/// whitespace and still including quotes. Parameters that consumed no input, like omitted
+/// `Option`s and attachments, are `None`. Used by [`crate::command`] to fill
+/// [`crate::PrefixContext::parameter_inputs`].
+#[macro_export]
+macro_rules! parse_prefix_args_with_inputs {
+    ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
+        $( #[$attr:ident $( = $attr_value:literal )?] )?
         ( $($type:tt)* )
     ),* $(,)? ) => {
         async {
@@ -222,16 +308,18 @@ macro_rules! parse_prefix_args {
             let args = $args;
             let attachment_index = $attachment_index;
 
-            let mut error: (Box<dyn std::error::Error + Send + Sync>, Option<String>)
-                = (Box::new($crate::TooManyArguments { __non_exhaustive: () }) as _, None);
+            let mut error: Option<$crate::_ParseError> = None;
 
             $crate::_parse_prefix!(
                 ctx msg args attachment_index => [error]
                 $(
-                    ($( #[$attr] )? $($type)*)
+                    ($( #[$attr $( = $attr_value )?] )? $($type)*)
                 )*
             );
-            Err(error)
+            Err(error.map_or_else(
+                || (Box::new($crate::TooManyArguments { __non_exhaustive: () }) as _, None),
+                |(error, _)| error,
+            ))
         }
     };
 }
@@ -264,6 +352,26 @@ mod test {
                 .unwrap(),
             (None, "hello".into()),
         );
+        assert_eq!(
+            parse_prefix_args!(&ctx, &msg, "5", 0 => (Option<u32>), (u32))
+                .await
+                .unwrap(),
+            (None, 5),
+        );
+        assert_eq!(
+            parse_prefix_args!(&ctx, &msg, "abc 5", 0 => (Option<u32>), (u32))
+                .await
+                .unwrap_err()
+                .1,
+            Some("abc".into()),
+        );
+        assert!(
+            parse_prefix_args!(&ctx, &msg, "abc def", 0 => (Option<u32>), (String))
+                .await
+                .unwrap_err()
+                .0
+                .is::<crate::TooManyArguments>(),
+        );
         assert_eq!(
             parse_prefix_args!(&ctx, &msg, "a b c", 0 => (Vec<String>), (String))
                 .await
@@ -5,6 +5,9 @@
 mod code_block;
 pub use code_block::*;
 
+mod fuzzy_member;
+pub use fuzzy_member::*;
+
 mod key_value_args;
 pub use key_value_args::*;
 
@@ -14,13 +17,17 @@ pub use macros::*;
 mod argument_trait;
 pub use argument_trait::*;
 
+use std::borrow::Cow;
+
 use crate::serenity_prelude as serenity;
 
 /// Pop a whitespace-separated word from the front of the arguments. Supports quotes and quote
 /// escaping.
 ///
 /// Leading whitespace will be trimmed; trailing whitespace is not consumed.
-fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
+///
+/// Words without quotes or escapes are borrowed from `args` instead of allocated.
+pub(crate) fn pop_string(args: &str) -> Result<(&str, Cow<'_, str>), crate::TooFewArguments> {
     // TODO: consider changing the behavior to parse quotes literally if they're in the middle
     // of the string:
     // - `"hello world"` => `hello world`
@@ -32,6 +39,13 @@ fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
         return Err(crate::TooFewArguments::default());
     }
 
+    // Fast path: if the word doesn't contain quotes or escapes, it's used verbatim
+    let word_end = args.find(char::is_whitespace).unwrap_or(args.len());
+    let (word, rest) = args.split_at(word_end);
+    if !word.contains(['"', '\\']) {
+        return Ok((rest, Cow::Borrowed(word)));
+    }
+
     let mut output = String::new();
     let mut inside_string = false;
     let mut escaping = false;
@@ -56,7 +70,68 @@ fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
         chars.next();
     }
 
-    Ok((chars.as_str(), output))
+    Ok((chars.as_str(), Cow::Owned(output)))
+}
+
+/// Returns the part of `before` that was consumed to arrive at its suffix `after`, without
+/// surrounding whitespace. Used by [`crate::parse_prefix_args_with_inputs`].
+#[doc(hidden)]
+pub fn _consumed_input<'a>(before: &'a str, after: &str) -> Option<&'a str> {
+    let consumed = before.strip_suffix(after)?.trim();
+    (!consumed.is_empty()).then_some(consumed)
+}
+
+/// Splits the arguments at each occurrence of `keyword` as a separate word, returning the input
+/// before the keyword and the arguments after it. Used by [`crate::parse_prefix_args!`] for
+/// `#[until]` parameters.
+///
+/// Splits at later keywords come first, then the entire input without a keyword. The keyword is
+/// matched case-insensitively and never at the very start, since the input must not be empty.
+#[doc(hidden)]
+pub fn _split_until_keyword<'a>(args: &'a str, keyword: &str) -> Vec<(&'a str, &'a str)> {
+    let args = args.trim();
+    let mut splits = args
+        .split_whitespace()
+        .filter_map(|word| {
+            let start = word.as_ptr() as usize - args.as_ptr() as usize;
+            let is_keyword = start > 0 && word.eq_ignore_ascii_case(keyword);
+            is_keyword.then(|| {
+                let rest = &args[start + word.len()..];
+                (args[..start].trim_end(), rest.trim_start())
+            })
+        })
+        .collect::<Vec<_>>();
+    splits.reverse();
+    if !args.is_empty() {
+        splits.push((args, ""));
+    }
+    splits
+}
+
+/// A parse error of [`crate::parse_prefix_args!`], and how much input was left when it occurred
+#[doc(hidden)]
+pub type _ParseError = (
+    (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+    usize,
+);
+
+/// Keeps the error that occurred furthest into the input, which is the one reported if no
+/// combination of parameters parses. On ties, the earlier error is kept. Used by
+/// [`crate::parse_prefix_args!`].
+#[doc(hidden)]
+pub fn _record_parse_error(
+    error: &mut Option<_ParseError>,
+    new_error: (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+    remaining: &str,
+) {
+    let remaining = remaining.trim_start().len();
+    let is_further = match error {
+        Some((_, previous)) => remaining < *previous,
+        None => true,
+    };
+    if is_further {
+        *error = Some((new_error, remaining));
+    }
 }
 
 /// Error thrown if user passes too many arguments to a command
@@ -131,6 +206,13 @@ fn test_pop_string() {
     // Test that trailing whitespace is not consumed
     assert_eq!(pop_string("AA BB").unwrap().0, " BB");
 
+    // Test that plain words are borrowed and quoted words are not
+    assert!(matches!(
+        pop_string("AA BB").unwrap().1,
+        Cow::Borrowed("AA")
+    ));
+    assert!(matches!(pop_string(r#""AA BB""#).unwrap().1, Cow::Owned(_)));
+
     for &(string, arg) in &[
         (r#"AA BB"#, r#"AA"#),
         (r#""AA BB""#, r#"AA BB"#),
@@ -144,3 +226,40 @@ fn test_pop_string() {
         assert_eq!(pop_string(string).unwrap().1, arg);
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_consumed_input() {
+    assert_eq!(_consumed_input("a b c", " b c"), Some("a"));
+    assert_eq!(_consumed_input(r#""a b" c"#, " c"), Some(r#""a b""#));
+    assert_eq!(_consumed_input("a b", "a b"), None);
+    assert_eq!(_consumed_input("a b", "x"), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_record_parse_error() {
+    let mut error = None;
+    _record_parse_error(&mut error, (TooFewArguments::default().into(), None), "a b");
+    _record_parse_error(&mut error, (InvalidBool::default().into(), None), " b");
+    // Same position, so the earlier error is kept
+    _record_parse_error(&mut error, (InvalidChoice::default().into(), None), "b");
+
+    let ((error, _), remaining) = error.unwrap();
+    assert!(error.is::<InvalidBool>());
+    assert_eq!(remaining, 1);
+}
+
+#[cfg(test)]
+#[test]
+fn test_split_until_keyword() {
+    assert_eq!(
+        _split_until_keyword("log in IN 2h", "in"),
+        vec![("log in", "2h"), ("log", "IN 2h"), ("log in IN 2h", "")]
+    );
+    assert_eq!(
+        _split_until_keyword("in plants", "in"),
+        vec![("in plants", "")]
+    );
+    assert_eq!(_split_until_keyword(" ", "in"), vec![]);
+}
@@ -0,0 +1,131 @@
+//! Periodically changing bot presences, see [`PresenceRotation`]
+
+use crate::{BoxFuture, serenity_prelude as serenity};
+use std::sync::Arc;
+
+/// Where [`PresenceRotation`] takes the next activity from
+#[derive(Clone)]
+enum PresenceSource {
+    /// Cycles through a fixed list of activities
+    Activities(Arc<[serenity::ActivityData]>),
+    /// Asks a callback for the next activity
+    Callback(fn(&serenity::Context) -> BoxFuture<'_, Option<serenity::ActivityData>>),
+}
+
+/// Updates the bot's presence periodically, for example to cycle through status messages
+///
+/// Set [`crate::FrameworkOptions::presence_rotation`] or call
+/// [`crate::FrameworkBuilder::presence_rotation`] to enable it. The framework starts the rotation
+/// on every shard once it receives the ready event, restarting it when a shard reconnects.
+///
+/// ```rust
+/// # use lumi::serenity_prelude as serenity;
+/// let rotation = lumi::PresenceRotation::new(
+///     vec![
+///         serenity::ActivityData::playing("with commands"),
+///         serenity::ActivityData::listening("/help"),
+///     ],
+///     std::time::Duration::from_secs(60),
+/// );
+/// ```
+///
+/// Discord rate limits presence updates, so keep the interval at a minute or longer.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct PresenceRotation {
+    /// See [`PresenceSource`]
+    #[derivative(Debug = "ignore")]
+    source: PresenceSource,
+    /// Time between two presence updates
+    interval: std::time::Duration,
+    /// Online status set along with every activity
+    status: serenity::OnlineStatus,
+    /// Rotation tasks by shard, aborted when the rotation is dropped
+    tasks: parking_lot::Mutex<
+        std::collections::HashMap<serenity::ShardId, tokio::task::JoinHandle<()>>,
+    >,
+}
+
+impl PresenceRotation {
+    /// Creates a rotation cycling through the given activities, switching to the next one every
+    /// `interval`
+    ///
+    /// Panics if `interval` is zero.
+    pub fn new(activities: Vec<serenity::ActivityData>, interval: std::time::Duration) -> Self {
+        Self::with_source(PresenceSource::Activities(activities.into()), interval)
+    }
+
+    /// Creates a rotation which asks `callback` for the activity every `interval`, for example to
+    /// show the current number of servers. Returning `None` clears the activity.
+    ///
+    /// Panics if `interval` is zero.
+    pub fn from_callback(
+        callback: fn(&serenity::Context) -> BoxFuture<'_, Option<serenity::ActivityData>>,
+        interval: std::time::Duration,
+    ) -> Self {
+        Self::with_source(PresenceSource::Callback(callback), interval)
+    }
+
+    /// Creates a rotation from the given source
+    fn with_source(source: PresenceSource, interval: std::time::Duration) -> Self {
+        assert!(
+            !interval.is_zero(),
+            "presence rotation interval must be non-zero"
+        );
+        Self {
+            source,
+            interval,
+            status: serenity::OnlineStatus::Online,
+            tasks: Default::default(),
+        }
+    }
+
+    /// Sets the online status shown along with the activities
+    ///
+    /// [`serenity::OnlineStatus::Online`] by default.
+    pub fn status(mut self, status: serenity::OnlineStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Starts rotating the presence of the shard of `ctx`, replacing a previous rotation of that
+    /// shard
+    pub(crate) fn start(&self, ctx: &serenity::Context) {
+        if let PresenceSource::Activities(activities) = &self.source {
+            if activities.is_empty() {
+                return;
+            }
+        }
+
+        let ctx = ctx.clone();
+        let source = self.source.clone();
+        let interval = self.interval;
+        let status = self.status;
+        let shard_id = ctx.shard_id;
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            for index in 0.. {
+                interval.tick().await;
+                let activity = match &source {
+                    PresenceSource::Activities(activities) => {
+                        Some(activities[index % activities.len()].clone())
+                    }
+                    PresenceSource::Callback(callback) => callback(&ctx).await,
+                };
+                ctx.set_presence(activity, status);
+            }
+        });
+
+        if let Some(previous) = self.tasks.lock().insert(shard_id, task) {
+            previous.abort();
+        }
+    }
+}
+
+impl Drop for PresenceRotation {
+    fn drop(&mut self) {
+        for task in self.tasks.get_mut().values() {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Reacting to Discord rate limits while sending command responses, see
+//! [`rate_limit_callback`]
+
+use crate::serenity_prelude as serenity;
+
+/// A rate limit that delayed a command response, passed to
+/// [`crate::FrameworkOptions::on_rate_limit`]
+#[derive(Clone, Debug)]
+pub struct RateLimitEvent {
+    /// How long the request is held back
+    pub delay: std::time::Duration,
+    /// Whether the global rate limit was hit, instead of the limit of a single route
+    pub global: bool,
+    /// The API route that was rate limited
+    pub path: String,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+tokio::task_local! {
+    /// Receives the rate limits hit while a response is being sent, see [`track_rate_limits`]
+    static RATE_LIMIT_EVENTS: tokio::sync::mpsc::UnboundedSender<RateLimitEvent>;
+}
+
+/// Returns a callback for serenity's ratelimiter which reports rate limits hit while sending
+/// command responses to [`crate::FrameworkOptions::on_rate_limit`] and
+/// [`crate::FrameworkOptions::rate_limit_warning`]
+///
+/// Serenity only accepts the callback when the HTTP client is built, so the framework can't
+/// install it on its own:
+/// ```rust,no_run
+/// # use lumi::serenity_prelude as serenity;
+/// # fn _f(token: serenity::Token) {
+/// let mut http = serenity::HttpBuilder::new(token).build();
+/// if let Some(ratelimiter) = &mut http.ratelimiter {
+///     ratelimiter.set_ratelimit_callback(lumi::rate_limit_callback());
+/// }
+/// # }
+/// ```
+///
+/// Rate limits of requests outside of [`crate::send_reply`] and [`crate::send_followup`] are
+/// ignored.
+pub fn rate_limit_callback() -> Box<dyn Fn(serenity::RatelimitInfo) + Send + Sync> {
+    Box::new(|info| {
+        let event = RateLimitEvent {
+            delay: info.timeout,
+            global: info.global,
+            path: info.path.to_string(),
+            __non_exhaustive: (),
+        };
+        // Not inside of a response, or the response is already done
+        let _: Result<_, _> = RATE_LIMIT_EVENTS.try_with(|sender| sender.send(event));
+    })
+}
+
+/// Runs the future that sends a response, invoking the rate limit hooks whenever serenity reports
+/// that it's rate limited
+///
+/// The ratelimiter invokes the callback from within the request future, so the task local
+/// identifies which response got delayed. The hooks run concurrently with the response, so a slow
+/// hook doesn't delay the response any further.
+pub(crate) async fn track_rate_limits<T: Send + Sync + 'static, E, O>(
+    ctx: crate::Context<'_, T, E>,
+    response: impl std::future::Future<Output = O>,
+) -> O {
+    let options = ctx.framework().options();
+    if options.on_rate_limit.is_none() && options.rate_limit_warning.is_none() {
+        return response.await;
+    }
+
+    // The sender is dropped together with the response future once it completes, which ends the
+    // event stream after the remaining hooks are done
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    let response = RATE_LIMIT_EVENTS.scope(sender, response);
+    let warned = std::sync::atomic::AtomicBool::new(false);
+    let events = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        let event = receiver.recv().await?;
+        Some((event, receiver))
+    });
+    // The hooks run outside of the task local scope, so their own requests aren't reported
+    let hooks = futures_util::StreamExt::for_each_concurrent(events, None, |event| {
+        run_hooks(ctx, event, &warned)
+    });
+
+    let (output, ()) = futures_util::future::join(response, hooks).await;
+    output
+}
+
+/// Invokes [`crate::FrameworkOptions::on_rate_limit`] and, once per response, the warning of
+/// [`crate::FrameworkOptions::rate_limit_warning`]
+async fn run_hooks<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    event: RateLimitEvent,
+    warned: &std::sync::atomic::AtomicBool,
+) {
+    let options = ctx.framework().options();
+    tracing::debug!(
+        "response to {} rate limited: {:?}",
+        ctx.redacted_invocation_string(),
+        event
+    );
+    if let Some(on_rate_limit) = options.on_rate_limit {
+        on_rate_limit(ctx, event.clone()).await;
+    }
+    if should_warn(options.rate_limit_warning, &event, warned) {
+        if let Err(e) = warn_rate_limited(ctx).await {
+            tracing::warn!("couldn't send rate limit warning: {}", e);
+        }
+    }
+}
+
+/// Whether the event warrants a warning, claiming it if so. At most one warning is sent per
+/// response
+fn should_warn(
+    threshold: Option<std::time::Duration>,
+    event: &RateLimitEvent,
+    warned: &std::sync::atomic::AtomicBool,
+) -> bool {
+    threshold.is_some_and(|threshold| event.delay >= threshold)
+        && !warned.swap(true, std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Tells the invoking user that the response is delayed, using a route that's unaffected by the
+/// rate limit of the response
+///
+/// Only prefix invocations are warned: every route to answer an interaction (followups and edits
+/// of the original response) shares the rate limit bucket of the delayed response.
+async fn warn_rate_limited<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+) -> Result<(), serenity::Error> {
+    if let crate::Context::Prefix(ctx) = ctx {
+        // Reactions have their own rate limit, unlike a warning message in the same channel
+        ctx.msg.react(ctx.http(), '⏳').await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_should_warn() {
+    let event = |secs| RateLimitEvent {
+        delay: std::time::Duration::from_secs(secs),
+        global: false,
+        path: "/channels/1/messages".into(),
+        __non_exhaustive: (),
+    };
+    let threshold = Some(std::time::Duration::from_secs(5));
+
+    let warned = std::sync::atomic::AtomicBool::new(false);
+    assert!(!should_warn(None, &event(10), &warned));
+    assert!(!should_warn(threshold, &event(1), &warned));
+    assert!(should_warn(threshold, &event(5), &warned));
+    // Only once per response
+    assert!(!should_warn(threshold, &event(10), &warned));
+}
@@ -0,0 +1,271 @@
+//! Replaying recorded gateway events for integration tests, see [`Replay`]
+//!
+//! Requires the `test-utils` feature.
+
+use crate::serenity_prelude as serenity;
+use std::sync::Arc;
+
+mod stub_http;
+pub use stub_http::*;
+
+/// Error that occurs when reading a gateway event fixture
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The fixture or its event data is not valid JSON of the expected shape
+    Json(serde_json::Error),
+    /// The fixture has no `t` event name or no `d` event data
+    MissingField(&'static str),
+    /// Replaying this kind of event is not supported
+    UnsupportedEvent {
+        /// The event name, e.g. `GUILD_CREATE`
+        name: String,
+    },
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid event fixture: {e}"),
+            Self::MissingField(field) => write!(f, "event fixture is missing `{field}`"),
+            Self::UnsupportedEvent { name } => write!(f, "replaying `{name}` is not supported"),
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// A gateway event that can be replayed with [`Replay::dispatch`]
+#[derive(Clone, Debug)]
+pub enum ReplayEvent {
+    /// `MESSAGE_CREATE`, dispatched onto prefix commands
+    MessageCreate(serenity::Message),
+    /// `MESSAGE_UPDATE`, dispatched onto prefix commands if edit tracking is enabled
+    MessageUpdate(serenity::MessageUpdateEvent),
+    /// `MESSAGE_DELETE`, deletes tracked bot responses if edit tracking is enabled
+    MessageDelete(serenity::MessageId),
+    /// `INTERACTION_CREATE`, dispatched onto application commands, autocomplete callbacks and
+    /// framework buttons
+    InteractionCreate(serenity::Interaction),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl ReplayEvent {
+    /// Reads a gateway dispatch payload, like `{"op": 0, "t": "MESSAGE_CREATE", "d": {...}}`
+    ///
+    /// Fields other than `t` and `d` are ignored.
+    pub fn from_json(json: &str) -> Result<Self, ReplayError> {
+        Self::from_value(serde_json::from_str(json)?)
+    }
+
+    /// Like [`Self::from_json`], but for an already parsed payload
+    pub fn from_value(mut payload: serde_json::Value) -> Result<Self, ReplayError> {
+        let name = payload
+            .get("t")
+            .and_then(|name| name.as_str())
+            .ok_or(ReplayError::MissingField("t"))?
+            .to_owned();
+        let data = payload
+            .get_mut("d")
+            .map(serde_json::Value::take)
+            .ok_or(ReplayError::MissingField("d"))?;
+
+        Ok(match &*name {
+            "MESSAGE_CREATE" => Self::MessageCreate(serde_json::from_value(data)?),
+            "MESSAGE_UPDATE" => Self::MessageUpdate(serde_json::from_value(data)?),
+            "MESSAGE_DELETE" => {
+                let id = data
+                    .get("id")
+                    .cloned()
+                    .ok_or(ReplayError::MissingField("id"))?;
+                Self::MessageDelete(serde_json::from_value(id)?)
+            }
+            "INTERACTION_CREATE" => Self::InteractionCreate(serde_json::from_value(data)?),
+            _ => return Err(ReplayError::UnsupportedEvent { name }),
+        })
+    }
+}
+
+/// Reads a fixture containing either a single gateway dispatch payload or an array of them, see
+/// [`ReplayEvent::from_json`]
+pub fn parse_fixture(json: &str) -> Result<Vec<ReplayEvent>, ReplayError> {
+    match serde_json::from_str(json)? {
+        serde_json::Value::Array(payloads) => {
+            payloads.into_iter().map(ReplayEvent::from_value).collect()
+        }
+        payload => Ok(vec![ReplayEvent::from_value(payload)?]),
+    }
+}
+
+/// An error produced while replaying an event, as recorded by [`Replay`]
+#[derive(Clone, Debug)]
+pub struct ReplayedError {
+    /// The kind of error
+    pub code: crate::ErrorCode,
+    /// [`crate::Command::qualified_name`] of the command the error occurred in, if any
+    pub command: Option<String>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// What a [`Replay`] recorded so far
+#[derive(Debug, Default)]
+struct Recording {
+    /// Completed command invocations, including denied ones
+    invocations: parking_lot::Mutex<Vec<crate::InvocationRecord>>,
+    /// Errors, in the order they were produced
+    errors: parking_lot::Mutex<Vec<ReplayedError>>,
+}
+
+tokio::task_local! {
+    /// The recording of the [`Replay::dispatch`] running in this task
+    static RECORDING: Arc<Recording>;
+}
+
+/// Records a completed invocation if it was dispatched by [`Replay::dispatch`]
+pub(crate) fn record_invocation(record: impl FnOnce() -> crate::InvocationRecord) {
+    let _ = RECORDING.try_with(|recording| recording.invocations.lock().push(record()));
+}
+
+/// Feeds gateway events from JSON fixtures through the framework's event dispatch, recording
+/// which commands were invoked and which errors were produced
+///
+/// Events are dispatched like [`crate::dispatch_event`] would, so prefix stripping, edit
+/// tracking, checks and permission logic all apply. Errors are still passed to the error
+/// handlers after being recorded.
+///
+/// Responses are sent through the HTTP client of the given [`serenity::Context`]. Point it at a
+/// [`StubHttp`] server, e.g. with serenity's `HttpBuilder::proxy`, so that tests don't talk to
+/// Discord. Invocations are recorded without touching [`crate::FrameworkOptions::audit_sink`].
+///
+/// ```rust,no_run
+/// # async fn _test(
+/// #     serenity_context: &lumi::serenity_prelude::Context,
+/// #     options: lumi::FrameworkOptions<(), ()>,
+/// # ) -> Result<(), lumi::replay::ReplayError> {
+/// let replay = lumi::replay::Replay::new();
+/// let framework = lumi::FrameworkContext {
+///     serenity_context,
+///     options: &options,
+/// };
+///
+/// let fixture = std::fs::read_to_string("tests/fixtures/ping.json").unwrap();
+/// let events = lumi::replay::parse_fixture(&fixture)?;
+/// for event in &events {
+///     replay.dispatch(framework, event).await;
+/// }
+/// assert_eq!(replay.invocations()[0].command, "ping");
+/// assert!(replay.errors().is_empty());
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Replay {
+    /// Shared with the tasks running [`Self::dispatch`]
+    recording: Arc<Recording>,
+}
+
+impl Replay {
+    /// Creates a replay with nothing recorded yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dispatches a single event and waits until it's fully handled, including running the
+    /// invoked command
+    ///
+    /// [`crate::FrameworkOptions::recent_invocations`] is cleared first, so that replaying the
+    /// same fixture several times runs its commands every time.
+    pub async fn dispatch<T: Send + Sync + 'static, E>(
+        &self,
+        framework: crate::FrameworkContext<'_, T, E>,
+        event: &ReplayEvent,
+    ) {
+        if let Some(recent_invocations) = &framework.options.recent_invocations {
+            recent_invocations.clear();
+        }
+        let record_error: &mut crate::dispatch::InspectError<'_, T, E> = &mut |error| {
+            self.recording.errors.lock().push(ReplayedError {
+                code: error.code(),
+                command: error
+                    .ctx()
+                    .map(|ctx| ctx.command().qualified_name.to_string()),
+                __non_exhaustive: (),
+            });
+        };
+        let dispatch = async {
+            match event {
+                ReplayEvent::MessageCreate(msg) => {
+                    let trigger = crate::MessageDispatchTrigger::MessageCreate;
+                    crate::dispatch::dispatch_message_event(framework, msg, trigger, record_error)
+                        .await;
+                }
+                ReplayEvent::MessageUpdate(event) => {
+                    crate::dispatch::dispatch_message_update(framework, event, record_error).await;
+                }
+                ReplayEvent::MessageDelete(message_id) => {
+                    crate::dispatch::dispatch_message_delete(framework, *message_id).await;
+                }
+                ReplayEvent::InteractionCreate(interaction) => {
+                    crate::dispatch::dispatch_interaction_event(
+                        framework,
+                        interaction,
+                        record_error,
+                    )
+                    .await;
+                }
+                ReplayEvent::__NonExhaustive => unreachable!(),
+            }
+        };
+        RECORDING.scope(self.recording.clone(), dispatch).await;
+    }
+
+    /// Returns the command invocations recorded so far, in the order they completed
+    pub fn invocations(&self) -> Vec<crate::InvocationRecord> {
+        self.recording.invocations.lock().clone()
+    }
+
+    /// Returns the errors recorded so far, in the order they were produced
+    pub fn errors(&self) -> Vec<ReplayedError> {
+        self.recording.errors.lock().clone()
+    }
+
+    /// Forgets everything recorded so far, e.g. between test cases
+    pub fn clear(&self) {
+        self.recording.invocations.lock().clear();
+        self.recording.errors.lock().clear();
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_fixture() {
+    let events = parse_fixture(r#"[{"op": 0, "t": "MESSAGE_DELETE", "d": {"id": "42"}}]"#).unwrap();
+    assert!(matches!(
+        events[..],
+        [ReplayEvent::MessageDelete(id)] if id == serenity::MessageId::new(42)
+    ));
+
+    let error = ReplayEvent::from_json(r#"{"t": "GUILD_CREATE", "d": {}}"#).unwrap_err();
+    assert!(matches!(error, ReplayError::UnsupportedEvent { name } if name == "GUILD_CREATE"));
+    assert!(matches!(
+        ReplayEvent::from_json(r#"{"d": {}}"#),
+        Err(ReplayError::MissingField("t"))
+    ));
+}
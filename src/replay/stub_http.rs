@@ -0,0 +1,248 @@
+//! A local stand-in for the Discord HTTP API, see [`StubHttp`]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _};
+
+/// A request received by a [`StubHttp`] server
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StubRequest {
+    /// HTTP method, e.g. `POST`
+    pub method: String,
+    /// Request path, e.g. `/api/v10/channels/1/messages`
+    pub path: String,
+    /// Request body, usually JSON
+    pub body: Vec<u8>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl StubRequest {
+    /// Parses the body as JSON, if it is
+    pub fn json(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+/// State shared between the [`StubHttp`] handle and its connection tasks
+#[derive(Debug, Default)]
+struct StubState {
+    /// Every request received so far, in order
+    requests: parking_lot::Mutex<Vec<StubRequest>>,
+    /// Snowflake of the next synthesized message
+    next_message_id: AtomicU64,
+}
+
+/// Minimal HTTP/1.1 server on localhost which answers requests like the Discord API would, so that
+/// [`super::Replay`] tests don't talk to Discord
+///
+/// Requests that create, edit or fetch a message are answered with a message echoing the sent
+/// content. Everything else is answered with `204 No Content`. All requests are recorded and can
+/// be inspected with [`Self::requests`].
+///
+/// Point serenity's HTTP client at [`Self::url`], e.g. with `HttpBuilder::proxy`. The server stops
+/// when this handle is dropped.
+#[derive(Debug)]
+pub struct StubHttp {
+    /// Local address the server listens on
+    addr: std::net::SocketAddr,
+    /// Shared with the connection tasks
+    state: Arc<StubState>,
+    /// Accept loop, aborted on drop
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl StubHttp {
+    /// Starts the server on a free localhost port
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(StubState {
+            next_message_id: AtomicU64::new(1),
+            ..Default::default()
+        });
+
+        let task = tokio::spawn({
+            let state = state.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve_connection(stream, &state).await {
+                            tracing::debug!("Stub HTTP connection failed: {}", e);
+                        }
+                    });
+                }
+            }
+        });
+
+        Ok(Self { addr, state, task })
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:41234`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns the requests received so far, in order
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.state.requests.lock().clone()
+    }
+
+    /// Forgets the requests received so far
+    pub fn clear(&self) {
+        self.state.requests.lock().clear();
+    }
+}
+
+impl Drop for StubHttp {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Answers requests on a single keep-alive connection until the client closes it
+async fn serve_connection(stream: tokio::net::TcpStream, state: &StubState) -> std::io::Result<()> {
+    let mut stream = tokio::io::BufReader::new(stream);
+    loop {
+        let mut request_line = String::new();
+        if stream.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_owned();
+        let path = parts.next().unwrap_or_default().to_owned();
+
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            if stream.read_line(&mut header).await? == 0 {
+                return Ok(());
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        stream.read_exact(&mut body).await?;
+
+        let request = StubRequest {
+            method,
+            path,
+            body,
+            __non_exhaustive: (),
+        };
+        let response = respond(&request, state);
+        state.requests.lock().push(request);
+        stream.get_mut().write_all(&response).await?;
+    }
+}
+
+/// Builds the raw HTTP response to a request
+fn respond(request: &StubRequest, state: &StubState) -> Vec<u8> {
+    if !returns_message(request) {
+        return b"HTTP/1.1 204 No Content\r\n\r\n".to_vec();
+    }
+
+    let id = state.next_message_id.fetch_add(1, Ordering::Relaxed);
+    let body = message_json(id, request).to_string();
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+/// Whether Discord answers this request with a message object
+fn returns_message(request: &StubRequest) -> bool {
+    if request.method == "DELETE" || request.path.ends_with("/callback") {
+        return false;
+    }
+    // Channel messages, and interaction responses and followups, which go through webhooks
+    request.path.contains("/messages") || request.path.contains("/webhooks/")
+}
+
+/// Synthesizes the message Discord would return, echoing the sent content
+fn message_json(id: u64, request: &StubRequest) -> serde_json::Value {
+    let channel_id = request
+        .path
+        .split('/')
+        .skip_while(|segment| *segment != "channels")
+        .nth(1)
+        .unwrap_or("1");
+    let content = request
+        .json()
+        .and_then(|body| Some(body.get("content")?.as_str()?.to_owned()))
+        .unwrap_or_default();
+
+    serde_json::json!({
+        "id": id.to_string(),
+        "channel_id": channel_id,
+        "author": {
+            "id": "1",
+            "username": "stub",
+            "discriminator": "0000",
+            "global_name": null,
+            "avatar": null,
+            "bot": true,
+        },
+        "content": content,
+        "timestamp": "2015-01-01T00:00:00+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+#[cfg(test)]
+#[test]
+fn test_stub_http() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let server = StubHttp::start().await.unwrap();
+        let mut stream = tokio::net::TcpStream::connect(server.addr).await.unwrap();
+
+        let body = r#"{"content":"pong"}"#;
+        let request = format!(
+            "POST /api/v10/channels/7/messages HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}\
+             DELETE /api/v10/channels/7/messages/1 HTTP/1.1\r\n\r\n",
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        stream.shutdown().await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, rest) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        let (message, rest) = rest.split_at(rest.find("HTTP/1.1").unwrap());
+        let message: serde_json::Value = serde_json::from_str(message).unwrap();
+        assert_eq!(message["channel_id"], "7");
+        assert_eq!(message["content"], "pong");
+        assert_eq!(rest, "HTTP/1.1 204 No Content\r\n\r\n");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].json().unwrap()["content"], "pong");
+        assert_eq!(requests[1].path, "/api/v10/channels/7/messages/1");
+    });
+}
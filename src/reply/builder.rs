@@ -20,6 +20,27 @@ pub struct CreateReply<'a> {
     poll: Option<serenity::CreatePoll<'a, serenity::builder::create_poll::Ready>>,
     reply: bool,
     flags: Option<serenity::MessageFlags>,
+    existing_attachments: ExistingAttachments,
+    framework_buttons: Vec<serenity::CreateButton<'a>>,
+}
+
+/// What happens to the attachments of a message when it's edited with a [`CreateReply`], see
+/// [`CreateReply::existing_attachments`]
+///
+/// This matters for prefix commands with [`crate::Command::reuse_response`], where the response
+/// to an edited invocation is edited instead of sending a new message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ExistingAttachments {
+    /// Removes all existing attachments; only the attachments of the new reply remain
+    #[default]
+    Remove,
+    /// Keeps all existing attachments and adds the attachments of the new reply
+    Keep,
+    /// Keeps existing attachments which are also part of the new reply (same filename and size)
+    /// without uploading them again, removes the others, and adds the remaining new attachments
+    Diff,
+    #[doc(hidden)]
+    __NonExhaustive,
 }
 
 impl<'a> CreateReply<'a> {
@@ -34,6 +55,25 @@ impl<'a> CreateReply<'a> {
         self
     }
 
+    /// Puts a line in front of the existing content, unless the content would exceed Discord's
+    /// limit of 2000 characters. Returns whether the line was added
+    pub(crate) fn prepend_line(&mut self, line: &str) -> bool {
+        const MAX_CONTENT_LENGTH: usize = 2000;
+
+        let content_length = self.content.as_ref().map_or(0, |content| {
+            // Plus the line break
+            content.chars().count() + 1
+        });
+        if line.chars().count() + content_length > MAX_CONTENT_LENGTH {
+            return false;
+        }
+        self.content = Some(match self.content.take() {
+            Some(content) => format!("{}\n{}", line, content).into(),
+            None => line.to_owned().into(),
+        });
+        true
+    }
+
     /// Adds an embed to the message.
     ///
     /// Existing embeds are kept.
@@ -114,6 +154,53 @@ impl<'a> CreateReply<'a> {
         self.reply = reply;
         self
     }
+
+    /// Sets what happens to the attachments of the message when this reply edits it (prefix-only)
+    ///
+    /// By default, existing attachments are removed. See [`ExistingAttachments`].
+    pub fn existing_attachments(mut self, existing_attachments: ExistingAttachments) -> Self {
+        self.existing_attachments = existing_attachments;
+        self
+    }
+}
+
+impl<'a> CreateReply<'a> {
+    /// Adds a button of the framework, like the delete button. All framework buttons share a
+    /// single action row after the existing components
+    pub(crate) fn push_framework_button(mut self, button: serenity::CreateButton<'a>) -> Self {
+        self.framework_buttons.push(button);
+        self
+    }
+
+    /// Appends the row of framework buttons to the components, unless there's no room left for
+    /// another action row. Then the framework buttons are left out
+    fn with_framework_buttons(mut self) -> Self {
+        const MAX_ACTION_ROWS: usize = 5;
+
+        if self.framework_buttons.is_empty() {
+            return self;
+        }
+        let buttons = std::mem::take(&mut self.framework_buttons);
+        let mut components = self
+            .components
+            .take()
+            .map(Cow::into_owned)
+            .unwrap_or_default();
+        if components.len() < MAX_ACTION_ROWS {
+            let row = serenity::CreateActionRow::buttons(buttons);
+            #[cfg(feature = "unstable")]
+            let row = serenity::CreateComponent::ActionRow(row);
+            components.push(row);
+        } else {
+            tracing::debug!(
+                "leaving out {} framework buttons, the reply already has {} action rows",
+                buttons.len(),
+                components.len()
+            );
+        }
+        self.components = Some(components.into());
+        self
+    }
 }
 
 /// Methods to create a message builder from any type from this [`CreateReply`]. Used by lumi
@@ -134,7 +221,9 @@ impl<'a> CreateReply<'a> {
             poll,
             flags,
             reply: _, // can't reply to a message in interactions
-        } = self;
+            existing_attachments: _,
+            framework_buttons: _,
+        } = self.with_framework_buttons();
 
         if let Some(content) = content {
             builder = builder.content(content);
@@ -173,7 +262,9 @@ impl<'a> CreateReply<'a> {
             poll,
             flags,
             reply: _,
-        } = self;
+            existing_attachments: _,
+            framework_buttons: _,
+        } = self.with_framework_buttons();
 
         if let Some(content) = content {
             builder = builder.content(content);
@@ -214,7 +305,10 @@ impl<'a> CreateReply<'a> {
             poll: _,
             reply: _,
             flags: _,
-        } = self;
+            // interaction responses are edited by their own builder
+            existing_attachments: _,
+            framework_buttons: _,
+        } = self.with_framework_buttons();
 
         if let Some(content) = content {
             builder = builder.content(content);
@@ -233,9 +327,20 @@ impl<'a> CreateReply<'a> {
     }
 
     /// Serialize this response builder to a [`serenity::EditMessage`]
-    pub fn to_prefix_edit(
+    ///
+    /// Existing attachments of the edited message are removed. Use
+    /// [`Self::to_prefix_edit_with_attachments`] to respect [`Self::existing_attachments`].
+    pub fn to_prefix_edit(self, builder: serenity::EditMessage<'a>) -> serenity::EditMessage<'a> {
+        self.to_prefix_edit_with_attachments(builder, &[])
+    }
+
+    /// Serialize this response builder to a [`serenity::EditMessage`] which edits a message with
+    /// the given attachments, keeping or removing them according to
+    /// [`Self::existing_attachments`]
+    pub fn to_prefix_edit_with_attachments(
         self,
         mut builder: serenity::EditMessage<'a>,
+        existing: &[serenity::Attachment],
     ) -> serenity::EditMessage<'a> {
         let crate::CreateReply {
             content,
@@ -248,12 +353,11 @@ impl<'a> CreateReply<'a> {
             poll: _,
             reply: _, // can't edit reference message afterwards
             flags,
-        } = self;
+            existing_attachments,
+            framework_buttons: _,
+        } = self.with_framework_buttons();
 
-        let mut attachments_builder = serenity::EditAttachments::new();
-        for attachment in attachments {
-            attachments_builder = attachments_builder.add(attachment);
-        }
+        let attachments_builder = edit_attachments(existing_attachments, existing, attachments);
 
         if let Some(content) = content {
             builder = builder.content(content);
@@ -277,6 +381,17 @@ impl<'a> CreateReply<'a> {
         self,
         invocation_message: serenity::MessageReference,
     ) -> serenity::CreateMessage<'a> {
+        let reply = self.reply;
+        let builder = self.to_message();
+        match reply {
+            true => builder.reference_message(invocation_message),
+            false => builder,
+        }
+    }
+
+    /// Serialize this response builder to a [`serenity::CreateMessage`] which doesn't reply to any
+    /// message, e.g. to send it to arbitrary channels
+    pub fn to_message(self) -> serenity::CreateMessage<'a> {
         let crate::CreateReply {
             content,
             embeds,
@@ -285,9 +400,11 @@ impl<'a> CreateReply<'a> {
             ephemeral: _, // not supported in prefix
             allowed_mentions,
             poll,
-            reply,
+            reply: _, // there's no message to reply to
             flags,
-        } = self;
+            existing_attachments: _, // there's no message to edit
+            framework_buttons: _,
+        } = self.with_framework_buttons();
 
         let mut builder = serenity::CreateMessage::new();
         if let Some(content) = content {
@@ -299,9 +416,6 @@ impl<'a> CreateReply<'a> {
         if let Some(components) = components {
             builder = builder.components(components);
         }
-        if reply {
-            builder = builder.reference_message(invocation_message);
-        }
         if let Some(poll) = poll {
             builder = builder.poll(poll);
         }
@@ -317,3 +431,89 @@ impl<'a> CreateReply<'a> {
         builder.embeds(embeds)
     }
 }
+
+/// Whether an existing attachment has the same contents as a new one, as far as we can tell
+/// without downloading it
+fn is_same_attachment(
+    existing: &serenity::Attachment,
+    new: &serenity::CreateAttachment<'_>,
+) -> bool {
+    existing.filename == new.filename && existing.size as usize == new.data.len()
+}
+
+/// Builds the attachment changes for editing a message with the given `existing` attachments
+fn edit_attachments<'a>(
+    mode: ExistingAttachments,
+    existing: &[serenity::Attachment],
+    mut new: Vec<serenity::CreateAttachment<'a>>,
+) -> serenity::EditAttachments<'a> {
+    let mut builder = serenity::EditAttachments::new();
+    match mode {
+        ExistingAttachments::Remove | ExistingAttachments::__NonExhaustive => {}
+        ExistingAttachments::Keep => {
+            for attachment in existing {
+                builder = builder.keep(attachment.id);
+            }
+        }
+        ExistingAttachments::Diff => {
+            for attachment in existing {
+                if let Some(i) = new.iter().position(|n| is_same_attachment(attachment, n)) {
+                    new.remove(i);
+                    builder = builder.keep(attachment.id);
+                }
+            }
+        }
+    }
+    for attachment in new {
+        builder = builder.add(attachment);
+    }
+    builder
+}
+
+#[cfg(test)]
+#[test]
+fn test_prepend_line() {
+    let mut reply = CreateReply::default();
+    assert!(reply.prepend_line("warning"));
+    assert_eq!(reply.content.as_deref(), Some("warning"));
+
+    let mut reply = CreateReply::default().content("response");
+    assert!(reply.prepend_line("warning"));
+    assert_eq!(reply.content.as_deref(), Some("warning\nresponse"));
+
+    let long_response = "a".repeat(1995);
+    let mut reply = CreateReply::default().content(long_response.clone());
+    assert!(!reply.prepend_line("warning"));
+    assert_eq!(reply.content.as_deref(), Some(&*long_response));
+}
+
+#[cfg(test)]
+#[test]
+fn test_framework_buttons() {
+    let row = || {
+        let row = serenity::CreateActionRow::buttons(vec![serenity::CreateButton::new("user")]);
+        #[cfg(feature = "unstable")]
+        let row = serenity::CreateComponent::ActionRow(row);
+        row
+    };
+    let with_rows = |rows: usize| {
+        CreateReply::default()
+            .components(vec![row(); rows])
+            .push_framework_button(serenity::CreateButton::new("lumi-delete-1"))
+            .push_framework_button(serenity::CreateButton::new("lumi-rerun-1"))
+            .with_framework_buttons()
+    };
+    let row_count = |reply: CreateReply<'_>| reply.components.map_or(0, |rows| rows.len());
+
+    // Both framework buttons share a single row
+    assert_eq!(row_count(with_rows(0)), 1);
+    assert_eq!(row_count(with_rows(4)), 5);
+    // No room for another row
+    assert_eq!(row_count(with_rows(5)), 5);
+    assert!(
+        CreateReply::default()
+            .with_framework_buttons()
+            .components
+            .is_none()
+    );
+}
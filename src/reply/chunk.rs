@@ -0,0 +1,239 @@
+//! Splitting long text into multiple messages, see [`chunk_message`]
+
+use std::borrow::Cow;
+
+/// Options for [`chunk_message`]
+#[derive(Debug, Clone)]
+pub struct ChunkOptions {
+    /// Maximum number of characters per chunk, including any code fences added to the chunk
+    ///
+    /// 2000 by default, Discord's limit for message content.
+    pub max_length: usize,
+    /// If set, the whole text is wrapped in a code block with this language, e.g. `rust` or `""`
+    /// for no highlighting. Useful for eval and log output.
+    ///
+    /// None by default.
+    pub code_block: Option<String>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            max_length: 2000,
+            code_block: None,
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Length of the fence that closes a code block at the end of a chunk, including the newline
+const CLOSING_FENCE_LENGTH: usize = "\n```".len();
+
+/// Longest text after an opening fence that's treated as language name when reopening the block
+const MAX_LANGUAGE_LENGTH: usize = 20;
+
+/// Accumulates lines into chunks, closing and reopening code blocks at chunk boundaries
+struct Chunker {
+    /// See [`ChunkOptions::max_length`]
+    max_length: usize,
+    /// Finished chunks
+    chunks: Vec<String>,
+    /// The chunk being built
+    current: String,
+    /// Length of [`Self::current`] in characters
+    current_length: usize,
+    /// Whether [`Self::current`] contains more than whitespace and a reopened code fence
+    has_content: bool,
+    /// The opening fence, e.g. ```` ```rust ````, if the text is currently inside a code block
+    open_fence: Option<String>,
+}
+
+impl Chunker {
+    /// Appends text to the current chunk without any checks
+    fn push_str(&mut self, text: &str) {
+        self.current += text;
+        self.current_length += text.chars().count();
+    }
+
+    /// Finishes the current chunk and starts a new one, reopening the code block if needed
+    fn flush(&mut self) {
+        let mut chunk = std::mem::take(&mut self.current);
+        self.current_length = 0;
+        if self.has_content {
+            chunk.truncate(chunk.trim_end_matches('\n').len());
+            if self.open_fence.is_some() {
+                chunk += "\n```";
+            }
+            self.chunks.push(chunk);
+        }
+        self.has_content = false;
+
+        if let Some(open_fence) = self.open_fence.clone() {
+            self.push_str(&open_fence);
+            self.push_str("\n");
+        }
+    }
+
+    /// Returns how many characters fit in the current chunk if the text ends up in a code block
+    fn available(&self, in_code_block: bool) -> usize {
+        let reserved = if in_code_block {
+            CLOSING_FENCE_LENGTH
+        } else {
+            0
+        };
+        self.max_length
+            .saturating_sub(self.current_length + reserved)
+    }
+
+    /// Returns the code block state after `line`, given the state before it
+    fn fence_after(open_fence: &Option<String>, line: &str) -> Option<String> {
+        // Code blocks opened and closed on the same line don't change anything
+        if line.matches("```").count() % 2 == 0 {
+            return open_fence.clone();
+        }
+        match open_fence {
+            Some(_) => None,
+            None => {
+                let language = line.rsplit("```").next().unwrap_or("").trim();
+                // Anything else than a language name after the fence is code
+                let is_language = language.len() <= MAX_LANGUAGE_LENGTH
+                    && language
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "+-#_.".contains(c));
+                if is_language {
+                    Some(format!("```{}", language))
+                } else {
+                    Some("```".to_owned())
+                }
+            }
+        }
+    }
+
+    /// Adds a line, including its trailing newline, splitting it if it doesn't fit in a chunk
+    fn push_line(&mut self, line: &str) {
+        let fence_after = Self::fence_after(&self.open_fence, line);
+        // The trailing newline is dropped if the chunk ends after this line
+        let length = |text: &str| text.trim_end_matches('\n').chars().count();
+
+        // A line closing the code block needs no additional closing fence
+        if length(line) > self.available(fence_after.is_some()) && self.has_content {
+            self.flush();
+        }
+
+        let mut rest = line;
+        let in_code_block = self.open_fence.is_some() || fence_after.is_some();
+        while length(rest) > self.available(fence_after.is_some()) {
+            // At least one character per chunk, even if the fence alone exceeds the limit
+            let available = self.available(in_code_block).max(1);
+            let split_at = rest
+                .char_indices()
+                .nth(available)
+                .map_or(rest.len(), |(i, _)| i);
+            let (piece, remainder) = rest.split_at(split_at);
+            self.push_str(piece);
+            self.has_content |= !piece.trim().is_empty();
+            self.flush();
+            rest = remainder;
+        }
+        self.push_str(rest);
+        // A chunk ending right after an opening fence would be an empty code block
+        let only_opens_code_block =
+            self.open_fence.is_none() && fence_after.as_deref() == Some(rest.trim());
+        self.has_content |= !only_opens_code_block && !rest.trim().is_empty();
+        self.open_fence = fence_after;
+    }
+
+    /// Finishes the last chunk and returns all chunks
+    fn finish(mut self) -> Vec<String> {
+        // An unterminated code block stays unterminated
+        self.open_fence = None;
+        self.flush();
+        self.chunks
+    }
+}
+
+/// Splits the text into chunks of at most [`ChunkOptions::max_length`] characters
+fn chunk_text(text: &str, options: &ChunkOptions) -> Vec<String> {
+    let text = match &options.code_block {
+        Some(language) => Cow::Owned(format!("```{}\n{}\n```", language, text)),
+        None => Cow::Borrowed(text),
+    };
+
+    let mut chunker = Chunker {
+        max_length: options.max_length,
+        chunks: Vec::new(),
+        current: String::new(),
+        current_length: 0,
+        has_content: false,
+        open_fence: None,
+    };
+    for line in text.split_inclusive('\n') {
+        chunker.push_line(line);
+    }
+    chunker.finish()
+}
+
+/// Splits arbitrarily long text into replies that fit into a Discord message each
+///
+/// Chunks are split at line breaks where possible. Lines too long for a single chunk are split
+/// mid-line. Code blocks spanning multiple chunks are closed at the end of each chunk and reopened
+/// with the same language at the start of the next one, so that formatting is preserved.
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: lumi::Context<'_, (), ()>) -> Result<(), lumi::serenity_prelude::Error> {
+/// # let log = String::new();
+/// let options = lumi::ChunkOptions {
+///     code_block: Some("".into()),
+///     ..Default::default()
+/// };
+/// for reply in lumi::chunk_message(&log, &options) {
+///     ctx.send(reply).await?;
+/// }
+/// # Ok(()) }
+/// ```
+pub fn chunk_message(text: &str, options: &ChunkOptions) -> Vec<crate::CreateReply<'static>> {
+    chunk_text(text, options)
+        .into_iter()
+        .map(|chunk| crate::CreateReply::default().content(chunk))
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn test_chunk_text() {
+    let options = |max_length| ChunkOptions {
+        max_length,
+        ..Default::default()
+    };
+
+    assert_eq!(chunk_text("hello\nworld", &options(2000)), ["hello\nworld"]);
+    assert_eq!(chunk_text("hello\nworld", &options(8)), ["hello", "world"]);
+    assert_eq!(chunk_text("abcdefgh", &options(3)), ["abc", "def", "gh"]);
+    assert!(chunk_text("", &options(2000)).is_empty());
+
+    // Code blocks are closed and reopened at chunk boundaries
+    let text = "intro\n```rust\nlet a = 1;\nlet b = 2;\n```\noutro";
+    let chunks = chunk_text(text, &options(30));
+    assert_eq!(
+        chunks,
+        [
+            "intro\n```rust\nlet a = 1;\n```",
+            "```rust\nlet b = 2;\n```\noutro"
+        ]
+    );
+    assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 30));
+
+    // Wrapping in a code block
+    let options = ChunkOptions {
+        max_length: 12,
+        code_block: Some("".into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        chunk_text("aaaa\nbbbb", &options),
+        ["```\naaaa\n```", "```\nbbbb\n```"]
+    );
+}
@@ -0,0 +1,84 @@
+//! The [`IntoCreateReply`] trait, for commands which return their response
+
+use crate::serenity_prelude as serenity;
+
+use super::CreateReply;
+
+/// Types that a command can return to have the framework send them as response
+///
+/// Commands generated by [`crate::command`] may return `Result<R, E>` for any `R` implementing
+/// this trait instead of `Result<(), E>`. After the command body completes successfully, the
+/// returned value is sent like with [`crate::Context::send`]. This requires
+/// `E: From<serenity::Error>` to report failures to send the response.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// /// Says hello
+/// #[lumi::command(slash_command, prefix_command)]
+/// pub async fn hello(ctx: Context<'_>) -> Result<String, Error> {
+///     Ok(format!("Hello, {}!", ctx.author().name))
+/// }
+/// ```
+///
+/// The macro detects this by looking at the spelled out return type, so type aliases like
+/// `CommandResult<String>` are not supported.
+pub trait IntoCreateReply<'a> {
+    /// Converts the value into the reply to send, or `None` to send nothing
+    fn into_create_reply(self) -> Option<CreateReply<'a>>;
+}
+
+impl<'a> IntoCreateReply<'a> for () {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        None
+    }
+}
+
+impl<'a> IntoCreateReply<'a> for CreateReply<'a> {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        Some(self)
+    }
+}
+
+impl<'a> IntoCreateReply<'a> for String {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        Some(CreateReply::default().content(self))
+    }
+}
+
+impl<'a> IntoCreateReply<'a> for &'a str {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        Some(CreateReply::default().content(self))
+    }
+}
+
+impl<'a> IntoCreateReply<'a> for serenity::CreateEmbed<'a> {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        Some(CreateReply::default().embed(self))
+    }
+}
+
+impl<'a> IntoCreateReply<'a> for serenity::CreateAttachment<'a> {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        Some(CreateReply::default().attachment(self))
+    }
+}
+
+/// Message content with a file attached
+impl<'a> IntoCreateReply<'a> for (String, serenity::CreateAttachment<'a>) {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        let (content, attachment) = self;
+        Some(
+            CreateReply::default()
+                .content(content)
+                .attachment(attachment),
+        )
+    }
+}
+
+/// Sends nothing if `None`
+impl<'a, R: IntoCreateReply<'a>> IntoCreateReply<'a> for Option<R> {
+    fn into_create_reply(self) -> Option<CreateReply<'a>> {
+        self.and_then(IntoCreateReply::into_create_reply)
+    }
+}
@@ -3,6 +3,12 @@
 mod builder;
 pub use builder::*;
 
+mod chunk;
+pub use chunk::*;
+
+mod into_reply;
+pub use into_reply::*;
+
 mod send_reply;
 pub use send_reply::*;
 
@@ -109,7 +115,10 @@ impl ReplyHandle<'_> {
                         // intuitive behavior). Notably, setting the builder to default doesn't
                         // mean the entire message is reset to empty: Discord only updates parts
                         // of the message that have had a modification specified
-                        reply.to_prefix_edit(serenity::EditMessage::new())
+                        reply.to_prefix_edit_with_attachments(
+                            serenity::EditMessage::new(),
+                            &msg.attachments,
+                        )
                     })
                     .await?;
             }
@@ -161,4 +170,107 @@ impl ReplyHandle<'_> {
         }
         Ok(())
     }
+
+    /// Deletes this message after the given delay, in a background task
+    ///
+    /// Returns immediately. Errors while deleting, e.g. because the message was already deleted,
+    /// are logged.
+    pub fn delete_after<T: Send + Sync + 'static, E>(
+        &self,
+        ctx: crate::Context<'_, T, E>,
+        delay: std::time::Duration,
+    ) {
+        /// Owned version of the message this handle points to
+        enum Target {
+            Message(Box<serenity::Message>),
+            Response(serenity::CommandInteraction),
+            Followup(serenity::CommandInteraction, serenity::MessageId),
+        }
+
+        let http = ctx.serenity_context().http.clone();
+        let target = match &self.0 {
+            ReplyHandleInner::Prefix(msg) => Target::Message(msg.clone()),
+            ReplyHandleInner::Application {
+                http: _,
+                interaction,
+                followup: None,
+            } => Target::Response((*interaction).clone()),
+            ReplyHandleInner::Application {
+                http: _,
+                interaction,
+                followup: Some(followup),
+            } => Target::Followup((*interaction).clone(), followup.id),
+            ReplyHandleInner::Autocomplete => panic!("delete is a no-op in autocomplete context"),
+        };
+        delete_later(delay, async move {
+            match target {
+                Target::Message(msg) => msg.delete(&http, None).await,
+                Target::Response(interaction) => interaction.delete_response(&http).await,
+                Target::Followup(interaction, followup_id) => {
+                    interaction.delete_followup(&http, followup_id).await
+                }
+            }
+        });
+    }
+}
+
+/// Runs `delete` after `delay` in a background task, logging its error. See
+/// [`ReplyHandle::delete_after`]
+fn delete_later(
+    delay: std::time::Duration,
+    delete: impl std::future::Future<Output = Result<(), serenity::Error>> + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if let Err(e) = delete.await {
+            tracing::warn!("failed to delete reply after {:?}: {}", delay, e);
+        }
+    })
+}
+
+/// Whether prefix command responses are sent to the invoking user's DMs instead if the bot can't
+/// respond in the invocation channel. See [`crate::FrameworkOptions::dm_fallback`].
+///
+/// Only affects prefix commands: interaction responses don't need channel permissions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DmFallback {
+    /// Never DM the user
+    #[default]
+    Disabled,
+    /// DM the [`crate::FrameworkError::MissingBotPermissions`] error message of
+    /// [`crate::builtins::on_error`] if the bot can't send messages in the channel
+    PermissionErrors,
+    /// DM any response sent via [`crate::send_reply`] that fails because of missing permissions,
+    /// including the error messages of [`crate::builtins::on_error`]
+    Always,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+#[cfg(test)]
+#[test]
+fn test_delete_later() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let deleted = std::sync::Arc::new(AtomicBool::new(false));
+        let task = delete_later(std::time::Duration::from_millis(20), {
+            let deleted = deleted.clone();
+            async move {
+                deleted.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+
+        // Returns before the delay elapsed
+        tokio::task::yield_now().await;
+        assert!(!deleted.load(Ordering::SeqCst));
+
+        task.await.unwrap();
+        assert!(deleted.load(Ordering::SeqCst));
+    });
 }
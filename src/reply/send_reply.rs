@@ -29,12 +29,55 @@ pub async fn send_reply<'ctx, T: Send + Sync + 'static, E>(
     ctx: crate::Context<'ctx, T, E>,
     builder: crate::CreateReply<'_>,
 ) -> Result<crate::ReplyHandle<'ctx>, serenity::Error> {
-    Ok(match ctx {
-        crate::Context::Prefix(ctx) => super::ReplyHandle(super::ReplyHandleInner::Prefix(
-            crate::send_prefix_reply(ctx, builder).await?,
-        )),
-        crate::Context::Application(ctx) => crate::send_application_reply(ctx, builder).await?,
+    let handle = crate::rate_limit::track_rate_limits(ctx, async {
+        Ok::<_, serenity::Error>(match ctx {
+            crate::Context::Prefix(ctx) => super::ReplyHandle(super::ReplyHandleInner::Prefix(
+                crate::send_prefix_reply(ctx, builder).await?,
+            )),
+            crate::Context::Application(ctx) => crate::send_application_reply(ctx, builder).await?,
+        })
     })
+    .await?;
+    run_after_reply(ctx, &handle).await;
+    Ok(handle)
+}
+
+/// Like [`send_reply`], but always sends a new message, even if [`crate::Command::reuse_response`]
+/// is set and the command already responded.
+///
+/// In prefix context, the new message isn't registered in the edit tracker: subsequent
+/// [`send_reply`] calls still edit the original response, and the followup isn't deleted together
+/// with the invocation message. In application context, this is equivalent to [`send_reply`].
+///
+/// Note: panics when called in an autocomplete context!
+pub async fn send_followup<'ctx, T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'ctx, T, E>,
+    builder: crate::CreateReply<'_>,
+) -> Result<crate::ReplyHandle<'ctx>, serenity::Error> {
+    let handle = crate::rate_limit::track_rate_limits(ctx, async {
+        Ok::<_, serenity::Error>(match ctx {
+            crate::Context::Prefix(ctx) => super::ReplyHandle(super::ReplyHandleInner::Prefix(
+                Box::new(send_prefix_message(ctx, builder).await?),
+            )),
+            crate::Context::Application(ctx) => crate::send_application_reply(ctx, builder).await?,
+        })
+    })
+    .await?;
+    run_after_reply(ctx, &handle).await;
+    Ok(handle)
+}
+
+/// Invokes [`crate::FrameworkOptions::after_reply`], if set, on a freshly sent reply
+async fn run_after_reply<'ctx, T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'ctx, T, E>,
+    handle: &crate::ReplyHandle<'ctx>,
+) {
+    if let super::ReplyHandleInner::Autocomplete = handle.0 {
+        return;
+    }
+    if let Some(after_reply) = ctx.framework().options().after_reply {
+        after_reply(ctx, handle).await;
+    }
 }
 
 /// Shorthand of [`send_reply`] for text-only messages
@@ -65,7 +108,7 @@ pub async fn send_application_reply<'ctx, T: Send + Sync + 'static, E>(
 
     let has_sent_initial_response = ctx
         .has_sent_initial_response
-        .load(std::sync::atomic::Ordering::SeqCst);
+        .load(std::sync::atomic::Ordering::Relaxed);
 
     let followup = if has_sent_initial_response {
         Some(Box::new({
@@ -75,6 +118,17 @@ pub async fn send_application_reply<'ctx, T: Send + Sync + 'static, E>(
             ctx.interaction.create_followup(ctx.http(), builder).await?
         }))
     } else {
+        let mut builder = builder;
+        if ctx.command.rerun_button {
+            ctx.framework.options().rerun_tracker.track(ctx.interaction);
+            let custom_id = format!("{}{}", crate::RERUN_BUTTON_PREFIX, ctx.interaction.id);
+            builder = builder.push_framework_button(
+                serenity::CreateButton::new(custom_id)
+                    .label("Re-run")
+                    .style(serenity::ButtonStyle::Secondary)
+                    .emoji('🔁'),
+            );
+        }
         let builder =
             builder.to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
 
@@ -85,7 +139,7 @@ pub async fn send_application_reply<'ctx, T: Send + Sync + 'static, E>(
             )
             .await?;
         ctx.has_sent_initial_response
-            .store(true, std::sync::atomic::Ordering::SeqCst);
+            .store(true, std::sync::atomic::Ordering::Relaxed);
 
         None
     };
@@ -102,27 +156,19 @@ pub async fn send_prefix_reply<T: Send + Sync + 'static, E>(
     ctx: crate::PrefixContext<'_, T, E>,
     builder: crate::CreateReply<'_>,
 ) -> Result<Box<serenity::Message>, serenity::Error> {
-    let builder = ctx.reply_builder(builder);
+    let edit_tracker = ctx.framework.options().prefix_options.edit_tracker.as_ref();
 
-    // This must only return None when we _actually_ want to reuse the existing response! There are
+    // This must only be None when we _actually_ want to reuse the existing response! There are
     // no checks later
-    let lock_edit_tracker = || {
-        if let Some(edit_tracker) = &ctx.framework.options().prefix_options.edit_tracker {
-            return Some(edit_tracker.write().unwrap());
-        }
-        None
-    };
-
     let existing_response = if ctx.command.reuse_response {
-        lock_edit_tracker()
-            .as_mut()
-            .and_then(|t| t.find_bot_response(ctx.msg.id))
-            .cloned()
+        edit_tracker.and_then(|t| t.find_bot_response(ctx.msg.id))
     } else {
         None
     };
 
     Ok(Box::new(if let Some(mut response) = existing_response {
+        let builder = ctx.reply_builder(builder);
+        let existing_attachments = response.attachments.clone();
         response
             .edit(ctx.serenity_context(), {
                 // Reset the message. We don't want leftovers of the previous message (e.g. user
@@ -135,29 +181,82 @@ pub async fn send_prefix_reply<T: Send + Sync + 'static, E>(
                     .components(Vec::new())
                     .remove_all_attachments();
 
-                builder.to_prefix_edit(b)
+                // Attachments are kept or removed according to the reply's ExistingAttachments
+                builder.to_prefix_edit_with_attachments(b, &existing_attachments)
             })
             .await?;
 
         // If the entry still exists after the await, update it to the new contents
         // We don't check ctx.command.reuse_response because it's true anyways in this branch
-        if let Some(mut edit_tracker) = lock_edit_tracker() {
+        if let Some(edit_tracker) = edit_tracker {
             edit_tracker.set_bot_response(ctx.msg, response.clone(), ctx.command.track_deletion);
         }
 
         response
     } else {
-        let new_response = ctx
-            .msg
-            .channel_id
-            .send_message(ctx.http(), builder.to_prefix(ctx.msg.into()))
-            .await?;
+        let new_response = send_prefix_message(ctx, builder).await?;
         // We don't check ctx.command.reuse_response because we need to store bot responses for
         // track_deletion too
-        if let Some(track_edits) = &mut lock_edit_tracker() {
-            track_edits.set_bot_response(ctx.msg, new_response.clone(), ctx.command.track_deletion);
+        if let Some(edit_tracker) = edit_tracker {
+            edit_tracker.set_bot_response(
+                ctx.msg,
+                new_response.clone(),
+                ctx.command.track_deletion,
+            );
         }
 
         new_response
     }))
 }
+
+/// Sends a new message in response to a prefix command, without touching the edit tracker.
+///
+/// Falls back to a DM according to [`crate::FrameworkOptions::dm_fallback`].
+async fn send_prefix_message<T: Send + Sync + 'static, E>(
+    ctx: crate::PrefixContext<'_, T, E>,
+    builder: crate::CreateReply<'_>,
+) -> Result<serenity::Message, serenity::Error> {
+    // Only clone if needed, attachments may be large
+    let dm_fallback_builder = (ctx.framework.options().dm_fallback == crate::DmFallback::Always
+        && ctx.msg.guild_id.is_some())
+    .then(|| builder.clone());
+    let builder = ctx.reply_builder(builder);
+
+    let result = ctx
+        .msg
+        .channel_id
+        .send_message(ctx.http(), builder.to_prefix(ctx.msg.into()))
+        .await;
+    match (result, dm_fallback_builder) {
+        (Err(e), Some(builder)) if is_missing_permissions(&e) => {
+            tracing::debug!("Can't respond in channel, falling back to DM: {}", e);
+            send_prefix_reply_as_dm(ctx, builder).await
+        }
+        (result, _) => result,
+    }
+}
+
+/// Whether sending a message failed because the bot lacks permissions in the channel
+pub(crate) fn is_missing_permissions(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response))
+            if response.status_code.as_u16() == 403
+    )
+}
+
+/// Sends a prefix command response to the invoking user's DMs instead of the invocation channel.
+///
+/// Used for [`crate::FrameworkOptions::dm_fallback`]. The response is never formatted as a reply,
+/// since the invocation message is in another channel.
+pub(crate) async fn send_prefix_reply_as_dm<T: Send + Sync + 'static, E>(
+    ctx: crate::PrefixContext<'_, T, E>,
+    builder: crate::CreateReply<'_>,
+) -> Result<serenity::Message, serenity::Error> {
+    let builder = ctx.reply_builder(builder).reply(false);
+    ctx.msg
+        .author
+        .id
+        .direct_message(ctx.http(), builder.to_prefix(ctx.msg.into()))
+        .await
+}
@@ -0,0 +1,532 @@
+//! Runs one-shot and recurring jobs in the background, e.g. reminders or temporary bans
+//!
+//! Jobs are plain data (a handler name and a string payload), so that they can be persisted with
+//! a [`JobStorage`] and picked up again after a restart.
+
+use crate::{BoxFuture, serenity_prelude as serenity};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Error type used by job handlers and [`JobStorage`]
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A handler for jobs of a given [`Job::kind`], registered with [`SchedulerBuilder::handler`]
+pub type JobHandler<T> = fn(JobContext<T>) -> BoxFuture<'static, Result<(), BoxedError>>;
+
+/// A job to be run by a [`Scheduler`]
+#[derive(Clone, Debug)]
+pub struct Job {
+    /// Name of the [`JobHandler`] that runs this job
+    pub kind: String,
+    /// Arbitrary data passed to the handler, e.g. a serialized reminder
+    pub payload: String,
+    /// If set, the job is rescheduled with this interval after every run
+    pub interval: Option<Duration>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Job {
+    /// Creates a one-shot job which is run by the handler registered for `kind`
+    pub fn new(kind: impl Into<String>, payload: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            payload: payload.into(),
+            interval: None,
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Makes this job recurring: after every run, it's run again after `interval`
+    pub fn every(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+}
+
+/// A [`Job`] that has been scheduled, as stored in a [`JobStorage`]
+#[derive(Clone, Debug)]
+pub struct ScheduledJob {
+    /// Unique ID of this job, returned by [`Scheduler::schedule_in`]
+    pub id: u64,
+    /// The job itself
+    pub job: Job,
+    /// When the job runs next
+    pub run_at: SystemTime,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl ScheduledJob {
+    /// Creates a scheduled job, e.g. when loading it in [`JobStorage::load`]
+    pub fn new(id: u64, job: Job, run_at: SystemTime) -> Self {
+        Self {
+            id,
+            job,
+            run_at,
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Passed to [`JobHandler`]s. Unlike [`crate::Context`], this doesn't refer to an invocation, since
+/// the invocation that scheduled the job may be long gone (or from before a restart).
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct JobContext<T> {
+    /// HTTP client of the bot
+    #[derivative(Debug = "ignore")]
+    pub http: Arc<serenity::Http>,
+    /// Your custom user data, see [`crate::Context::data`]
+    #[derivative(Debug = "ignore")]
+    pub data: Arc<T>,
+    /// The job being run
+    pub job: ScheduledJob,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Persists scheduled jobs, so that they survive restarts.
+///
+/// Set with [`SchedulerBuilder::storage`]. Stored jobs are loaded and scheduled again when the
+/// bot becomes ready, see [`Scheduler::restore`].
+#[async_trait::async_trait]
+pub trait JobStorage: Send + Sync {
+    /// Inserts a job or replaces the job with the same [`ScheduledJob::id`]
+    async fn save(&self, job: &ScheduledJob) -> Result<(), BoxedError>;
+    /// Removes a job because it finished or was cancelled
+    async fn remove(&self, id: u64) -> Result<(), BoxedError>;
+    /// Returns all stored jobs
+    async fn load(&self) -> Result<Vec<ScheduledJob>, BoxedError>;
+}
+
+/// Error that occurs when scheduling a job
+#[derive(Debug)]
+pub enum ScheduleError {
+    /// [`crate::FrameworkOptions::scheduler`] is not set
+    NoScheduler,
+    /// No handler is registered for the job's [`Job::kind`]
+    UnknownJobKind {
+        /// The job kind without handler
+        kind: String,
+    },
+    /// The job couldn't be saved to the [`JobStorage`]
+    Storage(BoxedError),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoScheduler => f.write_str("no scheduler configured in the framework options"),
+            Self::UnknownJobKind { kind } => write!(f, "no handler registered for job `{kind}`"),
+            Self::Storage(e) => write!(f, "failed to store job: {e}"),
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Storage(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+/// State shared between all clones of a [`Scheduler`] and its running jobs
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+struct SchedulerInner<T> {
+    /// Job handlers by [`Job::kind`]
+    #[derivative(Debug = "ignore")]
+    handlers: std::collections::HashMap<String, JobHandler<T>>,
+    /// Where jobs are persisted, if anywhere
+    #[derivative(Debug = "ignore")]
+    storage: Option<Arc<dyn JobStorage>>,
+    /// Pending jobs and the tasks running them, by job ID
+    tasks: parking_lot::Mutex<
+        std::collections::HashMap<u64, (ScheduledJob, tokio::task::AbortHandle)>,
+    >,
+    /// ID of the next scheduled job
+    next_id: AtomicU64,
+    /// Whether [`Scheduler::restore`] has already run
+    restored: AtomicBool,
+}
+
+/// Runs [`Job`]s after a delay, optionally recurring and optionally persisted.
+///
+/// Create with [`Self::builder`] and store in [`crate::FrameworkOptions::scheduler`], then
+/// schedule jobs from commands with [`crate::Context::schedule_in`]:
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// fn remind(ctx: lumi::JobContext<()>) -> lumi::BoxFuture<'static, Result<(), Error>> {
+///     Box::pin(async move {
+///         let (channel_id, text) = ctx.job.payload.split_once(' ').ok_or("invalid payload")?;
+///         let channel_id = lumi::serenity_prelude::GenericChannelId::new(channel_id.parse()?);
+///         let message = lumi::serenity_prelude::CreateMessage::new().content(text);
+///         channel_id.send_message(&ctx.http, message).await?;
+///         Ok(())
+///     })
+/// }
+///
+/// #[lumi::command(slash_command)]
+/// async fn remindme(ctx: Context<'_>, minutes: u64, text: String) -> Result<(), Error> {
+///     let payload = format!("{} {}", ctx.channel_id(), text);
+///     let delay = std::time::Duration::from_secs(minutes * 60);
+///     ctx.schedule_in(delay, lumi::Job::new("remind", payload)).await?;
+///     ctx.say("I'll remind you").await?;
+///     Ok(())
+/// }
+///
+/// lumi::FrameworkOptions {
+///     scheduler: Some(lumi::Scheduler::builder().handler("remind", remind).build()),
+///     commands: vec![remindme().into()],
+///     ..Default::default()
+/// };
+/// ```
+///
+/// Cloning is cheap: all clones share the same jobs.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""), Clone(bound = ""))]
+pub struct Scheduler<T> {
+    /// Shared state
+    inner: Arc<SchedulerInner<T>>,
+}
+
+impl<T> Scheduler<T> {
+    /// Creates a builder to register job handlers and storage
+    pub fn builder() -> SchedulerBuilder<T> {
+        SchedulerBuilder::new()
+    }
+
+    /// Returns all jobs that haven't finished yet
+    pub fn scheduled_jobs(&self) -> Vec<ScheduledJob> {
+        let tasks = self.inner.tasks.lock();
+        tasks.values().map(|(job, _)| job.clone()).collect()
+    }
+
+    /// Cancels a scheduled job and removes it from the storage.
+    ///
+    /// Returns false if there's no pending job with this ID.
+    pub async fn cancel(&self, id: u64) -> bool {
+        let Some((_, task)) = self.inner.tasks.lock().remove(&id) else {
+            return false;
+        };
+        task.abort();
+        self.inner.remove_from_storage(id).await;
+        true
+    }
+}
+
+impl<T> SchedulerInner<T> {
+    /// Removes a job from the storage, if any, logging failures
+    async fn remove_from_storage(&self, id: u64) {
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.remove(id).await {
+                tracing::warn!("Failed to remove job {} from storage: {}", id, e);
+            }
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Scheduler<T> {
+    /// Schedules a job to run after `delay`. Returns the job ID, which can be passed to
+    /// [`Self::cancel`].
+    ///
+    /// If a [`JobStorage`] is set, the job is saved before it's scheduled.
+    pub async fn schedule_in(
+        &self,
+        http: Arc<serenity::Http>,
+        data: Arc<T>,
+        delay: Duration,
+        job: Job,
+    ) -> Result<u64, ScheduleError> {
+        let handler = self.handler(&job.kind)?;
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let scheduled = ScheduledJob::new(id, job, SystemTime::now() + delay);
+
+        if let Some(storage) = &self.inner.storage {
+            storage
+                .save(&scheduled)
+                .await
+                .map_err(ScheduleError::Storage)?;
+        }
+        self.spawn(http, data, handler, scheduled);
+        Ok(id)
+    }
+
+    /// Loads all jobs from the [`JobStorage`] and schedules them. Jobs that were due while the bot
+    /// was offline run immediately.
+    ///
+    /// Called automatically by the framework in the background when the bot becomes ready. Only
+    /// the first successful call has an effect, so reconnects don't schedule jobs twice, but a
+    /// failed load is retried on the next call. Returns the number of restored jobs.
+    pub async fn restore(
+        &self,
+        http: Arc<serenity::Http>,
+        data: Arc<T>,
+    ) -> Result<usize, BoxedError> {
+        self.load_and_restore(|handler, scheduled| {
+            self.spawn(http.clone(), data.clone(), handler, scheduled);
+        })
+        .await
+    }
+
+    /// Loads the stored jobs and passes them to [`Self::restore_jobs`], unless that already
+    /// happened
+    async fn load_and_restore(
+        &self,
+        spawn: impl FnMut(JobHandler<T>, ScheduledJob),
+    ) -> Result<usize, BoxedError> {
+        let Some(storage) = &self.inner.storage else {
+            return Ok(0);
+        };
+        if self.inner.restored.swap(true, Ordering::Relaxed) {
+            return Ok(0);
+        }
+
+        match storage.load().await {
+            Ok(jobs) => Ok(self.restore_jobs(jobs, spawn)),
+            Err(e) => {
+                self.inner.restored.store(false, Ordering::Relaxed);
+                Err(e)
+            }
+        }
+    }
+
+    /// Passes the loaded jobs which aren't scheduled yet to `spawn`, along with their handlers.
+    /// Returns how many were passed
+    fn restore_jobs(
+        &self,
+        jobs: Vec<ScheduledJob>,
+        mut spawn: impl FnMut(JobHandler<T>, ScheduledJob),
+    ) -> usize {
+        let mut restored = 0;
+        for scheduled in jobs {
+            self.inner
+                .next_id
+                .fetch_max(scheduled.id + 1, Ordering::Relaxed);
+            if self.inner.tasks.lock().contains_key(&scheduled.id) {
+                continue;
+            }
+            match self.handler(&scheduled.job.kind) {
+                Ok(handler) => {
+                    spawn(handler, scheduled);
+                    restored += 1;
+                }
+                Err(e) => tracing::warn!("Skipping stored job {}: {}", scheduled.id, e),
+            }
+        }
+        restored
+    }
+
+    /// Looks up the handler for the given job kind
+    fn handler(&self, kind: &str) -> Result<JobHandler<T>, ScheduleError> {
+        self.inner
+            .handlers
+            .get(kind)
+            .copied()
+            .ok_or_else(|| ScheduleError::UnknownJobKind {
+                kind: kind.to_owned(),
+            })
+    }
+
+    /// Spawns the task that runs the job and keeps track of it
+    fn spawn(
+        &self,
+        http: Arc<serenity::Http>,
+        data: Arc<T>,
+        handler: JobHandler<T>,
+        scheduled: ScheduledJob,
+    ) {
+        // Lock before spawning so that a job finishing instantly can't remove itself before
+        // it's inserted
+        let mut tasks = self.inner.tasks.lock();
+        let task = tokio::spawn(run_job(
+            self.inner.clone(),
+            http,
+            data,
+            handler,
+            scheduled.clone(),
+        ));
+        tasks.insert(scheduled.id, (scheduled, task.abort_handle()));
+    }
+}
+
+/// Runs a job at its scheduled time, and again after every interval if it's recurring
+async fn run_job<T>(
+    inner: Arc<SchedulerInner<T>>,
+    http: Arc<serenity::Http>,
+    data: Arc<T>,
+    handler: JobHandler<T>,
+    mut scheduled: ScheduledJob,
+) {
+    loop {
+        let delay = scheduled
+            .run_at
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+        tokio::time::sleep(delay).await;
+
+        let ctx = JobContext {
+            http: http.clone(),
+            data: data.clone(),
+            job: scheduled.clone(),
+            __non_exhaustive: (),
+        };
+        if let Err(e) = handler(ctx).await {
+            tracing::warn!(
+                "Job {} ({}) failed: {}",
+                scheduled.id,
+                scheduled.job.kind,
+                e
+            );
+        }
+
+        let Some(interval) = scheduled.job.interval else {
+            break;
+        };
+        // Don't catch up on missed runs if the handler took longer than the interval
+        scheduled.run_at = std::cmp::max(scheduled.run_at + interval, SystemTime::now());
+        if let Some((job, _)) = inner.tasks.lock().get_mut(&scheduled.id) {
+            job.run_at = scheduled.run_at;
+        }
+        if let Some(storage) = &inner.storage {
+            if let Err(e) = storage.save(&scheduled).await {
+                tracing::warn!("Failed to update job {} in storage: {}", scheduled.id, e);
+            }
+        }
+    }
+
+    inner.tasks.lock().remove(&scheduled.id);
+    inner.remove_from_storage(scheduled.id).await;
+}
+
+/// Builds a [`Scheduler`]
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct SchedulerBuilder<T> {
+    /// See [`SchedulerInner::handlers`]
+    #[derivative(Debug = "ignore")]
+    handlers: std::collections::HashMap<String, JobHandler<T>>,
+    /// See [`SchedulerInner::storage`]
+    #[derivative(Debug = "ignore")]
+    storage: Option<Arc<dyn JobStorage>>,
+}
+
+impl<T> SchedulerBuilder<T> {
+    /// Creates a builder without any handlers or storage
+    pub fn new() -> Self {
+        Self {
+            handlers: std::collections::HashMap::new(),
+            storage: None,
+        }
+    }
+
+    /// Registers the handler for jobs with the given [`Job::kind`]
+    pub fn handler(mut self, kind: impl Into<String>, handler: JobHandler<T>) -> Self {
+        self.handlers.insert(kind.into(), handler);
+        self
+    }
+
+    /// Persists jobs in the given storage. Without a storage, pending jobs are lost on restart.
+    pub fn storage(mut self, storage: Arc<dyn JobStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Builds the [`Scheduler`]
+    pub fn build(self) -> Scheduler<T> {
+        // Seeded with the current time so that IDs of jobs created before a restart aren't reused,
+        // even before the storage is loaded
+        let first_id = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_micros() as u64);
+
+        Scheduler {
+            inner: Arc::new(SchedulerInner {
+                handlers: self.handlers,
+                storage: self.storage,
+                tasks: parking_lot::Mutex::new(std::collections::HashMap::new()),
+                next_id: AtomicU64::new(first_id),
+                restored: AtomicBool::new(false),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_restore_jobs() {
+    let scheduler = Scheduler::<()>::builder()
+        .handler("remind", |_| Box::pin(async { Ok(()) }))
+        .build();
+    let first_id = scheduler.inner.next_id.load(Ordering::Relaxed);
+    let job = |id, kind| ScheduledJob::new(id, Job::new(kind, ""), SystemTime::now());
+
+    let mut spawned = Vec::new();
+    let restored = scheduler.restore_jobs(
+        vec![
+            job(first_id + 5, "remind"),
+            job(1, "unknown"),
+            job(2, "remind"),
+        ],
+        |_, scheduled| spawned.push(scheduled.id),
+    );
+    assert_eq!(restored, 2);
+    assert_eq!(spawned, [first_id + 5, 2]);
+    // New jobs don't reuse the IDs of restored ones
+    assert_eq!(
+        scheduler.inner.next_id.load(Ordering::Relaxed),
+        first_id + 6
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_restore_after_failed_load() {
+    /// Fails the first load, then returns a single job
+    struct FlakyStorage(AtomicBool);
+    #[async_trait::async_trait]
+    impl JobStorage for FlakyStorage {
+        async fn save(&self, _: &ScheduledJob) -> Result<(), BoxedError> {
+            Ok(())
+        }
+        async fn remove(&self, _: u64) -> Result<(), BoxedError> {
+            Ok(())
+        }
+        async fn load(&self) -> Result<Vec<ScheduledJob>, BoxedError> {
+            if !self.0.swap(true, Ordering::Relaxed) {
+                return Err("database unavailable".into());
+            }
+            let job = Job::new("remind", "");
+            Ok(vec![ScheduledJob::new(1, job, SystemTime::now())])
+        }
+    }
+
+    let scheduler = Scheduler::<()>::builder()
+        .handler("remind", |_| Box::pin(async { Ok(()) }))
+        .storage(Arc::new(FlakyStorage(Default::default())))
+        .build();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let mut spawned = Vec::new();
+        let mut spawn = |_: JobHandler<()>, scheduled: ScheduledJob| spawned.push(scheduled.id);
+        assert!(scheduler.load_and_restore(&mut spawn).await.is_err());
+        assert_eq!(scheduler.load_and_restore(&mut spawn).await.unwrap(), 1);
+        // Later calls, e.g. on reconnects, don't restore the jobs again
+        assert_eq!(scheduler.load_and_restore(&mut spawn).await.unwrap(), 0);
+        assert_eq!(spawned, [1]);
+    });
+}
@@ -126,9 +126,21 @@ macro_rules! _parse_slash {
     };
 
     // Extract Option<T>
+    //
+    // If Discord sends a value that doesn't fit the parameter type, the command registration is
+    // outdated. That fails instead of treating the value as missing, so that the user is told to
+    // wait for the commands to be registered again rather than the command silently ignoring input
     ($ctx:ident, $interaction:ident, $args:ident => $name:literal: Option<$type:ty $(,)*>) => {
         if let Some(arg) = $args.iter().find(|arg| arg.name == $name) {
-            Some(<$type as $crate::SlashArgument>::extract($ctx, $interaction, &arg.value).await?)
+            match <$type as $crate::SlashArgument>::extract($ctx, $interaction, &arg.value).await {
+                Ok(value) => Some(value),
+                Err($crate::SlashArgError::CommandStructureMismatch { .. }) => {
+                    return Err($crate::SlashArgError::new_command_structure_mismatch(
+                        concat!("parameter `", $name, "` is outdated"),
+                    ));
+                }
+                Err(error) => return Err(error),
+            }
         } else {
             None
         }
@@ -149,10 +161,18 @@ macro_rules! _parse_slash {
             .unwrap_or(false)
     };
 
+    // Extract #[optional] T, which is the default value if omitted
+    ($ctx:ident, $interaction:ident, $args:ident => $name:literal: OPTIONAL $($type:tt)*) => {
+        $crate::_parse_slash!($ctx, $interaction, $args => $name: Option<$($type)*>)
+            .unwrap_or_default()
+    };
+
     // Extract T
     ($ctx:ident, $interaction:ident, $args:ident => $name:literal: $($type:tt)*) => {
         $crate::_parse_slash!($ctx, $interaction, $args => $name: Option<$($type)*>)
-            .ok_or($crate::SlashArgError::new_command_structure_mismatch("a required argument is missing"))?
+            .ok_or($crate::SlashArgError::new_command_structure_mismatch(
+                concat!("required parameter `", $name, "` is missing"),
+            ))?
     };
 }
 
@@ -163,6 +183,12 @@ Macro for extracting and parsing slash command arguments out of an array of
 An invocation of this macro is generated by `crate::command`, so you usually don't need this macro
 directly.
 
+Arguments are looked up by name, so options Discord sends for parameters that no longer exist are
+ignored. Missing required parameters and values that don't fit the parameter type, e.g. because
+the command registration is outdated, fail with
+[`crate::SlashArgError::CommandStructureMismatch`] naming the parameter, which
+[`crate::builtins::on_error`] turns into a notice that the commands need to be registered again.
+
 ```rust,no_run
 # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
 # use lumi::serenity_prelude as serenity;
@@ -25,6 +25,19 @@ pub trait SlashArgument: Sized {
     fn choices() -> CowVec<crate::CommandParameterChoice> {
         Cow::Borrowed(&[])
     }
+
+    /// Returns the autocomplete callback used for this parameter unless it has an explicit
+    /// `#[autocomplete]` callback, if any
+    ///
+    /// Used for [`crate::ChoiceParameter`]s with too many choices for Discord's choice list.
+    fn autocomplete_callback<T, E>() -> Option<
+        for<'a> fn(
+            crate::ApplicationContext<'a, T, E>,
+            &'a str,
+        ) -> crate::BoxFuture<'a, serenity::CreateAutocompleteResponse<'a>>,
+    > {
+        None
+    }
 }
 
 /// Converts a Command value via serenity's ArgumentConvert trait
@@ -185,3 +198,133 @@ impl_slash_argument!(serenity::GuildChannel, |ctx, inter, Channel(channel)| {
 });
 impl_slash_argument!(serenity::Role, |_, _, Role(role)| role.clone());
 impl_slash_argument!(serenity::RoleId, |_, _, Role(role)| role.id);
+
+/// Error thrown when a string option is neither a Discord ID nor a link containing one
+#[derive(Default, Debug)]
+pub struct InvalidSnowflake {
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for InvalidSnowflake {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected an ID or a Discord link")
+    }
+}
+impl std::error::Error for InvalidSnowflake {}
+
+/// Parses a Discord ID, or extracts it from a Discord link.
+///
+/// `links` lists the link kinds containing the ID as pairs of the first path segment and the
+/// position of the ID after it. For example, `("channels", 2)` extracts the message ID from
+/// `https://discord.com/channels/<guild>/<channel>/<message>`.
+fn parse_snowflake(input: &str, links: &[(&str, usize)]) -> Option<u64> {
+    let parse_id = |id: &str| id.parse().ok().filter(|&id| id != u64::MAX);
+
+    let input = input.trim();
+    if let Some(id) = parse_id(input) {
+        return Some(id);
+    }
+
+    // Links wrapped in <> don't embed, so users may paste them like that
+    let link = input.trim_start_matches('<').trim_end_matches('>');
+    let link = link.split(['?', '#']).next()?;
+    let link = link
+        .strip_prefix("https://")
+        .or_else(|| link.strip_prefix("http://"))
+        .unwrap_or(link);
+    let mut segments = link.split('/');
+    let host = segments.next()?;
+    if !["discord.com", "discordapp.com"]
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    {
+        return None;
+    }
+
+    let kind = segments.next()?;
+    let ids = segments.collect::<Vec<_>>();
+    links
+        .iter()
+        .filter(|(link_kind, _)| *link_kind == kind)
+        .find_map(|&(_, position)| parse_id(ids.get(position)?))
+}
+
+/// Implements `SlashArgument` for ID types, which are passed as string options containing the ID
+/// or a Discord link to the object
+macro_rules! impl_for_snowflake {
+    ($type:ty, [$( $link:expr ),*]) => {
+        #[async_trait::async_trait]
+        impl SlashArgument for $type {
+            async fn extract(
+                _: &serenity::Context,
+                _: &serenity::CommandInteraction,
+                value: &serenity::ResolvedValue<'_>,
+            ) -> Result<$type, SlashArgError> {
+                let string = match *value {
+                    serenity::ResolvedValue::String(str) => str,
+                    _ => {
+                        return Err(SlashArgError::CommandStructureMismatch {
+                            description: "expected string",
+                        });
+                    }
+                };
+                match parse_snowflake(string, &[$( $link ),*]) {
+                    Some(id) => Ok(<$type>::new(id)),
+                    None => Err(SlashArgError::Parse {
+                        error: InvalidSnowflake::default().into(),
+                        input: string.into(),
+                    }),
+                }
+            }
+
+            fn create(builder: serenity::CreateCommandOption<'_>) -> serenity::CreateCommandOption<'_> {
+                builder.kind(serenity::CommandOptionType::String)
+            }
+        }
+    };
+}
+
+impl_for_snowflake!(serenity::MessageId, [("channels", 2)]);
+impl_for_snowflake!(serenity::GuildId, [("channels", 0), ("events", 0)]);
+impl_for_snowflake!(serenity::ScheduledEventId, [("events", 1)]);
+impl_for_snowflake!(serenity::EmojiId, []);
+impl_for_snowflake!(serenity::StickerId, []);
+impl_for_snowflake!(serenity::ApplicationId, []);
+impl_for_snowflake!(serenity::CommandId, []);
+impl_for_snowflake!(serenity::WebhookId, []);
+
+#[cfg(test)]
+#[test]
+fn test_parse_snowflake() {
+    let message_link = &[("channels", 2)];
+    assert_eq!(parse_snowflake("1234", message_link), Some(1234));
+    assert_eq!(parse_snowflake(" 1234 ", &[]), Some(1234));
+    assert_eq!(
+        parse_snowflake("https://discord.com/channels/1/2/3", message_link),
+        Some(3)
+    );
+    assert_eq!(
+        parse_snowflake(
+            "<https://canary.discord.com/channels/@me/2/3>",
+            message_link
+        ),
+        Some(3)
+    );
+    assert_eq!(
+        parse_snowflake("https://discord.com/channels/@me/2/3", &[("channels", 0)]),
+        None
+    );
+    assert_eq!(
+        parse_snowflake("https://discord.com/events/1/2?foo", &[("events", 1)]),
+        Some(2)
+    );
+    assert_eq!(
+        parse_snowflake("https://example.com/channels/1/2/3", message_link),
+        None
+    );
+    assert_eq!(
+        parse_snowflake("https://discord.com/channels/1/2", message_link),
+        None
+    );
+    assert_eq!(parse_snowflake("abc", message_link), None);
+}
@@ -0,0 +1,69 @@
+//! Holds the per-type argument transformer registry, see [`ArgumentTransformers`]
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::BoxFuture;
+
+/// Transforms a parsed argument of type `X` before it's passed to the command, registered with
+/// [`ArgumentTransformers::register`]
+pub type ArgumentTransformer<T, E, X> =
+    for<'a> fn(crate::Context<'a, T, E>, X) -> BoxFuture<'a, Result<X, E>>;
+
+/// Registry of [`ArgumentTransformer`]s, at most one per argument type
+///
+/// Store in [`crate::FrameworkOptions::argument_transformers`]. Every argument of a command
+/// generated by [`crate::command`] runs through the transformer registered for its type, in
+/// prefix and slash commands alike. `Option<X>` and `Vec<X>` arguments use the transformer for
+/// `X`. Flags and inline choices are not transformed.
+///
+/// If the transformer returns an error, the command isn't run and
+/// [`crate::FrameworkError::Command`] is raised instead.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let argument_transformers = lumi::ArgumentTransformers::<(), Error>::new()
+///     // Trim all string arguments
+///     .register::<String>(|_ctx, value| Box::pin(async move { Ok(value.trim().to_owned()) }));
+///
+/// lumi::FrameworkOptions {
+///     argument_transformers,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct ArgumentTransformers<T, E> {
+    /// Boxed [`ArgumentTransformer`]s, keyed by the [`TypeId`] of the argument type
+    #[derivative(Debug = "ignore")]
+    transformers: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    /// The transformers are generic over these types
+    phantom: std::marker::PhantomData<fn() -> (T, E)>,
+}
+
+impl<T: 'static, E: 'static> ArgumentTransformers<T, E> {
+    /// Creates a registry without any transformers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the transformer for arguments of type `X`, replacing any previous one
+    pub fn register<X: 'static>(mut self, transformer: ArgumentTransformer<T, E, X>) -> Self {
+        self.transformers
+            .insert(TypeId::of::<X>(), Box::new(transformer));
+        self
+    }
+
+    /// Runs the transformer registered for type `X` on `value`, or returns `value` unchanged if
+    /// there's none
+    pub async fn apply<X: 'static>(&self, ctx: crate::Context<'_, T, E>, value: X) -> Result<X, E> {
+        let transformer = self
+            .transformers
+            .get(&TypeId::of::<X>())
+            .and_then(|transformer| transformer.downcast_ref::<ArgumentTransformer<T, E, X>>());
+        match transformer {
+            Some(transformer) => transformer(ctx, value).await,
+            None => Ok(value),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! The [`Check`] struct, a reusable precondition for running commands
+
+use crate::BoxFuture;
+
+use super::{CowStr, CowVec};
+
+/// How a [`Check`] decides whether a command may run
+///
+/// The combinators are usually created with the functions in [`crate::checks`].
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub enum CheckCallback<T, E> {
+    /// Runs a function
+    Fn(
+        #[derivative(Debug = "ignore")]
+        fn(crate::Context<'_, T, E>) -> BoxFuture<'_, Result<bool, E>>,
+    ),
+    /// Passes if all of the checks pass
+    All(Vec<Check<T, E>>),
+    /// Passes if any of the checks passes
+    Any(Vec<Check<T, E>>),
+    /// Passes if the check fails
+    Not(Box<Check<T, E>>),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// A precondition that must be met to run a command, stored in [`crate::Command::checks`]
+///
+/// Besides the callback, a check carries a name and a failure message, which are reported in
+/// [`crate::FrameworkError::CommandCheckFailed::failed_checks`] if it denies an invocation. This
+/// makes it possible to share self-describing checks between commands or crates:
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = lumi::Context<'a, (), Error>;
+/// async fn is_booster(ctx: Context<'_>) -> Result<bool, Error> {
+///     Ok(ctx
+///         .author_member()
+///         .await
+///         .is_some_and(|member| member.premium_since.is_some()))
+/// }
+///
+/// fn booster_only() -> lumi::Check<(), Error> {
+///     lumi::Check::new(|ctx| Box::pin(is_booster(ctx)))
+///         .name("booster_only")
+///         .failure_message("This command is reserved for server boosters")
+///         .required_features(["ROLE_SUBSCRIPTIONS_ENABLED"])
+/// }
+/// ```
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct Check<T, E> {
+    /// Identifies the check in error reports. Checks registered via the `check` attribute of
+    /// [`crate::command`] are named after their function.
+    pub name: Option<CowStr>,
+    /// Decides whether the command may run. Returning an error aborts the invocation, too.
+    pub callback: CheckCallback<T, E>,
+    /// User-facing explanation shown if this check denies an invocation
+    pub failure_message: Option<CowStr>,
+    /// Guild features (e.g. `COMMUNITY`) the guild must have for this check to pass. Outside of
+    /// guilds, checks with required features always fail.
+    pub required_features: CowVec<CowStr>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl<T, E> Check<T, E> {
+    /// Creates an unnamed check from its callback
+    pub fn new(callback: fn(crate::Context<'_, T, E>) -> BoxFuture<'_, Result<bool, E>>) -> Self {
+        Self::from_callback(CheckCallback::Fn(callback))
+    }
+
+    /// Creates an unnamed check from any kind of [`CheckCallback`]
+    pub fn from_callback(callback: CheckCallback<T, E>) -> Self {
+        Self {
+            name: None,
+            callback,
+            failure_message: None,
+            required_features: CowVec::default(),
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Sets [`Self::name`]
+    pub fn name(mut self, name: impl Into<CowStr>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets [`Self::failure_message`]
+    pub fn failure_message(mut self, failure_message: impl Into<CowStr>) -> Self {
+        self.failure_message = Some(failure_message.into());
+        self
+    }
+
+    /// Sets [`Self::required_features`]
+    pub fn required_features(
+        mut self,
+        required_features: impl IntoIterator<Item = impl Into<CowStr>>,
+    ) -> Self {
+        self.required_features = required_features.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Guild features fetched for [`Check::required_features`] of guilds that aren't cached, stored in
+/// [`crate::FrameworkOptions::guild_data`] so that they aren't fetched on every invocation
+struct GuildFeatures(Vec<String>);
+
+/// Whether `features` contains all of the `required` features
+fn has_all_features<'a>(
+    required: &[CowStr],
+    features: impl Iterator<Item = &'a str> + Clone,
+) -> bool {
+    required
+        .iter()
+        .all(|required| features.clone().any(|feature| feature == required))
+}
+
+/// Whether the guild of the invocation has all of the `required` features. Outside of guilds, it
+/// has none
+async fn guild_has_features<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    required: &[CowStr],
+) -> bool {
+    if let Some(guild) = ctx.guild() {
+        return has_all_features(required, guild.features.iter().map(|f| &**f));
+    }
+    let Some(guild_id) = ctx.guild_id() else {
+        return false;
+    };
+    let features = ctx
+        .guild_data::<GuildFeatures>()
+        .get_or_try_init(guild_id, || async {
+            let guild = ctx.partial_guild().await.ok_or(())?;
+            let features = guild.features.iter().map(|f| (**f).to_owned()).collect();
+            Ok::<_, ()>(GuildFeatures(features))
+        })
+        .await;
+    features.is_ok_and(|features| has_all_features(required, features.0.iter().map(String::as_str)))
+}
+
+impl<T: Send + Sync + 'static, E> Check<T, E> {
+    /// Runs the check, including the [`Self::required_features`] check
+    ///
+    /// Combined checks are evaluated in order and short-circuit, like `&&` and `||`. An error in
+    /// any of them is returned immediately.
+    ///
+    /// The features of guilds which aren't in the cache are fetched once and then kept in
+    /// [`crate::FrameworkOptions::guild_data`], until it evicts them.
+    pub fn run<'a>(&'a self, ctx: crate::Context<'a, T, E>) -> BoxFuture<'a, Result<bool, E>> {
+        self.evaluate(
+            ctx,
+            |required, ctx| Box::pin(guild_has_features(ctx, required)),
+            |check, ctx| match &check.callback {
+                CheckCallback::Fn(callback) => callback(ctx),
+                _ => unreachable!("only function checks are run directly"),
+            },
+        )
+    }
+}
+
+impl<T, E> Check<T, E> {
+    /// [`Self::run`] with the guild feature lookup and the function checks passed in, so that the
+    /// combinators can be tested without a [`crate::Context`]
+    pub(crate) fn evaluate<'a, C: Copy + Send + 'a>(
+        &'a self,
+        ctx: C,
+        has_features: fn(&'a [CowStr], C) -> BoxFuture<'a, bool>,
+        run_fn: fn(&'a Self, C) -> BoxFuture<'a, Result<bool, E>>,
+    ) -> BoxFuture<'a, Result<bool, E>> {
+        // Boxed because combined checks recurse
+        Box::pin(async move {
+            if !self.required_features.is_empty()
+                && !has_features(&self.required_features, ctx).await
+            {
+                return Ok(false);
+            }
+
+            match &self.callback {
+                CheckCallback::Fn(_) => run_fn(self, ctx).await,
+                CheckCallback::All(checks) => {
+                    for check in checks {
+                        if !check.evaluate(ctx, has_features, run_fn).await? {
+                            return Ok(false);
+                        }
+                    }
+                    Ok(true)
+                }
+                CheckCallback::Any(checks) => {
+                    for check in checks {
+                        if check.evaluate(ctx, has_features, run_fn).await? {
+                            return Ok(true);
+                        }
+                    }
+                    Ok(false)
+                }
+                CheckCallback::Not(check) => Ok(!check.evaluate(ctx, has_features, run_fn).await?),
+                CheckCallback::__NonExhaustive => unreachable!(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_check_builder() {
+    let check = Check::<(), ()>::new(|_| Box::pin(async { Ok(true) }))
+        .name("booster_only")
+        .failure_message("Boosters only")
+        .required_features(["ROLE_SUBSCRIPTIONS_ENABLED"]);
+    assert_eq!(check.name.as_deref(), Some("booster_only"));
+    assert_eq!(check.failure_message.as_deref(), Some("Boosters only"));
+    assert_eq!(&*check.required_features, ["ROLE_SUBSCRIPTIONS_ENABLED"]);
+    assert!(matches!(check.callback, CheckCallback::Fn(_)));
+}
+
+#[cfg(test)]
+#[test]
+fn test_required_features() {
+    let features = ["COMMUNITY", "NEWS"];
+    let has = |required: &[&'static str]| {
+        let required = required.iter().map(|&f| f.into()).collect::<Vec<CowStr>>();
+        has_all_features(&required, features.iter().copied())
+    };
+    assert!(has(&[]));
+    assert!(has(&["NEWS", "COMMUNITY"]));
+    assert!(!has(&["COMMUNITY", "PARTNERED"]));
+
+    // The callback doesn't run if the guild lacks a feature
+    let calls = parking_lot::Mutex::new(0);
+    let check = Check::<(), ()>::new(|_| Box::pin(async { Ok(true) }));
+    let run = |check: &Check<(), ()>| {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(check.evaluate(
+            &calls,
+            |required, _| {
+                Box::pin(async move { has_all_features(required, ["COMMUNITY"].into_iter()) })
+            },
+            |_, calls| {
+                *calls.lock() += 1;
+                Box::pin(async { Ok(true) })
+            },
+        ))
+    };
+    assert_eq!(
+        run(&check.clone().required_features(["COMMUNITY"])),
+        Ok(true)
+    );
+    assert_eq!(
+        run(&check.required_features(["COMMUNITY", "NEWS"])),
+        Ok(false)
+    );
+    assert_eq!(*calls.lock(), 1);
+}
@@ -22,6 +22,18 @@ pub struct Command<T, E> {
             crate::PrefixContext<'a, T, E>,
         ) -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, T, E>>>,
     >,
+    /// Callback to execute when this command is invoked in a prefix context with subcommands, but
+    /// without a recognized subcommand (prefix-only)
+    ///
+    /// Takes precedence over [`Self::prefix_action`] and [`Self::subcommand_required`] in that
+    /// case. For example, `~config` could show the current settings while `~config set ...`
+    /// modifies them.
+    #[derivative(Debug = "ignore")]
+    pub fallback_action: Option<
+        for<'a> fn(
+            crate::PrefixContext<'a, T, E>,
+        ) -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, T, E>>>,
+    >,
     /// Callback to execute when this command is invoked in a slash context
     #[derivative(Debug = "ignore")]
     pub slash_action: Option<
@@ -33,6 +45,16 @@ pub struct Command<T, E> {
     ///
     /// The enum variant shows which Discord item this context menu command works on
     pub context_menu_action: Option<crate::ContextMenuCommandAction<T, E>>,
+    /// Overrides the command type under which [`Self::slash_action`] is registered. `None`
+    /// registers a regular slash command.
+    ///
+    /// Set to [`serenity::CommandType::PrimaryEntryPoint`] to register Discord's primary entry
+    /// point command, used by Activities-enabled apps. Primary entry point commands can't have
+    /// parameters, subcommands or aliases.
+    pub slash_command_kind: Option<serenity::CommandType>,
+    /// Who handles invocations if this is a primary entry point command, see
+    /// [`Self::slash_command_kind`]
+    pub entry_point_handler: crate::EntryPointHandler,
 
     // ============= Command type agnostic data
     /// Subcommands of this command, if any
@@ -56,8 +78,12 @@ pub struct Command<T, E> {
     pub source_code_name: CowStr,
     /// Identifier for the category that this command will be displayed in for help commands.
     pub category: Option<CowStr>,
+    /// The group this command belongs to, see [`crate::CommandGroup`] and [`Self::in_group`]
+    pub group: Option<std::sync::Arc<crate::CommandGroup<T, E>>>,
     /// Whether to hide this command in help menus.
     pub hide_in_help: bool,
+    /// Marks this command as deprecated, see [`crate::DeprecationNotice`]
+    pub deprecated: Option<crate::DeprecationNotice>,
     /// Short description of the command. Displayed inline in help menus and similar.
     pub description: Option<CowStr>,
     /// Localized descriptions with locale string as the key (slash-only)
@@ -65,18 +91,34 @@ pub struct Command<T, E> {
     /// Multiline description with detailed usage instructions. Displayed in the command specific
     /// help: `~help command_name`
     pub help_text: Option<CowStr>,
+    /// Localized [`Self::help_text`]s with locale string as the key
+    pub help_text_localizations: CowVec<(CowStr, CowStr)>,
     /// if `true`, disables automatic cooldown handling before this commands invocation.
     ///
     /// Will override [`crate::FrameworkOptions::manual_cooldowns`] allowing manual cooldowns
     /// on select commands.
     pub manual_cooldowns: Option<bool>,
+    /// Whether the cooldowns of the parent commands apply when this command is invoked as a
+    /// subcommand. Default `true`
+    ///
+    /// If `true`, the parents' cooldowns are checked before this command runs and started
+    /// together with this command's cooldown, so all subcommands of a parent share its cooldown
+    /// buckets. If `false`, only this command's own cooldown is checked and started. Either way,
+    /// the other checks of the parent commands apply, see [`crate::Context::parent_commands`].
+    #[derivative(Default(value = "true"))]
+    pub inherit_cooldowns: bool,
     /// If true, commands will be parsed from the start of the string and take the rest as a
     /// modifier string
     pub has_modifier: bool,
     /// Handles command cooldowns. Mainly for framework internal use
-    pub cooldowns: std::sync::Mutex<crate::CooldownTracker>,
+    pub cooldowns: crate::CooldownTracker,
     /// Configuration for the [`crate::CooldownTracker`]
-    pub cooldown_config: std::sync::RwLock<crate::CooldownConfig>,
+    pub cooldown_config: parking_lot::RwLock<crate::CooldownConfig>,
+    /// Caches the application command payloads generated from this command. Mainly for framework
+    /// internal use
+    ///
+    /// See [`Self::invalidate_payload_cache`].
+    pub payload_cache: CommandPayloadCache,
     /// After the first response, whether to post subsequent responses as edits to the initial
     /// message
     ///
@@ -114,17 +156,34 @@ pub struct Command<T, E> {
     pub dm_only: bool,
     /// If true, the command may only run in NSFW channels
     pub nsfw_only: bool,
+    /// Feature flag which must be enabled by [`crate::FrameworkOptions::feature_gate`] for this
+    /// command to run, see [`crate::FeatureGate`]
+    pub feature: Option<CowStr>,
     /// Command-specific override for [`crate::FrameworkOptions::on_error`]
     #[derivative(Debug = "ignore")]
-    pub on_error: Option<fn(crate::FrameworkError<'_, T, E>) -> BoxFuture<'_, ()>>,
-    /// If any of these functions returns false, this command will not be executed.
-    #[derivative(Debug = "ignore")]
-    pub checks: Vec<fn(crate::Context<'_, T, E>) -> BoxFuture<'_, Result<bool, E>>>,
+    pub on_error:
+        Option<fn(crate::FrameworkError<'_, T, E>) -> BoxFuture<'_, Result<(), serenity::Error>>>,
+    /// If set, overrides [`crate::FrameworkOptions::command_timeout`] for this command
+    pub timeout: Option<std::time::Duration>,
+    /// How the command action is executed, see [`SpawnPolicy`]
+    pub spawn_policy: SpawnPolicy,
+    /// How invocations of this command are logged, see [`CommandLogLevel`]
+    pub log_level: CommandLogLevel,
+    /// How [`crate::builtins::on_error`] delivers error messages of this command, see
+    /// [`ErrorReplyPolicy`]
+    pub error_reply_policy: ErrorReplyPolicy,
+    /// If any of these checks fails, this command will not be executed.
+    ///
+    /// Failed checks are reported in [`crate::FrameworkError::CommandCheckFailed::failed_checks`].
+    pub checks: Vec<crate::Check<T, E>>,
     /// List of parameters for this command
     ///
     /// Used for registering and parsing slash commands. Can also be used in help commands
     pub parameters: Vec<crate::CommandParameter<T, E>>,
     /// Arbitrary data, useful for storing custom metadata about your commands
+    ///
+    /// Read it with [`Self::custom_data()`] and set it with [`Self::with_custom_data()`] or the
+    /// `custom_data` macro attribute.
     #[derivative(Default(value = "Box::new(())"))]
     pub custom_data: Box<dyn std::any::Any + Send + Sync>,
 
@@ -135,24 +194,142 @@ pub struct Command<T, E> {
     pub invoke_on_edit: bool,
     /// Whether to delete the bot response if an existing invocation message is deleted (prefix-only)
     pub track_deletion: bool,
+    /// Whether this command may be invoked by bots and webhooks even if
+    /// [`crate::PrefixFrameworkOptions::ignore_bots`] is set, e.g. for bridge integrations or
+    /// proxied messages (prefix-only)
+    ///
+    /// For subcommands, only the flag of the invoked subcommand matters.
+    pub allow_bot_invocations: bool,
     /// Whether to broadcast a typing indicator while executing this commmand (prefix-only)
     pub broadcast_typing: bool,
+    /// If set, overrides [`crate::PrefixFrameworkOptions::broadcast_typing_delay`] for this
+    /// command (prefix-only)
+    pub broadcast_typing_delay: Option<std::time::Duration>,
 
     // ============= Application-specific data
     /// Context menu specific name for this command, displayed in Discord's context menu
     pub context_menu_name: Option<CowStr>,
+    /// Localized context menu names with locale string as the key
+    pub context_menu_name_localizations: CowVec<(CowStr, CowStr)>,
     /// Whether responses to this command should be ephemeral by default (application-only)
+    ///
+    /// Overridden by [`crate::FrameworkOptions::ephemeral_callback`] if set.
     pub ephemeral: bool,
+    /// Whether to defer the interaction response before running the command (application-only)
+    ///
+    /// Replaces calling [`crate::Context::defer()`] at the start of the command body for commands
+    /// that may take longer than 3 seconds to respond. Not applied to reruns.
+    pub response_mode: crate::ResponseMode,
     /// List of installation contexts for this command (application-only)
     pub install_context: Option<Vec<serenity::InstallationContext>>,
     /// List of interaction contexts for this command (application-only)
     pub interaction_context: Option<Vec<serenity::InteractionContext>>,
+    /// Whether to add a "Re-run" button to the initial response, which reruns the command with the
+    /// same arguments when clicked by the invoking user (application-only)
+    ///
+    /// Checks, permissions and cooldowns are evaluated again. Responses of the rerun are sent as
+    /// followups. Only works within 15 minutes of the invocation, see [`crate::RerunTracker`].
+    pub rerun_button: bool,
+    /// Whether to add a 🗑 button to responses, which deletes the response when clicked by the
+    /// invoking user or a member with the Manage Messages permission
+    ///
+    /// Overrides [`crate::FrameworkOptions::delete_button`] if set. Not added to ephemeral
+    /// responses.
+    pub delete_button: Option<bool>,
+    /// Additional names under which this slash command is registered (application-only)
+    ///
+    /// Each alias is registered as a separate top-level command with the same parameters, and
+    /// invocations of any of them run this command. Useful to keep an old name working for a
+    /// while after renaming a command. Only has an effect on top-level commands.
+    pub slash_aliases: CowVec<CowStr>,
 
     // Like #[non_exhaustive], but #[lumi::command] still needs to be able to create an instance
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 
+/// Lazily built application command payloads of a [`Command`], so that repeated registration
+/// and JSON export don't rebuild them every time
+#[derive(Debug, Default)]
+pub struct CommandPayloadCache {
+    /// Result of [`Command::create_as_slash_command`]
+    slash_command: std::sync::OnceLock<Option<serenity::CreateCommand<'static>>>,
+    /// Result of [`Command::create_as_context_menu_command`]
+    context_menu_command: std::sync::OnceLock<Option<serenity::CreateCommand<'static>>>,
+    /// Result of [`Command::create_as_slash_command_aliases`]
+    slash_aliases: std::sync::OnceLock<Vec<serenity::CreateCommand<'static>>>,
+}
+
+/// How the action of a [`Command`] is executed, see [`Command::spawn_policy`]
+///
+/// There's no policy to run the action on a separate task: actions borrow the invocation (the
+/// serenity context, the message or interaction and the framework options), so they can't be
+/// moved into a `'static` task. CPU-heavy work inside an action can still be moved to
+/// [`tokio::task::spawn_blocking`] by cloning the data it needs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SpawnPolicy {
+    /// Run the action on the task dispatching the event
+    #[default]
+    Inline,
+    /// Run the action via [`tokio::task::block_in_place`], for CPU-heavy or blocking commands
+    ///
+    /// The worker thread hands its other tasks over to the rest of the runtime before the action
+    /// runs, so the dispatcher and other commands stay responsive. Requires the multi-threaded
+    /// runtime: [`crate::Framework`] refuses to start on a current-thread runtime if a command
+    /// uses this policy.
+    #[cfg(feature = "blocking_commands")]
+    Blocking,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// How invocations of a [`Command`] are logged, see [`Command::log_level`]
+///
+/// The command action runs inside a `command` tracing span carrying the command's qualified name,
+/// so that events logged by the command can be attributed to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CommandLogLevel {
+    /// Don't log invocations, but still run the command inside the tracing span
+    #[default]
+    Span,
+    /// Log invocations at INFO level, with the invocation string (including arguments) and the
+    /// invoking user
+    Info,
+    /// Log invocations at DEBUG level, without the message content or the invoking user. For
+    /// commands handling private data which shouldn't end up in logs
+    Debug,
+    /// Neither log invocations nor create a tracing span
+    Off,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// How [`crate::builtins::on_error`] delivers the error messages of a [`Command`], see
+/// [`Command::error_reply_policy`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ErrorReplyPolicy {
+    /// Let the error handler decide per error: command errors and argument parse errors are
+    /// posted publicly, everything else ephemerally
+    #[default]
+    Auto,
+    /// Post all error messages publicly
+    Public,
+    /// Post all error messages ephemerally. Prefix commands can't send ephemeral messages, so
+    /// they're posted publicly there
+    Ephemeral,
+    /// Post error messages like [`Self::Auto`] and delete them after the given duration
+    ///
+    /// The `error_reply_policy` attribute of [`crate::command`] can't express a duration, so set
+    /// [`Command::error_reply_policy`] on the returned command instead.
+    DeleteAfter(std::time::Duration),
+    /// Send error messages to the invoking user's DMs. Interactions are still answered with a
+    /// short ephemeral notice. If the user doesn't accept DMs, the message is posted ephemerally
+    /// instead
+    Dm,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
 impl<T, E> PartialEq for Command<T, E> {
     fn eq(&self, other: &Self) -> bool {
         std::ptr::eq(self, other)
@@ -161,6 +338,12 @@ impl<T, E> PartialEq for Command<T, E> {
 impl<T, E> Eq for Command<T, E> {}
 
 impl<T, E> Command<T, E> {
+    /// Whether this command is registered as Discord's primary entry point command, see
+    /// [`Self::slash_command_kind`]
+    pub fn is_primary_entry_point(&self) -> bool {
+        self.slash_command_kind == Some(serenity::CommandType::PrimaryEntryPoint)
+    }
+
     /// Serializes this Command into an application command option, which is the form which Discord
     /// requires subcommands to be in
     fn create_as_subcommand(&self) -> Option<serenity::CreateCommandOption<'static>> {
@@ -182,6 +365,11 @@ impl<T, E> Command<T, E> {
             builder = builder.description_localized(locale.clone(), description.clone());
         }
 
+        // Primary entry point commands have no options
+        if self.is_primary_entry_point() {
+            return Some(builder);
+        }
+
         if self.subcommands.is_empty() {
             for param in &self.parameters {
                 // Using `?` because if this command has slash-incompatible parameters, we cannot
@@ -201,14 +389,63 @@ impl<T, E> Command<T, E> {
 
     /// Generates a slash command builder from this [`Command`] instance. This can be used
     /// to register this command on Discord's servers
+    ///
+    /// The builder is only generated once and cached afterwards, see
+    /// [`Self::invalidate_payload_cache`].
     pub fn create_as_slash_command(&self) -> Option<serenity::CreateCommand<'static>> {
+        self.payload_cache
+            .slash_command
+            .get_or_init(|| self.build_slash_command())
+            .clone()
+    }
+
+    /// Generates one slash command builder per [`Self::slash_aliases`] entry. Apart from the name,
+    /// they're identical to [`Self::create_as_slash_command`].
+    ///
+    /// Primary entry point commands can't have aliases, so this is empty for them.
+    pub fn create_as_slash_command_aliases(&self) -> Vec<serenity::CreateCommand<'static>> {
+        self.payload_cache
+            .slash_aliases
+            .get_or_init(|| {
+                if self.is_primary_entry_point() {
+                    return Vec::new();
+                }
+                self.slash_aliases
+                    .iter()
+                    .filter_map(|alias| self.build_slash_command_named(alias.clone(), false))
+                    .collect()
+            })
+            .clone()
+    }
+
+    /// Uncached implementation of [`Self::create_as_slash_command`]
+    fn build_slash_command(&self) -> Option<serenity::CreateCommand<'static>> {
+        self.build_slash_command_named(self.name.clone(), true)
+    }
+
+    /// Builds the slash command under the given name. Name localizations are skipped for aliases,
+    /// because they would display the aliases under the main name.
+    fn build_slash_command_named(
+        &self,
+        name: CowStr,
+        with_name_localizations: bool,
+    ) -> Option<serenity::CreateCommand<'static>> {
         self.slash_action?;
 
-        let mut builder = serenity::CreateCommand::new(self.name.clone())
+        let mut builder = serenity::CreateCommand::new(name)
             .description(self.description.clone().unwrap_or(DEFAULT_NAME));
 
-        for (locale, name) in self.name_localizations.iter() {
-            builder = builder.name_localized(locale.clone(), name.clone());
+        if let Some(kind) = self.slash_command_kind {
+            builder = builder.kind(kind);
+        }
+        if self.is_primary_entry_point() {
+            builder = builder.handler(self.entry_point_handler.to_serenity());
+        }
+
+        if with_name_localizations {
+            for (locale, name) in self.name_localizations.iter() {
+                builder = builder.name_localized(locale.clone(), name.clone());
+            }
         }
         for (locale, description) in self.description_localizations.iter() {
             builder = builder.description_localized(locale.clone(), description.clone());
@@ -220,6 +457,11 @@ impl<T, E> Command<T, E> {
             builder = builder.default_member_permissions(self.default_member_permissions);
         }
 
+        // Makes Discord hide the command in non-NSFW channels client-side
+        if self.nsfw_only {
+            builder = builder.nsfw(true);
+        }
+
         if self.guild_only {
             builder = builder.contexts(vec![serenity::InteractionContext::Guild]);
         } else if self.dm_only {
@@ -234,6 +476,11 @@ impl<T, E> Command<T, E> {
             builder = builder.contexts(interaction_context);
         }
 
+        // Primary entry point commands have no options
+        if self.is_primary_entry_point() {
+            return Some(builder);
+        }
+
         if self.subcommands.is_empty() {
             for param in &self.parameters {
                 // Using `?` because if this command has slash-incompatible parameters, we cannot
@@ -253,10 +500,20 @@ impl<T, E> Command<T, E> {
 
     /// Generates a context menu command builder from this [`Command`] instance. This can be used
     /// to register this command on Discord's servers
+    ///
+    /// The builder is only generated once and cached afterwards, see
+    /// [`Self::invalidate_payload_cache`].
     pub fn create_as_context_menu_command(&self) -> Option<serenity::CreateCommand<'static>> {
+        self.payload_cache
+            .context_menu_command
+            .get_or_init(|| self.build_context_menu_command())
+            .clone()
+    }
+
+    /// Uncached implementation of [`Self::create_as_context_menu_command`]
+    fn build_context_menu_command(&self) -> Option<serenity::CreateCommand<'static>> {
         let context_menu_action = self.context_menu_action?;
 
-        // TODO: localization?
         let name = self.context_menu_name.clone().unwrap_or(self.name.clone());
         let mut builder = serenity::CreateCommand::new(name).kind(match context_menu_action {
             crate::ContextMenuCommandAction::User(_) => serenity::CommandType::User,
@@ -264,6 +521,15 @@ impl<T, E> Command<T, E> {
             crate::ContextMenuCommandAction::__NonExhaustive => unreachable!(),
         });
 
+        for (locale, name) in self.context_menu_name_localizations.iter() {
+            builder = builder.name_localized(locale.clone(), name.clone());
+        }
+
+        // Makes Discord hide the command in non-NSFW channels client-side
+        if self.nsfw_only {
+            builder = builder.nsfw(true);
+        }
+
         if self.guild_only {
             builder = builder.contexts(vec![serenity::InteractionContext::Guild]);
         } else if self.dm_only {
@@ -280,4 +546,224 @@ impl<T, E> Command<T, E> {
 
         Some(builder)
     }
+
+    /// Serializes the application command payloads generated from this [`Command`] into JSON,
+    /// exactly as they would be sent to Discord on registration.
+    ///
+    /// Returns a JSON array, because a single [`Command`] can generate both a slash command and
+    /// one or more context menu commands (see [`crate::builtins::create_application_commands`]).
+    ///
+    /// Useful for snapshot testing your command tree or for feeding external registration
+    /// tooling. Use `{:#}` to format the result as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        crate::builtins::export_commands_json(std::slice::from_ref(self))
+    }
+
+    /// Returns the [`Self::help_text`] in the given locale (see [`crate::Context::locale`]),
+    /// falling back to the unlocalized help text.
+    pub fn help_text_localized(&self, locale: Option<&str>) -> Option<&str> {
+        locale
+            .and_then(|locale| {
+                self.help_text_localizations
+                    .iter()
+                    .find(|(help_text_locale, _)| help_text_locale == locale)
+            })
+            .map(|(_, help_text)| &**help_text)
+            .or(self.help_text.as_deref())
+    }
+
+    /// Returns [`Self::custom_data`] if it's of type `M`
+    ///
+    /// ```rust
+    /// struct CommandMeta {
+    ///     danger_level: u8,
+    /// }
+    ///
+    /// let command = lumi::Command::<(), ()>::default().with_custom_data(CommandMeta {
+    ///     danger_level: 3,
+    /// });
+    /// let danger_level = command.custom_data::<CommandMeta>().map(|meta| meta.danger_level);
+    /// assert_eq!(danger_level, Some(3));
+    /// ```
+    pub fn custom_data<M: std::any::Any>(&self) -> Option<&M> {
+        self.custom_data.downcast_ref()
+    }
+
+    /// Replaces [`Self::custom_data`] with the given value
+    #[must_use]
+    pub fn with_custom_data<M: std::any::Any + Send + Sync>(mut self, custom_data: M) -> Self {
+        self.custom_data = Box::new(custom_data);
+        self
+    }
+
+    /// Adds this command to the given group, see [`crate::CommandGroup`]
+    ///
+    /// If the command has no [`Self::category`], [`Self::install_context`] or
+    /// [`Self::interaction_context`], it's assigned the group's.
+    #[must_use]
+    pub fn in_group(mut self, group: &std::sync::Arc<crate::CommandGroup<T, E>>) -> Self {
+        if self.category.is_none() {
+            self.category = group.category.clone();
+        }
+        if self.install_context.is_none() {
+            self.install_context = group.install_context.clone();
+        }
+        if self.interaction_context.is_none() {
+            self.interaction_context = group.interaction_context.clone();
+        }
+        self.invalidate_payload_cache();
+        self.group = Some(group.clone());
+        self
+    }
+
+    /// Discards the cached payloads of [`Self::create_as_slash_command`] and
+    /// [`Self::create_as_context_menu_command`] of this command and all its subcommands.
+    ///
+    /// Call this after modifying the fields of a command which has already been registered or
+    /// exported, so that the changes are picked up. Functions in this crate which modify commands,
+    /// like [`crate::set_default_contexts`] or [`crate::CommandManifest::apply_to`], do this
+    /// automatically, and so does the framework when it takes over the commands.
+    pub fn invalidate_payload_cache(&mut self) {
+        self.payload_cache = CommandPayloadCache::default();
+        for subcommand in &mut self.subcommands {
+            subcommand.invalidate_payload_cache();
+        }
+    }
+}
+
+/// Iterates over the commands and all their subcommands, depth-first, yielding each command along
+/// with its depth in the command tree (0 for the given commands, 1 for their subcommands, ...)
+///
+/// Parents are yielded before their subcommands, in the order they're listed.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # fn list_commands(options: &lumi::FrameworkOptions<(), Error>) {
+/// for (depth, command) in lumi::walk_commands(options.commands.iter().map(|c| &**c)) {
+///     println!("{}{}", "  ".repeat(depth), command.name);
+/// }
+/// # }
+/// ```
+pub fn walk_commands<'a, T: 'a, E: 'a>(
+    commands: impl IntoIterator<Item = &'a Command<T, E>>,
+) -> impl Iterator<Item = (usize, &'a Command<T, E>)> {
+    let mut stack = commands
+        .into_iter()
+        .map(|command| (0, command))
+        .collect::<Vec<_>>();
+    stack.reverse();
+
+    std::iter::from_fn(move || {
+        let (depth, command) = stack.pop()?;
+        stack.extend(
+            command
+                .subcommands
+                .iter()
+                .rev()
+                .map(|subcommand| (depth + 1, subcommand)),
+        );
+        Some((depth, command))
+    })
+}
+
+/// Searches the command with the given [`Command::qualified_name`] among `commands` and their
+/// subcommands
+pub fn find_by_qualified_name<'a, T, E>(
+    commands: impl IntoIterator<Item = &'a Command<T, E>>,
+    qualified_name: &str,
+) -> Option<&'a Command<T, E>> {
+    walk_commands(commands)
+        .map(|(_, command)| command)
+        .find(|command| command.qualified_name == qualified_name)
+}
+
+/// Searches the command with the given [`Command::identifying_name`] among `commands` and their
+/// subcommands
+pub fn find_by_identifying_name<'a, T, E>(
+    commands: impl IntoIterator<Item = &'a Command<T, E>>,
+    identifying_name: &str,
+) -> Option<&'a Command<T, E>> {
+    walk_commands(commands)
+        .map(|(_, command)| command)
+        .find(|command| command.identifying_name == identifying_name)
+}
+
+#[cfg(test)]
+#[test]
+fn test_walk_commands() {
+    let command = |name: &'static str, subcommands| Command::<(), ()> {
+        name: name.into(),
+        qualified_name: name.into(),
+        identifying_name: format!("{}_fn", name).into(),
+        subcommands,
+        ..Default::default()
+    };
+    let commands = [
+        command(
+            "a",
+            vec![
+                command("b", vec![command("c", vec![])]),
+                command("d", vec![]),
+            ],
+        ),
+        command("e", vec![]),
+    ];
+
+    let walked = walk_commands(&commands)
+        .map(|(depth, command)| (depth, &*command.name))
+        .collect::<Vec<_>>();
+    assert_eq!(walked, [(0, "a"), (1, "b"), (2, "c"), (1, "d"), (0, "e")]);
+
+    let found = find_by_identifying_name(&commands, "c_fn").map(|command| &*command.name);
+    assert_eq!(found, Some("c"));
+    assert!(find_by_qualified_name(&commands, "missing").is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn test_payload_cache() {
+    let description = |command: &Command<(), ()>| {
+        let payload = serde_json::to_value(command.create_as_slash_command()).unwrap();
+        payload["description"].as_str().map(str::to_owned)
+    };
+    let mut command = Command::<(), ()> {
+        name: "ping".into(),
+        description: Some("old".into()),
+        slash_action: Some(|_| Box::pin(async { Ok(()) })),
+        ..Default::default()
+    };
+    assert_eq!(description(&command).as_deref(), Some("old"));
+
+    command.description = Some("new".into());
+    assert_eq!(description(&command).as_deref(), Some("old"));
+    command.invalidate_payload_cache();
+    assert_eq!(description(&command).as_deref(), Some("new"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_primary_entry_point() {
+    let command = |kind, entry_point_handler| Command::<(), ()> {
+        name: "launch".into(),
+        slash_action: Some(|_| Box::pin(async { Ok(()) })),
+        slash_aliases: Cow::Borrowed(&[Cow::Borrowed("start")]),
+        slash_command_kind: kind,
+        entry_point_handler,
+        ..Default::default()
+    };
+
+    let slash_command = command(None, crate::EntryPointHandler::DiscordLaunchActivity);
+    let payload = serde_json::to_value(slash_command.create_as_slash_command()).unwrap();
+    assert!(payload["type"].is_null());
+    assert!(payload["handler"].is_null());
+    assert_eq!(slash_command.create_as_slash_command_aliases().len(), 1);
+
+    let entry_point = command(
+        Some(serenity::CommandType::PrimaryEntryPoint),
+        crate::EntryPointHandler::DiscordLaunchActivity,
+    );
+    let payload = serde_json::to_value(entry_point.create_as_slash_command()).unwrap();
+    assert_eq!(payload["type"], 4);
+    assert_eq!(payload["handler"], 2);
+    assert!(entry_point.create_as_slash_command_aliases().is_empty());
 }
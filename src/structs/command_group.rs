@@ -0,0 +1,135 @@
+//! A set of related commands sharing checks and a cooldown, see [`CommandGroup`]
+
+use crate::serenity_prelude as serenity;
+
+use super::CowStr;
+
+/// A named set of related top-level commands, e.g. all moderation commands
+///
+/// Groups are coarser than subcommands: their commands are invoked on their own (`~ban`, not
+/// `~moderation ban`), but share the group's settings:
+/// - the group's [`Self::checks`] run before the checks of each member command
+/// - the group's cooldown bucket (see [`Self::cooldown_config`]) is shared by all members, so
+///   invoking one member puts all of them on cooldown
+/// - the help menu (see [`crate::builtins::help_menu`]) lists members under the group's name
+/// - [`Self::default_member_permissions`], [`Self::install_context`] and
+///   [`Self::interaction_context`] are registered for members which don't set their own
+///
+/// Commands join a group with [`crate::Command::in_group`]:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # #[lumi::command(slash_command)] async fn ban(ctx: lumi::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+/// # #[lumi::command(slash_command)] async fn kick(ctx: lumi::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+/// use lumi::serenity_prelude as serenity;
+///
+/// let moderation = std::sync::Arc::new(
+///     lumi::CommandGroup::new("Moderation")
+///         .description("Keep the server tidy")
+///         .default_member_permissions(serenity::Permissions::KICK_MEMBERS),
+/// );
+/// let commands = vec![ban().in_group(&moderation), kick().in_group(&moderation)];
+/// ```
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct CommandGroup<T, E> {
+    /// Name of the group, shown in help menus
+    pub name: CowStr,
+    /// Short description of the group, shown in help menus
+    pub description: Option<CowStr>,
+    /// Category assigned to commands joining this group which don't have one, see
+    /// [`crate::Command::category`]
+    pub category: Option<CowStr>,
+    /// Checks which must pass for any command of this group to run. Evaluated before the checks
+    /// of the commands themselves
+    pub checks: Vec<crate::Check<T, E>>,
+    /// Tracks the cooldown shared by all commands of this group. Mainly for framework internal use
+    pub cooldowns: crate::CooldownTracker,
+    /// Configuration of the cooldown shared by all commands of this group
+    pub cooldown_config: parking_lot::RwLock<crate::CooldownConfig>,
+    /// Registered as [`crate::Command::default_member_permissions`] for commands of this group
+    /// which don't set any
+    pub default_member_permissions: serenity::Permissions,
+    /// Installation contexts of commands joining this group which don't set their own, see
+    /// [`crate::Command::install_context`]
+    ///
+    /// Takes precedence over [`crate::FrameworkOptions::install_context`].
+    pub install_context: Option<Vec<serenity::InstallationContext>>,
+    /// Interaction contexts of commands joining this group which don't set their own, see
+    /// [`crate::Command::interaction_context`]
+    ///
+    /// Takes precedence over [`crate::FrameworkOptions::interaction_context`].
+    pub interaction_context: Option<Vec<serenity::InteractionContext>>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl<T, E> CommandGroup<T, E> {
+    /// Creates a group with the given name and no checks, cooldowns or permissions
+    pub fn new(name: impl Into<CowStr>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            category: None,
+            checks: Vec::new(),
+            cooldowns: crate::CooldownTracker::new(),
+            cooldown_config: parking_lot::RwLock::new(crate::CooldownConfig::default()),
+            default_member_permissions: serenity::Permissions::empty(),
+            install_context: None,
+            interaction_context: None,
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Sets [`Self::description`]
+    #[must_use]
+    pub fn description(mut self, description: impl Into<CowStr>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets [`Self::category`]
+    #[must_use]
+    pub fn category(mut self, category: impl Into<CowStr>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Adds a check to [`Self::checks`]
+    #[must_use]
+    pub fn check(mut self, check: crate::Check<T, E>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Sets [`Self::cooldown_config`]
+    #[must_use]
+    pub fn cooldown_config(self, cooldown_config: crate::CooldownConfig) -> Self {
+        *self.cooldown_config.write() = cooldown_config;
+        self
+    }
+
+    /// Sets [`Self::default_member_permissions`]
+    #[must_use]
+    pub fn default_member_permissions(mut self, permissions: serenity::Permissions) -> Self {
+        self.default_member_permissions = permissions;
+        self
+    }
+
+    /// Sets [`Self::install_context`]
+    #[must_use]
+    pub fn install_context(mut self, install_context: Vec<serenity::InstallationContext>) -> Self {
+        self.install_context = Some(install_context);
+        self
+    }
+
+    /// Sets [`Self::interaction_context`]
+    #[must_use]
+    pub fn interaction_context(
+        mut self,
+        interaction_context: Vec<serenity::InteractionContext>,
+    ) -> Self {
+        self.interaction_context = Some(interaction_context);
+        self
+    }
+}
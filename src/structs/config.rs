@@ -0,0 +1,234 @@
+//! Holds the plain-data subset of the framework options that can be loaded from a config file.
+
+use crate::serenity_prelude as serenity;
+
+/// Subset of [`crate::FrameworkOptions`] which consists of plain data and can thus be loaded from
+/// a config file at runtime, e.g. to tweak prefixes without recompiling.
+///
+/// With the `serde` feature enabled, this struct implements [`serde::Deserialize`]. All fields
+/// are optional in the serialized form; missing fields leave the respective option untouched.
+///
+/// Apply the config to your options with [`crate::FrameworkOptions::apply_config`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FrameworkConfig {
+    /// Prefix command specific config
+    pub prefix: PrefixConfig,
+    /// Overrides [`crate::FrameworkOptions::allowed_mentions`]
+    pub allowed_mentions: Option<AllowedMentionsConfig>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub __non_exhaustive: (),
+}
+
+/// Subset of [`crate::PrefixFrameworkOptions`] that can be loaded from a config file.
+///
+/// See [`FrameworkConfig`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PrefixConfig {
+    /// Overrides [`crate::PrefixFrameworkOptions::prefix`]
+    pub prefix: Option<String>,
+    /// Literal prefixes which are appended to
+    /// [`crate::PrefixFrameworkOptions::additional_prefixes`]
+    pub additional_prefixes: Vec<String>,
+    /// Overrides [`crate::PrefixFrameworkOptions::case_insensitive_commands`]
+    pub case_insensitive_commands: Option<bool>,
+    /// Overrides [`crate::PrefixFrameworkOptions::mention_as_prefix`]
+    pub mention_as_prefix: Option<bool>,
+    /// If set, replaces [`crate::PrefixFrameworkOptions::edit_tracker`] with an edit tracker that
+    /// tracks messages for this many seconds. A value of `0` disables edit tracking.
+    pub edit_tracker_duration_secs: Option<u64>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub __non_exhaustive: (),
+}
+
+/// Plain-data representation of [`serenity::CreateAllowedMentions`]
+///
+/// See [`FrameworkConfig`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct AllowedMentionsConfig {
+    /// Whether `@everyone` and `@here` pings are allowed
+    pub everyone: bool,
+    /// Whether user pings are allowed
+    pub users: bool,
+    /// Whether role pings are allowed
+    pub roles: bool,
+    /// Whether the author of the message being replied to is pinged
+    pub replied_user: bool,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub __non_exhaustive: (),
+}
+
+impl AllowedMentionsConfig {
+    /// Converts this config into the corresponding serenity builder
+    pub fn to_builder(&self) -> serenity::CreateAllowedMentions<'static> {
+        serenity::CreateAllowedMentions::default()
+            .everyone(self.everyone)
+            .all_users(self.users)
+            .all_roles(self.roles)
+            .replied_user(self.replied_user)
+    }
+}
+
+impl<T, E> crate::FrameworkOptions<T, E> {
+    /// Overwrites the options covered by the given [`FrameworkConfig`].
+    ///
+    /// Fields which are unset in the config leave the corresponding options untouched.
+    /// Call this before passing the options to [`crate::Framework::new`].
+    pub fn apply_config(&mut self, config: FrameworkConfig) {
+        let FrameworkConfig {
+            prefix,
+            allowed_mentions,
+            __non_exhaustive: (),
+        } = config;
+
+        if let Some(allowed_mentions) = allowed_mentions {
+            self.allowed_mentions = Some(allowed_mentions.to_builder());
+        }
+        self.prefix_options.apply_config(prefix);
+    }
+}
+
+impl<T, E> crate::PrefixFrameworkOptions<T, E> {
+    /// Overwrites the options covered by the given [`PrefixConfig`].
+    ///
+    /// Fields which are unset in the config leave the corresponding options untouched.
+    pub fn apply_config(&mut self, config: PrefixConfig) {
+        let PrefixConfig {
+            prefix,
+            additional_prefixes,
+            case_insensitive_commands,
+            mention_as_prefix,
+            edit_tracker_duration_secs,
+            __non_exhaustive: (),
+        } = config;
+
+        if let Some(prefix) = prefix {
+            self.prefix = Some(prefix.into());
+        }
+        // `Prefix::Literal` requires a `&'static str`, so owned literals are matched via an
+        // anchored, escaped regex instead
+        self.additional_prefixes
+            .extend(additional_prefixes.iter().map(|prefix| {
+                let regex = regex::Regex::new(&format!("^{}", regex::escape(prefix)))
+                    .expect("escaped literal is always a valid regex");
+                crate::Prefix::Regex(regex)
+            }));
+        if let Some(case_insensitive_commands) = case_insensitive_commands {
+            self.case_insensitive_commands = case_insensitive_commands;
+        }
+        if let Some(mention_as_prefix) = mention_as_prefix {
+            self.mention_as_prefix = mention_as_prefix;
+        }
+        match edit_tracker_duration_secs {
+            Some(0) => self.edit_tracker = None,
+            Some(secs) => {
+                self.edit_tracker = Some(std::sync::Arc::new(crate::EditTracker::for_timespan(
+                    std::time::Duration::from_secs(secs),
+                )));
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_apply_config() {
+    let mut options = crate::FrameworkOptions::<(), String>::default();
+    options.apply_config(FrameworkConfig::default());
+    assert!(options.allowed_mentions.is_none());
+    assert!(options.prefix_options.prefix.is_none());
+    assert!(options.prefix_options.additional_prefixes.is_empty());
+    assert!(options.prefix_options.case_insensitive_commands);
+    assert!(options.prefix_options.mention_as_prefix);
+    assert!(options.prefix_options.edit_tracker.is_none());
+
+    let allowed_mentions = AllowedMentionsConfig {
+        users: true,
+        replied_user: true,
+        ..Default::default()
+    };
+    options.apply_config(FrameworkConfig {
+        prefix: PrefixConfig {
+            prefix: Some("~".into()),
+            additional_prefixes: vec!["?!".into()],
+            mention_as_prefix: Some(false),
+            edit_tracker_duration_secs: Some(60),
+            ..Default::default()
+        },
+        allowed_mentions: Some(allowed_mentions.clone()),
+        ..Default::default()
+    });
+    assert_eq!(
+        serde_json::to_value(&options.allowed_mentions).unwrap(),
+        serde_json::to_value(allowed_mentions.to_builder()).unwrap()
+    );
+    let prefix_options = &options.prefix_options;
+    assert_eq!(prefix_options.prefix.as_deref(), Some("~"));
+    assert!(prefix_options.case_insensitive_commands);
+    assert!(!prefix_options.mention_as_prefix);
+    assert!(prefix_options.edit_tracker.is_some());
+
+    // Additional prefixes match literally and only at the start
+    match &prefix_options.additional_prefixes[..] {
+        [crate::Prefix::Regex(regex)] => {
+            assert_eq!(regex.find("?!ping").map(|m| m.as_str()), Some("?!"));
+            assert!(!regex.is_match("!ping"));
+            assert!(!regex.is_match("ping ?!"));
+        }
+        other => panic!("expected a single regex prefix, got {other:?}"),
+    }
+
+    options.apply_config(FrameworkConfig {
+        prefix: PrefixConfig {
+            edit_tracker_duration_secs: Some(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    assert!(options.prefix_options.edit_tracker.is_none());
+    assert_eq!(options.prefix_options.prefix.as_deref(), Some("~"));
+}
+
+#[cfg(all(test, feature = "serde"))]
+#[test]
+fn test_deserialize_config() {
+    let config: FrameworkConfig = serde_json::from_str(
+        r#"{
+            "prefix": { "prefix": "!", "additional_prefixes": ["lumi "], "edit_tracker_duration_secs": 0 },
+            "allowed_mentions": { "users": true }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config,
+        FrameworkConfig {
+            prefix: PrefixConfig {
+                prefix: Some("!".into()),
+                additional_prefixes: vec!["lumi ".into()],
+                edit_tracker_duration_secs: Some(0),
+                ..Default::default()
+            },
+            allowed_mentions: Some(AllowedMentionsConfig {
+                users: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    );
+
+    // Missing fields leave the options untouched
+    let config: FrameworkConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config, FrameworkConfig::default());
+}
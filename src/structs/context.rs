@@ -92,7 +92,8 @@ context_methods! {
 
     /// See [`Self::defer()`]
     ///
-    /// This will make the response ephemeral; to make it public, use [`Self::defer()`].
+    /// This will make the response ephemeral; to make it public, use [`Self::defer()`]. To decide
+    /// at runtime, use [`Self::defer_with()`].
     await (defer_ephemeral self)
     (pub async fn defer_ephemeral(self) -> Result<(), serenity::Error>) {
         if let Self::Application(ctx) = self {
@@ -101,6 +102,42 @@ context_methods! {
         Ok(())
     }
 
+    /// See [`Self::defer()`]
+    ///
+    /// Decides at runtime whether the response will be ephemeral, by calling the given closure
+    /// with this context. For example, to respond publicly only in a dedicated bot channel:
+    /// ```rust,no_run
+    /// # async fn _test(ctx: lumi::Context<'_, (), ()>) -> Result<(), lumi::serenity_prelude::Error> {
+    /// # let bot_channel = lumi::serenity_prelude::GenericChannelId::new(123);
+    /// ctx.defer_with(|ctx| ctx.channel_id() != bot_channel).await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// To use the same decision as responses that don't set ephemerality explicitly, pass
+    /// [`Self::default_ephemeral()`].
+    await (defer_with self ephemeral)
+    (pub async fn defer_with(
+        self,
+        ephemeral: impl FnOnce(crate::Context<'a, T, E>) -> bool,
+    ) -> Result<(), serenity::Error>) {
+        if let Self::Application(ctx) = self {
+            ctx.defer_response(ephemeral(self)).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether responses in this invocation are ephemeral unless set explicitly
+    ///
+    /// Decided by [`crate::FrameworkOptions::ephemeral_callback`] if set, and by
+    /// [`crate::Command::ephemeral`] otherwise.
+    (default_ephemeral self)
+    (pub fn default_ephemeral(self) -> bool) {
+        match self.framework().options().ephemeral_callback {
+            Some(callback) => callback(self),
+            None => self.command().ephemeral,
+        }
+    }
+
     /// If this is an application command, [`Self::defer()`] is called
     ///
     /// If this is a prefix command, a typing broadcast is started until the return value is
@@ -157,6 +194,18 @@ context_methods! {
         crate::send_reply(self, builder).await
     }
 
+    /// Shorthand of [`crate::send_followup`]: like [`Self::send`], but always sends a new message
+    /// instead of editing the previous response of a [`crate::Command::reuse_response`] command
+    ///
+    /// Note: panics when called in an autocomplete context!
+    await (followup self builder)
+    (pub async fn followup(
+        self,
+        builder: crate::CreateReply<'_>,
+    ) -> Result<crate::ReplyHandle<'a>, serenity::Error>) {
+        crate::send_followup(self, builder).await
+    }
+
     /// Return the stored [`serenity::Context`] within the underlying context type.
     (serenity_context self)
     (pub fn serenity_context(self) -> &'a serenity::Context) {
@@ -267,12 +316,24 @@ context_methods! {
         }
     }
 
+    /// Return when the framework started dispatching this invocation
+    ///
+    /// Unlike [`Self::created_at`], this is measured locally and can be used to time the
+    /// invocation.
+    (dispatch_started self)
+    (pub fn dispatch_started(self) -> std::time::Instant) {
+        match self {
+            Self::Application(ctx) => ctx.dispatch_started,
+            Self::Prefix(ctx) => ctx.dispatch_started,
+        }
+    }
+
     /// Get the author of the command message or application command.
     (author self)
     (pub fn author(self) -> &'a serenity::User) {
         match self {
             Self::Application(ctx) => &ctx.interaction.user,
-            Self::Prefix(ctx) => &ctx.msg.author,
+            Self::Prefix(ctx) => ctx.author,
         }
     }
 
@@ -303,6 +364,12 @@ context_methods! {
 
     /// If the invoked command was a subcommand, these are the parent commands, ordered top-level
     /// downwards.
+    ///
+    /// The checks of the parent commands apply to their subcommands: owner, guild, DM and NSFW
+    /// restrictions, permissions, [`crate::Command::checks`], command toggles, channel
+    /// restrictions and feature gates. Their cooldowns only apply if
+    /// [`crate::Command::inherit_cooldowns`] is set on the invoked command. See
+    /// [`crate::check_permissions_and_cooldown`].
     (parent_commands self)
     (pub fn parent_commands(self) -> &'a [&'a crate::Command<T, E>]) {
         match self {
@@ -423,6 +490,19 @@ context_methods! {
         }
     }
 
+    /// Returns [`Self::invocation_string`] with
+    /// [`crate::FrameworkOptions::redact_invocation`] applied, if set
+    ///
+    /// The framework uses this wherever it logs or stores the invocation string.
+    (redacted_invocation_string self)
+    (pub fn redacted_invocation_string(self) -> String) {
+        let invocation = self.invocation_string();
+        match self.framework().options().redact_invocation {
+            Some(redact_invocation) => redact_invocation(self.command(), invocation),
+            None => invocation,
+        }
+    }
+
     /// Stores the given value as the data for this command invocation
     ///
     /// This data is carried across the `pre_command` hook, checks, main command execution, and
@@ -455,6 +535,90 @@ context_methods! {
         }
     }
 
+    /// Returns which installations of the app authorized this invocation: a guild install, a
+    /// user install, or both.
+    ///
+    /// Prefix commands can only be invoked where the bot is present, so they always count as
+    /// guild installed.
+    (installation_context self)
+    (pub fn installation_context(self) -> crate::InstallationInfo) {
+        let mut info = crate::InstallationInfo::default();
+        match self {
+            Self::Application(ctx) => {
+                for owner in &ctx.interaction.authorizing_integration_owners.0 {
+                    match owner {
+                        serenity::AuthorizingIntegrationOwner::GuildInstall(guild_id) => {
+                            info.guild_installed = true;
+                            info.authorizing_guild_id = *guild_id;
+                        }
+                        serenity::AuthorizingIntegrationOwner::UserInstall(user_id) => {
+                            info.authorizing_user_id = Some(*user_id);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Self::Prefix(ctx) => {
+                info.guild_installed = true;
+                info.authorizing_guild_id = ctx.msg.guild_id;
+            }
+        }
+        info
+    }
+
+    /// Whether this invocation was only authorized through a user install, see
+    /// [`crate::InstallationInfo::is_user_installed`]
+    (is_user_installed self)
+    (pub fn is_user_installed(self) -> bool) {
+        self.installation_context().is_user_installed()
+    }
+
+    /// Looks up a localized response string with [`crate::FrameworkOptions::translator`], in the
+    /// locale of the invoking user if available and otherwise in the translator's default locale.
+    ///
+    /// `args` are named arguments to fill into the string, e.g.
+    /// `ctx.t("cooldown-hit", &[("seconds", "5")])`. If no translator is configured or the key is
+    /// missing, the key itself is returned.
+    (t self key args)
+    (pub fn t(self, key: &str, args: &[(&str, &str)]) -> String) {
+        self.framework()
+            .options()
+            .translator
+            .as_ref()
+            .and_then(|translator| translator.translate_or_default(self.locale(), key, args))
+            .unwrap_or_else(|| key.to_owned())
+    }
+
+    /// Schedules a job to run after `delay` in [`crate::FrameworkOptions::scheduler`]. Returns
+    /// the job ID, see [`crate::Scheduler::cancel`].
+    ///
+    /// The job outlives this invocation, so its handler only receives a [`crate::JobContext`].
+    await (schedule_in self delay job)
+    (pub async fn schedule_in(
+        self,
+        delay: std::time::Duration,
+        job: crate::Job,
+    ) -> Result<u64, crate::ScheduleError>) {
+        let scheduler = self
+            .framework()
+            .options()
+            .scheduler
+            .as_ref()
+            .ok_or(crate::ScheduleError::NoScheduler)?;
+        let http = self.serenity_context().http.clone();
+        scheduler.schedule_in(http, self.data(), delay, job).await
+    }
+
+    /// Returns the framework-managed cache for per-guild data of type `D`, see
+    /// [`crate::GuildDataCache`]
+    ///
+    /// The same cache is returned to all invocations, so use a dedicated type for each kind of
+    /// data.
+    (guild_data self)
+    (pub fn guild_data<D: Send + Sync + 'static>(self) -> std::sync::Arc<crate::GuildDataCache<D>>) {
+        self.framework().options().guild_data.cache()
+    }
+
     /// Builds a [`crate::CreateReply`] by combining the builder closure with the defaults that were
     /// pre-configured in lumi.
     ///
@@ -464,9 +628,26 @@ context_methods! {
     (reply_builder self builder)
     (pub fn reply_builder<'args>(self, mut builder: crate::CreateReply<'args>) -> crate::CreateReply<'args>) {
         let fw_options = self.framework().options();
-        builder.ephemeral = builder.ephemeral.or(Some(self.command().ephemeral));
+        builder.ephemeral = builder.ephemeral.or_else(|| Some(self.default_ephemeral()));
         builder.allowed_mentions = builder.allowed_mentions.or_else(|| fw_options.allowed_mentions.clone());
 
+        let delete_button = self.command().delete_button.unwrap_or(fw_options.delete_button);
+        // Ephemeral messages can be dismissed by the user anyway
+        let is_ephemeral = matches!(self, Self::Application(_)) && builder.ephemeral == Some(true);
+        if delete_button && !is_ephemeral {
+            builder = builder.push_framework_button(crate::dispatch::delete_button(self.author().id));
+        }
+
+        if let Some(notice) = &self.command().deprecated {
+            let identifying_name = &self.command().identifying_name;
+            // Recorded only if the warning fits, so that it's shown with the next response instead
+            if fw_options.deprecation_warnings.is_due(self.author().id, identifying_name)
+                && builder.prepend_line(&notice.to_string())
+            {
+                fw_options.deprecation_warnings.record(self.author().id, identifying_name);
+            }
+        }
+
         if let Some(callback) = fw_options.reply_callback {
             builder = callback(self, builder);
         }
@@ -545,9 +726,8 @@ impl<'a, T, E> Context<'a, T, E> {
                 }
             }
             Self::Prefix(ctx) => {
-                if let Some(action) = ctx.command.prefix_action {
-                    return action(ctx).await;
-                }
+                // Not `ctx.command.prefix_action`, which would skip a fallback action
+                return (ctx.action)(ctx).await;
             }
         }
 
@@ -634,3 +814,50 @@ impl<'a, T: Send + Sync + 'static, E> From<Context<'a, T, E>> for PartialContext
         }
     }
 }
+
+/// Which installations of the app authorized an invocation, see
+/// [`Context::installation_context`].
+///
+/// Both a guild install and a user install can be present, e.g. if a user who installed the app
+/// invokes a command in a guild which installed the app too.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstallationInfo {
+    /// Whether the app is installed in the guild (or, in DMs with the bot, the invocation was
+    /// authorized as if it were)
+    pub guild_installed: bool,
+    /// The guild which installed the app. `None` if invoked in DMs with the bot or if there's no
+    /// guild install
+    pub authorizing_guild_id: Option<serenity::GuildId>,
+    /// The user who installed the app, if the invocation was authorized by a user install
+    pub authorizing_user_id: Option<serenity::UserId>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl InstallationInfo {
+    /// Whether the invocation was only authorized through a user install.
+    ///
+    /// If true, the bot is not a member of the guild, so guild-scoped API calls (e.g. fetching
+    /// roles or sending messages outside of the interaction response) will fail.
+    pub fn is_user_installed(&self) -> bool {
+        self.authorizing_user_id.is_some() && !self.guild_installed
+    }
+
+    /// Whether the invocation was authorized through a guild install
+    pub fn is_guild_installed(&self) -> bool {
+        self.guild_installed
+    }
+
+    /// The installation contexts which authorized the invocation
+    pub fn contexts(&self) -> Vec<serenity::InstallationContext> {
+        let mut contexts = Vec::new();
+        if self.guild_installed {
+            contexts.push(serenity::InstallationContext::Guild);
+        }
+        if self.authorizing_user_id.is_some() {
+            contexts.push(serenity::InstallationContext::User);
+        }
+        contexts
+    }
+}
@@ -0,0 +1,182 @@
+//! Holds the per-variant `FrameworkError` handler registry and its builder
+
+use crate::{BoxFuture, serenity_prelude as serenity};
+
+/// A handler for [`crate::FrameworkError`]s, with the same signature as
+/// [`crate::FrameworkOptions::on_error`]
+pub type ErrorHandler<T, E> =
+    fn(crate::FrameworkError<'_, T, E>) -> BoxFuture<'_, Result<(), serenity::Error>>;
+
+/// Decides whether an error is handled by the associated [`ErrorHandler`]
+type ErrorMatcher<T, E> = fn(&crate::FrameworkError<'_, T, E>) -> bool;
+
+/// Registry of [`FrameworkError`](crate::FrameworkError) handlers, one per variant, with a
+/// fallback for all variants without a dedicated handler.
+///
+/// Create with [`ErrorHandlerBuilder`] and store in [`crate::FrameworkOptions::error_handlers`].
+/// The default [`crate::FrameworkOptions::on_error`] ([`Self::dispatch`]) then routes every error
+/// to the matching handler.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct ErrorHandlers<T, E> {
+    /// Handlers for specific variants, checked in order of registration
+    #[derivative(Debug = "ignore")]
+    handlers: Vec<(ErrorMatcher<T, E>, ErrorHandler<T, E>)>,
+    /// Handler for all errors that none of [`Self::handlers`] matched
+    #[derivative(Debug = "ignore")]
+    fallback: ErrorHandler<T, E>,
+}
+
+/// The fallback used if [`ErrorHandlerBuilder::fallback`] isn't called: [`crate::builtins::on_error`]
+fn default_fallback<T, E>(
+    error: crate::FrameworkError<'_, T, E>,
+) -> BoxFuture<'_, Result<(), serenity::Error>>
+where
+    T: Send + Sync + 'static,
+    E: std::fmt::Display + std::fmt::Debug + Send,
+{
+    Box::pin(crate::builtins::on_error(error))
+}
+
+impl<T, E> Default for ErrorHandlers<T, E>
+where
+    T: Send + Sync + 'static,
+    E: std::fmt::Display + std::fmt::Debug + Send,
+{
+    fn default() -> Self {
+        Self {
+            handlers: Vec::new(),
+            fallback: default_fallback,
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static, E: Send> ErrorHandlers<T, E> {
+    /// Routes the error to the matching handler in the
+    /// [`crate::FrameworkOptions::error_handlers`] of the framework it originated from.
+    ///
+    /// This is the default [`crate::FrameworkOptions::on_error`]. If you set a custom
+    /// [`crate::FrameworkOptions::on_error`], you can still call this function from it.
+    pub fn dispatch(
+        error: crate::FrameworkError<'_, T, E>,
+    ) -> BoxFuture<'_, Result<(), serenity::Error>> {
+        Box::pin(async move {
+            let handlers = &error.framework().options().error_handlers;
+            let handler = handlers
+                .handlers
+                .iter()
+                .find(|(matches, _)| matches(&error))
+                .map_or(handlers.fallback, |&(_, handler)| handler);
+            handler(error).await
+        })
+    }
+}
+
+/// Builds [`ErrorHandlers`], so that customizing the behavior for a single
+/// [`crate::FrameworkError`] variant doesn't require reimplementing [`crate::builtins::on_error`].
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let error_handlers = lumi::ErrorHandlerBuilder::<(), Error>::new()
+///     .on_cooldown_hit(|error| Box::pin(async move {
+///         if let lumi::FrameworkError::CooldownHit { ctx, .. } = error {
+///             ctx.say("Slow down!").await?;
+///         }
+///         Ok(())
+///     }))
+///     .build();
+///
+/// lumi::FrameworkOptions {
+///     error_handlers,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct ErrorHandlerBuilder<T, E> {
+    /// See [`ErrorHandlers::handlers`]
+    #[derivative(Debug = "ignore")]
+    handlers: Vec<(ErrorMatcher<T, E>, ErrorHandler<T, E>)>,
+    /// See [`ErrorHandlers::fallback`]. If `None`, [`default_fallback`] is used
+    #[derivative(Debug = "ignore")]
+    fallback: Option<ErrorHandler<T, E>>,
+}
+
+impl<T, E> ErrorHandlerBuilder<T, E> {
+    /// Creates a builder without any handlers
+    pub fn new() -> Self {
+        Self {
+            handlers: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    /// Sets the handler for all variants without a dedicated handler.
+    ///
+    /// Defaults to [`crate::builtins::on_error`].
+    pub fn fallback(mut self, handler: ErrorHandler<T, E>) -> Self {
+        self.fallback = Some(handler);
+        self
+    }
+
+    /// Registers a handler for all errors for which `matches` returns true. Useful for
+    /// handling a group of variants with a single handler.
+    pub fn on_matching(mut self, matches: ErrorMatcher<T, E>, handler: ErrorHandler<T, E>) -> Self {
+        self.handlers.push((matches, handler));
+        self
+    }
+
+    /// Builds the [`ErrorHandlers`]
+    pub fn build(self) -> ErrorHandlers<T, E>
+    where
+        T: Send + Sync + 'static,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        ErrorHandlers {
+            handlers: self.handlers,
+            fallback: self.fallback.unwrap_or(default_fallback),
+        }
+    }
+}
+
+/// Generates one [`ErrorHandlerBuilder`] method per [`crate::FrameworkError`] variant
+macro_rules! variant_handlers {
+    ( $( $method:ident => $variant:ident, )* ) => {
+        impl<T, E> ErrorHandlerBuilder<T, E> { $(
+            #[doc = concat!("Registers a handler for [`crate::FrameworkError::", stringify!($variant), "`]")]
+            pub fn $method(self, handler: ErrorHandler<T, E>) -> Self {
+                let matches: ErrorMatcher<T, E> =
+                    |error| matches!(error, crate::FrameworkError::$variant { .. });
+                self.on_matching(matches, handler)
+            }
+        )* }
+    };
+}
+
+variant_handlers! {
+    on_command => Command,
+    on_subcommand_required => SubcommandRequired,
+    on_command_panic => CommandPanic,
+    on_command_timeout => CommandTimeout,
+    on_argument_parse => ArgumentParse,
+    on_command_structure_mismatch => CommandStructureMismatch,
+    on_cooldown_hit => CooldownHit,
+    on_missing_bot_permissions => MissingBotPermissions,
+    on_missing_user_permissions => MissingUserPermissions,
+    on_permission_fetch_failed => PermissionFetchFailed,
+    on_not_an_owner => NotAnOwner,
+    on_guild_only => GuildOnly,
+    on_dm_only => DmOnly,
+    on_nsfw_only => NsfwOnly,
+    on_command_disabled => CommandDisabled,
+    on_wrong_channel => WrongChannel,
+    on_settings_unavailable => SettingsUnavailable,
+    on_feature_disabled => FeatureDisabled,
+    on_command_check_failed => CommandCheckFailed,
+    on_dynamic_prefix => DynamicPrefix,
+    on_unknown_command => UnknownCommand,
+    on_unknown_interaction => UnknownInteraction,
+    on_non_command_message => NonCommandMessage,
+    on_component_handler => ComponentHandler,
+    on_internal => Internal,
+}
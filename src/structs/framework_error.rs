@@ -37,9 +37,21 @@ pub enum FrameworkError<'a, T, E> {
         /// The reason the original [`Box<dyn Any + Send>`] payload isn't provided here is that it
         /// would make [`FrameworkError`] not [`Sync`] anymore.
         payload: Option<String>,
+        /// Backtrace of the panic. Only captured if [`crate::install_panic_backtrace_hook`] was
+        /// called
+        backtrace: Option<std::backtrace::Backtrace>,
         /// Command context
         ctx: crate::Context<'a, T, E>,
     },
+    /// Command action didn't finish within [`crate::Command::timeout`] or
+    /// [`crate::FrameworkOptions::command_timeout`] and was cancelled
+    #[non_exhaustive]
+    CommandTimeout {
+        /// The timeout which was exceeded
+        timeout: std::time::Duration,
+        /// General context
+        ctx: crate::Context<'a, T, E>,
+    },
     /// A command argument failed to parse from the Discord message or interaction content
     #[non_exhaustive]
     ArgumentParse {
@@ -67,6 +79,10 @@ pub enum FrameworkError<'a, T, E> {
     CooldownHit {
         /// Time until the command may be invoked for the next time in the given context
         remaining_cooldown: std::time::Duration,
+        /// The cooldown bucket which triggered, i.e. the one with the longest remaining cooldown
+        bucket: crate::CooldownBucket,
+        /// The configured cooldown duration of [`Self::CooldownHit::bucket`]
+        cooldown_duration: std::time::Duration,
         /// General context
         ctx: crate::Context<'a, T, E>,
     },
@@ -76,6 +92,10 @@ pub enum FrameworkError<'a, T, E> {
     MissingBotPermissions {
         /// Which permissions in particular the bot is lacking for this command
         missing_permissions: serenity::Permissions,
+        /// The channel the permissions were checked in
+        channel_id: serenity::GenericChannelId,
+        /// Whether the permissions are lacking guild-wide or in this channel only
+        scope: PermissionScope,
         /// General context
         ctx: crate::Context<'a, T, E>,
     },
@@ -86,6 +106,10 @@ pub enum FrameworkError<'a, T, E> {
         /// List of permissions that the user is lacking. May be None if retrieving the user's
         /// permissions failed
         missing_permissions: Option<serenity::Permissions>,
+        /// The channel the permissions were checked in
+        channel_id: serenity::GenericChannelId,
+        /// Whether the permissions are lacking guild-wide or in this channel only
+        scope: PermissionScope,
         /// General context
         ctx: crate::Context<'a, T, E>,
     },
@@ -120,12 +144,54 @@ pub enum FrameworkError<'a, T, E> {
         /// General context
         ctx: crate::Context<'a, T, E>,
     },
+    /// Command was invoked but it's disabled in this guild according to
+    /// [`crate::FrameworkOptions::command_toggles`]
+    #[non_exhaustive]
+    CommandDisabled {
+        /// General context
+        ctx: crate::Context<'a, T, E>,
+    },
+    /// Command was invoked outside the channels it's restricted to according to
+    /// [`crate::FrameworkOptions::channel_restrictions`]
+    #[non_exhaustive]
+    WrongChannel {
+        /// The channels the command may be used in
+        allowed_channels: Vec<serenity::GenericChannelId>,
+        /// General context
+        ctx: crate::Context<'a, T, E>,
+    },
+    /// [`crate::FrameworkOptions::command_toggles`] or
+    /// [`crate::FrameworkOptions::channel_restrictions`] failed to load the guild's settings and
+    /// [`crate::FrameworkOptions::settings_store_failure`] is [`crate::SettingsStoreFailure::Deny`]
+    #[non_exhaustive]
+    SettingsUnavailable {
+        /// Error which was returned by the settings store
+        error: Box<dyn std::error::Error + Send + Sync>,
+        /// General context
+        ctx: crate::Context<'a, T, E>,
+    },
+    /// Command is behind a feature flag which [`crate::FrameworkOptions::feature_gate`] didn't
+    /// enable for this invocation, see [`crate::Command::feature`]
+    #[non_exhaustive]
+    FeatureDisabled {
+        /// The feature flag of the command
+        feature: &'a str,
+        /// General context
+        ctx: crate::Context<'a, T, E>,
+    },
     /// Provided pre-command check either errored, or returned false, so command execution aborted
     #[non_exhaustive]
     CommandCheckFailed {
         /// If execution wasn't aborted because of an error but because it successfully returned
         /// false, this field is None
         error: Option<E>,
+        /// The [`crate::Command::checks`] which returned false, with their names and failure
+        /// messages.
+        ///
+        /// Contains every failed check if [`crate::FrameworkOptions::aggregate_check_failures`]
+        /// is set, otherwise at most the first one. A failing
+        /// [`crate::FrameworkOptions::command_check`] is listed as an unnamed check.
+        failed_checks: Vec<crate::Check<T, E>>,
         /// General context
         ctx: crate::Context<'a, T, E>,
     },
@@ -149,9 +215,19 @@ pub enum FrameworkError<'a, T, E> {
         /// The prefix that was recognized
         prefix: &'a str,
         /// The rest of the message (after the prefix) which was not recognized as a command
-        ///
-        /// This is a single field instead of two fields (command name and args) due to subcommands
         msg_content: &'a str,
+        /// The first word of [`Self::UnknownCommand::msg_content`], i.e. the unrecognized command
+        /// name
+        command_name: &'a str,
+        /// The rest of [`Self::UnknownCommand::msg_content`] after the command name, without
+        /// leading whitespace
+        args: &'a str,
+        /// Commands whose name or alias is similar to [`Self::UnknownCommand::command_name`],
+        /// most similar first. Useful for "did you mean" suggestions
+        ///
+        /// Doesn't include commands hidden from help or without a prefix implementation.
+        #[derivative(Debug = "ignore")]
+        candidates: Vec<&'a crate::Command<T, E>>,
         /// Framework context
         #[derivative(Debug = "ignore")]
         framework: crate::FrameworkContext<'a, T, E>,
@@ -181,6 +257,30 @@ pub enum FrameworkError<'a, T, E> {
         /// The interaction in question
         msg: &'a serenity::Message,
     },
+    /// Error occurred in a [`crate::ComponentHandler`] or one of its checks
+    #[non_exhaustive]
+    ComponentHandler {
+        /// Error which was thrown in the handler code
+        error: E,
+        /// Component handler context
+        ctx: crate::ComponentContext<'a, T, E>,
+    },
+    /// A Discord request made by the framework itself failed, outside of a command invocation, for
+    /// example deleting the response to a deleted message or responding to a framework button
+    #[non_exhaustive]
+    Internal {
+        /// The error returned by serenity
+        error: serenity::Error,
+        /// What the framework was trying to do, e.g. `"delete bot response"`
+        operation: &'static str,
+        /// The channel the request concerned, if any
+        channel_id: Option<serenity::GenericChannelId>,
+        /// The message the request concerned, if any
+        message_id: Option<serenity::MessageId>,
+        /// Framework context
+        #[derivative(Debug = "ignore")]
+        framework: crate::FrameworkContext<'a, T, E>,
+    },
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     __NonExhaustive(std::convert::Infallible),
@@ -193,6 +293,7 @@ impl<'a, T: Send + Sync + 'static, E> FrameworkError<'a, T, E> {
             Self::Command { ctx, .. } => ctx.serenity_context(),
             Self::SubcommandRequired { ctx } => ctx.serenity_context(),
             Self::CommandPanic { ctx, .. } => ctx.serenity_context(),
+            Self::CommandTimeout { ctx, .. } => ctx.serenity_context(),
             Self::ArgumentParse { ctx, .. } => ctx.serenity_context(),
             Self::CommandStructureMismatch { ctx, .. } => ctx.framework.serenity_context,
             Self::CooldownHit { ctx, .. } => ctx.serenity_context(),
@@ -203,21 +304,74 @@ impl<'a, T: Send + Sync + 'static, E> FrameworkError<'a, T, E> {
             Self::GuildOnly { ctx, .. } => ctx.serenity_context(),
             Self::DmOnly { ctx, .. } => ctx.serenity_context(),
             Self::NsfwOnly { ctx, .. } => ctx.serenity_context(),
+            Self::CommandDisabled { ctx, .. } => ctx.serenity_context(),
+            Self::WrongChannel { ctx, .. } => ctx.serenity_context(),
+            Self::SettingsUnavailable { ctx, .. } => ctx.serenity_context(),
+            Self::FeatureDisabled { ctx, .. } => ctx.serenity_context(),
             Self::CommandCheckFailed { ctx, .. } => ctx.serenity_context(),
             Self::DynamicPrefix { ctx, .. } => ctx.framework.serenity_context,
             Self::UnknownCommand { framework, .. } => framework.serenity_context,
             Self::UnknownInteraction { framework, .. } => framework.serenity_context,
             Self::NonCommandMessage { framework, .. } => framework.serenity_context,
+            Self::ComponentHandler { ctx, .. } => ctx.framework.serenity_context,
+            Self::Internal { framework, .. } => framework.serenity_context,
             Self::__NonExhaustive(unreachable) => match unreachable {},
         }
     }
 
+    /// Returns the [`crate::FrameworkContext`] of the framework this error originated from
+    pub fn framework(&self) -> crate::FrameworkContext<'a, T, E> {
+        match *self {
+            Self::Command { ctx, .. } => ctx.framework(),
+            Self::SubcommandRequired { ctx } => ctx.framework(),
+            Self::CommandPanic { ctx, .. } => ctx.framework(),
+            Self::CommandTimeout { ctx, .. } => ctx.framework(),
+            Self::ArgumentParse { ctx, .. } => ctx.framework(),
+            Self::CommandStructureMismatch { ctx, .. } => ctx.framework,
+            Self::CooldownHit { ctx, .. } => ctx.framework(),
+            Self::MissingBotPermissions { ctx, .. } => ctx.framework(),
+            Self::MissingUserPermissions { ctx, .. } => ctx.framework(),
+            Self::PermissionFetchFailed { ctx } => ctx.framework(),
+            Self::NotAnOwner { ctx, .. } => ctx.framework(),
+            Self::GuildOnly { ctx, .. } => ctx.framework(),
+            Self::DmOnly { ctx, .. } => ctx.framework(),
+            Self::NsfwOnly { ctx, .. } => ctx.framework(),
+            Self::CommandDisabled { ctx, .. } => ctx.framework(),
+            Self::WrongChannel { ctx, .. } => ctx.framework(),
+            Self::SettingsUnavailable { ctx, .. } => ctx.framework(),
+            Self::FeatureDisabled { ctx, .. } => ctx.framework(),
+            Self::CommandCheckFailed { ctx, .. } => ctx.framework(),
+            Self::DynamicPrefix { ctx, .. } => ctx.framework,
+            Self::UnknownCommand { framework, .. } => framework,
+            Self::UnknownInteraction { framework, .. } => framework,
+            Self::NonCommandMessage { framework, .. } => framework,
+            Self::ComponentHandler { ctx, .. } => ctx.framework,
+            Self::Internal { framework, .. } => framework,
+            Self::__NonExhaustive(unreachable) => match unreachable {},
+        }
+    }
+
+    /// Wraps this error into an [`ErrorEnvelope`] which carries invocation metadata, for
+    /// consistent reporting regardless of the variant and context type.
+    pub fn into_envelope(self) -> ErrorEnvelope<'a, T, E> {
+        let ctx = self.ctx();
+        ErrorEnvelope {
+            code: self.code(),
+            shard_id: self.serenity_context().shard_id,
+            qualified_name: ctx.map(|ctx| ctx.command().qualified_name.to_string()),
+            dispatch_started: ctx.map(|ctx| ctx.dispatch_started()),
+            error: self,
+            __non_exhaustive: (),
+        }
+    }
+
     /// Returns the [`crate::Context`] of this error, if it has one
     pub fn ctx(&self) -> Option<crate::Context<'a, T, E>> {
         Some(match *self {
             Self::Command { ctx, .. } => ctx,
             Self::SubcommandRequired { ctx } => ctx,
             Self::CommandPanic { ctx, .. } => ctx,
+            Self::CommandTimeout { ctx, .. } => ctx,
             Self::ArgumentParse { ctx, .. } => ctx,
             Self::CommandStructureMismatch { ctx, .. } => crate::Context::Application(ctx),
             Self::CooldownHit { ctx, .. } => ctx,
@@ -228,22 +382,223 @@ impl<'a, T: Send + Sync + 'static, E> FrameworkError<'a, T, E> {
             Self::GuildOnly { ctx, .. } => ctx,
             Self::DmOnly { ctx, .. } => ctx,
             Self::NsfwOnly { ctx, .. } => ctx,
+            Self::CommandDisabled { ctx, .. } => ctx,
+            Self::WrongChannel { ctx, .. } => ctx,
+            Self::SettingsUnavailable { ctx, .. } => ctx,
+            Self::FeatureDisabled { ctx, .. } => ctx,
             Self::CommandCheckFailed { ctx, .. } => ctx,
             Self::UnknownCommand { .. }
             | Self::UnknownInteraction { .. }
             | Self::NonCommandMessage { .. }
+            | Self::ComponentHandler { .. }
+            | Self::Internal { .. }
             | Self::DynamicPrefix { .. } => return None,
             Self::__NonExhaustive(unreachable) => match unreachable {},
         })
     }
 
     /// Calls the appropriate `on_error` function (command-specific or global) with this error
+    ///
+    /// If the `on_error` function itself fails, the failure is logged.
     pub async fn handle(self, framework_options: &crate::FrameworkOptions<T, E>) {
+        let code = self.code();
+        let command = self.ctx().map(|ctx| ctx.command().qualified_name.clone());
         let on_error = self
             .ctx()
             .and_then(|c| c.command().on_error)
             .unwrap_or(framework_options.on_error);
-        on_error(self).await;
+        if let Err(e) = on_error(self).await {
+            match command {
+                Some(command) => tracing::error!(
+                    "Failed to handle {} error in command `{}`: {}",
+                    code,
+                    command,
+                    e
+                ),
+                None => tracing::error!("Failed to handle {} error: {}", code, e),
+            }
+        }
+    }
+}
+
+impl<T, E> FrameworkError<'_, T, E> {
+    /// Returns a stable, machine-readable code identifying the kind of this error
+    ///
+    /// Useful as i18n key or metrics label, instead of matching on the [`std::fmt::Display`]
+    /// output.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Command { .. } => ErrorCode::Command,
+            Self::SubcommandRequired { .. } => ErrorCode::SubcommandRequired,
+            Self::CommandPanic { .. } => ErrorCode::CommandPanic,
+            Self::CommandTimeout { .. } => ErrorCode::CommandTimeout,
+            Self::ArgumentParse { .. } => ErrorCode::ArgumentParse,
+            Self::CommandStructureMismatch { .. } => ErrorCode::CommandStructureMismatch,
+            Self::CooldownHit { .. } => ErrorCode::CooldownHit,
+            Self::MissingBotPermissions { .. } => ErrorCode::MissingBotPermissions,
+            Self::MissingUserPermissions { .. } => ErrorCode::MissingUserPermissions,
+            Self::PermissionFetchFailed { .. } => ErrorCode::PermissionFetchFailed,
+            Self::NotAnOwner { .. } => ErrorCode::NotAnOwner,
+            Self::GuildOnly { .. } => ErrorCode::GuildOnly,
+            Self::DmOnly { .. } => ErrorCode::DmOnly,
+            Self::NsfwOnly { .. } => ErrorCode::NsfwOnly,
+            Self::CommandDisabled { .. } => ErrorCode::CommandDisabled,
+            Self::WrongChannel { .. } => ErrorCode::WrongChannel,
+            Self::SettingsUnavailable { .. } => ErrorCode::SettingsUnavailable,
+            Self::FeatureDisabled { .. } => ErrorCode::FeatureDisabled,
+            Self::CommandCheckFailed { .. } => ErrorCode::CommandCheckFailed,
+            Self::DynamicPrefix { .. } => ErrorCode::DynamicPrefix,
+            Self::UnknownCommand { .. } => ErrorCode::UnknownCommand,
+            Self::UnknownInteraction { .. } => ErrorCode::UnknownInteraction,
+            Self::NonCommandMessage { .. } => ErrorCode::NonCommandMessage,
+            Self::ComponentHandler { .. } => ErrorCode::ComponentHandler,
+            Self::Internal { .. } => ErrorCode::Internal,
+            Self::__NonExhaustive(unreachable) => match *unreachable {},
+        }
+    }
+}
+
+/// A [`FrameworkError`] together with metadata about the invocation it originated from
+///
+/// Created with [`FrameworkError::into_envelope`].
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct ErrorEnvelope<'a, T, E> {
+    /// The wrapped error
+    pub error: FrameworkError<'a, T, E>,
+    /// See [`FrameworkError::code`]
+    pub code: ErrorCode,
+    /// Shard on which the invocation was received
+    pub shard_id: serenity::ShardId,
+    /// [`crate::Command::qualified_name`] of the invoked command, if the error occurred after a
+    /// command was resolved
+    pub qualified_name: Option<String>,
+    /// When the framework started dispatching the invocation, if the error occurred after a
+    /// command was resolved
+    pub dispatch_started: Option<std::time::Instant>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl<T, E> ErrorEnvelope<'_, T, E> {
+    /// Time elapsed since the framework started dispatching the invocation
+    pub fn elapsed(&self) -> Option<std::time::Duration> {
+        self.dispatch_started.map(|started| started.elapsed())
+    }
+}
+
+/// Where the permissions of [`FrameworkError::MissingBotPermissions`] and
+/// [`FrameworkError::MissingUserPermissions`] are lacking
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PermissionScope {
+    /// At least some of the permissions aren't granted by the roles in the guild
+    Guild,
+    /// The roles grant all permissions, but the channel's permission overwrites deny some
+    ChannelOverwrites,
+    /// The guild-wide permissions aren't known, e.g. in DMs or if the guild isn't cached
+    Unknown,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Stable, machine-readable identifier of a [`FrameworkError`] variant, returned by
+/// [`FrameworkError::code`]
+///
+/// The string representation ([`Self::as_str`]) is guaranteed not to change.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// [`FrameworkError::Command`]
+    Command,
+    /// [`FrameworkError::SubcommandRequired`]
+    SubcommandRequired,
+    /// [`FrameworkError::CommandPanic`]
+    CommandPanic,
+    /// [`FrameworkError::CommandTimeout`]
+    CommandTimeout,
+    /// [`FrameworkError::ArgumentParse`]
+    ArgumentParse,
+    /// [`FrameworkError::CommandStructureMismatch`]
+    CommandStructureMismatch,
+    /// [`FrameworkError::CooldownHit`]
+    CooldownHit,
+    /// [`FrameworkError::MissingBotPermissions`]
+    MissingBotPermissions,
+    /// [`FrameworkError::MissingUserPermissions`]
+    MissingUserPermissions,
+    /// [`FrameworkError::PermissionFetchFailed`]
+    PermissionFetchFailed,
+    /// [`FrameworkError::NotAnOwner`]
+    NotAnOwner,
+    /// [`FrameworkError::GuildOnly`]
+    GuildOnly,
+    /// [`FrameworkError::DmOnly`]
+    DmOnly,
+    /// [`FrameworkError::NsfwOnly`]
+    NsfwOnly,
+    /// [`FrameworkError::CommandDisabled`]
+    CommandDisabled,
+    /// [`FrameworkError::WrongChannel`]
+    WrongChannel,
+    /// [`FrameworkError::SettingsUnavailable`]
+    SettingsUnavailable,
+    /// [`FrameworkError::FeatureDisabled`]
+    FeatureDisabled,
+    /// [`FrameworkError::CommandCheckFailed`]
+    CommandCheckFailed,
+    /// [`FrameworkError::DynamicPrefix`]
+    DynamicPrefix,
+    /// [`FrameworkError::UnknownCommand`]
+    UnknownCommand,
+    /// [`FrameworkError::UnknownInteraction`]
+    UnknownInteraction,
+    /// [`FrameworkError::NonCommandMessage`]
+    NonCommandMessage,
+    /// [`FrameworkError::ComponentHandler`]
+    ComponentHandler,
+    /// [`FrameworkError::Internal`]
+    Internal,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl ErrorCode {
+    /// Returns the code as a `SCREAMING_SNAKE_CASE` string, e.g. `COOLDOWN_HIT`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Command => "COMMAND",
+            Self::SubcommandRequired => "SUBCOMMAND_REQUIRED",
+            Self::CommandPanic => "COMMAND_PANIC",
+            Self::CommandTimeout => "COMMAND_TIMEOUT",
+            Self::ArgumentParse => "ARGUMENT_PARSE",
+            Self::CommandStructureMismatch => "COMMAND_STRUCTURE_MISMATCH",
+            Self::CooldownHit => "COOLDOWN_HIT",
+            Self::MissingBotPermissions => "MISSING_BOT_PERMS",
+            Self::MissingUserPermissions => "MISSING_USER_PERMS",
+            Self::PermissionFetchFailed => "PERMISSION_FETCH_FAILED",
+            Self::NotAnOwner => "NOT_AN_OWNER",
+            Self::GuildOnly => "GUILD_ONLY",
+            Self::DmOnly => "DM_ONLY",
+            Self::NsfwOnly => "NSFW_ONLY",
+            Self::CommandDisabled => "COMMAND_DISABLED",
+            Self::WrongChannel => "WRONG_CHANNEL",
+            Self::SettingsUnavailable => "SETTINGS_UNAVAILABLE",
+            Self::FeatureDisabled => "FEATURE_DISABLED",
+            Self::CommandCheckFailed => "COMMAND_CHECK_FAILED",
+            Self::DynamicPrefix => "DYNAMIC_PREFIX",
+            Self::UnknownCommand => "UNKNOWN_COMMAND",
+            Self::UnknownInteraction => "UNKNOWN_INTERACTION",
+            Self::NonCommandMessage => "NON_COMMAND_MESSAGE",
+            Self::ComponentHandler => "COMPONENT_HANDLER",
+            Self::Internal => "INTERNAL",
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -292,9 +647,15 @@ impl<T: Send + Sync + 'static, E: std::fmt::Display> std::fmt::Display
                     full_command_name!(ctx)
                 )
             }
-            Self::CommandPanic { ctx, payload: _ } => {
+            Self::CommandPanic { ctx, .. } => {
                 write!(f, "panic in command `{}`", full_command_name!(ctx))
             }
+            Self::CommandTimeout { timeout, ctx } => write!(
+                f,
+                "command `{}` timed out after {:?}",
+                full_command_name!(ctx),
+                timeout
+            ),
             Self::ArgumentParse {
                 error: _,
                 input,
@@ -313,29 +674,38 @@ impl<T: Send + Sync + 'static, E: std::fmt::Display> std::fmt::Display
             ),
             Self::CooldownHit {
                 remaining_cooldown,
+                bucket,
                 ctx,
+                ..
             } => write!(
                 f,
-                "cooldown hit in command `{}` ({:?} remaining)",
+                "{} cooldown hit in command `{}` ({:?} remaining)",
+                bucket,
                 full_command_name!(ctx),
                 remaining_cooldown
             ),
             Self::MissingBotPermissions {
                 missing_permissions,
+                channel_id,
+                scope: _,
                 ctx,
             } => write!(
                 f,
-                "bot is missing permisions ({}) to execute command `{}`",
+                "bot is missing permisions ({}) in channel {} to execute command `{}`",
                 missing_permissions,
+                channel_id,
                 full_command_name!(ctx),
             ),
             Self::MissingUserPermissions {
                 missing_permissions,
+                channel_id,
+                scope: _,
                 ctx,
             } => write!(
                 f,
-                "user is or may be missing permisions ({:?}) to execute command `{}`",
+                "user is or may be missing permisions ({:?}) in channel {} to execute command `{}`",
                 missing_permissions,
+                channel_id,
                 full_command_name!(ctx),
             ),
             Self::PermissionFetchFailed { ctx } => write!(
@@ -363,11 +733,46 @@ impl<T: Send + Sync + 'static, E: std::fmt::Display> std::fmt::Display
                 "nsfw-only command `{}` cannot run in non-nsfw channels",
                 full_command_name!(ctx)
             ),
-            Self::CommandCheckFailed { error: _, ctx } => write!(
+            Self::CommandDisabled { ctx } => write!(
                 f,
-                "pre-command check for command `{}` either denied access or errored",
+                "command `{}` is disabled in this guild",
                 full_command_name!(ctx)
             ),
+            Self::WrongChannel { ctx, .. } => write!(
+                f,
+                "command `{}` cannot run in this channel",
+                full_command_name!(ctx)
+            ),
+            Self::SettingsUnavailable { ctx, .. } => write!(
+                f,
+                "failed to load the guild settings for command `{}`",
+                full_command_name!(ctx)
+            ),
+            Self::FeatureDisabled { feature, ctx } => write!(
+                f,
+                "command `{}` is behind the disabled feature `{}`",
+                full_command_name!(ctx),
+                feature
+            ),
+            Self::CommandCheckFailed {
+                error: _,
+                failed_checks,
+                ctx,
+            } => {
+                write!(
+                    f,
+                    "pre-command check for command `{}` either denied access or errored",
+                    full_command_name!(ctx)
+                )?;
+                let names = failed_checks
+                    .iter()
+                    .filter_map(|check| check.name.as_deref())
+                    .collect::<Vec<_>>();
+                if !names.is_empty() {
+                    write!(f, " (failed checks: {})", names.join(", "))?;
+                }
+                Ok(())
+            }
             Self::DynamicPrefix {
                 error: _,
                 ctx: _,
@@ -392,6 +797,29 @@ impl<T: Send + Sync + 'static, E: std::fmt::Display> std::fmt::Display
                     msg.channel_id, msg.id
                 )
             }
+            Self::ComponentHandler { ctx, .. } => {
+                write!(
+                    f,
+                    "error in component handler for custom ID `{}`",
+                    ctx.interaction.data.custom_id
+                )
+            }
+            Self::Internal {
+                error,
+                operation,
+                channel_id,
+                message_id,
+                ..
+            } => {
+                write!(f, "failed to {}", operation)?;
+                if let Some(channel_id) = channel_id {
+                    write!(f, " in channel {}", channel_id)?;
+                }
+                if let Some(message_id) = message_id {
+                    write!(f, " (message ID {})", message_id)?;
+                }
+                write!(f, ": {}", error)
+            }
             Self::__NonExhaustive(unreachable) => match *unreachable {},
         }
     }
@@ -407,6 +835,7 @@ where
             Self::Command { error, .. } => Some(error),
             Self::SubcommandRequired { .. } => None,
             Self::CommandPanic { .. } => None,
+            Self::CommandTimeout { .. } => None,
             Self::ArgumentParse { error, .. } => Some(&**error),
             Self::CommandStructureMismatch { .. } => None,
             Self::CooldownHit { .. } => None,
@@ -417,11 +846,17 @@ where
             Self::GuildOnly { .. } => None,
             Self::DmOnly { .. } => None,
             Self::NsfwOnly { .. } => None,
+            Self::CommandDisabled { .. } => None,
+            Self::WrongChannel { .. } => None,
+            Self::SettingsUnavailable { error, .. } => Some(&**error),
+            Self::FeatureDisabled { .. } => None,
             Self::CommandCheckFailed { error, .. } => error.as_ref().map(|x| x as _),
             Self::DynamicPrefix { error, .. } => Some(error),
             Self::UnknownCommand { .. } => None,
             Self::UnknownInteraction { .. } => None,
             Self::NonCommandMessage { error, .. } => Some(error),
+            Self::ComponentHandler { error, .. } => Some(error),
+            Self::Internal { error, .. } => Some(error),
             Self::__NonExhaustive(unreachable) => match *unreachable {},
         }
     }
@@ -7,10 +7,35 @@ use crate::{BoxFuture, serenity_prelude as serenity};
 #[derivative(Debug(bound = ""))]
 pub struct FrameworkOptions<T, E> {
     /// List of commands in the framework
-    pub commands: Vec<crate::Command<T, E>>,
+    ///
+    /// Commands are reference-counted, so that help menus, analytics or other long-lived tasks can
+    /// hold onto them without borrowing the framework options. Wrap commands with `.into()` or
+    /// [`std::sync::Arc::new`]. If you clone an `Arc` before the framework is initialized, call
+    /// [`crate::set_qualified_names`] and [`crate::set_default_contexts`] on the command first,
+    /// because shared commands can't be modified anymore. Otherwise, the framework logs an error
+    /// on startup and such subcommands keep their unqualified names.
+    pub commands: Vec<std::sync::Arc<crate::Command<T, E>>>,
+    /// Handlers for component interactions (buttons, select menus) by custom ID, see
+    /// [`crate::ComponentHandler`]
+    ///
+    /// Empty by default.
+    pub component_handlers: Vec<crate::ComponentHandler<T, E>>,
     /// Provide a callback to be invoked when any user code yields an error.
+    ///
+    /// By default, routes the error to the matching handler in [`Self::error_handlers`].
+    ///
+    /// If the callback returns an error, e.g. because the error message couldn't be sent, it's
+    /// logged by the framework.
     #[derivative(Debug = "ignore")]
-    pub on_error: fn(crate::FrameworkError<'_, T, E>) -> BoxFuture<'_, ()>,
+    pub on_error: fn(crate::FrameworkError<'_, T, E>) -> BoxFuture<'_, Result<(), serenity::Error>>,
+    /// Per-variant error handlers used by the default [`Self::on_error`]
+    ///
+    /// See [`crate::ErrorHandlerBuilder`].
+    pub error_handlers: crate::ErrorHandlers<T, E>,
+    /// Transforms command arguments before they're passed to the command, e.g. to trim strings
+    ///
+    /// See [`crate::ArgumentTransformers`].
+    pub argument_transformers: crate::ArgumentTransformers<T, E>,
     /// Called before every command
     #[derivative(Debug = "ignore")]
     pub pre_command: fn(crate::Context<'_, T, E>) -> BoxFuture<'_, ()>,
@@ -23,12 +48,50 @@ pub struct FrameworkOptions<T, E> {
     /// If individual commands add their own check, both callbacks are run and must return true.
     #[derivative(Debug = "ignore")]
     pub command_check: Option<fn(crate::Context<'_, T, E>) -> BoxFuture<'_, Result<bool, E>>>,
+    /// Decides which commands are disabled in which guild, see [`crate::CommandToggleStore`]
+    ///
+    /// None (all commands enabled) by default.
+    #[derivative(Debug = "ignore")]
+    pub command_toggles: Option<std::sync::Arc<dyn crate::CommandToggleStore>>,
+    /// Decides which commands may be used in which channels, see
+    /// [`crate::ChannelRestrictionStore`]
+    ///
+    /// None (no restrictions) by default.
+    #[derivative(Debug = "ignore")]
+    pub channel_restrictions: Option<std::sync::Arc<dyn crate::ChannelRestrictionStore>>,
+    /// What to do if [`Self::command_toggles`] or [`Self::channel_restrictions`] fail to load the
+    /// guild's settings
+    ///
+    /// [`crate::SettingsStoreFailure::Allow`] by default.
+    pub settings_store_failure: crate::SettingsStoreFailure,
+    /// Decides which feature flags are enabled for which invocations, see [`crate::FeatureGate`]
+    ///
+    /// None by default. Commands with a [`crate::Command::feature`] are disabled if unset.
+    #[derivative(Debug = "ignore")]
+    pub feature_gate: Option<std::sync::Arc<dyn crate::FeatureGate<T, E>>>,
     /// If set to true, skips command checks if command was issued by [`FrameworkOptions::owners`]
     pub skip_checks_for_owners: bool,
+    /// If set to true, [`FrameworkOptions::owners`] aren't subject to cooldowns, and their
+    /// invocations don't start cooldowns. Unlike [`Self::skip_checks_for_owners`], permission
+    /// checks still apply.
+    ///
+    /// False by default.
+    pub owners_bypass_cooldowns: bool,
+    /// If set to true, all command checks are evaluated even after one returned false, so that
+    /// [`crate::FrameworkError::CommandCheckFailed::failed_checks`] lists every failed check.
+    ///
+    /// Checks which return an error still abort immediately.
+    pub aggregate_check_failures: bool,
     /// Default set of allowed mentions to use for all responses
     ///
     /// By default, user pings are allowed and role pings and everyone pings are filtered
     pub allowed_mentions: Option<serenity::CreateAllowedMentions<'static>>,
+    /// Decides per invocation whether responses are ephemeral unless set explicitly, overriding
+    /// [`crate::Command::ephemeral`]. See [`crate::Context::default_ephemeral`].
+    ///
+    /// Useful to respond publicly in bot channels and ephemerally elsewhere. None by default.
+    #[derivative(Debug = "ignore")]
+    pub ephemeral_callback: Option<fn(crate::Context<'_, T, E>) -> bool>,
     /// Invoked before every message sent using [`crate::Context::say`] or [`crate::Context::send`]
     ///
     /// Allows you to modify every outgoing message in a central place
@@ -39,16 +102,80 @@ pub struct FrameworkOptions<T, E> {
             crate::CreateReply<'arg>,
         ) -> crate::CreateReply<'arg>,
     >,
+    /// Invoked after every message sent using [`crate::Context::say`], [`crate::Context::send`]
+    /// or [`crate::Context::followup`], with the handle to the sent message
+    ///
+    /// Allows you to process every response in a central place, for example to log message IDs or
+    /// to attach delete controls. Not invoked for autocomplete contexts. None by default.
+    #[derivative(Debug = "ignore")]
+    pub after_reply: Option<
+        for<'ctx, 'handle> fn(
+            crate::Context<'ctx, T, E>,
+            &'handle crate::ReplyHandle<'ctx>,
+        ) -> BoxFuture<'handle, ()>,
+    >,
+    /// Masks sensitive arguments like tokens or email addresses in invocation strings (see
+    /// [`crate::Context::invocation_string`]) before the framework logs or stores them, e.g. in
+    /// [`crate::InvocationRecord`]s or argument parse diagnostics
+    ///
+    /// Called with the invoked command and the full invocation string. None by default.
+    #[derivative(Debug = "ignore")]
+    pub redact_invocation: Option<fn(&crate::Command<T, E>, String) -> String>,
+    /// Invoked when sending a response with [`crate::Context::say`], [`crate::Context::send`] or
+    /// [`crate::Context::followup`] is delayed by a Discord rate limit, e.g. to feed metrics
+    ///
+    /// Requires installing [`crate::rate_limit_callback`] in serenity's ratelimiter. None by
+    /// default.
+    #[derivative(Debug = "ignore")]
+    pub on_rate_limit:
+        Option<fn(crate::Context<'_, T, E>, crate::RateLimitEvent) -> BoxFuture<'_, ()>>,
+    /// If set, the invoking user is told when a response is delayed by a rate limit for at least
+    /// this long
+    ///
+    /// Prefix invocations get an hourglass reaction. Application commands aren't warned, because
+    /// any message to the user would be held back by the same rate limit. Requires installing
+    /// [`crate::rate_limit_callback`] in serenity's ratelimiter. None by default.
+    pub rate_limit_warning: Option<std::time::Duration>,
+    /// Whether to DM the invoking user if a prefix command response can't be sent because the bot
+    /// lacks permissions in the channel
+    ///
+    /// [`crate::DmFallback::Disabled`] by default.
+    pub dm_fallback: crate::DmFallback,
+    /// Maximum duration a command action may run before it's cancelled and
+    /// [`crate::FrameworkError::CommandTimeout`] is raised
+    ///
+    /// Can be overridden per command with [`crate::Command::timeout`]. None (no limit) by default.
+    pub command_timeout: Option<std::time::Duration>,
     /// If `true`, disables automatic cooldown handling before every command invocation.
     ///
     /// Useful for implementing custom cooldown behavior. See [`crate::Command::cooldowns`] and
     /// the methods on [`crate::Cooldowns`] for how to do that.
     pub manual_cooldowns: bool,
+    /// Whether to add a 🗑 button to all responses, see [`crate::Command::delete_button`]
+    ///
+    /// Can be overridden per command. False by default.
+    pub delete_button: bool,
     /// If `true`, changes behavior of guild_only command check to abort execution if the guild is
     /// not in cache.
     ///
     /// **If `cache` feature is disabled, this has no effect!**
     pub require_cache_for_guild_check: bool,
+    /// How to determine the invoking member's permissions for
+    /// [`crate::Command::required_permissions`] if an application command interaction doesn't
+    /// include them, for example for some invocations of user-installed apps
+    ///
+    /// [`crate::PermissionFallback::Cache`] by default.
+    pub permission_fallback: crate::PermissionFallback,
+    /// Installation contexts of the commands which don't set [`crate::Command::install_context`]
+    ///
+    /// For example, set this to guild and user installs when building a user-installable app,
+    /// instead of setting it on every command. None (Discord's default) by default.
+    pub default_install_context: Option<Vec<serenity::InstallationContext>>,
+    /// Interaction contexts of the commands which don't set
+    /// [`crate::Command::interaction_context`]
+    ///
+    /// None (Discord's default) by default.
+    pub default_interaction_context: Option<Vec<serenity::InteractionContext>>,
     /// Prefix command specific options.
     pub prefix_options: crate::PrefixFrameworkOptions<T, E>,
     /// User IDs which are allowed to use owners_only commands
@@ -65,6 +192,73 @@ pub struct FrameworkOptions<T, E> {
     ///
     /// None by default.
     pub initialized_team_roles: Option<Vec<serenity::TeamMemberRole>>,
+    /// Resolves the localized strings returned by [`crate::Context::t`], for example
+    /// [`crate::Translations`] with the `fluent` feature
+    ///
+    /// None by default.
+    #[derivative(Debug = "ignore")]
+    pub translator: Option<std::sync::Arc<dyn crate::Translator>>,
+    /// Stores invocations of [`crate::Command::rerun_button`] commands until the button is clicked
+    pub rerun_tracker: crate::RerunTracker,
+    /// Prevents duplicate gateway events from running a command twice, see
+    /// [`crate::RecentInvocations`]
+    ///
+    /// Enabled by default. Set to None to run every event, e.g. if events are already deduplicated
+    /// upstream.
+    pub recent_invocations: Option<crate::RecentInvocations>,
+    /// Limits how often users are warned about deprecated commands, see
+    /// [`crate::Command::deprecated`]
+    pub deprecation_warnings: crate::DeprecationWarnings,
+    /// Runs background jobs scheduled with [`crate::Context::schedule_in`]
+    ///
+    /// None by default.
+    pub scheduler: Option<crate::Scheduler<T>>,
+    /// Receives a record of every completed command invocation, for an audit trail
+    ///
+    /// None by default. See [`crate::JsonLinesAuditSink`] for a file-based sink.
+    #[derivative(Debug = "ignore")]
+    pub audit_sink: Option<std::sync::Arc<dyn crate::AuditSink>>,
+    /// Counts command invocations, see [`crate::FrameworkContext::usage_stats`]
+    ///
+    /// None (disabled) by default.
+    pub usage_stats: Option<crate::UsageStats>,
+    /// Typed per-guild data shared across commands, see [`crate::Context::guild_data`]
+    ///
+    /// Empty by default, without eviction. Use [`crate::GuildData::with_ttl`] to evict data that
+    /// wasn't accessed for a while.
+    pub guild_data: crate::GuildData,
+    /// Updates the bot's presence periodically, see [`crate::PresenceRotation`]
+    ///
+    /// None by default.
+    pub presence_rotation: Option<crate::PresenceRotation>,
+    /// Called when the bot received the ready event, after lumi processed it, for example to set
+    /// the presence or start background tasks
+    ///
+    /// None by default.
+    #[derivative(Debug = "ignore")]
+    pub on_ready: Option<
+        for<'a> fn(crate::FrameworkContext<'a, T, E>, &'a serenity::Ready) -> BoxFuture<'a, ()>,
+    >,
+    /// Called when a shard connected to the gateway, including reconnects
+    ///
+    /// None by default.
+    #[derivative(Debug = "ignore")]
+    pub on_shard_connect:
+        Option<fn(crate::FrameworkContext<'_, T, E>, serenity::ShardId) -> BoxFuture<'_, ()>>,
+    /// Called when a shard lost its connection to the gateway
+    ///
+    /// None by default.
+    #[derivative(Debug = "ignore")]
+    pub on_shard_disconnect:
+        Option<fn(crate::FrameworkContext<'_, T, E>, serenity::ShardId) -> BoxFuture<'_, ()>>,
+    /// Called when the cache received the data of all guilds after startup, with their IDs, for
+    /// example to warm up caches of your own
+    ///
+    /// None by default.
+    #[derivative(Debug = "ignore")]
+    pub on_cache_ready: Option<
+        for<'a> fn(crate::FrameworkContext<'a, T, E>, &'a [serenity::GuildId]) -> BoxFuture<'a, ()>,
+    >,
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     pub __non_exhaustive: (),
@@ -78,17 +272,20 @@ where
     fn default() -> Self {
         Self {
             commands: Vec::new(),
-            on_error: |error| {
-                Box::pin(async move {
-                    if let Err(e) = crate::builtins::on_error(error).await {
-                        tracing::error!("Error while handling error: {}", e);
-                    }
-                })
-            },
+            component_handlers: Vec::new(),
+            on_error: crate::ErrorHandlers::dispatch,
+            error_handlers: Default::default(),
+            argument_transformers: Default::default(),
             pre_command: |_| Box::pin(async {}),
             post_command: |_| Box::pin(async {}),
             command_check: None,
+            command_toggles: None,
+            channel_restrictions: None,
+            settings_store_failure: crate::SettingsStoreFailure::Allow,
+            feature_gate: None,
             skip_checks_for_owners: false,
+            owners_bypass_cooldowns: false,
+            aggregate_check_failures: false,
             allowed_mentions: Some(
                 // Only support direct user pings by default
                 serenity::CreateAllowedMentions::default()
@@ -96,13 +293,37 @@ where
                     // https://github.com/serenity-rs/lumi/issues/176
                     .replied_user(true),
             ),
+            ephemeral_callback: None,
             reply_callback: None,
+            after_reply: None,
+            redact_invocation: None,
+            on_rate_limit: None,
+            rate_limit_warning: None,
+            dm_fallback: crate::DmFallback::Disabled,
+            command_timeout: None,
             manual_cooldowns: false,
+            delete_button: false,
             require_cache_for_guild_check: false,
+            permission_fallback: crate::PermissionFallback::Cache,
+            default_install_context: None,
+            default_interaction_context: None,
             prefix_options: Default::default(),
             owners: Default::default(),
             initialize_owners: true,
             initialized_team_roles: None,
+            translator: None,
+            rerun_tracker: crate::RerunTracker::new(),
+            recent_invocations: Some(crate::RecentInvocations::new()),
+            deprecation_warnings: crate::DeprecationWarnings::default(),
+            scheduler: None,
+            audit_sink: None,
+            usage_stats: None,
+            guild_data: crate::GuildData::new(),
+            presence_rotation: None,
+            on_ready: None,
+            on_shard_connect: None,
+            on_shard_disconnect: None,
+            on_cache_ready: None,
             __non_exhaustive: (),
         }
     }
@@ -9,9 +9,18 @@ pub use context::*;
 mod framework_options;
 pub use framework_options::*;
 
+mod config;
+pub use config::*;
+
 mod command;
 pub use command::*;
 
+mod command_group;
+pub use command_group::*;
+
+mod check;
+pub use check::*;
+
 mod prefix;
 pub use prefix::*;
 
@@ -21,6 +30,15 @@ pub use slash::*;
 mod framework_error;
 pub use framework_error::*;
 
+mod error_handlers;
+pub use error_handlers::*;
+
+mod argument_transformers;
+pub use argument_transformers::*;
+
+mod translator;
+pub use translator::*;
+
 /// A type alias for `&'static str` or `String`
 pub(crate) type CowStr = Cow<'static, str>;
 
@@ -4,6 +4,57 @@ use std::borrow::Cow;
 
 use crate::{BoxFuture, serenity_prelude as serenity};
 
+/// Both forms of a bot mention, `<@USER_ID>` and `<@!USER_ID>`, precomputed so that detecting a
+/// mention prefix is a cheap string comparison
+#[derive(Clone, Debug)]
+pub struct MentionPrefixes {
+    /// `<@USER_ID>`
+    plain: String,
+    /// `<@!USER_ID>`, the legacy nickname mention form
+    nickname: String,
+}
+
+impl MentionPrefixes {
+    /// Precomputes the mention prefixes of the given bot user
+    pub fn new(bot_id: serenity::UserId) -> Self {
+        Self {
+            plain: format!("<@{bot_id}>"),
+            nickname: format!("<@!{bot_id}>"),
+        }
+    }
+
+    /// If the message content starts with a bot mention, splits it into the mention and the
+    /// remaining content
+    pub fn strip<'a>(&self, content: &'a str) -> Option<(&'a str, &'a str)> {
+        [&self.plain, &self.nickname]
+            .into_iter()
+            .find(|mention| content.starts_with(mention.as_str()))
+            .map(|mention| content.split_at(mention.len()))
+    }
+
+    /// Removes all bot mentions from the content, along with the whitespace they leave behind.
+    /// Returns `None` if the content doesn't mention the bot.
+    pub fn remove_all(&self, content: &str) -> Option<String> {
+        if !content.contains(&self.plain) && !content.contains(&self.nickname) {
+            return None;
+        }
+
+        let content = content.replace(&self.nickname, &self.plain);
+        let mut stripped = String::with_capacity(content.len());
+        for piece in content.split(self.plain.as_str()) {
+            if stripped.is_empty() || stripped.ends_with(char::is_whitespace) {
+                stripped.push_str(piece.trim_start());
+            } else {
+                if !piece.is_empty() && !piece.starts_with(char::is_whitespace) {
+                    stripped.push(' ');
+                }
+                stripped.push_str(piece);
+            }
+        }
+        Some(stripped)
+    }
+}
+
 /// The event that triggered a prefix command execution
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MessageDispatchTrigger {
@@ -26,6 +77,9 @@ pub enum MessageDispatchTrigger {
 pub struct PrefixContext<'a, T, E> {
     /// The invoking user message
     pub msg: &'a serenity::Message,
+    /// The user who invoked the command. Same as the author of [`Self::msg`], unless the message
+    /// was proxied, see [`crate::PrefixFrameworkOptions::proxied_message_resolver`]
+    pub author: &'a serenity::User,
     /// Prefix used by the user to invoke this command
     pub prefix: &'a str,
     /// Command name used by the user to invoke this command
@@ -34,6 +88,12 @@ pub struct PrefixContext<'a, T, E> {
     pub args: &'a str,
     /// The modifier characters this command captured.
     pub mod_chars: &'a str,
+    /// The raw input of each parameter by name, filled in once the arguments are parsed
+    ///
+    /// The prefix counterpart to [`crate::ApplicationContext::args`]. Parameters that consumed no
+    /// input, e.g. omitted optional parameters, map to `None`. Use [`Self::parameter_inputs()`] to
+    /// read it.
+    pub parameter_inputs: &'a std::sync::OnceLock<Vec<(&'static str, Option<&'a str>)>>,
     /// Read-only reference to the framework
     ///
     /// Useful if you need the list of commands, for example for a custom help command
@@ -47,6 +107,8 @@ pub struct PrefixContext<'a, T, E> {
     pub invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
     /// How this command invocation was triggered
     pub trigger: MessageDispatchTrigger,
+    /// When the framework started dispatching this invocation
+    pub dispatch_started: std::time::Instant,
     /// The function that is called to execute the actual command
     #[derivative(Debug = "ignore")]
     pub action: fn(
@@ -64,11 +126,40 @@ impl<T, E> Clone for PrefixContext<'_, T, E> {
     }
 }
 impl<T, E> Copy for PrefixContext<'_, T, E> {}
+impl<'a, T, E> PrefixContext<'a, T, E> {
+    /// Returns the raw input of each parameter by name, see [`Self::parameter_inputs`]
+    ///
+    /// Empty until the arguments are parsed, i.e. in checks and before hooks.
+    pub fn parameter_inputs(&self) -> &[(&'static str, Option<&'a str>)] {
+        self.parameter_inputs.get().map_or(&[], Vec::as_slice)
+    }
+}
 impl<T, E> crate::_GetGenerics for PrefixContext<'_, T, E> {
     type T = T;
     type E = E;
 }
 
+/// An invocation returned by [`PrefixFrameworkOptions::command_resolver`]
+#[derive(Clone, Debug)]
+pub struct ResolvedInvocation {
+    /// The text to dispatch in place of the unrecognized text after the prefix, starting with the
+    /// command name, e.g. `tag show hello`
+    pub content: String,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl ResolvedInvocation {
+    /// Creates an invocation of the given command name and arguments, e.g. `tag show hello`
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
 /// Possible ways to define a command prefix
 #[derive(Clone, Debug)]
 pub enum Prefix {
@@ -124,9 +215,20 @@ pub struct PrefixFrameworkOptions<T, E> {
     >,
     /// Treat a bot mention (a ping) like a prefix
     pub mention_as_prefix: bool,
+    /// Precomputed mention prefixes of the bot, used for [`Self::mention_as_prefix`]
+    ///
+    /// Filled in by the framework as soon as the bot's user ID is known, so there's no need to
+    /// set this.
+    pub mention_prefixes: std::sync::OnceLock<MentionPrefixes>,
+    /// Whether to remove mentions of the bot from the invocation after the prefix, so that for
+    /// example `@Bot help @Bot music` is parsed like `@Bot help music`
+    ///
+    /// Users often mention the bot again when it's used as prefix (see
+    /// [`Self::mention_as_prefix`]), which confuses argument parsers. `false` by default.
+    pub strip_self_mentions: bool,
     /// If Some, the framework will react to message edits by editing the corresponding bot response
     /// with the new result.
-    pub edit_tracker: Option<std::sync::Arc<std::sync::RwLock<crate::EditTracker>>>,
+    pub edit_tracker: Option<std::sync::Arc<crate::EditTracker>>,
     /// If the user makes a typo in their message and a subsequent edit creates a valid invocation,
     /// the bot will execute the command if this attribute is set. [`Self::edit_tracker`] does not
     /// need to be set for this.
@@ -145,11 +247,75 @@ pub struct PrefixFrameworkOptions<T, E> {
     /// Whether commands in messages emitted by this bot itself should be executed as well.
     pub execute_self_messages: bool,
     /// Whether to ignore messages from bots for command invoking. Default `true`
+    ///
+    /// Can be overridden per command with [`crate::Command::allow_bot_invocations`].
     pub ignore_bots: bool,
     /// Whether to ignore commands contained within thread creation messages. Default `true`
     pub ignore_thread_creation: bool,
+    /// Whether to ignore messages sent by webhooks. Default `false`
+    ///
+    /// Takes precedence over [`Self::proxied_message_resolver`].
+    pub ignore_webhooks: bool,
+    /// Whether to ignore system messages like boost notices or pin notifications, which can
+    /// contain user-controlled text like thread or channel names. Default `true`
+    ///
+    /// Thread creation messages are governed by [`Self::ignore_thread_creation`] instead.
+    pub ignore_system_messages: bool,
+    /// Callback to decide whether a message may invoke commands, evaluated before the prefix is
+    /// stripped. Return `false` to ignore the message
+    ///
+    /// Runs after the built-in filters like [`Self::ignore_bots`] and for every remaining
+    /// message, so keep it cheap. None by default.
+    #[derivative(Debug = "ignore")]
+    pub message_filter: Option<fn(crate::FrameworkContext<'_, T, E>, &serenity::Message) -> bool>,
     /// Whether command names should be compared case-insensitively.
     pub case_insensitive_commands: bool,
+    /// How long a [`crate::Command::broadcast_typing`] command may run before the typing
+    /// indicator is shown, so that fast commands don't flash it. Can be overridden per command
+    /// with [`crate::Command::broadcast_typing_delay`].
+    ///
+    /// Zero (show immediately) by default.
+    pub broadcast_typing_delay: std::time::Duration,
+    /// Callback invoked for messages sent by webhooks, to find the "effective author" on whose
+    /// behalf a proxy bot like PluralKit sent the message.
+    ///
+    /// If it returns a user, that user is used in place of the webhook for
+    /// [`crate::Context::author`], permission checks, cooldowns and
+    /// [`Self::ignore_bots`]. Errors, e.g. from the proxy bot's API, should be handled by the
+    /// callback itself; return `None` to treat the message like any other webhook message.
+    ///
+    /// Only called for messages which start with a prefix.
+    #[derivative(Debug = "ignore")]
+    pub proxied_message_resolver: Option<
+        for<'a> fn(
+            &'a crate::FrameworkContext<'a, T, E>,
+            &'a serenity::Message,
+        ) -> crate::BoxFuture<'a, Option<serenity::User>>,
+    >,
+    /// Custom aliases for prefix commands configured per guild, see [`crate::CommandAliasStore`]
+    ///
+    /// None by default.
+    #[derivative(Debug = "ignore")]
+    pub command_aliases: Option<std::sync::Arc<dyn crate::CommandAliasStore>>,
+    /// Callback consulted when the text after the prefix doesn't match any command, which can
+    /// rewrite it into the invocation of an existing command
+    ///
+    /// Consulted after [`Self::command_aliases`].
+    ///
+    /// Enables dynamic commands like database-backed tags or aliases configured per guild, e.g.
+    /// resolving `~hello` to `tag show hello`. The rewritten invocation is dispatched normally,
+    /// including checks and cooldowns of the resolved command. Return `None` to treat the message
+    /// as unknown command.
+    ///
+    /// Called with the text after the prefix. None by default.
+    #[derivative(Debug = "ignore")]
+    pub command_resolver: Option<
+        for<'a> fn(
+            &'a crate::FrameworkContext<'a, T, E>,
+            &'a serenity::Message,
+            &'a str,
+        ) -> crate::BoxFuture<'a, Option<ResolvedInvocation>>,
+    >,
     /// Callback for all non-command messages. Useful if you want to run code on any message that
     /// is not a command
     pub non_command_message: Option<
@@ -180,13 +346,22 @@ impl<T, E> Default for PrefixFrameworkOptions<T, E> {
             dynamic_prefix: None,
             stripped_dynamic_prefix: None,
             mention_as_prefix: true,
+            mention_prefixes: std::sync::OnceLock::new(),
+            strip_self_mentions: false,
             edit_tracker: None,
             execute_untracked_edits: true,
             ignore_edits_if_not_yet_responded: false,
             execute_self_messages: false,
             ignore_bots: true,
             ignore_thread_creation: true,
+            ignore_webhooks: false,
+            ignore_system_messages: true,
+            message_filter: None,
             case_insensitive_commands: true,
+            broadcast_typing_delay: std::time::Duration::ZERO,
+            proxied_message_resolver: None,
+            command_aliases: None,
+            command_resolver: None,
             non_command_message: None,
             // help_when_mentioned: true,
             // help_commmand: None,
@@ -195,3 +370,19 @@ impl<T, E> Default for PrefixFrameworkOptions<T, E> {
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_remove_all_mentions() {
+    let mentions = MentionPrefixes::new(serenity::UserId::new(123));
+    assert_eq!(mentions.remove_all("help music"), None);
+    assert_eq!(
+        mentions.remove_all("help <@123> music").as_deref(),
+        Some("help music")
+    );
+    assert_eq!(
+        mentions.remove_all("<@!123> help<@123>music").as_deref(),
+        Some("help music")
+    );
+    assert_eq!(mentions.remove_all("help <@1234>"), None);
+}
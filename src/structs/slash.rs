@@ -32,6 +32,9 @@ pub struct ApplicationContext<'a, T, E> {
     /// Keeps track of whether an initial response has been sent.
     ///
     /// Discord requires different HTTP endpoints for initial and additional responses.
+    ///
+    /// Accessed with [`std::sync::atomic::Ordering::Relaxed`], because the flag doesn't guard any
+    /// other data.
     pub has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
     /// Read-only reference to the framework
     ///
@@ -44,6 +47,8 @@ pub struct ApplicationContext<'a, T, E> {
     pub command: &'a crate::Command<T, E>,
     /// Custom user data carried across a single command invocation
     pub invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    /// When the framework started dispatching this invocation
+    pub dispatch_started: std::time::Instant,
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     pub __non_exhaustive: (),
@@ -64,7 +69,7 @@ impl<T, E> ApplicationContext<'_, T, E> {
     pub async fn defer_response(&self, ephemeral: bool) -> Result<(), serenity::Error> {
         if !self
             .has_sent_initial_response
-            .load(std::sync::atomic::Ordering::SeqCst)
+            .load(std::sync::atomic::Ordering::Relaxed)
         {
             let response = serenity::CreateInteractionResponse::Defer(
                 serenity::CreateInteractionResponseMessage::new().ephemeral(ephemeral),
@@ -74,10 +79,111 @@ impl<T, E> ApplicationContext<'_, T, E> {
             self.interaction.create_response(http, response).await?;
 
             self.has_sent_initial_response
-                .store(true, std::sync::atomic::Ordering::SeqCst);
+                .store(true, std::sync::atomic::Ordering::Relaxed);
         }
         Ok(())
     }
+
+    /// Takes an owned snapshot of this invocation, which can be moved into background tasks that
+    /// outlive the command, see [`OwnedInvocation`]
+    pub fn to_owned_invocation(&self) -> OwnedInvocation {
+        let command_path = self
+            .parent_commands
+            .iter()
+            .chain(std::iter::once(&self.command))
+            .map(|command| command.name.to_string())
+            .collect();
+
+        OwnedInvocation {
+            interaction: self.interaction.clone(),
+            interaction_type: self.interaction_type,
+            command_path,
+            identifying_name: self.command.identifying_name.to_string(),
+            has_sent_initial_response: self
+                .has_sent_initial_response
+                .load(std::sync::atomic::Ordering::Relaxed),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Owned snapshot of an application command invocation, created with
+/// [`ApplicationContext::to_owned_invocation`]
+///
+/// Unlike [`ApplicationContext`], this doesn't borrow from the framework, so it can be moved into
+/// a spawned task to finish processing after the command returned. The interaction token stays
+/// valid for 15 minutes, so the task can still edit the response or send followups:
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// #[lumi::command(slash_command)]
+/// async fn report(ctx: lumi::ApplicationContext<'_, (), Error>) -> Result<(), Error> {
+///     ctx.defer_response(false).await?;
+///     let invocation = ctx.to_owned_invocation();
+///     let http = ctx.framework.serenity_context.http.clone();
+///     tokio::spawn(async move {
+///         let reply = lumi::CreateReply::default().content("Report finished");
+///         invocation.edit_response(&http, reply).await
+///     });
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct OwnedInvocation {
+    /// The interaction which triggered the command, including its token
+    pub interaction: serenity::CommandInteraction,
+    /// The type of the interaction which triggered the command
+    pub interaction_type: CommandInteractionType,
+    /// Names of the invoked command and its parent commands, ordered top down
+    pub command_path: Vec<String>,
+    /// [`crate::Command::identifying_name`] of the invoked command, see
+    /// [`crate::FrameworkContext::command_by_identifying_name`]
+    pub identifying_name: String,
+    /// Whether an initial response had been sent when the snapshot was taken
+    pub has_sent_initial_response: bool,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl OwnedInvocation {
+    /// Returns the arguments of the invoked (sub)command, equivalent to [`ApplicationContext::args`]
+    pub fn args(&self) -> Vec<serenity::ResolvedOption<'_>> {
+        let mut options = self.interaction.data.options();
+        while let Some(sub_options) =
+            options
+                .iter_mut()
+                .find_map(|option| match &mut option.value {
+                    serenity::ResolvedValue::SubCommand(o)
+                    | serenity::ResolvedValue::SubCommandGroup(o) => Some(std::mem::take(o)),
+                    _ => None,
+                })
+        {
+            options = sub_options;
+        }
+        options
+    }
+
+    /// Edits the initial interaction response, or the deferred response
+    pub async fn edit_response(
+        &self,
+        http: &serenity::Http,
+        reply: crate::CreateReply<'_>,
+    ) -> Result<serenity::Message, serenity::Error> {
+        let builder =
+            reply.to_slash_initial_response_edit(serenity::EditInteractionResponse::new());
+        self.interaction.edit_response(http, builder).await
+    }
+
+    /// Sends a followup message to the interaction
+    pub async fn send_followup(
+        &self,
+        http: &serenity::Http,
+        reply: crate::CreateReply<'_>,
+    ) -> Result<serenity::Message, serenity::Error> {
+        let builder =
+            reply.to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
+        self.interaction.create_followup(http, builder).await
+    }
 }
 
 /// Possible actions that a context menu entry can have
@@ -110,6 +216,70 @@ impl<T, E> Clone for ContextMenuCommandAction<T, E> {
     }
 }
 
+/// How the framework responds to an application command interaction before running the command,
+/// see [`crate::Command::response_mode`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ResponseMode {
+    /// Don't respond before the command runs, so the first response of the command becomes the
+    /// initial interaction response. The command must respond within 3 seconds or call
+    /// [`crate::Context::defer()`] itself
+    #[default]
+    Immediate,
+    /// Call [`crate::Context::defer()`] before the command runs
+    DeferPublic,
+    /// Call [`crate::Context::defer_ephemeral()`] before the command runs
+    DeferEphemeral,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Who handles invocations of a primary entry point command, see
+/// [`crate::Command::entry_point_handler`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EntryPointHandler {
+    /// The app receives the interaction, and [`crate::Command::slash_action`] runs like for a
+    /// regular slash command
+    #[default]
+    AppHandler,
+    /// Discord launches the app's Activity itself and sends the app a notification message, which
+    /// the framework ignores
+    DiscordLaunchActivity,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl EntryPointHandler {
+    /// Converts to the serenity equivalent, sent to Discord on registration
+    pub(crate) fn to_serenity(self) -> serenity::EntryPointHandlerType {
+        match self {
+            Self::DiscordLaunchActivity => serenity::EntryPointHandlerType::DiscordLaunchActivity,
+            Self::AppHandler | Self::__NonExhaustive => serenity::EntryPointHandlerType::AppHandler,
+        }
+    }
+}
+
+/// How to determine the invoking member's permissions if an application command interaction
+/// doesn't include them, see [`crate::FrameworkOptions::permission_fallback`]
+///
+/// If the permissions can't be determined, commands with
+/// [`crate::Command::required_permissions`] fail with
+/// [`crate::FrameworkError::PermissionFetchFailed`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PermissionFallback {
+    /// Compute the permissions from the cache, or via HTTP requests if the guild isn't cached
+    ///
+    /// The requests fail if the bot isn't a member of the guild, e.g. for user-installed apps, and
+    /// take several round trips otherwise.
+    Http,
+    /// Compute the permissions from the cache only
+    #[default]
+    Cache,
+    /// Don't compute the permissions
+    Disabled,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
 /// A single drop-down choice in a slash command choice parameter
 #[derive(Debug, Clone)]
 pub struct CommandParameterChoice {
@@ -0,0 +1,52 @@
+//! Holds the [`Translator`] trait which backs [`crate::Context::t`]
+
+/// Resolves localized response strings for [`crate::Context::t`].
+///
+/// Store in [`crate::FrameworkOptions::translator`]. With the `fluent` feature,
+/// [`crate::Translations`] implements this trait. For other sources, like a database or a static
+/// map, implement it yourself:
+///
+/// ```rust
+/// struct Greetings;
+///
+/// impl lumi::Translator for Greetings {
+///     fn translate(&self, locale: &str, key: &str, args: &[(&str, &str)]) -> Option<String> {
+///         let name = args.iter().find(|(arg, _)| *arg == "name")?.1;
+///         match (locale, key) {
+///             ("de", "greeting") => Some(format!("Hallo {name}!")),
+///             ("en-US", "greeting") => Some(format!("Hello {name}!")),
+///             _ => None,
+///         }
+///     }
+///
+///     fn default_locale(&self) -> &str {
+///         "en-US"
+///     }
+/// }
+///
+/// lumi::FrameworkOptions::<(), ()> {
+///     translator: Some(std::sync::Arc::new(Greetings)),
+///     ..Default::default()
+/// };
+/// ```
+pub trait Translator: Send + Sync {
+    /// Looks up `key` in exactly the given locale, filling in the named `args`.
+    ///
+    /// Returns `None` if there's no translation for `key` in this locale.
+    fn translate(&self, locale: &str, key: &str, args: &[(&str, &str)]) -> Option<String>;
+
+    /// The locale used if the user's locale is unknown or has no translation for a key
+    fn default_locale(&self) -> &str;
+
+    /// Looks up `key` in `locale` if available, falling back to [`Self::default_locale`]
+    fn translate_or_default(
+        &self,
+        locale: Option<&str>,
+        key: &str,
+        args: &[(&str, &str)],
+    ) -> Option<String> {
+        locale
+            .and_then(|locale| self.translate(locale, key, args))
+            .or_else(|| self.translate(self.default_locale(), key, args))
+    }
+}
@@ -1,6 +1,9 @@
 //! Tools for implementing automatic edit tracking, i.e. the bot automatically updating its response
 //! when the user edits their command invocation message.
 
+use std::collections::HashMap;
+use std::hash::BuildHasher as _;
+
 use crate::serenity_prelude as serenity;
 
 /// A single cached command invocation
@@ -14,15 +17,24 @@ struct CachedInvocation {
     track_deletion: bool,
 }
 
+/// Number of independently locked shards of [`EditTracker`]
+const SHARD_COUNT: usize = 16;
+
 /// Stores messages and the associated bot responses in order to implement lumi's edit tracking
 /// feature.
+///
+/// Safe to share between concurrent invocations without further locking: the cache is split into
+/// locked shards by message ID, so that only invocations whose messages land in the same shard
+/// contend.
 #[derive(Debug)]
 pub struct EditTracker {
     /// Duration after which cached messages can be purged
     max_duration: std::time::Duration,
-    /// Cache, which stores invocation messages, and the corresponding bot response message if any
-    // TODO: change to `OrderedMap<MessageId, (Message, Option<serenity::Message>)>`?
-    cache: Vec<CachedInvocation>,
+    /// Picks the shard of a message ID
+    hasher: std::collections::hash_map::RandomState,
+    /// Cache, which stores invocation messages, and the corresponding bot response message if any,
+    /// keyed by the invocation message ID. Each message is stored in the shard picked by its hash
+    shards: Box<[parking_lot::Mutex<HashMap<serenity::MessageId, CachedInvocation>>]>,
 }
 
 impl EditTracker {
@@ -31,11 +43,20 @@ impl EditTracker {
     /// Note: [`EditTracker`] will only purge messages outside the duration when [`Self::purge`]
     /// is called. If you supply the created [`EditTracker`] to [`crate::Framework`], the framework
     /// will take care of that by calling [`Self::purge`] periodically.
-    pub fn for_timespan(duration: std::time::Duration) -> std::sync::RwLock<Self> {
-        std::sync::RwLock::new(Self {
+    pub fn for_timespan(duration: std::time::Duration) -> Self {
+        Self {
             max_duration: duration,
-            cache: Vec::new(),
-        })
+            hasher: Default::default(),
+            shards: (0..SHARD_COUNT).map(|_| Default::default()).collect(),
+        }
+    }
+
+    /// Returns the shard which stores the message
+    fn shard(
+        &self,
+        message_id: serenity::MessageId,
+    ) -> &parking_lot::Mutex<HashMap<serenity::MessageId, CachedInvocation>> {
+        &self.shards[self.hasher.hash_one(message_id) as usize % self.shards.len()]
     }
 
     /// Updates the internal invocation cache for a message and returns:
@@ -43,17 +64,13 @@ impl EditTracker {
     /// - `Some(true)` if the command should be re-run, and the command was previously tracked.
     /// - `Some(false)` if the command should be re-run, but the command wasn't previously tracked.
     /// - `None` if the command should not be re-run.
-    pub fn process_message_update<'a>(
-        &'a mut self,
-        user_msg_update: &'a serenity::MessageUpdateEvent,
+    pub fn process_message_update(
+        &self,
+        user_msg_update: &serenity::MessageUpdateEvent,
         ignore_edits_if_not_yet_responded: bool,
     ) -> Option<bool> {
         let new_message = &user_msg_update.message;
-        match self
-            .cache
-            .iter_mut()
-            .find(|invocation| invocation.user_msg.id == new_message.id)
-        {
+        match self.shard(new_message.id).lock().get_mut(&new_message.id) {
             Some(invocation) => {
                 if ignore_edits_if_not_yet_responded && invocation.bot_response.is_none() {
                     return None;
@@ -76,14 +93,13 @@ impl EditTracker {
     /// if the command invocation is cached, and it has an associated bot response, and the command
     /// is marked track_deletion
     pub fn process_message_delete(
-        &mut self,
+        &self,
         deleted_message_id: serenity::MessageId,
     ) -> Option<serenity::Message> {
-        let invocation = self.cache.remove(
-            self.cache
-                .iter()
-                .position(|invocation| invocation.user_msg.id == deleted_message_id)?,
-        );
+        let invocation = self
+            .shard(deleted_message_id)
+            .lock()
+            .remove(&deleted_message_id)?;
         if invocation.track_deletion {
             invocation.bot_response
         } else {
@@ -92,67 +108,108 @@ impl EditTracker {
     }
 
     /// Forget all of the messages that are older than the specified duration.
-    pub fn purge(&mut self) {
+    pub fn purge(&self) {
         let max_duration = self.max_duration;
-        self.cache.retain(|invocation| {
-            let last_update = invocation
-                .user_msg
-                .edited_timestamp
-                .unwrap_or(invocation.user_msg.timestamp);
-            let age = serenity::Timestamp::now().unix_timestamp() - last_update.unix_timestamp();
-            age < max_duration.as_secs() as i64
-        });
+        let now = serenity::Timestamp::now().unix_timestamp();
+        for shard in &self.shards {
+            shard.lock().retain(|_, invocation| {
+                let last_update = invocation
+                    .user_msg
+                    .edited_timestamp
+                    .unwrap_or(invocation.user_msg.timestamp);
+                now - last_update.unix_timestamp() < max_duration.as_secs() as i64
+            });
+        }
     }
 
     /// Given a message by a user, find the corresponding bot response, if one exists and is cached.
-    pub fn find_bot_response(
-        &self,
-        user_msg_id: serenity::MessageId,
-    ) -> Option<&serenity::Message> {
-        let invocation = self
-            .cache
-            .iter()
-            .find(|invocation| invocation.user_msg.id == user_msg_id)?;
-        invocation.bot_response.as_ref()
+    pub fn find_bot_response(&self, user_msg_id: serenity::MessageId) -> Option<serenity::Message> {
+        self.shard(user_msg_id)
+            .lock()
+            .get(&user_msg_id)?
+            .bot_response
+            .clone()
     }
 
     /// Notify the [`EditTracker`] that the given user message should be associated with the given
     /// bot response. Overwrites any previous associated bot response
     pub fn set_bot_response(
-        &mut self,
+        &self,
         user_msg: &serenity::Message,
         bot_response: serenity::Message,
         track_deletion: bool,
     ) {
-        if let Some(invocation) = self
-            .cache
-            .iter_mut()
-            .find(|invocation| invocation.user_msg.id == user_msg.id)
-        {
+        let mut shard = self.shard(user_msg.id).lock();
+        if let Some(invocation) = shard.get_mut(&user_msg.id) {
             invocation.bot_response = Some(bot_response);
         } else {
-            self.cache.push(CachedInvocation {
-                user_msg: user_msg.clone(),
-                bot_response: Some(bot_response),
-                track_deletion,
-            });
+            shard.insert(
+                user_msg.id,
+                CachedInvocation {
+                    user_msg: user_msg.clone(),
+                    bot_response: Some(bot_response),
+                    track_deletion,
+                },
+            );
         }
     }
 
     /// Store that this command is currently running; so that if the command is editing its own
     /// invocation message (e.g. removing embeds), we don't accidentally treat it as an
     /// `execute_untracked_edits` situation and start an infinite loop
-    pub fn track_command(&mut self, user_msg: &serenity::Message, track_deletion: bool) {
-        if !self
-            .cache
-            .iter()
-            .any(|invocation| invocation.user_msg.id == user_msg.id)
-        {
-            self.cache.push(CachedInvocation {
+    pub fn track_command(&self, user_msg: &serenity::Message, track_deletion: bool) {
+        self.shard(user_msg.id)
+            .lock()
+            .entry(user_msg.id)
+            .or_insert_with(|| CachedInvocation {
                 user_msg: user_msg.clone(),
                 bot_response: None,
                 track_deletion,
             });
-        }
+    }
+}
+
+/// Prefix of the custom ID of [`crate::Command::rerun_button`] buttons, followed by the ID of the
+/// command interaction to rerun
+pub(crate) const RERUN_BUTTON_PREFIX: &str = "lumi-rerun-";
+
+/// How long interactions are kept for [`RerunTracker`]. Discord invalidates interaction tokens
+/// after 15 minutes, so responses to older interactions can't be sent anyway.
+const RERUN_MAX_DURATION: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Stores the command interactions of recent [`crate::Command::rerun_button`] invocations, so
+/// that they can be run again when the button is clicked.
+///
+/// This approximates [`crate::Command::invoke_on_edit`] for application commands, which can't be
+/// edited. Stored in [`crate::FrameworkOptions::rerun_tracker`].
+#[derive(Debug, Default)]
+pub struct RerunTracker {
+    /// Tracked interactions and when they were received, keyed by interaction ID
+    interactions: parking_lot::Mutex<
+        HashMap<serenity::InteractionId, (std::time::Instant, serenity::CommandInteraction)>,
+    >,
+}
+
+impl RerunTracker {
+    /// Creates an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores an interaction to be rerun later and forgets expired ones
+    pub(crate) fn track(&self, interaction: &serenity::CommandInteraction) {
+        let mut interactions = self.interactions.lock();
+        interactions.retain(|_, (received, _)| received.elapsed() < RERUN_MAX_DURATION);
+        interactions.insert(
+            interaction.id,
+            (std::time::Instant::now(), interaction.clone()),
+        );
+    }
+
+    /// Returns the stored interaction with the given ID, if it hasn't expired yet
+    pub fn find(&self, id: serenity::InteractionId) -> Option<serenity::CommandInteraction> {
+        let interactions = self.interactions.lock();
+        let (received, interaction) = interactions.get(&id)?;
+        (received.elapsed() < RERUN_MAX_DURATION).then(|| interaction.clone())
     }
 }
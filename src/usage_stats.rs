@@ -0,0 +1,109 @@
+//! In-memory command usage statistics, see [`UsageStats`]
+
+use crate::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// Usage statistics of a single command, as returned by [`UsageStats`]
+#[derive(Clone, Debug)]
+pub struct CommandUsage {
+    /// How often the command was invoked
+    pub invocations: u64,
+    /// When the command was invoked most recently
+    pub last_invoked: SystemTime,
+    /// How often each user invoked the command
+    pub users: HashMap<serenity::UserId, u64>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl CommandUsage {
+    /// Returns up to `n` users who invoked the command most often, with their invocation count
+    pub fn top_users(&self, n: usize) -> Vec<(serenity::UserId, u64)> {
+        let mut users = self
+            .users
+            .iter()
+            .map(|(&user_id, &count)| (user_id, count))
+            .collect::<Vec<_>>();
+        users.sort_by(|a, b| b.1.cmp(&a.1));
+        users.truncate(n);
+        users
+    }
+}
+
+/// Counts command invocations per command and user, e.g. to find out which commands are unused
+/// and could be removed.
+///
+/// Enabled by setting [`crate::FrameworkOptions::usage_stats`], after which every invocation that
+/// wasn't denied by a permission check or cooldown is counted. Access it with
+/// [`crate::FrameworkContext::usage_stats`], or show it with [`crate::builtins::stats`].
+///
+/// Statistics are kept in memory only and reset when the bot restarts.
+#[derive(Debug, Default)]
+pub struct UsageStats {
+    /// Usage statistics keyed by [`crate::Command::qualified_name`]
+    commands: parking_lot::Mutex<HashMap<String, CommandUsage>>,
+}
+
+impl UsageStats {
+    /// Creates an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts an invocation of the given command by the given user
+    pub fn record(&self, qualified_name: &str, user_id: serenity::UserId) {
+        let mut commands = self.commands.lock();
+        let usage = commands
+            .entry(qualified_name.to_owned())
+            .or_insert_with(|| CommandUsage {
+                invocations: 0,
+                last_invoked: SystemTime::now(),
+                users: HashMap::new(),
+                __non_exhaustive: (),
+            });
+        usage.invocations += 1;
+        usage.last_invoked = SystemTime::now();
+        *usage.users.entry(user_id).or_insert(0) += 1;
+    }
+
+    /// Returns the usage statistics of the command with the given qualified name, if it was
+    /// invoked at least once
+    pub fn get(&self, qualified_name: &str) -> Option<CommandUsage> {
+        self.commands.lock().get(qualified_name).cloned()
+    }
+
+    /// Returns the usage statistics of all commands which were invoked at least once, most
+    /// invoked first
+    pub fn commands(&self) -> Vec<(String, CommandUsage)> {
+        let mut commands = self
+            .commands
+            .lock()
+            .iter()
+            .map(|(name, usage)| (name.clone(), usage.clone()))
+            .collect::<Vec<_>>();
+        commands.sort_by(|a, b| b.1.invocations.cmp(&a.1.invocations));
+        commands
+    }
+
+    /// Forgets all statistics
+    pub fn reset(&self) {
+        self.commands.lock().clear();
+    }
+}
+
+/// Counts a completed invocation in [`crate::FrameworkOptions::usage_stats`], if set
+pub(crate) fn record_invocation<T: Send + Sync + 'static, E>(
+    ctx: crate::Context<'_, T, E>,
+    result: &Result<(), crate::FrameworkError<'_, T, E>>,
+) {
+    let Some(usage_stats) = &ctx.framework().options().usage_stats else {
+        return;
+    };
+    match crate::InvocationOutcome::from_result(result) {
+        crate::InvocationOutcome::Denied(_)
+        | crate::InvocationOutcome::Failed(crate::ErrorCode::CooldownHit) => {}
+        _ => usage_stats.record(&ctx.command().qualified_name, ctx.author().id),
+    }
+}